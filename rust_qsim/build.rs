@@ -11,6 +11,8 @@ fn main() {
         "src/simulation/io/proto/types/network.proto",
         "src/simulation/io/proto/types/population.proto",
         "src/simulation/io/proto/types/vehicles.proto",
+        "src/simulation/io/proto/types/transit.proto",
+        "src/simulation/io/proto/types/messages.proto",
         "src/external_services/routing/routing.proto",
     ];
 
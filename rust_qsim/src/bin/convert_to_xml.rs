@@ -4,6 +4,7 @@ use clap::Parser;
 use tracing::info;
 
 use rust_qsim::simulation::config::PartitionMethod;
+use rust_qsim::simulation::pt::TransitSchedule;
 use rust_qsim::simulation::scenario::network::Network;
 use rust_qsim::simulation::scenario::population::Population;
 use rust_qsim::simulation::scenario::vehicles::Garage;
@@ -17,6 +18,8 @@ struct InputArgs {
     #[arg(short, long)]
     pub vehicles: Option<String>,
     #[arg(short, long)]
+    pub transit_schedule: Option<String>,
+    #[arg(short, long)]
     pub ids: String,
 }
 
@@ -28,6 +31,7 @@ fn main() {
     let net_path = args.network.map(|s| PathBuf::from(&s));
     let pop_path = args.population.map(|s| PathBuf::from(&s));
     let veh_path = args.vehicles.map(|s| PathBuf::from(&s));
+    let schedule_path = args.transit_schedule.map(|s| PathBuf::from(&s));
 
     rust_qsim::simulation::id::load_from_file(&ids_path);
 
@@ -39,6 +43,14 @@ fn main() {
         net.to_file(&replace_filename(net_path));
     }
 
+    if let Some(schedule_path) = schedule_path {
+        info!("Loading transit schedule from {:?}", schedule_path);
+        let schedule = TransitSchedule::from_file(&schedule_path);
+
+        info!("Converting transit schedule to XML format");
+        schedule.to_file(&replace_filename(schedule_path));
+    }
+
     let mut veh = if let Some(veh_path) = veh_path {
         info!("Loading vehicles from {:?}", veh_path);
         let veh = Garage::from_file(&veh_path);
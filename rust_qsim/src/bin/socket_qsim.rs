@@ -0,0 +1,142 @@
+use clap::Parser;
+use rust_qsim::simulation::config::{CommandLineArgs, Config};
+use rust_qsim::simulation::controller::controller::ControllerBuilder;
+use rust_qsim::simulation::logging::init_std_out_logging_thread_local;
+use rust_qsim::simulation::messaging::sim_communication::socket_communicator::{
+    SocketAddress, SocketSimCommunicator,
+};
+use rust_qsim::simulation::scenario::Scenario;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::process::{Command, ExitCode};
+use std::sync::{Arc, Barrier};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Runs a simulation with one process per partition. The processes exchange vehicles and agents
+/// over TCP or Unix domain sockets. Without `--rank`, the binary acts as launcher and starts one
+/// process per partition on the local machine. To run on several machines, start each rank with
+/// `--rank` and the same `--addresses`.
+///
+/// As each process only knows the plans of its own partition, scoring and replanning are not
+/// available. The run is rejected unless `controller.last_iteration` equals
+/// `controller.first_iteration`.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct SocketArgs {
+    #[command(flatten)]
+    sim: CommandLineArgs,
+    /// Rank of this process. Starts all ranks on the local machine if omitted.
+    #[arg(long)]
+    rank: Option<u32>,
+    /// Listening addresses of all ranks, ordered by rank, e.g. `10.0.0.1:9000,10.0.0.2:9000` or
+    /// `unix:/tmp/rank0.sock,unix:/tmp/rank1.sock`.
+    #[arg(long, value_delimiter = ',')]
+    addresses: Vec<SocketAddress>,
+    /// If no addresses are given, rank i listens on localhost at port `base_port + i`.
+    #[arg(long, default_value_t = 9000)]
+    base_port: u16,
+    /// Seconds to wait for the other ranks to come up.
+    #[arg(long, default_value_t = 60)]
+    connect_timeout: u64,
+}
+
+fn main() -> ExitCode {
+    let args = SocketArgs::parse();
+    match args.rank {
+        Some(rank) => {
+            run_rank(rank, args);
+            ExitCode::SUCCESS
+        }
+        None => launch(args),
+    }
+}
+
+fn launch(args: SocketArgs) -> ExitCode {
+    let _guard = init_std_out_logging_thread_local();
+    let config = Config::from_args(args.sim.clone());
+    let addresses = addresses(&args, config.partitioning().num_parts);
+    let addresses = addresses
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let exe = std::env::current_exe().expect("Failed to determine path of executable");
+    info!(
+        "Launching {} ranks on {addresses}",
+        config.partitioning().num_parts
+    );
+    let children: Vec<_> = (0..config.partitioning().num_parts)
+        .map(|rank| {
+            let mut command = Command::new(&exe);
+            command
+                .args(std::env::args_os().skip(1))
+                .arg("--rank")
+                .arg(rank.to_string());
+            if args.addresses.is_empty() {
+                command.arg("--addresses").arg(&addresses);
+            }
+            command
+                .spawn()
+                .unwrap_or_else(|e| panic!("Failed to start rank {rank}: {e}"))
+        })
+        .collect();
+
+    let mut success = true;
+    for (rank, mut child) in children.into_iter().enumerate() {
+        let status = child.wait().expect("Failed to wait for rank");
+        if !status.success() {
+            error!("Rank {rank} failed with {status}");
+            success = false;
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn run_rank(rank: u32, args: SocketArgs) {
+    let _guard = init_std_out_logging_thread_local();
+    info!("Started rank {rank} with args: {:?}", args);
+
+    let config = Arc::new(Config::from_args(args.sim.clone()));
+    let addresses = addresses(&args, config.partitioning().num_parts);
+
+    // connect first, so that ranks don't time out while the others load the scenario
+    let communicator =
+        SocketSimCommunicator::connect(rank, &addresses, Duration::from_secs(args.connect_timeout))
+            .unwrap_or_else(|e| panic!("Rank {rank} failed to connect to the other ranks: {e}"));
+
+    // every rank only loads and routes the persons of its own partition
+    let scenario = Scenario::load_partition(config, rank);
+
+    // each process runs a single partition, so there is no one to wait for within the process.
+    ControllerBuilder::default_with_scenario(scenario)
+        .global_barrier(Arc::new(Barrier::new(1)))
+        .partition_per_process(true)
+        .build()
+        .unwrap_or_else(|e| panic!("{e}"))
+        .run_partition(communicator);
+}
+
+fn addresses(args: &SocketArgs, num_parts: u32) -> Vec<SocketAddress> {
+    let addresses = if args.addresses.is_empty() {
+        (0..num_parts)
+            .map(|rank| {
+                let port = args.base_port + rank as u16;
+                SocketAddress::Tcp(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+            })
+            .collect()
+    } else {
+        args.addresses.clone()
+    };
+    assert_eq!(
+        num_parts as usize,
+        addresses.len(),
+        "Expected one address per partition."
+    );
+    addresses
+}
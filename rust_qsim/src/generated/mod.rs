@@ -28,6 +28,13 @@ pub mod population {
 pub mod vehicles {
     include!(concat!(env!("OUT_DIR"), "/vehicles.rs"));
 }
+pub mod transit {
+    include!(concat!(env!("OUT_DIR"), "/transit.rs"));
+}
+
+pub mod messages {
+    include!(concat!(env!("OUT_DIR"), "/messages.rs"));
+}
 
 pub mod general {
     include!(concat!(env!("OUT_DIR"), "/general.rs"));
@@ -1,7 +1,11 @@
+use crate::generated::messages;
+use crate::generated::messages::agent::Logic;
 use crate::simulation::Identifiable;
 use crate::simulation::agents::agent_logic::{
     AdaptivePlanBasedSimulationLogic, PlanBasedSimulationLogic,
 };
+use crate::simulation::agents::drt_driver::DrtDriverLogic;
+use crate::simulation::agents::transit_driver::TransitDriverLogic;
 use crate::simulation::agents::{
     AgentEvent, EndTime, EnvironmentalEventObserver, SimulationAgentLogic, SimulationAgentState,
 };
@@ -92,8 +96,35 @@ impl SimulationAgentLogic for SimulationAgent {
     fn wakeup_time(&self, now: SimTime) -> SimTime {
         self.logic.wakeup_time(now)
     }
+    fn is_wanting_to_stop_on_current_link(&self) -> bool {
+        self.logic.is_wanting_to_stop_on_current_link()
+    }
+    fn transit_driver(&self) -> Option<&TransitDriverLogic> {
+        self.logic.transit_driver()
+    }
+    fn drt_driver(&self) -> Option<&DrtDriverLogic> {
+        self.logic.drt_driver()
+    }
+    fn to_message(&self) -> messages::Agent {
+        self.logic.to_message()
+    }
 
     fn into_person(self: Box<Self>) -> Option<InternalPerson> {
         self.logic.into_person()
     }
 }
+
+impl From<messages::Agent> for SimulationAgent {
+    fn from(value: messages::Agent) -> Self {
+        let logic: Box<dyn SimulationAgentLogic> =
+            match value.logic.expect("Agent message without agent logic.") {
+                Logic::PlanBased(agent) => Box::new(PlanBasedSimulationLogic::from(agent)),
+                Logic::AdaptivePlanBased(agent) => {
+                    Box::new(AdaptivePlanBasedSimulationLogic::from(agent))
+                }
+                Logic::TransitDriver(driver) => Box::new(TransitDriverLogic::from(driver)),
+                Logic::DrtDriver(driver) => Box::new(DrtDriverLogic::from(driver)),
+            };
+        Self { logic }
+    }
+}
@@ -2,6 +2,10 @@ use crate::external_services::ExternalServiceType;
 use crate::external_services::routing::{
     InternalRoutingRequest, InternalRoutingRequestPayloadBuilder, InternalRoutingResponse,
 };
+use crate::generated::messages;
+use crate::generated::messages::PlanBasedAgent;
+use crate::generated::messages::agent::Logic;
+use crate::generated::population::Person;
 use crate::simulation::Identifiable;
 use crate::simulation::agents::{
     AgentEvent, EndTime, EnvironmentalEventObserver, SimulationAgentLogic, SimulationAgentState,
@@ -55,7 +59,9 @@ impl Identifiable<InternalPerson> for PlanBasedSimulationLogic {
 impl EnvironmentalEventObserver for PlanBasedSimulationLogic {
     fn notify_event(&mut self, event: &mut AgentEvent, _now: SimTime) {
         match event {
-            AgentEvent::TeleportationStarted { .. } => {
+            AgentEvent::TeleportationStarted { .. }
+            | AgentEvent::LeftTransitVehicle { .. }
+            | AgentEvent::LeftDrtVehicle { .. } => {
                 self.set_curr_route_element_to_last();
             }
             AgentEvent::LeftLink { .. } => {
@@ -214,11 +220,51 @@ impl SimulationAgentLogic for PlanBasedSimulationLogic {
         self.activity_end_time.unwrap()
     }
 
+    fn to_message(&self) -> messages::Agent {
+        messages::Agent {
+            logic: Some(Logic::PlanBased(PlanBasedAgent::from(self))),
+        }
+    }
+
     fn into_person(self: Box<Self>) -> Option<InternalPerson> {
         Some(self.basic_agent_delegate)
     }
 }
 
+impl From<&PlanBasedSimulationLogic> for PlanBasedAgent {
+    fn from(value: &PlanBasedSimulationLogic) -> Self {
+        PlanBasedAgent {
+            person: Some(Person::from(&value.basic_agent_delegate)),
+            curr_plan_element: value.curr_plan_element as u32,
+            curr_route_element: value.curr_route_element as u32,
+            activity_end_time_ns: value.activity_end_time.map(SimTime::as_nanos),
+        }
+    }
+}
+
+impl From<PlanBasedAgent> for PlanBasedSimulationLogic {
+    fn from(value: PlanBasedAgent) -> Self {
+        let person = value.person.expect("Agent message without person.");
+        // the agent might have been created in the sending process only, e.g. a transit driver
+        Id::<InternalPerson>::create(&person.id);
+        PlanBasedSimulationLogic {
+            basic_agent_delegate: InternalPerson::from(person),
+            curr_plan_element: value.curr_plan_element as usize,
+            curr_route_element: value.curr_route_element as usize,
+            activity_end_time: value.activity_end_time_ns.map(SimTime::from_nanos),
+        }
+    }
+}
+
+impl From<PlanBasedAgent> for AdaptivePlanBasedSimulationLogic {
+    fn from(value: PlanBasedAgent) -> Self {
+        Self {
+            delegate: PlanBasedSimulationLogic::from(value),
+            route_receiver: None,
+        }
+    }
+}
+
 impl EndTime for PlanBasedSimulationLogic {
     fn end_time(&self, now: SimTime) -> SimTime {
         match self
@@ -295,6 +341,16 @@ impl SimulationAgentLogic for AdaptivePlanBasedSimulationLogic {
         end
     }
 
+    fn to_message(&self) -> messages::Agent {
+        // A pending route request can't be handed over. Agents only wait for routes during
+        // activities, while only agents on a leg change the partition.
+        messages::Agent {
+            logic: Some(Logic::AdaptivePlanBased(PlanBasedAgent::from(
+                &self.delegate,
+            ))),
+        }
+    }
+
     fn into_person(self: Box<Self>) -> Option<InternalPerson> {
         Box::new(self.delegate).into_person()
     }
@@ -503,6 +559,7 @@ mod tests {
             act_type: Id::create(act_type),
             link_id: Id::create(link),
             coord: Some(Coordinate::default()),
+            facility_id: None,
             start_time: None,
             end_time: None,
             max_dur: None,
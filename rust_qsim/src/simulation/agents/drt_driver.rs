@@ -0,0 +1,181 @@
+use crate::generated::messages;
+use crate::generated::messages::agent::Logic;
+use crate::generated::messages::{DrtDriver, PlanBasedAgent};
+use crate::simulation::Identifiable;
+use crate::simulation::agents::agent_logic::PlanBasedSimulationLogic;
+use crate::simulation::agents::{
+    AgentEvent, EndTime, EnvironmentalEventObserver, SimulationAgentLogic, SimulationAgentState,
+};
+use crate::simulation::drt::DrtPath;
+use crate::simulation::id::Id;
+use crate::simulation::scenario::network::Link;
+use crate::simulation::scenario::population::{
+    InternalActivity, InternalGenericRoute, InternalLeg, InternalNetworkRoute, InternalPerson,
+    InternalPlan, InternalRoute,
+};
+use crate::simulation::scenario::vehicles::InternalVehicle;
+use crate::simulation::time::SimTime;
+
+const DRT_DRIVER_ACT_TYPE: &str = "DrtStay";
+
+/// Agent logic of the driver of a DRT vehicle for one drive from a stop to the next. The DRT engine
+/// creates a new driver for every drive; the driver has a plan with a single leg along the path to
+/// the next stop. DRT drivers are not part of the population and are dropped after their leg.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DrtDriverLogic {
+    delegate: PlanBasedSimulationLogic,
+    vehicle: Id<InternalVehicle>,
+}
+
+impl DrtDriverLogic {
+    /// Creates the driver of `vehicle` for a drive along `path`, starting at `now`. The driver's
+    /// plan is already advanced to its leg, i.e. the driver can directly be sent onto the network.
+    pub fn new(
+        id: Id<InternalPerson>,
+        mode: &Id<String>,
+        vehicle: Id<InternalVehicle>,
+        path: &DrtPath,
+        now: SimTime,
+    ) -> Self {
+        let start_link = path.links.first().unwrap().clone();
+        let end_link = path.links.last().unwrap().clone();
+
+        let generic_route = InternalGenericRoute::new(
+            start_link.clone(),
+            end_link.clone(),
+            Some(path.travel_time),
+            Some(path.distance),
+            Some(vehicle.clone()),
+        );
+        let leg = InternalLeg {
+            mode: mode.clone(),
+            routing_mode: Some(mode.clone()),
+            dep_time: Some(now),
+            trav_time: Some(path.travel_time),
+            route: Some(InternalRoute::Network(InternalNetworkRoute::new(
+                generic_route,
+                path.links.clone(),
+            ))),
+            attributes: Default::default(),
+        };
+
+        let mut plan = InternalPlan::default();
+        plan.add_act(InternalActivity::new(
+            None,
+            DRT_DRIVER_ACT_TYPE,
+            start_link,
+            None,
+            Some(now),
+            None,
+        ));
+        plan.add_leg(leg);
+        plan.add_act(InternalActivity::new(
+            None,
+            DRT_DRIVER_ACT_TYPE,
+            end_link,
+            None,
+            None,
+            None,
+        ));
+
+        let mut delegate = PlanBasedSimulationLogic::new(InternalPerson::new(id, plan));
+        delegate.advance_plan(now);
+
+        DrtDriverLogic { delegate, vehicle }
+    }
+
+    pub fn vehicle(&self) -> &Id<InternalVehicle> {
+        &self.vehicle
+    }
+}
+
+impl Identifiable<InternalPerson> for DrtDriverLogic {
+    fn id(&self) -> &Id<InternalPerson> {
+        self.delegate.id()
+    }
+}
+
+impl EnvironmentalEventObserver for DrtDriverLogic {
+    fn notify_event(&mut self, event: &mut AgentEvent, now: SimTime) {
+        self.delegate.notify_event(event, now);
+    }
+}
+
+impl EndTime for DrtDriverLogic {
+    fn end_time(&self, now: SimTime) -> SimTime {
+        self.delegate.end_time(now)
+    }
+}
+
+impl SimulationAgentLogic for DrtDriverLogic {
+    fn curr_act(&self) -> &InternalActivity {
+        self.delegate.curr_act()
+    }
+
+    fn next_act(&self) -> &InternalActivity {
+        self.delegate.next_act()
+    }
+
+    fn curr_leg(&self) -> &InternalLeg {
+        self.delegate.curr_leg()
+    }
+
+    fn next_leg(&self) -> Option<&InternalLeg> {
+        self.delegate.next_leg()
+    }
+
+    fn advance_plan(&mut self, now: SimTime) {
+        self.delegate.advance_plan(now);
+    }
+
+    fn state(&self) -> SimulationAgentState {
+        self.delegate.state()
+    }
+
+    fn is_wanting_to_arrive_on_current_link(&self) -> bool {
+        self.delegate.is_wanting_to_arrive_on_current_link()
+    }
+
+    fn curr_link_id(&self) -> Option<&Id<Link>> {
+        self.delegate.curr_link_id()
+    }
+
+    fn peek_next_link_id(&self) -> Option<&Id<Link>> {
+        self.delegate.peek_next_link_id()
+    }
+
+    fn wakeup_time(&self, now: SimTime) -> SimTime {
+        self.delegate.wakeup_time(now)
+    }
+
+    fn drt_driver(&self) -> Option<&DrtDriverLogic> {
+        Some(self)
+    }
+
+    fn to_message(&self) -> messages::Agent {
+        messages::Agent {
+            logic: Some(Logic::DrtDriver(DrtDriver {
+                delegate: Some(PlanBasedAgent::from(&self.delegate)),
+                vehicle: self.vehicle.external().to_string(),
+            })),
+        }
+    }
+
+    fn into_person(self: Box<Self>) -> Option<InternalPerson> {
+        // DRT drivers are generated by the DRT engine and are not part of the population.
+        None
+    }
+}
+
+impl From<DrtDriver> for DrtDriverLogic {
+    fn from(value: DrtDriver) -> Self {
+        DrtDriverLogic {
+            delegate: PlanBasedSimulationLogic::from(
+                value
+                    .delegate
+                    .expect("DRT driver message without delegate."),
+            ),
+            vehicle: Id::get_from_ext(&value.vehicle),
+        }
+    }
+}
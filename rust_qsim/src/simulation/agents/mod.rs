@@ -1,7 +1,12 @@
 pub mod agent;
 pub mod agent_logic;
+pub mod drt_driver;
+pub mod transit_driver;
 
+use crate::generated::messages;
 use crate::simulation::Identifiable;
+use crate::simulation::agents::drt_driver::DrtDriverLogic;
+use crate::simulation::agents::transit_driver::TransitDriverLogic;
 use crate::simulation::controller::ThreadLocalComputationalEnvironment;
 use crate::simulation::id::Id;
 use crate::simulation::scenario::network::Link;
@@ -29,6 +34,25 @@ pub trait SimulationAgentLogic:
     fn peek_next_link_id(&self) -> Option<&Id<Link>>;
     fn wakeup_time(&self, now: SimTime) -> SimTime;
 
+    /// Only transit drivers serve stops. Vehicles of other agents never stop on a link.
+    fn is_wanting_to_stop_on_current_link(&self) -> bool {
+        false
+    }
+
+    /// Returns the transit specific part of the agent, if the agent drives a transit vehicle.
+    fn transit_driver(&self) -> Option<&TransitDriverLogic> {
+        None
+    }
+
+    /// Returns the DRT specific part of the agent, if the agent drives a DRT vehicle.
+    fn drt_driver(&self) -> Option<&DrtDriverLogic> {
+        None
+    }
+
+    /// Converts the agent into its wire format, so that it can be handed over to a partition which
+    /// is simulated in another process.
+    fn to_message(&self) -> messages::Agent;
+
     fn into_person(self: Box<Self>) -> Option<InternalPerson>;
 }
 
@@ -51,6 +75,13 @@ pub enum AgentEvent<'a> {
     NetworkLegStarted(),
     LeftLink(),
     NetworkLegFinished(),
+
+    // transit-related events
+    DepartedFromStop(),
+    LeftTransitVehicle(),
+
+    // drt-related events
+    LeftDrtVehicle(),
 }
 
 pub struct ActivityStartedEvent<'a> {
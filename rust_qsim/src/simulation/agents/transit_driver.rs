@@ -0,0 +1,334 @@
+use crate::generated::messages;
+use crate::generated::messages::agent::Logic;
+use crate::generated::messages::{PlanBasedAgent, TransitDriver};
+use crate::simulation::Identifiable;
+use crate::simulation::agents::agent_logic::PlanBasedSimulationLogic;
+use crate::simulation::agents::{
+    AgentEvent, EndTime, EnvironmentalEventObserver, SimulationAgentLogic, SimulationAgentState,
+};
+use crate::simulation::id::Id;
+use crate::simulation::pt::{
+    TransitDeparture, TransitLine, TransitRoute, TransitSchedule, TransitStopFacility,
+};
+use crate::simulation::scenario::network::Link;
+use crate::simulation::scenario::population::{
+    InternalActivity, InternalGenericRoute, InternalLeg, InternalNetworkRoute, InternalPerson,
+    InternalPlan, InternalRoute,
+};
+use crate::simulation::scenario::vehicles::InternalVehicle;
+use crate::simulation::time::SimTime;
+use std::time::Duration;
+
+const TRANSIT_DRIVER_ACT_TYPE: &str = "pt driver interaction";
+
+/// A stop of a transit route with the scheduled times of one particular departure.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransitDriverStop {
+    pub facility_id: Id<TransitStopFacility>,
+    pub link_id: Id<Link>,
+    pub arrival_time: SimTime,
+    pub departure_time: SimTime,
+    pub await_departure: bool,
+    pub allow_boarding: bool,
+    pub allow_alighting: bool,
+    pub minimum_stop_duration: Duration,
+}
+
+/// Agent logic of a driver operating one departure of a transit route. The driver has a plan with
+/// a single leg along the network route of the transit route and serves the route's stops in order.
+/// Transit drivers are not part of the population and are dropped after their leg has ended.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransitDriverLogic {
+    delegate: PlanBasedSimulationLogic,
+    vehicle: Id<InternalVehicle>,
+    transit_line: Id<TransitLine>,
+    transit_route: Id<TransitRoute>,
+    departure: Id<TransitDeparture>,
+    stops: Vec<TransitDriverStop>,
+    next_stop: usize,
+}
+
+impl TransitDriverLogic {
+    /// Creates the driver for `departure` of `route`. The driver's plan is already advanced to its
+    /// leg, i.e. the driver can directly be sent onto the network.
+    pub fn new(
+        schedule: &TransitSchedule,
+        line: &TransitLine,
+        route: &TransitRoute,
+        departure: &TransitDeparture,
+        vehicle: Id<InternalVehicle>,
+    ) -> Self {
+        let start_link = route.network_route.first().unwrap_or_else(|| {
+            panic!(
+                "Transit route {} of line {} has no network route.",
+                route.id, line.id
+            )
+        });
+        let end_link = route.network_route.last().unwrap();
+        let stops: Vec<_> = route
+            .stops
+            .iter()
+            .map(|stop| {
+                let facility = schedule.get_facility(&stop.facility_id);
+                let link_id = facility.link_ref_id.clone().unwrap_or_else(|| {
+                    panic!(
+                        "Stop facility {} of transit route {} is not attached to a link.",
+                        facility.id, route.id
+                    )
+                });
+                // MATSim falls back to the other offset if only one of them is given.
+                let arrival_offset = stop.arrival_offset.or(stop.departure_offset);
+                let departure_offset = stop.departure_offset.or(stop.arrival_offset);
+                TransitDriverStop {
+                    facility_id: stop.facility_id.clone(),
+                    link_id,
+                    arrival_time: departure
+                        .departure_time
+                        .saturating_add(arrival_offset.unwrap_or_default()),
+                    departure_time: departure
+                        .departure_time
+                        .saturating_add(departure_offset.unwrap_or_default()),
+                    await_departure: stop.await_departure.unwrap_or(false),
+                    allow_boarding: stop.allow_boarding,
+                    allow_alighting: stop.allow_alighting,
+                    minimum_stop_duration: stop.minimum_stop_duration,
+                }
+            })
+            .collect();
+
+        let travel_time = stops
+            .last()
+            .map(|s| s.arrival_time.duration_since(departure.departure_time))
+            .unwrap_or_default();
+
+        let generic_route = InternalGenericRoute::new(
+            start_link.clone(),
+            end_link.clone(),
+            Some(travel_time),
+            None,
+            Some(vehicle.clone()),
+        );
+        let leg = InternalLeg {
+            mode: route.transport_mode.clone(),
+            routing_mode: Some(route.transport_mode.clone()),
+            dep_time: Some(departure.departure_time),
+            trav_time: Some(travel_time),
+            route: Some(InternalRoute::Network(InternalNetworkRoute::new(
+                generic_route,
+                route.network_route.clone(),
+            ))),
+            attributes: Default::default(),
+        };
+
+        let mut plan = InternalPlan::default();
+        plan.add_act(InternalActivity::new(
+            None,
+            TRANSIT_DRIVER_ACT_TYPE,
+            start_link.clone(),
+            None,
+            Some(departure.departure_time),
+            None,
+        ));
+        plan.add_leg(leg);
+        plan.add_act(InternalActivity::new(
+            None,
+            TRANSIT_DRIVER_ACT_TYPE,
+            end_link.clone(),
+            None,
+            None,
+            None,
+        ));
+
+        let id = Id::create(&format!(
+            "pt_{}_{}",
+            vehicle.external(),
+            departure.id.external()
+        ));
+        let mut delegate = PlanBasedSimulationLogic::new(InternalPerson::new(id, plan));
+        delegate.advance_plan(departure.departure_time);
+
+        TransitDriverLogic {
+            delegate,
+            vehicle,
+            transit_line: line.id.clone(),
+            transit_route: route.id.clone(),
+            departure: departure.id.clone(),
+            stops,
+            next_stop: 0,
+        }
+    }
+
+    pub fn vehicle(&self) -> &Id<InternalVehicle> {
+        &self.vehicle
+    }
+
+    pub fn transit_line(&self) -> &Id<TransitLine> {
+        &self.transit_line
+    }
+
+    pub fn transit_route(&self) -> &Id<TransitRoute> {
+        &self.transit_route
+    }
+
+    pub fn departure(&self) -> &Id<TransitDeparture> {
+        &self.departure
+    }
+
+    /// The next stop the driver has to serve, if any.
+    pub fn next_stop(&self) -> Option<&TransitDriverStop> {
+        self.stops.get(self.next_stop)
+    }
+}
+
+impl Identifiable<InternalPerson> for TransitDriverLogic {
+    fn id(&self) -> &Id<InternalPerson> {
+        self.delegate.id()
+    }
+}
+
+impl EnvironmentalEventObserver for TransitDriverLogic {
+    fn notify_event(&mut self, event: &mut AgentEvent, now: SimTime) {
+        if let AgentEvent::DepartedFromStop() = event {
+            self.next_stop += 1;
+        }
+        self.delegate.notify_event(event, now);
+    }
+}
+
+impl EndTime for TransitDriverLogic {
+    fn end_time(&self, now: SimTime) -> SimTime {
+        self.delegate.end_time(now)
+    }
+}
+
+impl SimulationAgentLogic for TransitDriverLogic {
+    fn curr_act(&self) -> &InternalActivity {
+        self.delegate.curr_act()
+    }
+
+    fn next_act(&self) -> &InternalActivity {
+        self.delegate.next_act()
+    }
+
+    fn curr_leg(&self) -> &InternalLeg {
+        self.delegate.curr_leg()
+    }
+
+    fn next_leg(&self) -> Option<&InternalLeg> {
+        self.delegate.next_leg()
+    }
+
+    fn advance_plan(&mut self, now: SimTime) {
+        self.delegate.advance_plan(now);
+    }
+
+    fn state(&self) -> SimulationAgentState {
+        self.delegate.state()
+    }
+
+    fn is_wanting_to_arrive_on_current_link(&self) -> bool {
+        self.delegate.is_wanting_to_arrive_on_current_link()
+    }
+
+    fn is_wanting_to_stop_on_current_link(&self) -> bool {
+        self.next_stop()
+            .is_some_and(|stop| Some(&stop.link_id) == self.curr_link_id())
+    }
+
+    fn curr_link_id(&self) -> Option<&Id<Link>> {
+        self.delegate.curr_link_id()
+    }
+
+    fn peek_next_link_id(&self) -> Option<&Id<Link>> {
+        self.delegate.peek_next_link_id()
+    }
+
+    fn wakeup_time(&self, now: SimTime) -> SimTime {
+        self.delegate.wakeup_time(now)
+    }
+
+    fn transit_driver(&self) -> Option<&TransitDriverLogic> {
+        Some(self)
+    }
+
+    fn to_message(&self) -> messages::Agent {
+        messages::Agent {
+            logic: Some(Logic::TransitDriver(TransitDriver::from(self))),
+        }
+    }
+
+    fn into_person(self: Box<Self>) -> Option<InternalPerson> {
+        // Transit drivers are generated from the schedule and are not part of the population.
+        None
+    }
+}
+
+impl From<&TransitDriverLogic> for TransitDriver {
+    fn from(value: &TransitDriverLogic) -> Self {
+        TransitDriver {
+            delegate: Some(PlanBasedAgent::from(&value.delegate)),
+            vehicle: value.vehicle.external().to_string(),
+            transit_line: value.transit_line.external().to_string(),
+            transit_route: value.transit_route.external().to_string(),
+            departure: value.departure.external().to_string(),
+            stops: value
+                .stops
+                .iter()
+                .map(messages::TransitDriverStop::from)
+                .collect(),
+            next_stop: value.next_stop as u32,
+        }
+    }
+}
+
+impl From<TransitDriver> for TransitDriverLogic {
+    fn from(value: TransitDriver) -> Self {
+        TransitDriverLogic {
+            delegate: PlanBasedSimulationLogic::from(
+                value
+                    .delegate
+                    .expect("Transit driver message without delegate."),
+            ),
+            vehicle: Id::get_from_ext(&value.vehicle),
+            transit_line: Id::get_from_ext(&value.transit_line),
+            transit_route: Id::get_from_ext(&value.transit_route),
+            departure: Id::get_from_ext(&value.departure),
+            stops: value
+                .stops
+                .into_iter()
+                .map(TransitDriverStop::from)
+                .collect(),
+            next_stop: value.next_stop as usize,
+        }
+    }
+}
+
+impl From<&TransitDriverStop> for messages::TransitDriverStop {
+    fn from(value: &TransitDriverStop) -> Self {
+        messages::TransitDriverStop {
+            facility_id: value.facility_id.external().to_string(),
+            link_id: value.link_id.external().to_string(),
+            arrival_time_ns: value.arrival_time.as_nanos(),
+            departure_time_ns: value.departure_time.as_nanos(),
+            await_departure: value.await_departure,
+            allow_boarding: value.allow_boarding,
+            allow_alighting: value.allow_alighting,
+            minimum_stop_duration_ns: value.minimum_stop_duration.as_nanos() as u64,
+        }
+    }
+}
+
+impl From<messages::TransitDriverStop> for TransitDriverStop {
+    fn from(value: messages::TransitDriverStop) -> Self {
+        TransitDriverStop {
+            facility_id: Id::get_from_ext(&value.facility_id),
+            link_id: Id::get_from_ext(&value.link_id),
+            arrival_time: SimTime::from_nanos(value.arrival_time_ns),
+            departure_time: SimTime::from_nanos(value.departure_time_ns),
+            await_departure: value.await_departure,
+            allow_boarding: value.allow_boarding,
+            allow_alighting: value.allow_alighting,
+            minimum_stop_duration: Duration::from_nanos(value.minimum_stop_duration_ns),
+        }
+    }
+}
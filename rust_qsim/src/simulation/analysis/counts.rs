@@ -0,0 +1,236 @@
+use crate::simulation::analysis::link_volumes::LinkVolumes;
+use crate::simulation::analysis::{Column, write_table};
+use crate::simulation::config::AnalysisFormat;
+use crate::simulation::id::Id;
+use crate::simulation::io::xml::counts;
+use crate::simulation::io::xml::counts::IOCounts;
+use crate::simulation::scenario::network::Link;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const COUNTS_COMPARISON_FILE: &str = "counts_comparison";
+
+/// Observed hourly volumes of one link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Count {
+    pub link: Id<Link>,
+    pub station: Option<String>,
+    /// Observed volumes per hour, where hour 0 is the hour from 0:00 to 1:00.
+    pub volumes: BTreeMap<u32, f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Counts {
+    pub counts: Vec<Count>,
+}
+
+impl Counts {
+    pub fn from_file(path: &Path) -> Self {
+        Counts::from(counts::load_from_xml(path))
+    }
+
+    /// Writes one row per observed link volume with the simulated volume of the same link and
+    /// hour. Simulated volumes are scaled up by `1 / sample_size`, so that they are comparable
+    /// with the observed ones.
+    pub(crate) fn write_comparison(
+        &self,
+        volumes: &LinkVolumes,
+        sample_size: f64,
+        path: &Path,
+        format: AnalysisFormat,
+    ) {
+        let mut links = Vec::new();
+        let mut stations = Vec::new();
+        let mut hours = Vec::new();
+        let mut observed = Vec::new();
+        let mut simulated = Vec::new();
+        let mut relative_errors = Vec::new();
+        let mut gehs = Vec::new();
+        for count in &self.counts {
+            for (hour, observed_volume) in &count.volumes {
+                let simulated_volume =
+                    volumes.volume(&count.link, *hour as usize) as f64 / sample_size;
+                links.push(count.link.external().to_string());
+                stations.push(count.station.clone().unwrap_or_default());
+                hours.push(*hour as u64);
+                observed.push(*observed_volume);
+                simulated.push(simulated_volume);
+                relative_errors.push(relative_error(simulated_volume, *observed_volume));
+                gehs.push(geh(simulated_volume, *observed_volume));
+            }
+        }
+
+        write_table(
+            path,
+            format,
+            &[
+                ("link", Column::Text(links)),
+                ("station", Column::Text(stations)),
+                ("hour", Column::UInt(hours)),
+                ("observed", Column::Float(observed)),
+                ("simulated", Column::Float(simulated)),
+                ("relative_error", Column::Float(relative_errors)),
+                ("geh", Column::Float(gehs)),
+            ],
+        );
+    }
+}
+
+/// The deviation of the simulated from the observed volume, relative to the observed volume. It is
+/// infinite if only the observed volume is zero.
+fn relative_error(simulated: f64, observed: f64) -> f64 {
+    if simulated == observed {
+        0.0
+    } else {
+        (simulated - observed) / observed
+    }
+}
+
+/// The GEH statistic, which relates the deviation to the magnitude of the volumes. Values below 5
+/// are usually considered a good fit of hourly volumes.
+fn geh(simulated: f64, observed: f64) -> f64 {
+    if simulated + observed == 0.0 {
+        0.0
+    } else {
+        (2.0 * (simulated - observed).powi(2) / (simulated + observed)).sqrt()
+    }
+}
+
+impl From<IOCounts> for Counts {
+    fn from(io_counts: IOCounts) -> Self {
+        let counts = io_counts
+            .counts
+            .into_iter()
+            .map(|count| {
+                let volumes = count
+                    .volumes
+                    .iter()
+                    .map(|volume| {
+                        assert!(
+                            volume.h > 0,
+                            "Invalid hour {} of the count on link {}. Hours start at 1.",
+                            volume.h,
+                            count.loc_id
+                        );
+                        (volume.h - 1, volume.val)
+                    })
+                    .collect();
+                Count {
+                    link: Id::create(&count.loc_id),
+                    station: count.cs_id,
+                    volumes,
+                }
+            })
+            .collect();
+        Counts { counts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Count, Counts, geh, relative_error};
+    use crate::simulation::analysis::link_volumes::LinkVolumesCollector;
+    use crate::simulation::config::AnalysisFormat;
+    use crate::simulation::events::{EventsManager, LinkEnterEvent};
+    use crate::simulation::id::Id;
+    use crate::simulation::io::xml::counts::{IOCount, IOCounts, IOVolume};
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[deterministic_id_test]
+    fn converts_hours_to_start_at_zero() {
+        let counts = Counts::from(IOCounts {
+            name: None,
+            desc: None,
+            year: None,
+            layer: None,
+            counts: vec![IOCount {
+                loc_id: "1".to_string(),
+                cs_id: Some("station".to_string()),
+                volumes: vec![IOVolume { h: 1, val: 10.0 }, IOVolume { h: 24, val: 2.0 }],
+            }],
+        });
+
+        assert_eq!(
+            Counts {
+                counts: vec![Count {
+                    link: Id::get_from_ext("1"),
+                    station: Some("station".to_string()),
+                    volumes: BTreeMap::from([(0, 10.0), (23, 2.0)]),
+                }],
+            },
+            counts
+        );
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "Invalid hour 0 of the count on link 1. Hours start at 1.")]
+    fn hour_zero_panics() {
+        let _ = Counts::from(IOCounts {
+            name: None,
+            desc: None,
+            year: None,
+            layer: None,
+            counts: vec![IOCount {
+                loc_id: "1".to_string(),
+                cs_id: None,
+                volumes: vec![IOVolume { h: 0, val: 10.0 }],
+            }],
+        });
+    }
+
+    #[test]
+    fn error_measures() {
+        assert_eq!(0.5, relative_error(15.0, 10.0));
+        assert_eq!(-1.0, relative_error(0.0, 10.0));
+        assert_eq!(0.0, relative_error(0.0, 0.0));
+        assert_eq!(f64::INFINITY, relative_error(1.0, 0.0));
+        assert_eq!(0.0, geh(0.0, 0.0));
+        assert_eq!(4.0, geh(8.0, 0.0));
+        assert_eq!(0.0, geh(100.0, 100.0));
+    }
+
+    #[deterministic_id_test]
+    fn writes_comparison_with_scaled_simulated_volumes() {
+        let collector = LinkVolumesCollector::new(1);
+        let mut events = EventsManager::new();
+        (collector.register(0))(&mut events);
+        for time in [100, 200, 3700] {
+            events.process_event(&LinkEnterEvent {
+                time: SimTime::from_secs(time),
+                link: Id::create("1"),
+                vehicle: Id::create("vehicle"),
+                attributes: Default::default(),
+            });
+        }
+        let counts = Counts {
+            counts: vec![
+                Count {
+                    link: Id::create("1"),
+                    station: Some("loop".to_string()),
+                    volumes: BTreeMap::from([(0, 4.0), (1, 2.0)]),
+                },
+                Count {
+                    link: Id::create("2"),
+                    station: None,
+                    volumes: BTreeMap::from([(0, 0.0)]),
+                },
+            ],
+        };
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("counts_comparison.csv");
+        counts.write_comparison(&collector.merged(), 0.5, &path, AnalysisFormat::Csv);
+
+        assert_eq!(
+            "link,station,hour,observed,simulated,relative_error,geh\n\
+             1,loop,0,4,4,0,0\n\
+             1,loop,1,2,2,0,0\n\
+             2,,0,0,0,0,0\n",
+            fs::read_to_string(path).unwrap()
+        );
+    }
+}
@@ -0,0 +1,396 @@
+use crate::simulation::analysis::{Column, write_table};
+use crate::simulation::config::AnalysisFormat;
+use crate::simulation::framework_events::{ControllerEvent, ControllerListenerRegisterFn};
+use crate::simulation::scenario::population::{
+    InternalLeg, InternalPerson, InternalPlan, Population,
+};
+use crate::simulation::scenario::trip_structure_utils::{
+    get_trip_spans_default, identify_main_mode,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const SCORE_STATS_FILE: &str = "scorestats.csv";
+pub const MODE_STATS_FILE: &str = "modestats.csv";
+pub const TRAVEL_DISTANCE_STATS_FILE: &str = "traveldistancestats.csv";
+
+type StatsWriter = fn(&Path, &[IterationStats]);
+
+/// Summary of the plans of one iteration, taken right after scoring, i.e., before replanning
+/// selects other plans.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IterationStats {
+    iteration: u32,
+    scores: ScoreStats,
+    trips_per_mode: BTreeMap<String, usize>,
+    distances: TravelDistanceStats,
+}
+
+/// Scores averaged over all persons with at least one scored plan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScoreStats {
+    avg_executed: f64,
+    avg_worst: f64,
+    avg_average: f64,
+    avg_best: f64,
+}
+
+/// Average planned distances of the legs and trips of the executed plans, i.e. the distances of
+/// their routes. They differ from the travelled distances if vehicles got stuck or legs were
+/// aborted. The event-based distances are part of the trip and leg tables.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TravelDistanceStats {
+    avg_planned_leg_distance: f64,
+    avg_planned_trip_distance: f64,
+}
+
+impl IterationStats {
+    pub(crate) fn from_population(iteration: u32, population: &Population) -> Self {
+        let persons: Vec<_> = population.persons.values().collect();
+        let executed: Vec<_> = persons
+            .iter()
+            .filter_map(|person| person.selected_plan())
+            .collect();
+
+        IterationStats {
+            iteration,
+            scores: ScoreStats::new(&persons),
+            trips_per_mode: trips_per_mode(&executed),
+            distances: TravelDistanceStats::new(&executed),
+        }
+    }
+}
+
+impl ScoreStats {
+    fn new(persons: &[&InternalPerson]) -> Self {
+        let mut executed = Vec::new();
+        let mut worst = Vec::new();
+        let mut average = Vec::new();
+        let mut best = Vec::new();
+        for person in persons {
+            let scores: Vec<_> = person
+                .plans()
+                .iter()
+                .filter_map(|plan| plan.score)
+                .collect();
+            if scores.is_empty() {
+                continue;
+            }
+            worst.push(scores.iter().copied().fold(f64::INFINITY, f64::min));
+            best.push(scores.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+            average.push(mean(&scores));
+            if let Some(score) = person.selected_plan().and_then(|plan| plan.score) {
+                executed.push(score);
+            }
+        }
+
+        ScoreStats {
+            avg_executed: mean(&executed),
+            avg_worst: mean(&worst),
+            avg_average: mean(&average),
+            avg_best: mean(&best),
+        }
+    }
+}
+
+impl TravelDistanceStats {
+    /// Legs without a route distance are left out, and so are trips of which no leg has one.
+    fn new(plans: &[&InternalPlan]) -> Self {
+        let mut legs = Vec::new();
+        let mut trips = Vec::new();
+        for plan in plans {
+            legs.extend(plan.legs().into_iter().filter_map(planned_distance));
+            for span in get_trip_spans_default(&plan.elements) {
+                let distances: Vec<_> = span
+                    .legs(&plan.elements)
+                    .filter_map(planned_distance)
+                    .collect();
+                if !distances.is_empty() {
+                    trips.push(distances.iter().sum::<f64>());
+                }
+            }
+        }
+
+        TravelDistanceStats {
+            avg_planned_leg_distance: mean(&legs),
+            avg_planned_trip_distance: mean(&trips),
+        }
+    }
+}
+
+fn planned_distance(leg: &InternalLeg) -> Option<f64> {
+    leg.route.as_ref()?.as_generic().distance()
+}
+
+fn trips_per_mode(plans: &[&InternalPlan]) -> BTreeMap<String, usize> {
+    let mut trips_per_mode = BTreeMap::new();
+    for plan in plans {
+        for span in get_trip_spans_default(&plan.elements) {
+            if let Some(mode) = identify_main_mode(span.trip_elements(&plan.elements)) {
+                *trips_per_mode.entry(mode).or_insert(0) += 1;
+            }
+        }
+    }
+    trips_per_mode
+}
+
+/// Returns NaN for no values, so that iterations without data are recognisable in the output.
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Keeps the `IterationStats` of all iterations and writes them into `scorestats.csv`,
+/// `modestats.csv` and `traveldistancestats.csv` of the output directory whenever an iteration ends.
+/// The files are rewritten each time, as modes which show up in later iterations become additional
+/// columns of the mode stats.
+#[derive(Debug, Clone)]
+pub(crate) struct IterationStatsCollector {
+    output_path: PathBuf,
+    stats: Arc<Mutex<Vec<IterationStats>>>,
+}
+
+impl IterationStatsCollector {
+    pub(crate) fn new(output_path: PathBuf) -> Self {
+        IterationStatsCollector {
+            output_path,
+            stats: Arc::default(),
+        }
+    }
+
+    /// Records the stats of the population, which must contain the scored plans of `iteration`.
+    pub(crate) fn record(&self, iteration: u32, population: &Population) {
+        let stats = IterationStats::from_population(iteration, population);
+        self.stats.lock().unwrap().push(stats);
+    }
+
+    /// Registers one listener per stats file, which writes the file when an iteration ends.
+    pub(crate) fn register(&self) -> Box<ControllerListenerRegisterFn> {
+        let writers: [(&str, StatsWriter); 3] = [
+            (SCORE_STATS_FILE, write_score_stats),
+            (MODE_STATS_FILE, write_mode_stats),
+            (TRAVEL_DISTANCE_STATS_FILE, write_travel_distance_stats),
+        ];
+        let collector = self.clone();
+        Box::new(move |events| {
+            for (file_name, write) in writers {
+                let path = collector.output_path.join(file_name);
+                let stats = collector.stats.clone();
+                events.on_event(move |event| {
+                    if let ControllerEvent::IterationEnds(_) = event.payload {
+                        write(&path, &stats.lock().unwrap());
+                    }
+                });
+            }
+        })
+    }
+}
+
+fn write_score_stats(path: &Path, stats: &[IterationStats]) {
+    let column = |value: fn(&ScoreStats) -> f64| {
+        Column::Float(stats.iter().map(|stats| value(&stats.scores)).collect())
+    };
+    write_table(
+        path,
+        AnalysisFormat::Csv,
+        &[
+            ("iteration", iterations(stats)),
+            ("avg_executed", column(|scores| scores.avg_executed)),
+            ("avg_worst", column(|scores| scores.avg_worst)),
+            ("avg_average", column(|scores| scores.avg_average)),
+            ("avg_best", column(|scores| scores.avg_best)),
+        ],
+    );
+}
+
+/// Writes the share of trips per main mode. Every mode which occurred in any iteration gets a
+/// column.
+fn write_mode_stats(path: &Path, stats: &[IterationStats]) {
+    let modes: BTreeSet<_> = stats
+        .iter()
+        .flat_map(|stats| stats.trips_per_mode.keys())
+        .collect();
+    let shares = modes.iter().map(|mode| {
+        let shares = stats.iter().map(|stats| {
+            let trips: usize = stats.trips_per_mode.values().sum();
+            let mode_trips = stats.trips_per_mode.get(*mode).copied().unwrap_or(0);
+            mode_trips as f64 / trips as f64
+        });
+        (mode.as_str(), Column::Float(shares.collect()))
+    });
+    let columns: Vec<_> = std::iter::once(("iteration", iterations(stats)))
+        .chain(shares)
+        .collect();
+    write_table(path, AnalysisFormat::Csv, &columns);
+}
+
+fn write_travel_distance_stats(path: &Path, stats: &[IterationStats]) {
+    let column = |value: fn(&TravelDistanceStats) -> f64| {
+        Column::Float(stats.iter().map(|stats| value(&stats.distances)).collect())
+    };
+    write_table(
+        path,
+        AnalysisFormat::Csv,
+        &[
+            ("iteration", iterations(stats)),
+            (
+                "avg_planned_leg_distance",
+                column(|d| d.avg_planned_leg_distance),
+            ),
+            (
+                "avg_planned_trip_distance",
+                column(|d| d.avg_planned_trip_distance),
+            ),
+        ],
+    );
+}
+
+fn iterations(stats: &[IterationStats]) -> Column {
+    Column::UInt(stats.iter().map(|stats| stats.iteration as u64).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        IterationStats, IterationStatsCollector, MODE_STATS_FILE, SCORE_STATS_FILE,
+        TRAVEL_DISTANCE_STATS_FILE,
+    };
+    use crate::simulation::InternalAttributes;
+    use crate::simulation::framework_events::{ControllerEvent, ControllerEventsManager};
+    use crate::simulation::id::Id;
+    use crate::simulation::scenario::population::{
+        InternalActivity, InternalGenericRoute, InternalLeg, InternalPerson, InternalPlan,
+        InternalPlanElement, InternalRoute, Population,
+    };
+    use macros::deterministic_id_test;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[deterministic_id_test]
+    fn from_population_averages_scores_of_persons_with_scored_plans() {
+        let mut first = InternalPerson::new(Id::create("first"), plan(&[], Some(10.0), false));
+        first.plans_mut().push(plan(&[], Some(4.0), true));
+        first.plans_mut().push(plan(&[], None, false));
+        let second = InternalPerson::new(Id::create("second"), plan(&[], Some(2.0), true));
+        let unscored = InternalPerson::new(Id::create("unscored"), plan(&[], None, true));
+        let population = Population::from_persons(vec![first, second, unscored]);
+
+        let stats = IterationStats::from_population(3, &population);
+
+        assert_eq!(3, stats.iteration);
+        assert_eq!(3.0, stats.scores.avg_executed);
+        assert_eq!(3.0, stats.scores.avg_worst);
+        assert_eq!(4.5, stats.scores.avg_average);
+        assert_eq!(6.0, stats.scores.avg_best);
+    }
+
+    #[deterministic_id_test]
+    fn from_population_counts_trips_and_distances_of_executed_plans() {
+        let mut person = InternalPerson::new(
+            Id::create("person"),
+            plan(&[("car", 1000.0), ("walk", 200.0)], Some(0.0), true),
+        );
+        // not executed, so it is ignored
+        person
+            .plans_mut()
+            .push(plan(&[("bike", 5000.0)], Some(0.0), false));
+        let other = InternalPerson::new(
+            Id::create("other"),
+            plan(&[("car", 3000.0)], Some(0.0), true),
+        );
+        let population = Population::from_persons(vec![person, other]);
+
+        let stats = IterationStats::from_population(0, &population);
+
+        assert_eq!(
+            vec![("car".to_string(), 2), ("walk".to_string(), 1)],
+            stats.trips_per_mode.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(1400.0, stats.distances.avg_planned_leg_distance);
+        assert_eq!(1400.0, stats.distances.avg_planned_trip_distance);
+    }
+
+    #[deterministic_id_test]
+    fn from_population_leaves_out_legs_without_route_distance() {
+        let mut executed = plan(&[("car", 1000.0), ("walk", 0.0)], Some(0.0), true);
+        // the walk trip has no route, e.g., because it could not be routed
+        executed.legs_mut()[1].route = None;
+        let person = InternalPerson::new(Id::create("person"), executed);
+        let population = Population::from_persons(vec![person]);
+
+        let stats = IterationStats::from_population(0, &population);
+
+        assert_eq!(1000.0, stats.distances.avg_planned_leg_distance);
+        assert_eq!(1000.0, stats.distances.avg_planned_trip_distance);
+    }
+
+    #[deterministic_id_test]
+    fn listeners_write_stats_of_all_iterations_at_iteration_end() {
+        let dir = tempdir().unwrap();
+        let collector = IterationStatsCollector::new(dir.path().to_path_buf());
+        let mut events = ControllerEventsManager::default();
+        (collector.register())(&mut events);
+
+        let walk = InternalPerson::new(
+            Id::create("walk"),
+            plan(&[("walk", 100.0)], Some(1.0), true),
+        );
+        collector.record(0, &Population::from_persons(vec![walk]));
+        events.process_event(ControllerEvent::iteration_ends(false));
+        let car = InternalPerson::new(Id::create("car"), plan(&[("car", 300.0)], Some(3.0), true));
+        collector.record(1, &Population::from_persons(vec![car]));
+        // files are only written when an iteration ends
+        events.process_event(ControllerEvent::scoring(true));
+        assert_eq!(
+            "iteration,walk\n0,1\n",
+            fs::read_to_string(dir.path().join(MODE_STATS_FILE)).unwrap()
+        );
+        events.process_event(ControllerEvent::iteration_ends(true));
+
+        assert_eq!(
+            "iteration,avg_executed,avg_worst,avg_average,avg_best\n0,1,1,1,1\n1,3,3,3,3\n",
+            fs::read_to_string(dir.path().join(SCORE_STATS_FILE)).unwrap()
+        );
+        assert_eq!(
+            "iteration,car,walk\n0,0,1\n1,1,0\n",
+            fs::read_to_string(dir.path().join(MODE_STATS_FILE)).unwrap()
+        );
+        assert_eq!(
+            "iteration,avg_planned_leg_distance,avg_planned_trip_distance\n0,100,100\n1,300,300\n",
+            fs::read_to_string(dir.path().join(TRAVEL_DISTANCE_STATS_FILE)).unwrap()
+        );
+    }
+
+    /// Creates a plan with one trip per given mode and distance. Each trip consists of a single leg
+    /// with a generic route.
+    fn plan(trips: &[(&str, f64)], score: Option<f64>, selected: bool) -> InternalPlan {
+        let mut plan = InternalPlan {
+            score,
+            selected,
+            elements: Vec::new(),
+        };
+        plan.add_act(activity("home"));
+        for (mode, distance) in trips {
+            plan.elements.push(InternalPlanElement::Leg(InternalLeg {
+                mode: Id::create(mode),
+                routing_mode: Some(Id::create(mode)),
+                dep_time: None,
+                trav_time: None,
+                route: Some(InternalRoute::Generic(InternalGenericRoute::new(
+                    Id::create("1"),
+                    Id::create("1"),
+                    None,
+                    Some(*distance),
+                    None,
+                ))),
+                attributes: InternalAttributes::default(),
+            }));
+            plan.add_act(activity("work"));
+        }
+        plan
+    }
+
+    fn activity(act_type: &str) -> InternalActivity {
+        InternalActivity::new(None, act_type, Id::create("1"), None, None, None)
+    }
+}
@@ -0,0 +1,231 @@
+use crate::simulation::analysis::{Column, write_table};
+use crate::simulation::config::AnalysisFormat;
+use crate::simulation::events::{EventHandlerRegisterFn, PersonArrivalEvent, PersonDepartureEvent};
+use crate::simulation::id::Id;
+use crate::simulation::time::SimTime;
+use nohash_hasher::IntMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+pub const LEG_HISTOGRAM_FILE: &str = "leg_histogram";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct LegCounts {
+    departures: u64,
+    arrivals: u64,
+}
+
+/// Departures and arrivals per leg mode and time bin. A bin covers the time from its start time up
+/// to the start time of the next bin.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LegHistogram {
+    bin_size: u32,
+    counts_per_mode: IntMap<Id<String>, Vec<LegCounts>>,
+}
+
+impl LegHistogram {
+    pub(crate) fn new(bin_size: u32) -> Self {
+        assert!(bin_size > 0, "The leg histogram bin size must be positive.");
+        LegHistogram {
+            bin_size,
+            counts_per_mode: IntMap::default(),
+        }
+    }
+
+    fn add_departure(&mut self, mode: &Id<String>, time: SimTime) {
+        self.counts_mut(mode, time).departures += 1;
+    }
+
+    fn add_arrival(&mut self, mode: &Id<String>, time: SimTime) {
+        self.counts_mut(mode, time).arrivals += 1;
+    }
+
+    fn counts_mut(&mut self, mode: &Id<String>, time: SimTime) -> &mut LegCounts {
+        let bin = (time.as_secs() / self.bin_size as u64) as usize;
+        let bins = self.counts_per_mode.entry(mode.clone()).or_default();
+        if bins.len() <= bin {
+            bins.resize(bin + 1, LegCounts::default());
+        }
+        &mut bins[bin]
+    }
+
+    /// Adds the counts of `other`, e.g., of another partition.
+    pub(crate) fn merge(&mut self, other: &LegHistogram) {
+        for (mode, other_bins) in &other.counts_per_mode {
+            let bins = self.counts_per_mode.entry(mode.clone()).or_default();
+            if bins.len() < other_bins.len() {
+                bins.resize(other_bins.len(), LegCounts::default());
+            }
+            for (bin, counts) in bins.iter_mut().zip(other_bins) {
+                bin.departures += counts.departures;
+                bin.arrivals += counts.arrivals;
+            }
+        }
+    }
+
+    /// Writes one row per mode and time bin with the departures and arrivals within the bin and
+    /// the number of legs which are en route at the end of the bin. All modes have rows for the
+    /// same bins, ordered by mode and time.
+    pub(crate) fn write(&self, path: &Path, format: AnalysisFormat) {
+        let mut modes: Vec<_> = self.counts_per_mode.keys().collect();
+        modes.sort_by_key(|mode| mode.external());
+        let num_bins = self
+            .counts_per_mode
+            .values()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0);
+
+        let mut times = Vec::new();
+        let mut mode_column = Vec::new();
+        let mut departures = Vec::new();
+        let mut arrivals = Vec::new();
+        let mut en_route = Vec::new();
+        for mode in modes {
+            let bins = &self.counts_per_mode[mode];
+            let mut legs_en_route: u64 = 0;
+            for bin in 0..num_bins {
+                let counts = bins.get(bin).copied().unwrap_or_default();
+                legs_en_route = (legs_en_route + counts.departures).saturating_sub(counts.arrivals);
+                times.push(bin as u64 * self.bin_size as u64);
+                mode_column.push(mode.external().to_string());
+                departures.push(counts.departures);
+                arrivals.push(counts.arrivals);
+                en_route.push(legs_en_route);
+            }
+        }
+
+        write_table(
+            path,
+            format,
+            &[
+                ("time", Column::UInt(times)),
+                ("mode", Column::Text(mode_column)),
+                ("departures", Column::UInt(departures)),
+                ("arrivals", Column::UInt(arrivals)),
+                ("en_route", Column::UInt(en_route)),
+            ],
+        );
+    }
+}
+
+/// Collects leg histograms on every partition. A leg might depart on one partition and arrive on
+/// another one, so that only the merged histogram of all partitions is complete.
+#[derive(Debug)]
+pub(crate) struct LegHistogramCollector {
+    bin_size: u32,
+    histograms: Vec<Arc<Mutex<LegHistogram>>>,
+}
+
+impl LegHistogramCollector {
+    pub(crate) fn new(num_parts: u32, bin_size: u32) -> Self {
+        LegHistogramCollector {
+            bin_size,
+            histograms: (0..num_parts)
+                .map(|_| Arc::new(Mutex::new(LegHistogram::new(bin_size))))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn register(&self, rank: u32) -> Box<EventHandlerRegisterFn> {
+        let histogram = self.histograms[rank as usize].clone();
+        let bin_size = self.bin_size;
+        Box::new(move |events| {
+            let h = histogram.clone();
+            events.on_reset_iteration(move |_| {
+                *h.lock().unwrap() = LegHistogram::new(bin_size);
+            });
+            let h = histogram.clone();
+            events.on::<PersonDepartureEvent, _>(move |e| {
+                h.lock().unwrap().add_departure(&e.leg_mode, e.time);
+            });
+            let h = histogram;
+            events.on::<PersonArrivalEvent, _>(move |e| {
+                h.lock().unwrap().add_arrival(&e.leg_mode, e.time);
+            });
+        })
+    }
+
+    /// Merges the histograms of all partitions of the current iteration.
+    pub(crate) fn merged(&self) -> LegHistogram {
+        let mut merged = LegHistogram::new(self.bin_size);
+        for histogram in &self.histograms {
+            merged.merge(&histogram.lock().unwrap());
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LegHistogramCollector;
+    use crate::simulation::config::AnalysisFormat;
+    use crate::simulation::events::{EventsManager, PersonArrivalEvent, PersonDepartureEvent};
+    use crate::simulation::id::Id;
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[deterministic_id_test]
+    fn merges_legs_departing_and_arriving_on_different_partitions() {
+        let collector = LegHistogramCollector::new(2, 600);
+        let mut first = EventsManager::new();
+        (collector.register(0))(&mut first);
+        let mut second = EventsManager::new();
+        (collector.register(1))(&mut second);
+
+        first.process_event(&departure("car", 100));
+        first.process_event(&departure("walk", 200));
+        second.process_event(&arrival("car", 1300));
+        first.process_event(&arrival("walk", 500));
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("leg_histogram.csv");
+        collector.merged().write(&path, AnalysisFormat::Csv);
+
+        assert_eq!(
+            "time,mode,departures,arrivals,en_route\n\
+             0,car,1,0,1\n\
+             600,car,0,0,1\n\
+             1200,car,0,1,0\n\
+             0,walk,1,1,0\n\
+             600,walk,0,0,0\n\
+             1200,walk,0,0,0\n",
+            fs::read_to_string(path).unwrap()
+        );
+    }
+
+    #[deterministic_id_test]
+    fn reset_iteration_clears_histogram() {
+        let collector = LegHistogramCollector::new(1, 600);
+        let mut events = EventsManager::new();
+        (collector.register(0))(&mut events);
+
+        events.process_event(&departure("car", 100));
+        events.reset_iteration(1);
+
+        assert_eq!(collector.merged(), super::LegHistogram::new(600));
+    }
+
+    fn departure(mode: &str, time: u64) -> PersonDepartureEvent {
+        PersonDepartureEvent {
+            time: SimTime::from_secs(time),
+            person: Id::create("person"),
+            link: Id::create("link"),
+            leg_mode: Id::create(mode),
+            routing_mode: Id::create(mode),
+            attributes: Default::default(),
+        }
+    }
+
+    fn arrival(mode: &str, time: u64) -> PersonArrivalEvent {
+        PersonArrivalEvent {
+            time: SimTime::from_secs(time),
+            person: Id::create("person"),
+            link: Id::create("link"),
+            leg_mode: Id::create(mode),
+            attributes: Default::default(),
+        }
+    }
+}
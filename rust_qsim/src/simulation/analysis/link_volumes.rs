@@ -0,0 +1,166 @@
+use crate::simulation::analysis::{Column, write_table};
+use crate::simulation::config::AnalysisFormat;
+use crate::simulation::events::{EventHandlerRegisterFn, LinkEnterEvent};
+use crate::simulation::id::Id;
+use crate::simulation::scenario::network::Link;
+use crate::simulation::time::SimTime;
+use nohash_hasher::IntMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+pub const LINK_VOLUMES_FILE: &str = "link_volumes";
+
+const SECONDS_PER_HOUR: u64 = 3600;
+
+/// Number of vehicles entering each link per hour. Only link enter events are counted, i.e.,
+/// vehicles are not counted on the link on which they enter traffic.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct LinkVolumes {
+    volumes_per_link: IntMap<Id<Link>, Vec<u64>>,
+}
+
+impl LinkVolumes {
+    fn add(&mut self, link: &Id<Link>, time: SimTime) {
+        let hour = (time.as_secs() / SECONDS_PER_HOUR) as usize;
+        let volumes = self.volumes_per_link.entry(link.clone()).or_default();
+        if volumes.len() <= hour {
+            volumes.resize(hour + 1, 0);
+        }
+        volumes[hour] += 1;
+    }
+
+    /// Number of vehicles which entered the link within the hour.
+    pub(crate) fn volume(&self, link: &Id<Link>, hour: usize) -> u64 {
+        self.volumes_per_link
+            .get(link)
+            .and_then(|volumes| volumes.get(hour))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Adds the volumes of `other`, e.g., of another partition.
+    pub(crate) fn merge(&mut self, other: &LinkVolumes) {
+        for (link, other_volumes) in &other.volumes_per_link {
+            let volumes = self.volumes_per_link.entry(link.clone()).or_default();
+            if volumes.len() < other_volumes.len() {
+                volumes.resize(other_volumes.len(), 0);
+            }
+            for (volume, other_volume) in volumes.iter_mut().zip(other_volumes) {
+                *volume += other_volume;
+            }
+        }
+    }
+
+    /// Writes one row per link and hour, ordered by link and hour. Hours without vehicles are left
+    /// out.
+    pub(crate) fn write(&self, path: &Path, format: AnalysisFormat) {
+        let mut links: Vec<_> = self.volumes_per_link.keys().collect();
+        links.sort_by_key(|link| link.external());
+
+        let mut link_column = Vec::new();
+        let mut hours = Vec::new();
+        let mut volumes = Vec::new();
+        for link in links {
+            for (hour, volume) in self.volumes_per_link[link].iter().enumerate() {
+                if *volume > 0 {
+                    link_column.push(link.external().to_string());
+                    hours.push(hour as u64);
+                    volumes.push(*volume);
+                }
+            }
+        }
+
+        write_table(
+            path,
+            format,
+            &[
+                ("link", Column::Text(link_column)),
+                ("hour", Column::UInt(hours)),
+                ("volume", Column::UInt(volumes)),
+            ],
+        );
+    }
+}
+
+/// Collects the link volumes on every partition. Each link belongs to one partition, but the
+/// volumes are merged, so that they can be written into one file.
+#[derive(Debug)]
+pub(crate) struct LinkVolumesCollector {
+    volumes: Vec<Arc<Mutex<LinkVolumes>>>,
+}
+
+impl LinkVolumesCollector {
+    pub(crate) fn new(num_parts: u32) -> Self {
+        LinkVolumesCollector {
+            volumes: (0..num_parts).map(|_| Arc::default()).collect(),
+        }
+    }
+
+    pub(crate) fn register(&self, rank: u32) -> Box<EventHandlerRegisterFn> {
+        let volumes = self.volumes[rank as usize].clone();
+        Box::new(move |events| {
+            let v = volumes.clone();
+            events.on_reset_iteration(move |_| {
+                *v.lock().unwrap() = LinkVolumes::default();
+            });
+            let v = volumes;
+            events.on::<LinkEnterEvent, _>(move |e| {
+                v.lock().unwrap().add(&e.link, e.time);
+            });
+        })
+    }
+
+    /// Merges the volumes of all partitions of the current iteration.
+    pub(crate) fn merged(&self) -> LinkVolumes {
+        let mut merged = LinkVolumes::default();
+        for volumes in &self.volumes {
+            merged.merge(&volumes.lock().unwrap());
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinkVolumesCollector;
+    use crate::simulation::config::AnalysisFormat;
+    use crate::simulation::events::{EventsManager, LinkEnterEvent};
+    use crate::simulation::id::Id;
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[deterministic_id_test]
+    fn merges_hourly_volumes_of_all_partitions() {
+        let collector = LinkVolumesCollector::new(2);
+        let mut first = EventsManager::new();
+        (collector.register(0))(&mut first);
+        let mut second = EventsManager::new();
+        (collector.register(1))(&mut second);
+
+        first.process_event(&link_enter("2", 100));
+        first.process_event(&link_enter("1", 3500));
+        first.process_event(&link_enter("1", 3600));
+        second.process_event(&link_enter("1", 7300));
+        second.process_event(&link_enter("1", 7400));
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("link_volumes.csv");
+        collector.merged().write(&path, AnalysisFormat::Csv);
+
+        assert_eq!(
+            "link,hour,volume\n1,0,1\n1,1,1\n1,2,2\n2,0,1\n",
+            fs::read_to_string(path).unwrap()
+        );
+    }
+
+    fn link_enter(link: &str, time: u64) -> LinkEnterEvent {
+        LinkEnterEvent {
+            time: SimTime::from_secs(time),
+            link: Id::create(link),
+            vehicle: Id::create("vehicle"),
+            attributes: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+pub mod counts;
+pub mod iteration_stats;
+pub mod leg_histogram;
+pub mod link_volumes;
+pub mod trips;
+
+use crate::simulation::config::AnalysisFormat;
+use arrow2::array::{Array, Float64Array, UInt64Array, Utf8Array};
+use arrow2::chunk::Chunk;
+use arrow2::datatypes::{DataType, Field, Schema};
+use arrow2::io::parquet::write::{
+    CompressionOptions, Encoding, FileWriter, RowGroupIterator, Version, WriteOptions,
+};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// A column of an analysis table.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Column {
+    UInt(Vec<u64>),
+    Float(Vec<f64>),
+    Text(Vec<String>),
+}
+
+impl Column {
+    fn len(&self) -> usize {
+        match self {
+            Column::UInt(values) => values.len(),
+            Column::Float(values) => values.len(),
+            Column::Text(values) => values.len(),
+        }
+    }
+
+    fn value(&self, row: usize) -> String {
+        match self {
+            Column::UInt(values) => values[row].to_string(),
+            Column::Float(values) => values[row].to_string(),
+            Column::Text(values) => values[row].clone(),
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Column::UInt(_) => DataType::UInt64,
+            Column::Float(_) => DataType::Float64,
+            Column::Text(_) => DataType::Utf8,
+        }
+    }
+
+    fn to_array(&self) -> Box<dyn Array> {
+        match self {
+            Column::UInt(values) => Box::new(UInt64Array::from_slice(values)),
+            Column::Float(values) => Box::new(Float64Array::from_slice(values)),
+            Column::Text(values) => Box::new(Utf8Array::<i32>::from_slice(values)),
+        }
+    }
+}
+
+/// Writes the named columns into a CSV or Parquet file. All columns must have the same length.
+pub(crate) fn write_table(path: &Path, format: AnalysisFormat, columns: &[(&str, Column)]) {
+    let rows = columns.first().map_or(0, |(_, column)| column.len());
+    assert!(
+        columns.iter().all(|(_, column)| column.len() == rows),
+        "All columns of {} must have the same length.",
+        path.display()
+    );
+    match format {
+        AnalysisFormat::Csv => write_csv(path, columns, rows),
+        AnalysisFormat::Parquet => write_parquet(path, columns)
+            .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display())),
+    }
+}
+
+fn write_csv(path: &Path, columns: &[(&str, Column)], rows: usize) {
+    let mut writer = csv::Writer::from_path(path)
+        .unwrap_or_else(|e| panic!("Failed to create {}: {e}", path.display()));
+    writer
+        .write_record(columns.iter().map(|(name, _)| name))
+        .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()));
+    for row in 0..rows {
+        writer
+            .write_record(columns.iter().map(|(_, column)| column.value(row)))
+            .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()));
+    }
+    writer
+        .flush()
+        .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()));
+}
+
+fn write_parquet(path: &Path, columns: &[(&str, Column)]) -> Result<(), arrow2::error::Error> {
+    let schema = Schema::from(
+        columns
+            .iter()
+            .map(|(name, column)| Field::new(*name, column.data_type(), false))
+            .collect::<Vec<_>>(),
+    );
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        compression: CompressionOptions::Snappy,
+        data_pagesize_limit: None,
+    };
+    let chunk = Chunk::new(
+        columns
+            .iter()
+            .map(|(_, column)| column.to_array())
+            .collect(),
+    );
+    let row_groups = RowGroupIterator::try_new(
+        std::iter::once(Ok(chunk)),
+        &schema,
+        options,
+        vec![vec![Encoding::Plain]; columns.len()],
+    )?;
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut writer = FileWriter::try_new(file, schema, options)?;
+    for row_group in row_groups {
+        writer.write(row_group?)?;
+    }
+    writer.end(None)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Column, write_table};
+    use crate::simulation::config::AnalysisFormat;
+    use arrow2::array::{UInt64Array, Utf8Array};
+    use arrow2::io::parquet::read;
+    use std::fs;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    fn columns() -> Vec<(&'static str, Column)> {
+        vec![
+            ("link", Column::Text(vec!["1".to_string(), "2".to_string()])),
+            ("volume", Column::UInt(vec![3, 4])),
+            ("share", Column::Float(vec![0.25, 0.75])),
+        ]
+    }
+
+    #[test]
+    fn writes_csv_table() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("table.csv");
+
+        write_table(&path, AnalysisFormat::Csv, &columns());
+
+        assert_eq!(
+            "link,volume,share\n1,3,0.25\n2,4,0.75\n",
+            fs::read_to_string(path).unwrap()
+        );
+    }
+
+    #[test]
+    fn writes_parquet_table() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("table.parquet");
+
+        write_table(&path, AnalysisFormat::Parquet, &columns());
+
+        let mut file = File::open(path).unwrap();
+        let metadata = read::read_metadata(&mut file).unwrap();
+        let schema = read::infer_schema(&metadata).unwrap();
+        let names: Vec<_> = schema.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(vec!["link", "volume", "share"], names);
+        let chunks: Vec<_> =
+            read::FileReader::new(file, metadata.row_groups, schema, None, None, None)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(1, chunks.len());
+        let arrays = chunks[0].arrays();
+        assert_eq!(
+            &Utf8Array::<i32>::from_slice(["1", "2"]),
+            arrays[0].as_any().downcast_ref::<Utf8Array<i32>>().unwrap()
+        );
+        assert_eq!(
+            &UInt64Array::from_slice([3, 4]),
+            arrays[1].as_any().downcast_ref::<UInt64Array>().unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must have the same length")]
+    fn columns_of_different_length_panic() {
+        let dir = tempdir().unwrap();
+        let columns = [
+            ("link", Column::Text(vec!["1".to_string()])),
+            ("volume", Column::UInt(vec![3, 4])),
+        ];
+
+        write_table(&dir.path().join("table.csv"), AnalysisFormat::Csv, &columns);
+    }
+}
@@ -0,0 +1,745 @@
+use crate::simulation::analysis::{Column, write_table};
+use crate::simulation::config::AnalysisFormat;
+use crate::simulation::controller::controller::is_write_iteration;
+use crate::simulation::events::{
+    ActivityEndEvent, ActivityStartEvent, EventHandlerRegisterFn, LinkEnterEvent,
+    PersonArrivalEvent, PersonDepartureEvent, PersonEntersVehicleEvent, PersonLeavesVehicleEvent,
+    PtTeleportationArrivalEvent, TeleportationArrivalEvent,
+};
+use crate::simulation::id::Id;
+use crate::simulation::scenario::Coordinate;
+use crate::simulation::scenario::network::{Link, Network};
+use crate::simulation::scenario::population::InternalPerson;
+use crate::simulation::scenario::trip_structure_utils::is_stage_activity_type;
+use crate::simulation::scenario::vehicles::InternalVehicle;
+use crate::simulation::time::SimTime;
+use nohash_hasher::IntMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+pub const TRIPS_FILE: &str = "trips";
+pub const LEGS_FILE: &str = "legs";
+
+#[derive(Debug, Clone, PartialEq)]
+struct TripRecord {
+    person: Id<InternalPerson>,
+    time: SimTime,
+    kind: TripRecordKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TripRecordKind {
+    ActivityStart {
+        act_type: Id<String>,
+        coordinate: Coordinate,
+    },
+    ActivityEnd {
+        act_type: Id<String>,
+        coordinate: Coordinate,
+    },
+    Departure {
+        link: Id<Link>,
+        leg_mode: Id<String>,
+        routing_mode: Id<String>,
+    },
+    Arrival {
+        link: Id<Link>,
+    },
+    Travelled(f64),
+    EntersVehicle,
+    LeavesVehicle(Id<InternalVehicle>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LinkEnterRecord {
+    vehicle: Id<InternalVehicle>,
+    time: SimTime,
+    link: Id<Link>,
+}
+
+/// All trip relevant records of one partition, in the order of the events. Records are only kept
+/// in iterations in which trips and legs are written.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct TripRecords {
+    recording: bool,
+    persons: Vec<TripRecord>,
+    link_enters: Vec<LinkEnterRecord>,
+}
+
+impl TripRecords {
+    fn push_person(&mut self, person: &Id<InternalPerson>, time: SimTime, kind: TripRecordKind) {
+        if self.recording {
+            self.persons.push(TripRecord {
+                person: person.clone(),
+                time,
+                kind,
+            });
+        }
+    }
+
+    fn push_link_enter(&mut self, e: &LinkEnterEvent) {
+        if self.recording {
+            self.link_enters.push(LinkEnterRecord {
+                vehicle: e.vehicle.clone(),
+                time: e.time,
+                link: e.link.clone(),
+            });
+        }
+    }
+}
+
+/// Collects the events which make up trips and legs on every partition. Like for scoring, the
+/// events of one person might be spread over several partitions, so that trips can only be
+/// assembled from the records of all partitions.
+#[derive(Debug)]
+pub(crate) struct TripsCollector {
+    buffers: Vec<Arc<Mutex<TripRecords>>>,
+    write_interval: u32,
+    last_iteration: u32,
+}
+
+impl TripsCollector {
+    /// Records the events of the iterations in which the analysis is written, see
+    /// [`is_write_iteration`].
+    pub(crate) fn new(num_parts: u32, write_interval: u32, last_iteration: u32) -> Self {
+        TripsCollector {
+            buffers: (0..num_parts).map(|_| Arc::default()).collect(),
+            write_interval,
+            last_iteration,
+        }
+    }
+
+    pub(crate) fn register(&self, rank: u32) -> Box<EventHandlerRegisterFn> {
+        let buffer = self.buffers[rank as usize].clone();
+        let write_interval = self.write_interval;
+        let last_iteration = self.last_iteration;
+        Box::new(move |events| {
+            let b = buffer.clone();
+            events.on_reset_iteration(move |iteration| {
+                *b.lock().unwrap() = TripRecords {
+                    recording: is_write_iteration(
+                        write_interval,
+                        iteration,
+                        iteration == last_iteration,
+                    ),
+                    ..TripRecords::default()
+                };
+            });
+
+            let b = buffer.clone();
+            events.on::<ActivityStartEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    TripRecordKind::ActivityStart {
+                        act_type: e.act_type.clone(),
+                        coordinate: e.coordinate.clone(),
+                    },
+                );
+            });
+            let b = buffer.clone();
+            events.on::<ActivityEndEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    TripRecordKind::ActivityEnd {
+                        act_type: e.act_type.clone(),
+                        coordinate: e.coordinate.clone(),
+                    },
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PersonDepartureEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    TripRecordKind::Departure {
+                        link: e.link.clone(),
+                        leg_mode: e.leg_mode.clone(),
+                        routing_mode: e.routing_mode.clone(),
+                    },
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PersonArrivalEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    TripRecordKind::Arrival {
+                        link: e.link.clone(),
+                    },
+                );
+            });
+            let b = buffer.clone();
+            events.on::<TeleportationArrivalEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    TripRecordKind::Travelled(e.distance),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PtTeleportationArrivalEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    TripRecordKind::Travelled(e.distance),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PersonEntersVehicleEvent, _>(move |e| {
+                b.lock()
+                    .unwrap()
+                    .push_person(&e.person, e.time, TripRecordKind::EntersVehicle);
+            });
+            let b = buffer.clone();
+            events.on::<PersonLeavesVehicleEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    TripRecordKind::LeavesVehicle(e.vehicle.clone()),
+                );
+            });
+            events.on::<LinkEnterEvent, _>(move |e| {
+                buffer.lock().unwrap().push_link_enter(e);
+            });
+        })
+    }
+
+    /// Assembles the trips and legs of the current iteration from the records of all partitions.
+    pub(crate) fn tables(&self, network: &Network) -> TripTables {
+        let mut records_per_person: IntMap<Id<InternalPerson>, Vec<TripRecord>> = IntMap::default();
+        let mut links_per_vehicle: IntMap<Id<InternalVehicle>, Vec<(SimTime, Id<Link>)>> =
+            IntMap::default();
+        for buffer in &self.buffers {
+            let records = buffer.lock().unwrap();
+            for record in &records.persons {
+                records_per_person
+                    .entry(record.person.clone())
+                    .or_default()
+                    .push(record.clone());
+            }
+            for record in &records.link_enters {
+                links_per_vehicle
+                    .entry(record.vehicle.clone())
+                    .or_default()
+                    .push((record.time, record.link.clone()));
+            }
+        }
+
+        // as for scoring, the events of one person at the same time are always emitted on the
+        // same partition, so a stable sort keeps their order.
+        for records in records_per_person.values_mut() {
+            records.sort_by_key(|r| r.time);
+        }
+        for links in links_per_vehicle.values_mut() {
+            links.sort_by_key(|(time, _)| *time);
+        }
+
+        let mut persons: Vec<_> = records_per_person.keys().collect();
+        persons.sort_by_key(|person| person.external());
+        let mut tables = TripTables::default();
+        for person in persons {
+            tables.add_person(
+                person,
+                &records_per_person[person],
+                &links_per_vehicle,
+                network,
+            );
+        }
+        tables
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TripRow {
+    person: Id<InternalPerson>,
+    trip_number: u64,
+    main_mode: Option<Id<String>>,
+    dep_time: SimTime,
+    trav_time: f64,
+    wait_time: f64,
+    routed_distance: f64,
+    euclidean_distance: f64,
+    start_activity_type: Id<String>,
+    end_activity_type: Id<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LegRow {
+    person: Id<InternalPerson>,
+    trip_number: u64,
+    mode: Id<String>,
+    dep_time: SimTime,
+    trav_time: f64,
+    wait_time: f64,
+    distance: f64,
+    start_link: Id<Link>,
+    end_link: Id<Link>,
+}
+
+/// Trips and their legs as they were executed in the mobsim. A trip starts when a person ends an
+/// activity which is not a stage activity and ends when the person starts the next such activity.
+/// Trips which are not finished at the end of the mobsim, e.g., of stuck agents, are left out.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct TripTables {
+    trips: Vec<TripRow>,
+    legs: Vec<LegRow>,
+}
+
+/// A trip which has started, but has not reached its destination yet.
+struct OpenTrip {
+    start_activity_type: Id<String>,
+    origin: Coordinate,
+    dep_time: SimTime,
+    main_mode: Option<Id<String>>,
+    legs: Vec<LegRow>,
+}
+
+/// A leg which has departed, but has not arrived yet.
+struct OpenLeg {
+    mode: Id<String>,
+    dep_time: SimTime,
+    start_link: Id<Link>,
+    wait_time: Option<f64>,
+    entered_vehicle: Option<SimTime>,
+    distance: f64,
+}
+
+impl TripTables {
+    fn add_person(
+        &mut self,
+        person: &Id<InternalPerson>,
+        records: &[TripRecord],
+        links_per_vehicle: &IntMap<Id<InternalVehicle>, Vec<(SimTime, Id<Link>)>>,
+        network: &Network,
+    ) {
+        let mut trip_number = 0;
+        let mut trip: Option<OpenTrip> = None;
+        let mut leg: Option<OpenLeg> = None;
+
+        for record in records {
+            match &record.kind {
+                TripRecordKind::ActivityEnd {
+                    act_type,
+                    coordinate,
+                } if !is_stage_activity_type(act_type.external()) => {
+                    trip = Some(OpenTrip {
+                        start_activity_type: act_type.clone(),
+                        origin: coordinate.clone(),
+                        dep_time: record.time,
+                        main_mode: None,
+                        legs: Vec::new(),
+                    });
+                }
+                TripRecordKind::ActivityStart {
+                    act_type,
+                    coordinate,
+                } if !is_stage_activity_type(act_type.external()) => {
+                    let Some(trip) = trip.take() else {
+                        continue;
+                    };
+                    trip_number += 1;
+                    self.trips.push(TripRow {
+                        person: person.clone(),
+                        trip_number,
+                        main_mode: trip.main_mode,
+                        dep_time: trip.dep_time,
+                        trav_time: seconds_between(trip.dep_time, record.time),
+                        wait_time: trip.legs.iter().map(|leg| leg.wait_time).sum(),
+                        routed_distance: trip.legs.iter().map(|leg| leg.distance).sum(),
+                        euclidean_distance: Coordinate::euclidean_distance(
+                            &trip.origin,
+                            coordinate,
+                        ),
+                        start_activity_type: trip.start_activity_type,
+                        end_activity_type: act_type.clone(),
+                    });
+                    self.legs.extend(
+                        trip.legs
+                            .into_iter()
+                            .map(|leg| LegRow { trip_number, ..leg }),
+                    );
+                }
+                TripRecordKind::ActivityStart { .. } | TripRecordKind::ActivityEnd { .. } => {}
+                TripRecordKind::Departure {
+                    link,
+                    leg_mode,
+                    routing_mode,
+                } => {
+                    if let Some(trip) = &mut trip {
+                        trip.main_mode.get_or_insert_with(|| routing_mode.clone());
+                    }
+                    leg = Some(OpenLeg {
+                        mode: leg_mode.clone(),
+                        dep_time: record.time,
+                        start_link: link.clone(),
+                        wait_time: None,
+                        entered_vehicle: None,
+                        distance: 0.,
+                    });
+                }
+                TripRecordKind::Travelled(distance) => {
+                    if let Some(leg) = &mut leg {
+                        leg.distance += distance;
+                    }
+                }
+                TripRecordKind::EntersVehicle => {
+                    if let Some(leg) = &mut leg {
+                        leg.wait_time
+                            .get_or_insert_with(|| seconds_between(leg.dep_time, record.time));
+                        leg.entered_vehicle = Some(record.time);
+                    }
+                }
+                TripRecordKind::LeavesVehicle(vehicle) => {
+                    let Some(leg) = &mut leg else {
+                        continue;
+                    };
+                    let Some(entered) = leg.entered_vehicle.take() else {
+                        continue;
+                    };
+                    leg.distance += links_per_vehicle
+                        .get(vehicle)
+                        .map(|links| {
+                            links
+                                .iter()
+                                .filter(|(t, _)| entered <= *t && *t <= record.time)
+                                .map(|(_, link)| network.get_link(link).length)
+                                .sum()
+                        })
+                        .unwrap_or(0.);
+                }
+                TripRecordKind::Arrival { link } => {
+                    let (Some(trip), Some(leg)) = (&mut trip, leg.take()) else {
+                        continue;
+                    };
+                    trip.legs.push(LegRow {
+                        person: person.clone(),
+                        // set when the trip is finished
+                        trip_number: 0,
+                        mode: leg.mode,
+                        dep_time: leg.dep_time,
+                        trav_time: seconds_between(leg.dep_time, record.time),
+                        wait_time: leg.wait_time.unwrap_or(0.),
+                        distance: leg.distance,
+                        start_link: leg.start_link,
+                        end_link: link.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Writes one row per trip, ordered by person and trip number. Times are in seconds and
+    /// distances in meters. The main mode is the routing mode of the first leg.
+    pub(crate) fn write_trips(&self, path: &Path, format: AnalysisFormat) {
+        write_table(
+            path,
+            format,
+            &[
+                ("person", text(&self.trips, |t| t.person.external())),
+                ("trip_number", uint(&self.trips, |t| t.trip_number)),
+                (
+                    "main_mode",
+                    text(&self.trips, |t| {
+                        t.main_mode.as_ref().map_or("", |mode| mode.external())
+                    }),
+                ),
+                (
+                    "dep_time",
+                    float(&self.trips, |t| t.dep_time.as_duration().as_secs_f64()),
+                ),
+                ("trav_time", float(&self.trips, |t| t.trav_time)),
+                ("wait_time", float(&self.trips, |t| t.wait_time)),
+                ("routed_distance", float(&self.trips, |t| t.routed_distance)),
+                (
+                    "euclidean_distance",
+                    float(&self.trips, |t| t.euclidean_distance),
+                ),
+                (
+                    "start_activity_type",
+                    text(&self.trips, |t| t.start_activity_type.external()),
+                ),
+                (
+                    "end_activity_type",
+                    text(&self.trips, |t| t.end_activity_type.external()),
+                ),
+            ],
+        );
+    }
+
+    /// Writes one row per leg, ordered by person and departure time. The legs of a trip share its
+    /// trip number.
+    pub(crate) fn write_legs(&self, path: &Path, format: AnalysisFormat) {
+        write_table(
+            path,
+            format,
+            &[
+                ("person", text(&self.legs, |l| l.person.external())),
+                ("trip_number", uint(&self.legs, |l| l.trip_number)),
+                ("mode", text(&self.legs, |l| l.mode.external())),
+                (
+                    "dep_time",
+                    float(&self.legs, |l| l.dep_time.as_duration().as_secs_f64()),
+                ),
+                ("trav_time", float(&self.legs, |l| l.trav_time)),
+                ("wait_time", float(&self.legs, |l| l.wait_time)),
+                ("distance", float(&self.legs, |l| l.distance)),
+                ("start_link", text(&self.legs, |l| l.start_link.external())),
+                ("end_link", text(&self.legs, |l| l.end_link.external())),
+            ],
+        );
+    }
+}
+
+fn seconds_between(from: SimTime, to: SimTime) -> f64 {
+    to.as_duration().as_secs_f64() - from.as_duration().as_secs_f64()
+}
+
+fn text<T>(rows: &[T], value: impl Fn(&T) -> &str) -> Column {
+    Column::Text(rows.iter().map(|row| value(row).to_string()).collect())
+}
+
+fn uint<T>(rows: &[T], value: impl Fn(&T) -> u64) -> Column {
+    Column::UInt(rows.iter().map(value).collect())
+}
+
+fn float<T>(rows: &[T], value: impl Fn(&T) -> f64) -> Column {
+    Column::Float(rows.iter().map(value).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TripsCollector;
+    use crate::simulation::config::AnalysisFormat;
+    use crate::simulation::events::{
+        ActivityEndEvent, ActivityStartEvent, EventsManager, LinkEnterEvent, PersonArrivalEvent,
+        PersonDepartureEvent, PersonEntersVehicleEvent, PersonLeavesVehicleEvent,
+        TeleportationArrivalEvent,
+    };
+    use crate::simulation::id::Id;
+    use crate::simulation::scenario::Coordinate;
+    use crate::simulation::scenario::network::{Link, Network, Node};
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[deterministic_id_test]
+    fn assembles_trips_and_legs_across_partitions() {
+        let collector = TripsCollector::new(2, 1, 0);
+        let mut first = EventsManager::new();
+        (collector.register(0))(&mut first);
+        let mut second = EventsManager::new();
+        (collector.register(1))(&mut second);
+        first.reset_iteration(0);
+        second.reset_iteration(0);
+
+        // car trip from home to work with an access walk, which leaves the first partition
+        first.process_event(&act_end("p1", "h", 100, "l1", (0., 0.)));
+        first.process_event(&departure("p1", "walk", "car", 100, "l1"));
+        first.process_event(&teleportation("p1", "walk", 150, 50.));
+        first.process_event(&arrival("p1", "walk", 150, "l1"));
+        first.process_event(&act_start("p1", "car interaction", 150, "l1", (0., 0.)));
+        first.process_event(&act_end("p1", "car interaction", 150, "l1", (0., 0.)));
+        first.process_event(&departure("p1", "car", "car", 150, "l1"));
+        first.process_event(&PersonEntersVehicleEvent {
+            time: SimTime::from_secs(160),
+            person: Id::create("p1"),
+            vehicle: Id::create("v1"),
+            attributes: Default::default(),
+        });
+        second.process_event(&LinkEnterEvent {
+            time: SimTime::from_secs(200),
+            link: Id::create("l2"),
+            vehicle: Id::create("v1"),
+            attributes: Default::default(),
+        });
+        second.process_event(&PersonLeavesVehicleEvent {
+            time: SimTime::from_secs(300),
+            person: Id::create("p1"),
+            vehicle: Id::create("v1"),
+            attributes: Default::default(),
+        });
+        second.process_event(&arrival("p1", "car", 300, "l2"));
+        second.process_event(&act_start("p1", "w", 300, "l2", (1500., 0.)));
+
+        // teleported walk trip from work to shop
+        second.process_event(&act_end("p1", "w", 1000, "l2", (1500., 0.)));
+        second.process_event(&departure("p1", "walk", "walk", 1000, "l2"));
+        second.process_event(&teleportation("p1", "walk", 1200, 300.));
+        second.process_event(&arrival("p1", "walk", 1200, "l2"));
+        second.process_event(&act_start("p1", "shop", 1200, "l2", (1500., 400.)));
+
+        // the trip of p2 is not finished
+        first.process_event(&act_end("p2", "h", 100, "l1", (0., 0.)));
+        first.process_event(&departure("p2", "car", "car", 100, "l1"));
+
+        let tables = collector.tables(&network());
+        let dir = tempdir().unwrap();
+        let trips = dir.path().join("trips.csv");
+        let legs = dir.path().join("legs.csv");
+        tables.write_trips(&trips, AnalysisFormat::Csv);
+        tables.write_legs(&legs, AnalysisFormat::Csv);
+
+        assert_eq!(
+            "person,trip_number,main_mode,dep_time,trav_time,wait_time,routed_distance,\
+             euclidean_distance,start_activity_type,end_activity_type\n\
+             p1,1,car,100,200,10,1050,1500,h,w\n\
+             p1,2,walk,1000,200,0,300,400,w,shop\n",
+            fs::read_to_string(trips).unwrap()
+        );
+        assert_eq!(
+            "person,trip_number,mode,dep_time,trav_time,wait_time,distance,start_link,end_link\n\
+             p1,1,walk,100,50,0,50,l1,l1\n\
+             p1,1,car,150,150,10,1000,l1,l2\n\
+             p1,2,walk,1000,200,0,300,l2,l2\n",
+            fs::read_to_string(legs).unwrap()
+        );
+    }
+
+    #[deterministic_id_test]
+    fn reset_iteration_clears_records() {
+        let collector = TripsCollector::new(1, 1, 1);
+        let mut events = EventsManager::new();
+        (collector.register(0))(&mut events);
+        events.reset_iteration(0);
+
+        events.process_event(&act_end("p1", "h", 100, "l1", (0., 0.)));
+        events.process_event(&departure("p1", "walk", "walk", 100, "l1"));
+        events.process_event(&arrival("p1", "walk", 200, "l1"));
+        events.process_event(&act_start("p1", "w", 200, "l1", (0., 0.)));
+        events.reset_iteration(1);
+
+        assert_eq!(super::TripTables::default(), collector.tables(&network()));
+    }
+
+    #[deterministic_id_test]
+    fn records_only_in_write_iterations() {
+        let collector = TripsCollector::new(1, 2, 3);
+        let mut events = EventsManager::new();
+        (collector.register(0))(&mut events);
+
+        for iteration in 0..=3 {
+            events.reset_iteration(iteration);
+            events.process_event(&act_end("p1", "h", 100, "l1", (0., 0.)));
+            events.process_event(&departure("p1", "car", "car", 100, "l1"));
+            events.process_event(&LinkEnterEvent {
+                time: SimTime::from_secs(150),
+                link: Id::create("l2"),
+                vehicle: Id::create("v1"),
+                attributes: Default::default(),
+            });
+
+            // iteration 3 is the last one
+            let recording = iteration != 1;
+            let records = collector.buffers[0].lock().unwrap();
+            assert_eq!(recording, !records.persons.is_empty());
+            assert_eq!(recording, !records.link_enters.is_empty());
+        }
+    }
+
+    #[deterministic_id_test]
+    fn records_nothing_if_analysis_is_disabled() {
+        let collector = TripsCollector::new(1, 0, 0);
+        let mut events = EventsManager::new();
+        (collector.register(0))(&mut events);
+        events.reset_iteration(0);
+
+        events.process_event(&act_end("p1", "h", 100, "l1", (0., 0.)));
+        events.process_event(&departure("p1", "walk", "walk", 100, "l1"));
+
+        assert!(collector.buffers[0].lock().unwrap().persons.is_empty());
+    }
+
+    fn network() -> Network {
+        let n1 = Node::new(Id::create("n1"), Coordinate::new_2d(0., 0.), 0, 0);
+        let n2 = Node::new(Id::create("n2"), Coordinate::new_2d(500., 0.), 0, 0);
+        let n3 = Node::new(Id::create("n3"), Coordinate::new_2d(1500., 0.), 1, 0);
+        let l1 = Link::new_with_default(Id::create("l1"), &n1, &n2);
+        let l2 = Link::new_with_default(Id::create("l2"), &n2, &n3);
+        let mut network = Network::new();
+        network.add_node(n1);
+        network.add_node(n2);
+        network.add_node(n3);
+        network.add_link(l1);
+        network.add_link(l2);
+        network
+    }
+
+    fn act_start(
+        person: &str,
+        act_type: &str,
+        time: u64,
+        link: &str,
+        (x, y): (f64, f64),
+    ) -> ActivityStartEvent {
+        ActivityStartEvent {
+            time: SimTime::from_secs(time),
+            person: Id::create(person),
+            link: Id::create(link),
+            coordinate: Coordinate::new_2d(x, y),
+            act_type: Id::create(act_type),
+            attributes: Default::default(),
+        }
+    }
+
+    fn act_end(
+        person: &str,
+        act_type: &str,
+        time: u64,
+        link: &str,
+        (x, y): (f64, f64),
+    ) -> ActivityEndEvent {
+        ActivityEndEvent {
+            time: SimTime::from_secs(time),
+            person: Id::create(person),
+            link: Id::create(link),
+            coordinate: Coordinate::new_2d(x, y),
+            act_type: Id::create(act_type),
+            attributes: Default::default(),
+        }
+    }
+
+    fn departure(
+        person: &str,
+        leg_mode: &str,
+        routing_mode: &str,
+        time: u64,
+        link: &str,
+    ) -> PersonDepartureEvent {
+        PersonDepartureEvent {
+            time: SimTime::from_secs(time),
+            person: Id::create(person),
+            link: Id::create(link),
+            leg_mode: Id::create(leg_mode),
+            routing_mode: Id::create(routing_mode),
+            attributes: Default::default(),
+        }
+    }
+
+    fn arrival(person: &str, leg_mode: &str, time: u64, link: &str) -> PersonArrivalEvent {
+        PersonArrivalEvent {
+            time: SimTime::from_secs(time),
+            person: Id::create(person),
+            link: Id::create(link),
+            leg_mode: Id::create(leg_mode),
+            attributes: Default::default(),
+        }
+    }
+
+    fn teleportation(
+        person: &str,
+        mode: &str,
+        time: u64,
+        distance: f64,
+    ) -> TeleportationArrivalEvent {
+        TeleportationArrivalEvent {
+            time: SimTime::from_secs(time),
+            person: Id::create(person),
+            mode: Id::create(mode),
+            distance,
+            attributes: Default::default(),
+        }
+    }
+}
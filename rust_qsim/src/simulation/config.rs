@@ -155,10 +155,17 @@ impl Config {
         self.controller_mut();
         self.routing_mut();
         self.replanning_mut();
+        self.scoring_mut();
+        self.drt_mut();
+        self.analysis_mut();
+        self.counts_mut();
         self.computational_setup_mut();
         self.network_mut();
         self.population_mut();
         self.vehicles_mut();
+        self.transit_schedule_mut();
+        self.transit_vehicles_mut();
+        self.facilities_mut();
         self.ids_mut();
     }
 
@@ -235,6 +242,66 @@ impl Config {
             .insert("vehicles".to_string(), Box::new(vehicles));
     }
 
+    pub fn transit_schedule(&self) -> &TransitSchedule {
+        self.module::<TransitSchedule>("transit_schedule")
+            .expect("TransitSchedule was not set.")
+    }
+
+    pub fn transit_schedule_mut(&mut self) -> &mut TransitSchedule {
+        if !self.modules.contains_key("transit_schedule") {
+            self.modules.insert(
+                "transit_schedule".to_string(),
+                Box::new(TransitSchedule::default()),
+            );
+        }
+        self.module_mut::<TransitSchedule>("transit_schedule")
+            .unwrap()
+    }
+
+    pub fn set_transit_schedule(&mut self, transit_schedule: TransitSchedule) {
+        self.modules
+            .insert("transit_schedule".to_string(), Box::new(transit_schedule));
+    }
+
+    pub fn transit_vehicles(&self) -> &TransitVehicles {
+        self.module::<TransitVehicles>("transit_vehicles")
+            .expect("TransitVehicles was not set.")
+    }
+
+    pub fn transit_vehicles_mut(&mut self) -> &mut TransitVehicles {
+        if !self.modules.contains_key("transit_vehicles") {
+            self.modules.insert(
+                "transit_vehicles".to_string(),
+                Box::new(TransitVehicles::default()),
+            );
+        }
+        self.module_mut::<TransitVehicles>("transit_vehicles")
+            .unwrap()
+    }
+
+    pub fn set_transit_vehicles(&mut self, transit_vehicles: TransitVehicles) {
+        self.modules
+            .insert("transit_vehicles".to_string(), Box::new(transit_vehicles));
+    }
+
+    pub fn facilities(&self) -> &Facilities {
+        self.module::<Facilities>("facilities")
+            .expect("Facilities was not set.")
+    }
+
+    pub fn facilities_mut(&mut self) -> &mut Facilities {
+        if !self.modules.contains_key("facilities") {
+            self.modules
+                .insert("facilities".to_string(), Box::new(Facilities::default()));
+        }
+        self.module_mut::<Facilities>("facilities").unwrap()
+    }
+
+    pub fn set_facilities(&mut self, facilities: Facilities) {
+        self.modules
+            .insert("facilities".to_string(), Box::new(facilities));
+    }
+
     pub fn ids(&self) -> &Ids {
         self.module::<Ids>("ids").expect("Ids was not set.")
     }
@@ -347,6 +414,75 @@ impl Config {
             .insert("replanning".to_string(), Box::new(replanning));
     }
 
+    pub fn scoring(&self) -> &Scoring {
+        self.module::<Scoring>("scoring")
+            .expect("Scoring was not set.")
+    }
+
+    pub fn scoring_mut(&mut self) -> &mut Scoring {
+        if !self.modules.contains_key("scoring") {
+            self.modules
+                .insert("scoring".to_string(), Box::new(Scoring::default()));
+        }
+        self.module_mut::<Scoring>("scoring").unwrap()
+    }
+
+    pub fn set_scoring(&mut self, scoring: Scoring) {
+        self.modules
+            .insert("scoring".to_string(), Box::new(scoring));
+    }
+
+    pub fn drt(&self) -> &Drt {
+        self.module::<Drt>("drt").expect("Drt was not set.")
+    }
+
+    pub fn drt_mut(&mut self) -> &mut Drt {
+        if !self.modules.contains_key("drt") {
+            self.modules
+                .insert("drt".to_string(), Box::new(Drt::default()));
+        }
+        self.module_mut::<Drt>("drt").unwrap()
+    }
+
+    pub fn set_drt(&mut self, drt: Drt) {
+        self.modules.insert("drt".to_string(), Box::new(drt));
+    }
+
+    pub fn analysis(&self) -> &Analysis {
+        self.module::<Analysis>("analysis")
+            .expect("Analysis was not set.")
+    }
+
+    pub fn analysis_mut(&mut self) -> &mut Analysis {
+        if !self.modules.contains_key("analysis") {
+            self.modules
+                .insert("analysis".to_string(), Box::new(Analysis::default()));
+        }
+        self.module_mut::<Analysis>("analysis").unwrap()
+    }
+
+    pub fn set_analysis(&mut self, analysis: Analysis) {
+        self.modules
+            .insert("analysis".to_string(), Box::new(analysis));
+    }
+
+    pub fn counts(&self) -> &Counts {
+        self.module::<Counts>("counts")
+            .expect("Counts was not set.")
+    }
+
+    pub fn counts_mut(&mut self) -> &mut Counts {
+        if !self.modules.contains_key("counts") {
+            self.modules
+                .insert("counts".to_string(), Box::new(Counts::default()));
+        }
+        self.module_mut::<Counts>("counts").unwrap()
+    }
+
+    pub fn set_counts(&mut self, counts: Counts) {
+        self.modules.insert("counts".to_string(), Box::new(counts));
+    }
+
     pub fn qsim(&self) -> &QSim {
         self.module::<QSim>("qsim").expect("QSim was not set.")
     }
@@ -454,6 +590,12 @@ pub fn write_config(config: &Config, output_path: PathBuf) {
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Network {
     pub path: Option<PathBuf>,
+    /// MATSim network change events, which change the capacity, freespeed and lanes of links at
+    /// given times during the mobsim. The network is static if no file is given.
+    pub change_events_path: Option<PathBuf>,
+    /// MATSim lanes, which split the downstream end of links into lanes leading to different
+    /// links. Links have a single queue if no file is given.
+    pub lanes_path: Option<PathBuf>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -466,6 +608,21 @@ pub struct Vehicles {
     pub path: Option<PathBuf>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TransitSchedule {
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TransitVehicles {
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Facilities {
+    pub path: Option<PathBuf>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Ids {
     pub path: Option<PathBuf>,
@@ -475,6 +632,14 @@ register_override!("network.path", |config, value| {
     config.network_mut().path = Some(PathBuf::from(value));
 });
 
+register_override!("network.change_events_path", |config, value| {
+    config.network_mut().change_events_path = Some(PathBuf::from(value));
+});
+
+register_override!("network.lanes_path", |config, value| {
+    config.network_mut().lanes_path = Some(PathBuf::from(value));
+});
+
 register_override!("population.path", |config, value| {
     config.population_mut().path = Some(PathBuf::from(value));
 });
@@ -483,6 +648,18 @@ register_override!("vehicles.path", |config, value| {
     config.vehicles_mut().path = Some(PathBuf::from(value));
 });
 
+register_override!("transit_schedule.path", |config, value| {
+    config.transit_schedule_mut().path = Some(PathBuf::from(value));
+});
+
+register_override!("transit_vehicles.path", |config, value| {
+    config.transit_vehicles_mut().path = Some(PathBuf::from(value));
+});
+
+register_override!("facilities.path", |config, value| {
+    config.facilities_mut().path = Some(PathBuf::from(value));
+});
+
 register_override!("ids.path", |config, value| {
     config.set_ids(Ids {
         path: Some(PathBuf::from(value)),
@@ -549,6 +726,38 @@ pub struct Routing {
         deserialize_with = "deserialize_teleported_mode_params"
     )]
     pub teleported_mode_params: Vec<TeleportedParams>,
+    /// Size of the time bins in seconds, in which observed link travel times are aggregated for
+    /// routing.
+    #[serde(default = "default_travel_time_bin_size")]
+    pub travel_time_bin_size: u32,
+    #[serde(default)]
+    pub transit_routing: TransitRoutingParams,
+}
+
+/// Parameters of the schedule-based router for pt trips.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct TransitRoutingParams {
+    /// Radius in meters around origin and destination in which stops are considered for access
+    /// and egress.
+    pub search_radius: f64,
+    /// If no stop is within the search radius, stops within this distance of the nearest stop
+    /// are considered.
+    pub extension_radius: f64,
+    /// Stops closer than this beeline distance in meters are connected by walking transfers.
+    pub max_beeline_walk_connection_distance: f64,
+    pub max_transfers: u32,
+}
+
+impl Default for TransitRoutingParams {
+    fn default() -> Self {
+        TransitRoutingParams {
+            search_radius: 1000.,
+            extension_radius: 200.,
+            max_beeline_walk_connection_distance: 100.,
+            max_transfers: 10,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -562,6 +771,10 @@ fn default_access_egress_mode() -> String {
     "walk".to_string()
 }
 
+fn default_travel_time_bin_size() -> u32 {
+    900
+}
+
 fn default_walk_teleported_params() -> TeleportedParams {
     TeleportedParams {
         mode: "walk".to_string(),
@@ -581,6 +794,15 @@ where
     D: Deserializer<'de>,
 {
     let mut params = Vec::<TeleportedParams>::deserialize(deserializer)?;
+    if let Some(param) = params
+        .iter()
+        .find(|param| param.teleported_mode_speed.is_nan() || param.teleported_mode_speed <= 0.)
+    {
+        return Err(serde::de::Error::custom(format!(
+            "teleported_mode_speed of mode {} must be greater than 0, but is {}",
+            param.mode, param.teleported_mode_speed
+        )));
+    }
     let last_walk_index = params.iter().rposition(|param| param.mode == "walk");
 
     if let Some(last_walk_index) = last_walk_index {
@@ -606,6 +828,18 @@ register_override!("routing.mode", |config, value| {
     };
 });
 
+register_override!("routing.travel_time_bin_size", |config, value| {
+    config.routing_mut().travel_time_bin_size = value.parse().unwrap();
+});
+
+register_override!("routing.transit_routing.search_radius", |config, value| {
+    config.routing_mut().transit_routing.search_radius = value.parse().unwrap();
+});
+
+register_override!("routing.transit_routing.max_transfers", |config, value| {
+    config.routing_mut().transit_routing.max_transfers = value.parse().unwrap();
+});
+
 impl Default for Routing {
     fn default() -> Self {
         Routing {
@@ -613,6 +847,8 @@ impl Default for Routing {
             network_modes: Vec::new(),
             access_egress_mode: default_access_egress_mode(),
             teleported_mode_params: default_teleported_mode_params(),
+            travel_time_bin_size: default_travel_time_bin_size(),
+            transit_routing: TransitRoutingParams::default(),
         }
     }
 }
@@ -623,14 +859,120 @@ pub struct Replanning {
     pub fraction_of_iterations_to_disable_innovation: f64,
     pub max_agent_plan_memory: u32,
     pub plan_selector_for_removal: String,
+    /// Scale of the plan scores in the `ChangeExpBeta` and `SelectExpBeta` selectors. The larger
+    /// it is, the more likely plans with higher scores are selected.
+    pub brain_exp_beta: f64,
     pub strategy_settings: Vec<StrategySetting>,
+    pub time_allocation_mutator: TimeAllocationMutatorParams,
+    pub change_trip_mode: ChangeTripModeParams,
+    pub subtour_mode_choice: SubtourModeChoiceParams,
+    pub diversity_generating_plans_remover: DiversityGeneratingPlansRemoverParams,
+}
+
+/// Parameters of the `TimeAllocationMutator` strategy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct TimeAllocationMutatorParams {
+    /// Activity times are shifted by a uniformly drawn amount of at most this many seconds in
+    /// either direction.
+    pub mutation_range: u32,
+    /// If `false`, only activity end times are mutated. Otherwise, maximum durations of activities
+    /// are mutated as well.
+    pub affecting_duration: bool,
+}
+
+impl Default for TimeAllocationMutatorParams {
+    fn default() -> Self {
+        TimeAllocationMutatorParams {
+            mutation_range: 1800,
+            affecting_duration: true,
+        }
+    }
+}
+
+/// Parameters of the `ChangeTripMode` strategy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ChangeTripModeParams {
+    /// Modes trips may be changed to. Trips with other modes are left untouched.
+    pub modes: Vec<String>,
+}
+
+impl Default for ChangeTripModeParams {
+    fn default() -> Self {
+        ChangeTripModeParams {
+            modes: vec!["car".to_string(), "pt".to_string()],
+        }
+    }
+}
+
+/// Parameters of the `SubtourModeChoice` strategy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct SubtourModeChoiceParams {
+    /// Modes subtours may be changed to. Subtours with trips of other modes are left untouched.
+    pub modes: Vec<String>,
+    /// Modes which need a vehicle that the agent has to bring back. They are only used for whole
+    /// closed subtours which start where the vehicle is.
+    pub chain_based_modes: Vec<String>,
+}
+
+impl Default for SubtourModeChoiceParams {
+    fn default() -> Self {
+        SubtourModeChoiceParams {
+            modes: ["car", "pt", "bike", "walk"].map(String::from).to_vec(),
+            chain_based_modes: ["car", "bike"].map(String::from).to_vec(),
+        }
+    }
 }
 
+/// Parameters of the `DiversityGeneratingPlansRemover`. A plan is penalised by the given amount of
+/// utils for each other plan of the person which is identical in the respective aspect, and by a
+/// share of it if the plans are partially the same.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct DiversityGeneratingPlansRemoverParams {
+    /// Penalty for the same sequence of activity types.
+    pub act_type_penalty: f64,
+    /// Penalty for the same sequence of activity locations.
+    pub location_penalty: f64,
+    /// Penalty for the same sequence of trip modes.
+    pub mode_penalty: f64,
+    /// Penalty for using the same links in network routes.
+    pub route_penalty: f64,
+}
+
+impl Default for DiversityGeneratingPlansRemoverParams {
+    fn default() -> Self {
+        DiversityGeneratingPlansRemoverParams {
+            act_type_penalty: 5.0,
+            location_penalty: 5.0,
+            mode_penalty: 5.0,
+            route_penalty: 5.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
 pub struct StrategySetting {
     pub name: String,
     pub weight: f64,
     pub subpopulation: String,
+    /// The strategy is not chosen anymore after this iteration.
+    #[serde(default)]
+    pub disable_after_iteration: Option<u32>,
+    /// Changes the weight over the iterations. Between two points, the weight is interpolated
+    /// linearly, e.g., `[{ iteration: 0, weight: 0.2 }, { iteration: 100, weight: 0.0 }]` anneals
+    /// the weight to zero within the first 100 iterations. Before the first point, `weight` is
+    /// used, after the last point the weight of the last point.
+    #[serde(default)]
+    pub weight_schedule: Vec<WeightSchedulePoint>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WeightSchedulePoint {
+    pub iteration: u32,
+    pub weight: f64,
 }
 
 register_override!(
@@ -650,21 +992,378 @@ register_override!("replanning.plan_selector_for_removal", |config, value| {
     config.replanning_mut().plan_selector_for_removal = value.to_string();
 });
 
+register_override!("replanning.brain_exp_beta", |config, value| {
+    config.replanning_mut().brain_exp_beta = value.parse().unwrap();
+});
+
+register_override!(
+    "replanning.time_allocation_mutator.mutation_range",
+    |config, value| {
+        config
+            .replanning_mut()
+            .time_allocation_mutator
+            .mutation_range = value.parse().unwrap();
+    }
+);
+
+register_override!(
+    "replanning.time_allocation_mutator.affecting_duration",
+    |config, value| {
+        config
+            .replanning_mut()
+            .time_allocation_mutator
+            .affecting_duration = value.parse().unwrap();
+    }
+);
+
+register_override!("replanning.change_trip_mode.modes", |config, value| {
+    config.replanning_mut().change_trip_mode.modes = parse_mode_list(value);
+});
+
+register_override!("replanning.subtour_mode_choice.modes", |config, value| {
+    config.replanning_mut().subtour_mode_choice.modes = parse_mode_list(value);
+});
+
+register_override!(
+    "replanning.subtour_mode_choice.chain_based_modes",
+    |config, value| {
+        config
+            .replanning_mut()
+            .subtour_mode_choice
+            .chain_based_modes = parse_mode_list(value);
+    }
+);
+
+register_override!(
+    "replanning.diversity_generating_plans_remover.act_type_penalty",
+    |config, value| {
+        config
+            .replanning_mut()
+            .diversity_generating_plans_remover
+            .act_type_penalty = value.parse().unwrap();
+    }
+);
+
+register_override!(
+    "replanning.diversity_generating_plans_remover.location_penalty",
+    |config, value| {
+        config
+            .replanning_mut()
+            .diversity_generating_plans_remover
+            .location_penalty = value.parse().unwrap();
+    }
+);
+
+register_override!(
+    "replanning.diversity_generating_plans_remover.mode_penalty",
+    |config, value| {
+        config
+            .replanning_mut()
+            .diversity_generating_plans_remover
+            .mode_penalty = value.parse().unwrap();
+    }
+);
+
+register_override!(
+    "replanning.diversity_generating_plans_remover.route_penalty",
+    |config, value| {
+        config
+            .replanning_mut()
+            .diversity_generating_plans_remover
+            .route_penalty = value.parse().unwrap();
+    }
+);
+
 impl Default for Replanning {
     fn default() -> Self {
         Self {
             fraction_of_iterations_to_disable_innovation: 1.0,
             max_agent_plan_memory: 5,
             plan_selector_for_removal: WORST_SCORE_STRATEGY_NAME.to_string(),
+            brain_exp_beta: 1.0,
             strategy_settings: vec![StrategySetting {
                 name: KEEP_LAST_SELECTED_STRATEGY_NAME.to_string(),
                 weight: 1.0,
                 subpopulation: "person".to_string(),
+                ..Default::default()
             }],
+            time_allocation_mutator: TimeAllocationMutatorParams::default(),
+            change_trip_mode: ChangeTripModeParams::default(),
+            subtour_mode_choice: SubtourModeChoiceParams::default(),
+            diversity_generating_plans_remover: DiversityGeneratingPlansRemoverParams::default(),
+        }
+    }
+}
+
+/// Parameters of the Charypar-Nagel scoring function. All marginal utilities are given in utils
+/// per hour, except for the distance related ones, which are given per meter. Times are given in
+/// seconds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Scoring {
+    /// Weight of the new score when it is blended into the existing plan score, i.e.
+    /// `score = (1 - learning_rate) * old_score + learning_rate * new_score`.
+    pub learning_rate: f64,
+    pub marginal_utility_of_performing: f64,
+    pub marginal_utility_of_waiting: f64,
+    pub marginal_utility_of_late_arrival: f64,
+    pub marginal_utility_of_early_departure: f64,
+    pub marginal_utility_of_money: f64,
+    pub activity_params: Vec<ActivityParams>,
+    pub mode_params: Vec<ModeParams>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ActivityParams {
+    pub activity_type: String,
+    /// Typical duration in seconds, which must be greater than 0.
+    pub typical_duration: u32,
+    #[serde(default)]
+    pub typical_duration_score_computation: TypicalDurationScoreComputation,
+    #[serde(default)]
+    pub opening_time: Option<u32>,
+    #[serde(default)]
+    pub closing_time: Option<u32>,
+    #[serde(default)]
+    pub latest_start_time: Option<u32>,
+    #[serde(default)]
+    pub earliest_end_time: Option<u32>,
+}
+
+/// Determines the duration at which performing an activity yields zero utility. `Relative` uses
+/// `typical_duration / e`, `Uniform` uses `typical_duration * exp(-10h / typical_duration)`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TypicalDurationScoreComputation {
+    #[default]
+    Relative,
+    Uniform,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ModeParams {
+    pub mode: String,
+    #[serde(default)]
+    pub constant: f64,
+    #[serde(default = "default_marginal_utility_of_traveling")]
+    pub marginal_utility_of_traveling: f64,
+    #[serde(default)]
+    pub marginal_utility_of_distance: f64,
+    #[serde(default)]
+    pub monetary_distance_rate: f64,
+}
+
+fn default_marginal_utility_of_traveling() -> f64 {
+    -6.0
+}
+
+impl ModeParams {
+    /// Parameters used for modes without explicitly configured mode params.
+    pub fn default_for_mode(mode: &str) -> Self {
+        Self {
+            mode: mode.to_string(),
+            constant: 0.0,
+            marginal_utility_of_traveling: default_marginal_utility_of_traveling(),
+            marginal_utility_of_distance: 0.0,
+            monetary_distance_rate: 0.0,
+        }
+    }
+}
+
+register_override!("scoring.learning_rate", |config, value| {
+    config.scoring_mut().learning_rate = value.parse().unwrap();
+});
+
+register_override!("scoring.marginal_utility_of_performing", |config, value| {
+    config.scoring_mut().marginal_utility_of_performing = value.parse().unwrap();
+});
+
+register_override!("scoring.marginal_utility_of_waiting", |config, value| {
+    config.scoring_mut().marginal_utility_of_waiting = value.parse().unwrap();
+});
+
+register_override!(
+    "scoring.marginal_utility_of_late_arrival",
+    |config, value| {
+        config.scoring_mut().marginal_utility_of_late_arrival = value.parse().unwrap();
+    }
+);
+
+register_override!(
+    "scoring.marginal_utility_of_early_departure",
+    |config, value| {
+        config.scoring_mut().marginal_utility_of_early_departure = value.parse().unwrap();
+    }
+);
+
+register_override!("scoring.marginal_utility_of_money", |config, value| {
+    config.scoring_mut().marginal_utility_of_money = value.parse().unwrap();
+});
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            learning_rate: 1.0,
+            marginal_utility_of_performing: 6.0,
+            marginal_utility_of_waiting: 0.0,
+            marginal_utility_of_late_arrival: -18.0,
+            marginal_utility_of_early_departure: 0.0,
+            marginal_utility_of_money: 1.0,
+            activity_params: Vec::new(),
+            mode_params: Vec::new(),
+        }
+    }
+}
+
+/// Demand responsive transport (DRT) services. A service operates all vehicles whose `dvrpMode`
+/// attribute equals the service's mode and serves all legs of that mode.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Drt {
+    pub services: Vec<DrtService>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DrtService {
+    pub mode: String,
+    /// Network mode on which the vehicles of the service are routed.
+    #[serde(default = "default_drt_network_mode")]
+    pub network_mode: String,
+    /// Time in seconds a vehicle spends at a stop to pick up and drop off passengers.
+    #[serde(default = "default_drt_stop_duration")]
+    pub stop_duration: u32,
+    /// Maximum time in seconds between the submission of a request and the pickup.
+    #[serde(default = "default_drt_max_wait_time")]
+    pub max_wait_time: u32,
+    /// A passenger must be dropped off within `alpha * direct ride time + beta` after the
+    /// submission of the request.
+    #[serde(default = "default_drt_max_travel_time_alpha")]
+    pub max_travel_time_alpha: f64,
+    /// See `max_travel_time_alpha`, given in seconds.
+    #[serde(default = "default_drt_max_travel_time_beta")]
+    pub max_travel_time_beta: u32,
+    #[serde(default)]
+    pub insertion: DrtInsertionHeuristic,
+}
+
+/// Determines where the pickup and dropoff of a new request are inserted into the vehicle
+/// schedules.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DrtInsertionHeuristic {
+    /// Tries all positions in the schedules of all vehicles and picks the insertion which adds the
+    /// least time to a schedule.
+    #[default]
+    Extensive,
+    /// Only appends the pickup and the dropoff to the end of the vehicle schedules.
+    Append,
+}
+
+fn default_drt_network_mode() -> String {
+    "car".to_string()
+}
+
+fn default_drt_stop_duration() -> u32 {
+    60
+}
+
+fn default_drt_max_wait_time() -> u32 {
+    600
+}
+
+fn default_drt_max_travel_time_alpha() -> f64 {
+    1.5
+}
+
+fn default_drt_max_travel_time_beta() -> u32 {
+    600
+}
+
+/// Analyses of the mobsim events, i.e., leg histograms, hourly link volumes and trip and leg
+/// tables. They are written into the iteration directories.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Analysis {
+    /// The analyses are written every `write_interval` iterations and in the last iteration. They
+    /// are not written at all if the interval is 0.
+    pub write_interval: u32,
+    /// Size of the leg histogram time bins in seconds.
+    pub leg_histogram_bin_size: u32,
+    pub format: AnalysisFormat,
+}
+
+impl Default for Analysis {
+    fn default() -> Self {
+        Self {
+            write_interval: 10,
+            leg_histogram_bin_size: 300,
+            format: AnalysisFormat::Csv,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub enum AnalysisFormat {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+impl AnalysisFormat {
+    pub fn with_extension(&self, file_name: &str) -> String {
+        match self {
+            AnalysisFormat::Csv => format!("{file_name}.csv"),
+            AnalysisFormat::Parquet => format!("{file_name}.parquet"),
         }
     }
 }
 
+fn parse_analysis_format(value: &str) -> AnalysisFormat {
+    match value.to_lowercase().as_str() {
+        "csv" => AnalysisFormat::Csv,
+        "parquet" => AnalysisFormat::Parquet,
+        _ => panic!("Invalid analysis format: {}", value),
+    }
+}
+
+register_override!("analysis.write_interval", |config, value| {
+    config.analysis_mut().write_interval = value.parse().unwrap();
+});
+
+register_override!("analysis.leg_histogram_bin_size", |config, value| {
+    config.analysis_mut().leg_histogram_bin_size = value.parse().unwrap();
+});
+
+register_override!("analysis.format", |config, value| {
+    config.analysis_mut().format = parse_analysis_format(value);
+});
+
+/// Observed link volumes in MATSim's counts format, e.g., from loop detectors. If a counts file is
+/// given, the simulated link volumes are compared with the observed ones.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Counts {
+    pub path: Option<PathBuf>,
+    /// The comparison is written every `write_interval` iterations and in the last iteration, in
+    /// the format of the analyses. It is not written at all if the interval is 0.
+    pub write_interval: u32,
+}
+
+impl Default for Counts {
+    fn default() -> Self {
+        Self {
+            path: None,
+            write_interval: 10,
+        }
+    }
+}
+
+register_override!("counts.path", |config, value| {
+    config.counts_mut().path = Some(PathBuf::from(value));
+});
+
+register_override!("counts.write_interval", |config, value| {
+    config.counts_mut().write_interval = value.parse().unwrap();
+});
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(default)]
 pub struct QSim {
@@ -674,6 +1373,75 @@ pub struct QSim {
     pub sample_size: f64,
     pub stuck_threshold: u32,
     pub main_modes: Vec<String>,
+    pub traffic_dynamics: TrafficDynamics,
+    /// The speed in km/h at which holes travel backwards over links with
+    /// [TrafficDynamics::KinematicWaves]. Must be greater than 0.
+    #[serde(deserialize_with = "deserialize_hole_speed")]
+    pub hole_speed: f64,
+    pub link_dynamics: LinkDynamics,
+}
+
+/// Determines when a vehicle leaving a link frees its storage capacity.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrafficDynamics {
+    /// The storage capacity is freed as soon as the vehicle leaves the link.
+    #[default]
+    Queue,
+    /// The vehicle leaves a hole at the downstream end of the link, which travels backwards with
+    /// the hole speed. The storage capacity is freed once the hole reaches the upstream end, so
+    /// that spillback propagates upstream with a finite speed, as with MATSim's kinematic waves.
+    KinematicWaves,
+}
+
+/// Determines the order in which vehicles leave a link.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LinkDynamics {
+    /// Vehicles leave the link in the order in which they entered it.
+    #[default]
+    Fifo,
+    /// Vehicles are ordered by their earliest exit time, so that faster vehicles can pass slower
+    /// ones, e.g., cars can overtake bikes on a shared link.
+    PassingQueue,
+}
+
+fn parse_link_dynamics(value: &str) -> LinkDynamics {
+    match value.to_lowercase().replace(['-', '_'], "").as_str() {
+        "fifo" => LinkDynamics::Fifo,
+        "passingqueue" => LinkDynamics::PassingQueue,
+        _ => panic!("Invalid link_dynamics: {}", value),
+    }
+}
+
+fn parse_traffic_dynamics(value: &str) -> TrafficDynamics {
+    match value.to_lowercase().replace(['-', '_'], "").as_str() {
+        "queue" => TrafficDynamics::Queue,
+        "kinematicwaves" => TrafficDynamics::KinematicWaves,
+        _ => panic!("Invalid traffic_dynamics: {}", value),
+    }
+}
+
+fn is_valid_hole_speed(hole_speed: f64) -> bool {
+    hole_speed > 0. && hole_speed.is_finite()
+}
+
+fn deserialize_hole_speed<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let hole_speed = f64::deserialize(deserializer)?;
+    if !is_valid_hole_speed(hole_speed) {
+        return Err(serde::de::Error::custom(format!(
+            "hole_speed must be greater than 0, but is {hole_speed}"
+        )));
+    }
+    Ok(hole_speed)
+}
+
+fn parse_hole_speed(value: &str) -> f64 {
+    match value.parse() {
+        Ok(hole_speed) if is_valid_hole_speed(hole_speed) => hole_speed,
+        _ => panic!("Invalid hole_speed: {}. It must be greater than 0.", value),
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -711,6 +1479,7 @@ impl From<&Simulation> for QSim {
             sample_size: value.sample_size,
             stuck_threshold: value.stuck_threshold,
             main_modes: value.main_modes.clone(),
+            ..QSim::default()
         }
     }
 }
@@ -748,12 +1517,19 @@ register_override!("qsim.stuck_threshold", |config, value| {
 });
 
 register_override!("qsim.main_modes", |config, value| {
-    config.qsim_mut().main_modes = value
-        .split(',')
-        .map(str::trim)
-        .filter(|mode| !mode.is_empty())
-        .map(ToString::to_string)
-        .collect();
+    config.qsim_mut().main_modes = parse_mode_list(value);
+});
+
+register_override!("qsim.traffic_dynamics", |config, value| {
+    config.qsim_mut().traffic_dynamics = parse_traffic_dynamics(value);
+});
+
+register_override!("qsim.hole_speed", |config, value| {
+    config.qsim_mut().hole_speed = parse_hole_speed(value);
+});
+
+register_override!("qsim.link_dynamics", |config, value| {
+    config.qsim_mut().link_dynamics = parse_link_dynamics(value);
 });
 
 register_override!("controller.first_iteration", |config, value| {
@@ -846,7 +1622,77 @@ impl ConfigModule for Population {
 }
 
 #[typetag::serde]
-impl ConfigModule for Vehicles {
+impl ConfigModule for Vehicles {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[typetag::serde]
+impl ConfigModule for TransitSchedule {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[typetag::serde]
+impl ConfigModule for TransitVehicles {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[typetag::serde]
+impl ConfigModule for Facilities {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[typetag::serde]
+impl ConfigModule for Ids {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[typetag::serde]
+impl ConfigModule for Partitioning {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[typetag::serde]
+impl ConfigModule for Output {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[typetag::serde]
+impl ConfigModule for Routing {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -856,7 +1702,7 @@ impl ConfigModule for Vehicles {
 }
 
 #[typetag::serde]
-impl ConfigModule for Ids {
+impl ConfigModule for Replanning {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -866,7 +1712,7 @@ impl ConfigModule for Ids {
 }
 
 #[typetag::serde]
-impl ConfigModule for Partitioning {
+impl ConfigModule for Scoring {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -876,7 +1722,7 @@ impl ConfigModule for Partitioning {
 }
 
 #[typetag::serde]
-impl ConfigModule for Output {
+impl ConfigModule for Drt {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -886,7 +1732,7 @@ impl ConfigModule for Output {
 }
 
 #[typetag::serde]
-impl ConfigModule for Routing {
+impl ConfigModule for Analysis {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -896,7 +1742,7 @@ impl ConfigModule for Routing {
 }
 
 #[typetag::serde]
-impl ConfigModule for Replanning {
+impl ConfigModule for Counts {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -957,6 +1803,9 @@ impl Default for QSim {
             sample_size: 1.0,
             stuck_threshold: 10,
             main_modes: vec![],
+            traffic_dynamics: TrafficDynamics::Queue,
+            hole_speed: 15.,
+            link_dynamics: LinkDynamics::Fifo,
         }
     }
 }
@@ -1075,6 +1924,16 @@ impl CompressionType {
     }
 }
 
+/// Parses a comma separated list of modes, e.g. `car,bike`.
+fn parse_mode_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|mode| !mode.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
 fn parse_compression_type(value: &str) -> CompressionType {
     match value.to_lowercase().replace(['-', '_'], "").as_str() {
         "none" | "xml" => CompressionType::None,
@@ -1231,9 +2090,15 @@ mod tests {
     use crate::simulation::config::Profiling;
     use crate::simulation::config::WriteEvents;
     use crate::simulation::config::{
-        CommandLineArgs, CompressionType, ComputationalSetup, Config, Controller, EdgeWeight,
-        MetisOptions, PartitionMethod, Partitioning, QSim, Replanning, Routing, StrategySetting,
-        TeleportedParams, VertexWeight, parse_key_val,
+        ActivityParams, ModeParams, Scoring, TypicalDurationScoreComputation,
+    };
+    use crate::simulation::config::{
+        Analysis, AnalysisFormat, ChangeTripModeParams, CommandLineArgs, CompressionType,
+        ComputationalSetup, Config, Controller, Counts, DiversityGeneratingPlansRemoverParams, Drt,
+        DrtInsertionHeuristic, DrtService, EdgeWeight, LinkDynamics, MetisOptions, PartitionMethod,
+        Partitioning, QSim, Replanning, Routing, StrategySetting, SubtourModeChoiceParams,
+        TeleportedParams, TimeAllocationMutatorParams, TrafficDynamics, TransitRoutingParams,
+        VertexWeight, WeightSchedulePoint, parse_key_val,
     };
     use crate::simulation::config::{Ids, Network, Population, Vehicles};
     use crate::simulation::config::{Logging, RoutingMode};
@@ -1271,6 +2136,9 @@ mod tests {
             sample_size: 0.1,
             stuck_threshold: 1,
             main_modes: vec!["bike".to_string()],
+            traffic_dynamics: TrafficDynamics::KinematicWaves,
+            hole_speed: 20.,
+            link_dynamics: LinkDynamics::PassingQueue,
         };
         let controller = Controller {
             first_iteration: 2,
@@ -1326,6 +2194,15 @@ mod tests {
         assert_eq!(parsed_config.qsim().sample_size, 0.1);
         assert_eq!(parsed_config.qsim().stuck_threshold, 1);
         assert_eq!(parsed_config.qsim().main_modes, vec!["bike"]);
+        assert_eq!(
+            parsed_config.qsim().traffic_dynamics,
+            TrafficDynamics::KinematicWaves
+        );
+        assert_eq!(parsed_config.qsim().hole_speed, 20.);
+        assert_eq!(
+            parsed_config.qsim().link_dynamics,
+            LinkDynamics::PassingQueue
+        );
     }
 
     #[test]
@@ -1439,6 +2316,7 @@ mod tests {
         assert_eq!(parsed_config.routing().mode, RoutingMode::UsePlans);
         assert_eq!(parsed_config.routing().network_modes, vec!["car", "bike"]);
         assert_eq!(parsed_config.routing().access_egress_mode, "walk");
+        assert_eq!(parsed_config.routing().travel_time_bin_size, 900);
         assert_eq!(
             parsed_config.routing().teleported_mode_params,
             vec![
@@ -1511,13 +2389,25 @@ mod tests {
             fraction_of_iterations_to_disable_innovation: 0.8
             max_agent_plan_memory: 7
             plan_selector_for_removal: BestScore
+            brain_exp_beta: 2.0
             strategy_settings:
               - name: ReRoute
                 weight: 0.1
                 subpopulation: person
+                disable_after_iteration: 50
+                weight_schedule:
+                  - iteration: 10
+                    weight: 0.1
+                  - iteration: 40
+                    weight: 0.0
               - name: BestScore
                 weight: 0.9
                 subpopulation: freight
+            time_allocation_mutator:
+              mutation_range: 900
+              affecting_duration: false
+            change_trip_mode:
+              modes: [ car, bike ]
         "#;
 
         let parsed_config: Config = serde_yaml::from_str(yaml).expect("failed to parse config");
@@ -1528,18 +2418,41 @@ mod tests {
                 fraction_of_iterations_to_disable_innovation: 0.8,
                 max_agent_plan_memory: 7,
                 plan_selector_for_removal: "BestScore".to_string(),
+                brain_exp_beta: 2.0,
                 strategy_settings: vec![
                     StrategySetting {
                         name: "ReRoute".to_string(),
                         weight: 0.1,
                         subpopulation: "person".to_string(),
+                        disable_after_iteration: Some(50),
+                        weight_schedule: vec![
+                            WeightSchedulePoint {
+                                iteration: 10,
+                                weight: 0.1,
+                            },
+                            WeightSchedulePoint {
+                                iteration: 40,
+                                weight: 0.0,
+                            },
+                        ],
                     },
                     StrategySetting {
                         name: "BestScore".to_string(),
                         weight: 0.9,
                         subpopulation: "freight".to_string(),
+                        ..Default::default()
                     },
                 ],
+                time_allocation_mutator: TimeAllocationMutatorParams {
+                    mutation_range: 900,
+                    affecting_duration: false,
+                },
+                change_trip_mode: ChangeTripModeParams {
+                    modes: vec!["car".to_string(), "bike".to_string()],
+                },
+                subtour_mode_choice: SubtourModeChoiceParams::default(),
+                diversity_generating_plans_remover: DiversityGeneratingPlansRemoverParams::default(
+                ),
             }
         );
     }
@@ -1554,11 +2467,216 @@ mod tests {
                 fraction_of_iterations_to_disable_innovation: 1.0,
                 max_agent_plan_memory: 5,
                 plan_selector_for_removal: WORST_SCORE_STRATEGY_NAME.to_string(),
+                brain_exp_beta: 1.0,
                 strategy_settings: vec![StrategySetting {
                     name: KEEP_LAST_SELECTED_STRATEGY_NAME.to_string(),
                     weight: 1.0,
                     subpopulation: "person".to_string(),
+                    ..Default::default()
                 }],
+                time_allocation_mutator: TimeAllocationMutatorParams::default(),
+                change_trip_mode: ChangeTripModeParams::default(),
+                subtour_mode_choice: SubtourModeChoiceParams::default(),
+                diversity_generating_plans_remover: DiversityGeneratingPlansRemoverParams::default(
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn read_drt_from_yaml() {
+        let yaml = r#"
+        modules:
+          drt:
+            type: Drt
+            services:
+              - mode: drt
+              - mode: taxi
+                network_mode: taxi
+                stop_duration: 30
+                max_wait_time: 300
+                max_travel_time_alpha: 2.0
+                max_travel_time_beta: 120
+                insertion: Append
+        "#;
+
+        let parsed_config: Config = serde_yaml::from_str(yaml).expect("failed to parse config");
+
+        assert_eq!(
+            parsed_config.drt(),
+            &Drt {
+                services: vec![
+                    DrtService {
+                        mode: "drt".to_string(),
+                        network_mode: "car".to_string(),
+                        stop_duration: 60,
+                        max_wait_time: 600,
+                        max_travel_time_alpha: 1.5,
+                        max_travel_time_beta: 600,
+                        insertion: DrtInsertionHeuristic::Extensive,
+                    },
+                    DrtService {
+                        mode: "taxi".to_string(),
+                        network_mode: "taxi".to_string(),
+                        stop_duration: 30,
+                        max_wait_time: 300,
+                        max_travel_time_alpha: 2.0,
+                        max_travel_time_beta: 120,
+                        insertion: DrtInsertionHeuristic::Append,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn drt_defaults_to_no_services() {
+        let config = Config::default();
+
+        assert!(config.drt().services.is_empty());
+    }
+
+    #[test]
+    fn read_analysis_from_yaml() {
+        let yaml = r#"
+        modules:
+          analysis:
+            type: Analysis
+            write_interval: 5
+            format: Parquet
+        "#;
+
+        let parsed_config: Config = serde_yaml::from_str(yaml).expect("failed to parse config");
+
+        assert_eq!(
+            parsed_config.analysis(),
+            &Analysis {
+                write_interval: 5,
+                leg_histogram_bin_size: 300,
+                format: AnalysisFormat::Parquet,
+            }
+        );
+    }
+
+    #[test]
+    fn analysis_defaults_are_available_on_default_config() {
+        let config = Config::default();
+
+        assert_eq!(config.analysis(), &Analysis::default());
+    }
+
+    #[test]
+    fn read_counts_from_yaml() {
+        let yaml = r#"
+        modules:
+          counts:
+            type: Counts
+            path: ./counts.xml
+        "#;
+
+        let parsed_config: Config = serde_yaml::from_str(yaml).expect("failed to parse config");
+
+        assert_eq!(
+            parsed_config.counts(),
+            &Counts {
+                path: Some(PathBuf::from("./counts.xml")),
+                write_interval: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn counts_default_to_no_path() {
+        let config = Config::default();
+
+        assert_eq!(config.counts().path, None);
+    }
+
+    #[test]
+    fn read_scoring_from_yaml() {
+        let yaml = r#"
+        modules:
+          scoring:
+            type: Scoring
+            learning_rate: 0.5
+            marginal_utility_of_performing: 8.0
+            activity_params:
+              - activity_type: h
+                typical_duration: 43200
+              - activity_type: w
+                typical_duration: 28800
+                typical_duration_score_computation: Uniform
+                opening_time: 25200
+                closing_time: 64800
+                latest_start_time: 32400
+            mode_params:
+              - mode: car
+                constant: -1.0
+              - mode: walk
+                marginal_utility_of_traveling: -12.0
+                marginal_utility_of_distance: -0.001
+        "#;
+
+        let parsed_config: Config = serde_yaml::from_str(yaml).expect("failed to parse config");
+
+        assert_eq!(
+            parsed_config.scoring(),
+            &Scoring {
+                learning_rate: 0.5,
+                marginal_utility_of_performing: 8.0,
+                activity_params: vec![
+                    ActivityParams {
+                        activity_type: "h".to_string(),
+                        typical_duration: 43200,
+                        typical_duration_score_computation:
+                            TypicalDurationScoreComputation::Relative,
+                        opening_time: None,
+                        closing_time: None,
+                        latest_start_time: None,
+                        earliest_end_time: None,
+                    },
+                    ActivityParams {
+                        activity_type: "w".to_string(),
+                        typical_duration: 28800,
+                        typical_duration_score_computation:
+                            TypicalDurationScoreComputation::Uniform,
+                        opening_time: Some(25200),
+                        closing_time: Some(64800),
+                        latest_start_time: Some(32400),
+                        earliest_end_time: None,
+                    },
+                ],
+                mode_params: vec![
+                    ModeParams {
+                        constant: -1.0,
+                        ..ModeParams::default_for_mode("car")
+                    },
+                    ModeParams {
+                        marginal_utility_of_traveling: -12.0,
+                        marginal_utility_of_distance: -0.001,
+                        ..ModeParams::default_for_mode("walk")
+                    },
+                ],
+                ..Scoring::default()
+            }
+        );
+    }
+
+    #[test]
+    fn scoring_defaults_are_available_on_default_config() {
+        let config = Config::default();
+
+        assert_eq!(
+            config.scoring(),
+            &Scoring {
+                learning_rate: 1.0,
+                marginal_utility_of_performing: 6.0,
+                marginal_utility_of_waiting: 0.0,
+                marginal_utility_of_late_arrival: -18.0,
+                marginal_utility_of_early_departure: 0.0,
+                marginal_utility_of_money: 1.0,
+                activity_params: Vec::new(),
+                mode_params: Vec::new(),
             }
         );
     }
@@ -1617,6 +2735,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn routing_walk_with_zero_speed_fails() {
+        let yaml = r#"
+        modules:
+          routing:
+            type: Routing
+            mode: UsePlans
+            teleported_mode_params:
+              - mode: walk
+                beeline_distance_factor: 1.3
+                teleported_mode_speed: 0.0
+        "#;
+
+        let error = serde_yaml::from_str::<Config>(yaml).unwrap_err();
+
+        assert!(
+            error.to_string().contains("teleported_mode_speed"),
+            "unexpected error: {error}"
+        );
+    }
+
     #[test]
     fn routing_explicit_walk_params_replace_defaults_without_duplicates() {
         let yaml = r#"
@@ -1712,6 +2851,44 @@ modules:
         );
     }
 
+    #[test]
+    fn test_transit_and_facilities_paths() {
+        let yaml = r#"
+modules:
+  transit_schedule:
+    type: TransitSchedule
+    path: schedule.xml
+  facilities:
+    type: Facilities
+    path: facilities.xml
+"#;
+
+        let file = write_temp_config(yaml);
+
+        let args = CommandLineArgs {
+            config: file.path().to_str().unwrap().to_string(),
+            overrides: vec![(
+                "transit_vehicles.path".to_string(),
+                "transit_vehicles.xml".to_string(),
+            )],
+        };
+
+        let config = Config::from_args(args);
+
+        assert_eq!(
+            config.transit_schedule().path,
+            Some(PathBuf::from("schedule.xml"))
+        );
+        assert_eq!(
+            config.transit_vehicles().path,
+            Some(PathBuf::from("transit_vehicles.xml"))
+        );
+        assert_eq!(
+            config.facilities().path,
+            Some(PathBuf::from("facilities.xml"))
+        );
+    }
+
     #[test]
     fn test_optional_path() {
         let yaml = r#"
@@ -1849,6 +3026,8 @@ modules:
 
         config.set_network(Network {
             path: Some("net".into()),
+            change_events_path: None,
+            lanes_path: None,
         });
         config.set_population(Population {
             path: Some("pop".into()),
@@ -1880,6 +3059,8 @@ modules:
                 beeline_distance_factor: 1.3,
                 teleported_mode_speed: 3.0 / 3.6,
             }],
+            travel_time_bin_size: 900,
+            transit_routing: TransitRoutingParams::default(),
         });
         config
     }
@@ -1891,6 +3072,31 @@ modules:
         assert_eq!(config.network().path, Some(PathBuf::from("new_net")));
     }
 
+    #[test]
+    fn override_network_change_events_path() {
+        let mut config = base_config();
+        assert_eq!(config.network().change_events_path, None);
+        config.apply_overrides(&[(
+            "network.change_events_path".to_string(),
+            "change_events.xml".to_string(),
+        )]);
+        assert_eq!(
+            config.network().change_events_path,
+            Some(PathBuf::from("change_events.xml"))
+        );
+    }
+
+    #[test]
+    fn override_network_lanes_path() {
+        let mut config = base_config();
+        assert_eq!(config.network().lanes_path, None);
+        config.apply_overrides(&[("network.lanes_path".to_string(), "lanes.xml".to_string())]);
+        assert_eq!(
+            config.network().lanes_path,
+            Some(PathBuf::from("lanes.xml"))
+        );
+    }
+
     #[test]
     fn override_partitioning_num_parts() {
         let mut config = base_config();
@@ -1908,6 +3114,143 @@ modules:
         assert_eq!(config.computational_setup().replanning_threads, 3);
     }
 
+    #[test]
+    fn override_analysis_settings() {
+        let mut config = base_config();
+        config.apply_overrides(&[
+            ("analysis.write_interval".to_string(), "3".to_string()),
+            (
+                "analysis.leg_histogram_bin_size".to_string(),
+                "900".to_string(),
+            ),
+            ("analysis.format".to_string(), "parquet".to_string()),
+        ]);
+        assert_eq!(config.analysis().write_interval, 3);
+        assert_eq!(config.analysis().leg_histogram_bin_size, 900);
+        assert_eq!(config.analysis().format, AnalysisFormat::Parquet);
+    }
+
+    #[test]
+    fn override_counts_settings() {
+        let mut config = base_config();
+        config.apply_overrides(&[
+            ("counts.path".to_string(), "counts.xml".to_string()),
+            ("counts.write_interval".to_string(), "5".to_string()),
+        ]);
+        assert_eq!(config.counts().path, Some(PathBuf::from("counts.xml")));
+        assert_eq!(config.counts().write_interval, 5);
+    }
+
+    #[test]
+    fn override_brain_exp_beta() {
+        let mut config = base_config();
+        config.apply_overrides(&[("replanning.brain_exp_beta".to_string(), "0.5".to_string())]);
+        assert_eq!(config.replanning().brain_exp_beta, 0.5);
+    }
+
+    #[test]
+    fn override_time_allocation_mutator_settings() {
+        let mut config = base_config();
+        config.apply_overrides(&[
+            (
+                "replanning.time_allocation_mutator.mutation_range".to_string(),
+                "600".to_string(),
+            ),
+            (
+                "replanning.time_allocation_mutator.affecting_duration".to_string(),
+                "false".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            config.replanning().time_allocation_mutator,
+            TimeAllocationMutatorParams {
+                mutation_range: 600,
+                affecting_duration: false,
+            }
+        );
+    }
+
+    #[test]
+    fn override_diversity_generating_plans_remover_settings() {
+        let mut config = base_config();
+        config.apply_overrides(&[
+            (
+                "replanning.diversity_generating_plans_remover.act_type_penalty".to_string(),
+                "1".to_string(),
+            ),
+            (
+                "replanning.diversity_generating_plans_remover.location_penalty".to_string(),
+                "2".to_string(),
+            ),
+            (
+                "replanning.diversity_generating_plans_remover.mode_penalty".to_string(),
+                "3".to_string(),
+            ),
+            (
+                "replanning.diversity_generating_plans_remover.route_penalty".to_string(),
+                "4.5".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            config.replanning().diversity_generating_plans_remover,
+            DiversityGeneratingPlansRemoverParams {
+                act_type_penalty: 1.0,
+                location_penalty: 2.0,
+                mode_penalty: 3.0,
+                route_penalty: 4.5,
+            }
+        );
+    }
+
+    #[test]
+    fn override_mode_choice_settings() {
+        let mut config = base_config();
+        config.apply_overrides(&[
+            (
+                "replanning.change_trip_mode.modes".to_string(),
+                "car, walk".to_string(),
+            ),
+            (
+                "replanning.subtour_mode_choice.modes".to_string(),
+                "car,bike,walk".to_string(),
+            ),
+            (
+                "replanning.subtour_mode_choice.chain_based_modes".to_string(),
+                "bike".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            config.replanning().change_trip_mode.modes,
+            vec!["car", "walk"]
+        );
+        assert_eq!(
+            config.replanning().subtour_mode_choice,
+            SubtourModeChoiceParams {
+                modes: vec!["car".to_string(), "bike".to_string(), "walk".to_string()],
+                chain_based_modes: vec!["bike".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn override_scoring_settings() {
+        let mut config = base_config();
+        config.apply_overrides(&[
+            ("scoring.learning_rate".to_string(), "0.2".to_string()),
+            (
+                "scoring.marginal_utility_of_performing".to_string(),
+                "4.5".to_string(),
+            ),
+            (
+                "scoring.marginal_utility_of_late_arrival".to_string(),
+                "-9".to_string(),
+            ),
+        ]);
+        assert_eq!(config.scoring().learning_rate, 0.2);
+        assert_eq!(config.scoring().marginal_utility_of_performing, 4.5);
+        assert_eq!(config.scoring().marginal_utility_of_late_arrival, -9.0);
+    }
+
     #[test]
     fn override_controller_and_qsim_settings() {
         let mut config = base_config();
@@ -1929,6 +3272,15 @@ modules:
             ("qsim.sample_size".to_string(), "0.25".to_string()),
             ("qsim.stuck_threshold".to_string(), "30".to_string()),
             ("qsim.main_modes".to_string(), "car,bike".to_string()),
+            (
+                "qsim.traffic_dynamics".to_string(),
+                "kinematic-waves".to_string(),
+            ),
+            ("qsim.hole_speed".to_string(), "12.5".to_string()),
+            (
+                "qsim.link_dynamics".to_string(),
+                "passing_queue".to_string(),
+            ),
         ]);
 
         assert_eq!(config.controller().first_iteration, 12);
@@ -1942,6 +3294,37 @@ modules:
         assert_eq!(config.qsim().sample_size, 0.25);
         assert_eq!(config.qsim().stuck_threshold, 30);
         assert_eq!(config.qsim().main_modes, vec!["car", "bike"]);
+        assert_eq!(
+            config.qsim().traffic_dynamics,
+            TrafficDynamics::KinematicWaves
+        );
+        assert_eq!(config.qsim().hole_speed, 12.5);
+        assert_eq!(config.qsim().link_dynamics, LinkDynamics::PassingQueue);
+    }
+
+    #[test]
+    fn read_qsim_with_zero_hole_speed_fails() {
+        let yaml = r#"
+        modules:
+          qsim:
+            type: QSim
+            traffic_dynamics: KinematicWaves
+            hole_speed: 0
+        "#;
+
+        let error = serde_yaml::from_str::<Config>(yaml).unwrap_err();
+
+        assert!(
+            error.to_string().contains("hole_speed"),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid hole_speed")]
+    fn override_negative_hole_speed_panics() {
+        let mut config = base_config();
+        config.apply_overrides(&[("qsim.hole_speed".to_string(), "-5".to_string())]);
     }
 
     #[test]
@@ -1951,6 +3334,16 @@ modules:
         assert_eq!(config.routing().mode, RoutingMode::AdHoc);
     }
 
+    #[test]
+    fn override_routing_travel_time_bin_size() {
+        let mut config = base_config();
+        config.apply_overrides(&[(
+            "routing.travel_time_bin_size".to_string(),
+            "300".to_string(),
+        )]);
+        assert_eq!(config.routing().travel_time_bin_size, 300);
+    }
+
     #[test]
     #[should_panic]
     fn override_routing_mode_invalid() {
@@ -1,8 +1,13 @@
 use crate::external_services::AdapterHandle;
+use crate::simulation::analysis::counts::{COUNTS_COMPARISON_FILE, Counts};
+use crate::simulation::analysis::iteration_stats::IterationStatsCollector;
+use crate::simulation::analysis::leg_histogram::{LEG_HISTOGRAM_FILE, LegHistogramCollector};
+use crate::simulation::analysis::link_volumes::{LINK_VOLUMES_FILE, LinkVolumesCollector};
+use crate::simulation::analysis::trips::{LEGS_FILE, TRIPS_FILE, TripsCollector};
 use crate::simulation::config::{Config, Logging, OverwriteFiles, WriteEvents, write_config};
 use crate::simulation::controller::{
-    ExternalServices, MobsimWorkerPool, MobsimWorkerPoolArgumentsBuilder, ReplanningPool,
-    create_output_filename,
+    ExternalServices, MobsimWorkerArgumentsBuilder, MobsimWorkerPool,
+    MobsimWorkerPoolArgumentsBuilder, ReplanningPool, create_output_filename, run_mobsim_partition,
 };
 use crate::simulation::events::EventHandlerRegisterFn;
 use crate::simulation::framework_events::{
@@ -10,20 +15,32 @@ use crate::simulation::framework_events::{
     MobsimListenerRegisterFn, PartitionListenerRegisterFn,
 };
 use crate::simulation::id::Id;
+use crate::simulation::messaging::sim_communication::SimCommunicator;
 use crate::simulation::population::agent_source::{
     DynAgentSource, IntoDynAgentSource, PopulationAgentSource,
 };
+use crate::simulation::replanning::registry::StrategyRegistry;
 use crate::simulation::replanning::routing::a_star::{AStar, AltHeuristic};
+use crate::simulation::replanning::routing::drt_routing::DrtRoutingModule;
 use crate::simulation::replanning::routing::least_cost_path_calculator::FreeSpeedTravelTimeAndDisutility;
 use crate::simulation::replanning::routing::network_routing::NetworkRoutingModule;
 use crate::simulation::replanning::routing::teleportation::TeleportationRoutingModule;
+use crate::simulation::replanning::routing::transit_routing::TransitRoutingModule;
+use crate::simulation::replanning::routing::travel_time_collector::{
+    TimeBinnedTravelTimeAndDisutility, routed_vehicle_modes,
+};
 use crate::simulation::replanning::routing::{RoutingModule, TripRouter};
+use crate::simulation::replanning::{
+    PlanSelector, PlanStrategy, PlanStrategyModule, ReplanningEnvironment,
+};
 use crate::simulation::scenario::population::Population;
 use crate::simulation::scenario::prepare_for_sim::prepare_for_sim;
 use crate::simulation::scenario::{ControllerScenario, Scenario};
+use crate::simulation::scoring::charypar_nagel::ScoringParameters;
+use crate::simulation::scoring::{ScoringEventsCollector, score_population};
 use crate::simulation::{id, io};
 use derive_more::Debug;
-use nohash_hasher::IntMap;
+use nohash_hasher::{IntMap, IntSet};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Barrier};
@@ -46,7 +63,18 @@ pub struct Controller {
     external_services: ExternalServices,
     global_barrier: Arc<Barrier>,
     adapter_handles: Vec<AdapterHandle>,
-    trip_router: TripRouter,
+    trip_router: Arc<TripRouter>,
+    travel_times: Arc<TimeBinnedTravelTimeAndDisutility>,
+    scoring_events: ScoringEventsCollector,
+    scoring_parameters: ScoringParameters,
+    iteration_stats: IterationStatsCollector,
+    leg_histogram: LegHistogramCollector,
+    link_volumes: LinkVolumesCollector,
+    trips: TripsCollector,
+    counts: Option<Counts>,
+    #[debug(skip)]
+    strategy_registry: StrategyRegistry,
+    partition_per_process: bool,
 }
 
 pub struct ControllerBuilder {
@@ -59,6 +87,8 @@ pub struct ControllerBuilder {
     external_services: ExternalServices,
     global_barrier: Option<Arc<Barrier>>,
     adapter_handles: Vec<AdapterHandle>,
+    strategy_registry: StrategyRegistry,
+    partition_per_process: bool,
 }
 
 impl ControllerBuilder {
@@ -73,11 +103,37 @@ impl ControllerBuilder {
             external_services: ExternalServices::default(),
             global_barrier: None,
             adapter_handles: Vec::new(),
+            strategy_registry: StrategyRegistry::default(),
+            partition_per_process: false,
         }
     }
 
     // Implementing a custom build function in order to set the barrier if not set by the user.
     pub fn build(mut self) -> Result<Controller, String> {
+        // DRT vehicles are dispatched by a single engine, which only knows the requests and
+        // vehicles of its own partition.
+        let num_parts = self.scenario.config.partitioning().num_parts;
+        if !self.scenario.config.drt().services.is_empty() && num_parts > 1 {
+            return Err(format!(
+                "DRT services can only be simulated on a single partition, but {num_parts} \
+                partitions are configured. Set partitioning.num_parts to 1."
+            ));
+        }
+
+        // Scoring and replanning need the plans of all partitions, which are not available if
+        // each process only simulates its own partition.
+        let controller_config = self.scenario.config.controller();
+        if self.partition_per_process
+            && controller_config.last_iteration != controller_config.first_iteration
+        {
+            return Err(format!(
+                "Running partitions in separate processes supports only a single iteration, as \
+                scoring and replanning are not available in this mode. Set \
+                controller.last_iteration to controller.first_iteration ({}) instead of {}.",
+                controller_config.first_iteration, controller_config.last_iteration
+            ));
+        }
+
         // create a barrier for the number of partitions, if not provided
         let barrier = self.global_barrier.take().unwrap_or_else(|| {
             Arc::new(Barrier::new(
@@ -85,15 +141,83 @@ impl ControllerBuilder {
             ))
         });
 
+        let scenario: ControllerScenario = self.scenario.into();
+        let config = scenario.core.config.clone();
+
+        // the stats of the scored plans are written into the output directory whenever an
+        // iteration ends.
+        let iteration_stats = IterationStatsCollector::new(io::resolve_path(
+            config.context(),
+            &config.output().output_dir,
+        ));
         let mut controller_event_manager = ControllerEventsManager::default();
+        iteration_stats.register()(&mut controller_event_manager);
         for register_fn in self.controller_event_register_fn {
             register_fn(&mut controller_event_manager);
         }
 
-        let scenario: ControllerScenario = self.scenario.into();
-        let config = scenario.core.config.clone();
-
-        let router = Self::create_trip_router(config.as_ref(), &scenario)?;
+        // link travel times of the routed main modes are collected per mode on every partition and
+        // used by the router after each mobsim run. Only the vehicles of the garage driving on
+        // these modes are collected, which leaves out transit and DRT vehicles, as their travel
+        // times include the stops.
+        let drt_modes: IntSet<Id<String>> = config
+            .drt()
+            .services
+            .iter()
+            .map(|service| Id::create(&service.mode))
+            .collect();
+        let routed_modes: IntSet<Id<String>> = config
+            .qsim()
+            .main_modes
+            .iter()
+            .map(|mode| Id::create(mode))
+            .filter(|mode| !drt_modes.contains(mode))
+            .collect();
+        let travel_times = Arc::new(TimeBinnedTravelTimeAndDisutility::new(
+            num_parts,
+            config.routing().travel_time_bin_size,
+            routed_vehicle_modes(&scenario.core.garage, &routed_modes),
+        ));
+        for rank in 0..num_parts {
+            self.event_handler_register_fn
+                .entry(rank)
+                .or_default()
+                .push(travel_times.register(rank));
+        }
+        let router = Self::create_trip_router(config.as_ref(), &scenario, travel_times.clone())?;
+
+        // scoring events are collected on every partition and merged in the scoring phase.
+        let scoring_events = ScoringEventsCollector::new(num_parts);
+        for rank in 0..num_parts {
+            self.event_handler_register_fn
+                .entry(rank)
+                .or_default()
+                .push(scoring_events.register(rank));
+        }
+        let scoring_parameters = ScoringParameters::from_config(config.scoring());
+
+        // leg histograms, link volumes and trips are collected on every partition and merged when
+        // they are written.
+        let leg_histogram =
+            LegHistogramCollector::new(num_parts, config.analysis().leg_histogram_bin_size);
+        let link_volumes = LinkVolumesCollector::new(num_parts);
+        let trips = TripsCollector::new(
+            num_parts,
+            config.analysis().write_interval,
+            config.controller().last_iteration,
+        );
+        for rank in 0..num_parts {
+            let handlers = self.event_handler_register_fn.entry(rank).or_default();
+            handlers.push(leg_histogram.register(rank));
+            handlers.push(link_volumes.register(rank));
+            handlers.push(trips.register(rank));
+        }
+        // observed link volumes are compared with the merged link volumes.
+        let counts = config
+            .counts()
+            .path
+            .as_ref()
+            .map(|path| Counts::from_file(&io::resolve_path(config.context(), path)));
 
         Ok(Controller {
             scenario,
@@ -106,7 +230,17 @@ impl ControllerBuilder {
             external_services: self.external_services,
             global_barrier: barrier,
             adapter_handles: self.adapter_handles,
-            trip_router: router,
+            trip_router: Arc::new(router),
+            travel_times,
+            scoring_events,
+            scoring_parameters,
+            iteration_stats,
+            leg_histogram,
+            link_volumes,
+            trips,
+            counts,
+            strategy_registry: self.strategy_registry,
+            partition_per_process: self.partition_per_process,
         })
     }
 
@@ -162,9 +296,48 @@ impl ControllerBuilder {
         self
     }
 
+    /// Builds a controller for [Controller::run_partition], which simulates a single partition in
+    /// this process. Such controllers support only a single iteration.
+    pub fn partition_per_process(mut self, b: bool) -> Self {
+        self.partition_per_process = b;
+        self
+    }
+
+    /// Registers an innovative replanning strategy, which can be referenced by `name` in the
+    /// strategy settings of the replanning config.
+    pub fn plan_strategy<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(&ReplanningEnvironment) -> Box<dyn PlanStrategy> + Send + Sync + 'static,
+    {
+        self.strategy_registry.register_strategy(name, factory);
+        self
+    }
+
+    /// Registers an innovative replanning strategy which adapts a copy of the last selected plan
+    /// with the created module.
+    pub fn plan_strategy_module<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(&ReplanningEnvironment) -> Box<dyn PlanStrategyModule> + Send + Sync + 'static,
+    {
+        self.strategy_registry
+            .register_strategy_module(name, factory);
+        self
+    }
+
+    /// Registers a plan selector, which can be referenced by `name` in the strategy settings and
+    /// as `plan_selector_for_removal` of the replanning config.
+    pub fn plan_selector<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(&ReplanningEnvironment) -> Box<dyn PlanSelector> + Send + Sync + 'static,
+    {
+        self.strategy_registry.register_selector(name, factory);
+        self
+    }
+
     fn create_trip_router(
         config: &Config,
         controller_scenario: &ControllerScenario,
+        travel_times: Arc<TimeBinnedTravelTimeAndDisutility>,
     ) -> Result<TripRouter, String> {
         let mut routers: IntMap<Id<String>, Arc<dyn RoutingModule>> = IntMap::default();
 
@@ -193,12 +366,12 @@ impl ControllerBuilder {
                     id.external(),
                 ));
             };
-            let time_utility = Arc::new(FreeSpeedTravelTimeAndDisutility);
+            let mode_travel_times = Arc::new(travel_times.for_mode(id.clone()));
             let astar = AStar::<AltHeuristic>::new(
                 controller_scenario.core.network.clone(),
                 Some(id.clone()),
-                time_utility.clone(),
-                time_utility,
+                mode_travel_times.clone(),
+                mode_travel_times,
             )
             .map_err(|error| {
                 format!(
@@ -217,6 +390,53 @@ impl ControllerBuilder {
             routers.insert(id, module);
         }
 
+        // if a transit schedule is loaded, route pt trips on it.
+        if !controller_scenario.core.transit_schedule.lines().is_empty() {
+            let id = Id::create("pt");
+            let Some(access_egress) = routers.get(&access_egress_mode).cloned() else {
+                return Err(format!(
+                    "No {} access/egress router found for mode {}. Please ensure that the teleported mode params include the configured access/egress mode.",
+                    access_egress_mode.external(),
+                    id.external(),
+                ));
+            };
+
+            let module: Arc<dyn RoutingModule> = Arc::new(TransitRoutingModule::new(
+                id.clone(),
+                access_egress,
+                controller_scenario.core.clone(),
+            ));
+
+            routers.insert(id, module);
+        }
+
+        // DRT legs get the direct ride as route. Vehicles drive on free speed paths, so the
+        // direct ride is routed on free speed travel times as well.
+        for service in &config.drt().services {
+            let id = Id::create(&service.mode);
+            let free_speed = Arc::new(FreeSpeedTravelTimeAndDisutility);
+            let astar = AStar::<AltHeuristic>::new(
+                controller_scenario.core.network.clone(),
+                Some(Id::create(&service.network_mode)),
+                free_speed.clone(),
+                free_speed,
+            )
+            .map_err(|error| {
+                format!(
+                    "Failed to create network router for mode {}: {error}",
+                    id.external()
+                )
+            })?;
+
+            let module: Arc<dyn RoutingModule> = Arc::new(DrtRoutingModule::new(
+                id.clone(),
+                Box::new(astar),
+                controller_scenario.core.network.clone(),
+            ));
+
+            routers.insert(id, module);
+        }
+
         Ok(TripRouter::new(routers))
     }
 }
@@ -257,8 +477,17 @@ impl Controller {
             fs::create_dir_all(&log_path).expect("Failed to create logs output path");
         }
 
+        let strategy_weights_path = output_path.join("strategy_weights.csv");
+        create_strategy_weights_file(&strategy_weights_path);
+
         let mut mobsim_workers = self.start_mobsim_workers();
-        let replanning_pool = ReplanningPool::new(&self.config);
+        let replanning_pool = ReplanningPool::new(
+            &ReplanningEnvironment {
+                scenario: self.scenario.core.clone(),
+                trip_router: self.trip_router.clone(),
+            },
+            &self.strategy_registry,
+        );
 
         for iteration in first_iteration..=last_iteration {
             self.run_iteration(
@@ -267,6 +496,7 @@ impl Controller {
                 &mut mobsim_workers,
                 &replanning_pool,
                 &iters_path,
+                &strategy_weights_path,
             );
         }
 
@@ -289,6 +519,80 @@ impl Controller {
             .process_event(ControllerEvent::shutdown(true));
     }
 
+    /// Runs the mobsim of the partition with the rank of `communicator` in this process. The
+    /// scenario must only hold the persons of this partition, see [Scenario::load_partition]. The
+    /// other partitions are simulated by other processes, which exchange vehicles with this one
+    /// through the communicator. Scoring and replanning need the plans of all partitions, which is
+    /// why this mode only supports a single iteration, which is checked when the controller is
+    /// built with [ControllerBuilder::partition_per_process]. Every rank writes the events and the plans
+    /// of its own partition.
+    pub fn run_partition<C: SimCommunicator>(mut self, communicator: C) {
+        assert!(
+            self.partition_per_process,
+            "Controllers running a single partition must be built with \
+            ControllerBuilder::partition_per_process."
+        );
+        let rank = communicator.rank();
+        let iteration = self.config.controller().first_iteration;
+        let output_path = io::resolve_path(self.config.context(), &self.config.output().output_dir);
+
+        if rank == 0 {
+            prepare_output_directory(&output_path, self.config.output().overwrite_files)
+                .unwrap_or_else(|err| panic!("{err}"));
+            fs::create_dir_all(output_path.join("ITERS"))
+                .expect("Failed to create iters output path");
+        }
+        // the other ranks must not write any output before rank 0 has prepared the directory.
+        communicator.barrier();
+
+        prepare_for_sim(&mut self.scenario, &self.trip_router)
+            .unwrap_or_else(|err| panic!("{err}: {:?}", err.issues()));
+        let input = self.scenario.partition_for_mobsim(rank);
+
+        let args = MobsimWorkerArgumentsBuilder::default()
+            .rank(rank)
+            .communicator(communicator)
+            .scenario_core(self.scenario.core.clone())
+            .agent_source(self.agent_source.clone())
+            .external_services(self.external_services.clone())
+            .event_handler(
+                self.event_handler_per_partition
+                    .remove(&rank)
+                    .unwrap_or_default(),
+            )
+            .mobsim_event_listener(
+                self.mobsim_event_listener_per_partition
+                    .remove(&rank)
+                    .unwrap_or_default(),
+            )
+            .partition_event_listener(
+                self.partition_event_listener_per_partition
+                    .remove(&rank)
+                    .unwrap_or_default(),
+            )
+            .global_barrier(self.global_barrier.clone())
+            .build()
+            .unwrap();
+        let agents = run_mobsim_partition(args, iteration, input);
+        self.shutdown_adapters();
+
+        info!("Writing output files of rank {rank}:");
+        Population::from_agents(agents).to_file(
+            &output_path.join(
+                self.config
+                    .controller()
+                    .compression_type
+                    .with_extension(&format!("output_plans.{rank}")),
+            ),
+        );
+        if rank == 0 {
+            info!("    ... Config ...");
+            self.write_output_config(output_path.clone());
+            info!("    ... Network ...");
+            self.write_output_network(output_path);
+        }
+    }
+
     fn run_iteration(
         &mut self,
         iteration: u32,
@@ -296,6 +600,7 @@ impl Controller {
         mobsim_workers: &mut MobsimWorkerPool,
         replanning_pool: &ReplanningPool,
         iters_path: impl AsRef<Path>,
+        strategy_weights_path: &Path,
     ) {
         let is_last_iteration = iteration == end_iter;
         info!("=========== Start Iteration {} ===========", iteration);
@@ -307,12 +612,23 @@ impl Controller {
         let population = self.run_scoring_phase(iteration, is_last_iteration, population);
 
         if self.should_write_iteration_plans(iteration, is_last_iteration) {
-            self.write_iteration_files(iteration, iters_path, &population);
+            self.write_iteration_files(iteration, &iters_path, &population);
+        }
+        if self.should_write_analysis(iteration, is_last_iteration) {
+            self.write_analysis(iteration, &iters_path);
+        }
+        if self.should_write_counts_comparison(iteration, is_last_iteration) {
+            self.write_counts_comparison(iteration, &iters_path);
         }
 
         let population = if is_last_iteration {
             population
         } else {
+            append_strategy_weights(
+                strategy_weights_path,
+                iteration,
+                &replanning_pool.effective_weights(iteration),
+            );
             self.run_replanning_phase(iteration, replanning_pool, population)
         };
 
@@ -339,6 +655,7 @@ impl Controller {
             .unwrap_or_else(|err| panic!("{err}: {:?}", err.issues()));
         let inputs = self.scenario.split_for_mobsim();
         let agents = mobsim_workers.run_mobsim(iteration, is_last_iteration, inputs);
+        self.travel_times.update();
 
         self.controller_events_manager
             .process_event(ControllerEvent::after_mobsim(is_last_iteration));
@@ -350,10 +667,18 @@ impl Controller {
         &mut self,
         iteration: u32,
         is_last_iteration: bool,
-        population: Population,
+        mut population: Population,
     ) -> Population {
         info!("Starting scoring phase for iteration {iteration}");
 
+        score_population(
+            &mut population,
+            self.scoring_events.take_records(),
+            &self.scoring_parameters,
+            &self.scenario.core.network,
+        );
+        self.iteration_stats.record(iteration, &population);
+
         self.controller_events_manager
             .process_event(ControllerEvent::scoring(is_last_iteration));
 
@@ -466,12 +791,95 @@ impl Controller {
         );
     }
 
+    fn write_analysis(&self, iteration: u32, iters_path: impl AsRef<Path>) {
+        let iter_path = iters_path.as_ref().join(format!("it.{}", iteration));
+        fs::create_dir_all(&iter_path).expect("Failed to create iteration output path");
+        let format = self.config.analysis().format;
+        self.leg_histogram.merged().write(
+            &iter_path.join(format.with_extension(LEG_HISTOGRAM_FILE)),
+            format,
+        );
+        self.link_volumes.merged().write(
+            &iter_path.join(format.with_extension(LINK_VOLUMES_FILE)),
+            format,
+        );
+        let trips = self.trips.tables(&self.scenario.core.network);
+        trips.write_trips(&iter_path.join(format.with_extension(TRIPS_FILE)), format);
+        trips.write_legs(&iter_path.join(format.with_extension(LEGS_FILE)), format);
+    }
+
+    fn write_counts_comparison(&self, iteration: u32, iters_path: impl AsRef<Path>) {
+        let Some(counts) = &self.counts else {
+            return;
+        };
+        let iter_path = iters_path.as_ref().join(format!("it.{}", iteration));
+        fs::create_dir_all(&iter_path).expect("Failed to create iteration output path");
+        let format = self.config.analysis().format;
+        counts.write_comparison(
+            &self.link_volumes.merged(),
+            self.config.qsim().sample_size,
+            &iter_path.join(format.with_extension(COUNTS_COMPARISON_FILE)),
+            format,
+        );
+    }
+
+    fn should_write_analysis(&self, iteration: u32, is_last_iteration: bool) -> bool {
+        is_write_iteration(
+            self.config.analysis().write_interval,
+            iteration,
+            is_last_iteration,
+        )
+    }
+
+    fn should_write_counts_comparison(&self, iteration: u32, is_last_iteration: bool) -> bool {
+        self.counts.is_some()
+            && is_write_iteration(
+                self.config.counts().write_interval,
+                iteration,
+                is_last_iteration,
+            )
+    }
+
     fn should_write_iteration_plans(&self, iteration: u32, is_last_iteration: bool) -> bool {
         is_last_iteration
             || (iteration != 0 && iteration % self.config.controller().write_plans_interval == 0)
     }
 }
 
+/// Whether an output with the given write interval is written in this iteration. An interval of 0
+/// disables the output.
+pub(crate) fn is_write_iteration(interval: u32, iteration: u32, is_last_iteration: bool) -> bool {
+    interval > 0 && (is_last_iteration || iteration % interval == 0)
+}
+
+/// The effective strategy weights of every iteration with replanning are appended to this file, so
+/// that weight schedules and disabled strategies can be audited.
+fn create_strategy_weights_file(path: &Path) {
+    let mut writer = csv::Writer::from_path(path).expect("Failed to create strategy weights file");
+    writer
+        .write_record(["iteration", "subpopulation", "strategy", "weight"])
+        .expect("Failed to write strategy weights header");
+}
+
+fn append_strategy_weights(path: &Path, iteration: u32, weights: &[(Id<String>, Id<String>, f64)]) {
+    let file = fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .expect("Failed to open strategy weights file");
+    let mut writer = csv::Writer::from_writer(file);
+    for (subpopulation, strategy, weight) in weights {
+        writer
+            .write_record([
+                iteration.to_string(),
+                subpopulation.external().to_string(),
+                strategy.external().to_string(),
+                weight.to_string(),
+            ])
+            .expect("Failed to write strategy weights");
+    }
+    writer.flush().expect("Failed to write strategy weights");
+}
+
 fn prepare_output_directory(
     output_path: &Path,
     overwrite_files: OverwriteFiles,
@@ -9,12 +9,15 @@ use crate::simulation::framework_events::{
     MobsimEventsManager, MobsimListenerRegisterFn, PartitionEventsManager,
     PartitionListenerRegisterFn,
 };
+use crate::simulation::id::Id;
 use crate::simulation::io::proto::proto_events::ProtoEventsWriter;
 use crate::simulation::io::xml::events::XmlEventsWriter;
+use crate::simulation::messaging::sim_communication::SimCommunicator;
 use crate::simulation::messaging::sim_communication::local_communicator::ChannelSimCommunicator;
 use crate::simulation::messaging::sim_communication::message_broker::NetMessageBroker;
 use crate::simulation::population::agent_source::DynAgentSource;
-use crate::simulation::replanning::{StrategyManager, replan_population};
+use crate::simulation::replanning::registry::StrategyRegistry;
+use crate::simulation::replanning::{ReplanningEnvironment, StrategyManager, replan_population};
 use crate::simulation::scenario::population::Population;
 use crate::simulation::scenario::{MobsimInput, ScenarioCore};
 use crate::simulation::simulation::{Simulation, SimulationBuilder};
@@ -201,9 +204,9 @@ pub(crate) struct MobsimWorkerPoolArguments {
 
 #[derive(Builder)]
 #[builder(pattern = "owned")]
-struct MobsimWorkerArguments {
+pub(crate) struct MobsimWorkerArguments<C: SimCommunicator> {
     rank: u32,
-    communicator: ChannelSimCommunicator,
+    communicator: C,
     scenario_core: ScenarioCore,
     agent_source: DynAgentSource,
     #[builder(default)]
@@ -217,10 +220,10 @@ struct MobsimWorkerArguments {
     global_barrier: Arc<Barrier>,
 }
 
-struct MobsimWorker {
+struct MobsimWorker<C: SimCommunicator> {
     rank: u32,
     // the current implementation requires a new worker every iteration. Thus, the worker holds a reference to the communicator, but does not own it.
-    communicator: Rc<ChannelSimCommunicator>,
+    communicator: Rc<C>,
     scenario_core: ScenarioCore,
     agent_source: DynAgentSource,
     comp_env: ThreadLocalComputationalEnvironment,
@@ -364,7 +367,7 @@ impl MobsimWorkerPool {
 }
 
 fn run_mobsim_worker(
-    args: MobsimWorkerArguments,
+    args: MobsimWorkerArguments<ChannelSimCommunicator>,
     command_receiver: StdReceiver<MobsimWorkerCommand>,
     result_sender: StdSender<MobsimWorkerResult>,
 ) {
@@ -376,8 +379,25 @@ fn run_mobsim_worker(
     drop(_guards);
 }
 
-impl MobsimWorker {
-    fn new(args: MobsimWorkerArguments) -> Self {
+/// Runs the mobsim of a single partition on the current thread. This is used when the partitions
+/// are simulated in separate processes, which exchange vehicles through the communicator.
+pub(crate) fn run_mobsim_partition<C: SimCommunicator>(
+    args: MobsimWorkerArguments<C>,
+    iteration: u32,
+    input: MobsimInput,
+) -> Vec<SimulationAgent> {
+    let mut worker = MobsimWorker::new(args);
+    let _guards = logging::init_logging(&worker.scenario_core.config, worker.rank);
+
+    let agents = worker.run_iteration(iteration, input);
+    worker.comp_env.finish_events();
+
+    drop(_guards);
+    agents
+}
+
+impl<C: SimCommunicator> MobsimWorker<C> {
+    fn new(args: MobsimWorkerArguments<C>) -> Self {
         let MobsimWorkerArguments {
             rank,
             communicator,
@@ -489,7 +509,7 @@ impl MobsimWorker {
         );
 
         // Create a new simulation for this worker each iteration. This makes sure that there is no state carried over from previous iterations, which could lead to bugs.
-        let mut simulation: Simulation<ChannelSimCommunicator> = SimulationBuilder::new(
+        let mut simulation: Simulation<C> = SimulationBuilder::new(
             input,
             net_message_broker,
             self.comp_env.clone(),
@@ -520,7 +540,8 @@ pub(crate) struct ReplanningPool {
 }
 
 impl ReplanningPool {
-    pub(crate) fn new(config: &Config) -> Self {
+    pub(crate) fn new(environment: &ReplanningEnvironment, registry: &StrategyRegistry) -> Self {
+        let config = environment.scenario.config.as_ref();
         let threads = config.computational_setup().replanning_threads;
         let pool = if threads == 0 {
             None
@@ -535,7 +556,11 @@ impl ReplanningPool {
         };
         Self {
             pool,
-            strategy_manager: StrategyManager::from_replanning_config(config.replanning()),
+            strategy_manager: StrategyManager::from_replanning_config(
+                config.replanning(),
+                environment,
+                registry,
+            ),
             first_iteration: config.controller().first_iteration,
             last_iteration: config.controller().last_iteration,
             innovation_disable_fraction: config
@@ -571,6 +596,12 @@ impl ReplanningPool {
         }
     }
 
+    /// Weights of the strategies per subpopulation, as they are used in `iteration`.
+    pub(crate) fn effective_weights(&self, iteration: u32) -> Vec<(Id<String>, Id<String>, f64)> {
+        self.strategy_manager
+            .effective_weights_per_subpopulation(iteration, self.innovation_disabled(iteration))
+    }
+
     fn innovation_disabled(&self, iteration: u32) -> bool {
         let total_iterations = self.last_iteration.saturating_sub(self.first_iteration);
         let progress = if total_iterations == 0 {
@@ -783,6 +814,11 @@ mod tests {
     use crate::simulation::id::Id;
     use crate::simulation::network::sim_network::SimNetworkPartition;
     use crate::simulation::population::agent_source::PopulationAgentSource;
+    use crate::simulation::pt::TransitSchedule;
+    use crate::simulation::replanning::ReplanningEnvironment;
+    use crate::simulation::replanning::registry::StrategyRegistry;
+    use crate::simulation::replanning::routing::TripRouter;
+    use crate::simulation::scenario::facilities::ActivityFacilities;
     use crate::simulation::scenario::network::Network;
     use crate::simulation::scenario::population::{InternalPerson, InternalPlan, Population};
     use crate::simulation::scenario::vehicles::Garage;
@@ -790,7 +826,7 @@ mod tests {
         MobsimInput, MobsimScenarioPartition, PopulationShard, ScenarioCore,
     };
     use macros::deterministic_id_test;
-    use nohash_hasher::IntSet;
+    use nohash_hasher::{IntMap, IntSet};
     use std::sync::{Arc, Barrier};
 
     #[deterministic_id_test]
@@ -801,6 +837,9 @@ mod tests {
         let scenario_core = ScenarioCore {
             network: Arc::new(Network::new()),
             garage: Arc::new(Garage::default()),
+            transit_schedule: Arc::new(TransitSchedule::default()),
+            transit_vehicles: Arc::new(Garage::default()),
+            facilities: Arc::new(ActivityFacilities::default()),
             config: config.clone(),
         };
 
@@ -825,7 +864,21 @@ mod tests {
     fn replanning_pool_noop_preserves_person_ids() {
         let mut config = Config::default();
         config.computational_setup_mut().replanning_threads = 2;
-        let pool = ReplanningPool::new(&config);
+        let config = Arc::new(config);
+        let pool = ReplanningPool::new(
+            &ReplanningEnvironment {
+                scenario: ScenarioCore {
+                    network: Arc::new(Network::new()),
+                    garage: Arc::new(Garage::default()),
+                    transit_schedule: Arc::new(TransitSchedule::default()),
+                    transit_vehicles: Arc::new(Garage::default()),
+                    facilities: Arc::new(ActivityFacilities::default()),
+                    config: config.clone(),
+                },
+                trip_router: Arc::new(TripRouter::new(IntMap::default())),
+            },
+            &StrategyRegistry::default(),
+        );
 
         let population = Population::from_persons(vec![
             person("replanning-pool-person-1"),
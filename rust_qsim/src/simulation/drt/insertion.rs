@@ -0,0 +1,551 @@
+use crate::simulation::config::DrtInsertionHeuristic;
+use crate::simulation::drt::{DrtRequest, DrtRouter, DrtSchedule, DrtStop};
+use crate::simulation::id::Id;
+use crate::simulation::scenario::network::Link;
+use crate::simulation::time::SimTime;
+use nohash_hasher::IntMap;
+use std::time::Duration;
+
+/// A vehicle which is considered for the insertion of a request.
+pub struct InsertionCandidate<'a> {
+    pub schedule: &'a DrtSchedule,
+    pub capacity: usize,
+    pub service_end: SimTime,
+}
+
+/// Where to insert a request into the schedule of a candidate. The pickup is inserted before the
+/// stop at `pickup_index` and the dropoff before the stop at `dropoff_index` of the original
+/// schedule. If both indices are equal, the dropoff directly follows the pickup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insertion {
+    pub candidate: usize,
+    pub pickup_index: usize,
+    pub dropoff_index: usize,
+    /// Time the insertion adds to the end of the candidate's schedule.
+    pub cost: Duration,
+}
+
+impl Insertion {
+    /// Inserts the pickup and dropoff stops of `request` into `schedule`.
+    pub fn apply(&self, schedule: &mut DrtSchedule, request: &DrtRequest) {
+        schedule.stops.insert(
+            self.pickup_index,
+            DrtStop::pickup(request.from_link.clone(), request.id.clone()),
+        );
+        schedule.stops.insert(
+            self.dropoff_index + 1,
+            DrtStop::dropoff(request.to_link.clone(), request.id.clone()),
+        );
+    }
+}
+
+/// Finds the insertion of `request` which adds the least time to the schedule of one of the
+/// `candidates` while keeping the time windows of all requests, the capacity and the service end
+/// of the vehicle. Stops of other requests may be delayed beyond their time windows only as far as
+/// they would be delayed without the insertion. Ties are broken in favor of earlier candidates.
+pub fn find_best_insertion(
+    heuristic: DrtInsertionHeuristic,
+    request: &DrtRequest,
+    candidates: &[InsertionCandidate],
+    requests: &IntMap<Id<DrtRequest>, DrtRequest>,
+    stop_duration: Duration,
+    now: SimTime,
+    router: &mut DrtRouter,
+) -> Option<Insertion> {
+    let mut best: Option<Insertion> = None;
+
+    for (index, candidate) in candidates.iter().enumerate() {
+        let schedule = candidate.schedule;
+        let n = schedule.stops.len();
+        let stops: Vec<_> = (0..n).map(VirtualStop::Existing).collect();
+        let Some(baseline) = simulate(
+            candidate,
+            &stops,
+            None,
+            request,
+            requests,
+            stop_duration,
+            now,
+            router,
+        ) else {
+            // vehicles whose current schedule is not drivable are not considered
+            continue;
+        };
+
+        let positions: Vec<(usize, usize)> = match heuristic {
+            DrtInsertionHeuristic::Extensive => {
+                (0..=n).flat_map(|i| (i..=n).map(move |j| (i, j))).collect()
+            }
+            DrtInsertionHeuristic::Append => vec![(n, n)],
+        };
+
+        for (pickup_index, dropoff_index) in positions {
+            let stops = virtual_stops(n, pickup_index, dropoff_index);
+            let Some(result) = simulate(
+                candidate,
+                &stops,
+                Some(&baseline.arrivals),
+                request,
+                requests,
+                stop_duration,
+                now,
+                router,
+            ) else {
+                continue;
+            };
+            if result.end_time > candidate.service_end {
+                continue;
+            }
+
+            let cost = result.end_time.duration_since(baseline.end_time);
+            if best.as_ref().is_none_or(|b| cost < b.cost) {
+                best = Some(Insertion {
+                    candidate: index,
+                    pickup_index,
+                    dropoff_index,
+                    cost,
+                });
+            }
+        }
+    }
+
+    best
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VirtualStop {
+    Existing(usize),
+    Pickup,
+    Dropoff,
+}
+
+/// The stop sequence `orig[..i], pickup, orig[i..j], dropoff, orig[j..]`.
+fn virtual_stops(n: usize, i: usize, j: usize) -> Vec<VirtualStop> {
+    let mut stops = Vec::with_capacity(n + 2);
+    stops.extend((0..i).map(VirtualStop::Existing));
+    stops.push(VirtualStop::Pickup);
+    stops.extend((i..j).map(VirtualStop::Existing));
+    stops.push(VirtualStop::Dropoff);
+    stops.extend((j..n).map(VirtualStop::Existing));
+    stops
+}
+
+struct SimulatedSchedule {
+    /// Arrival times at the existing stops of the schedule.
+    arrivals: Vec<SimTime>,
+    end_time: SimTime,
+}
+
+/// Drives through the given sequence of stops and returns the arrival times at the existing stops
+/// and the time the last stop is left. Returns `None` if a stop can't be reached or if, with
+/// `baseline` given, a constraint is violated.
+#[allow(clippy::too_many_arguments)]
+fn simulate(
+    candidate: &InsertionCandidate,
+    stops: &[VirtualStop],
+    baseline: Option<&Vec<SimTime>>,
+    request: &DrtRequest,
+    requests: &IntMap<Id<DrtRequest>, DrtRequest>,
+    stop_duration: Duration,
+    now: SimTime,
+    router: &mut DrtRouter,
+) -> Option<SimulatedSchedule> {
+    let schedule = candidate.schedule;
+    let mut time = schedule.start_time.max(now);
+    let mut link: &Id<Link> = &schedule.start_link;
+    let mut occupancy = schedule.start_occupancy as isize;
+    let mut arrivals = vec![SimTime::default(); schedule.stops.len()];
+    let mut last_arrival: Option<SimTime> = None;
+
+    for stop in stops {
+        let (stop_link, change) = match stop {
+            VirtualStop::Existing(k) => {
+                let existing = &schedule.stops[*k];
+                (&existing.link, existing.occupancy_change())
+            }
+            VirtualStop::Pickup => (&request.from_link, 1),
+            VirtualStop::Dropoff => (&request.to_link, -1),
+        };
+        // consecutive stops on the same link are served at once, see `DrtEngine::serve_stop`
+        let merged = last_arrival.is_some() && link == stop_link;
+        let arrival = match last_arrival {
+            Some(last_arrival) if merged => last_arrival,
+            _ => time.saturating_add(router.travel_time(link, stop_link)?),
+        };
+
+        if let Some(baseline) = baseline {
+            let latest = match stop {
+                VirtualStop::Existing(k) => {
+                    latest_arrival(&schedule.stops[*k], requests).max(baseline[*k])
+                }
+                VirtualStop::Pickup => request.latest_start_time,
+                VirtualStop::Dropoff => request.latest_arrival_time,
+            };
+            if arrival > latest {
+                return None;
+            }
+        }
+
+        occupancy += change;
+        if baseline.is_some() && occupancy > candidate.capacity as isize {
+            return None;
+        }
+
+        if let VirtualStop::Existing(k) = stop {
+            arrivals[*k] = arrival;
+        }
+        if !merged {
+            time = arrival.saturating_add(stop_duration);
+        }
+        last_arrival = Some(arrival);
+        link = stop_link;
+    }
+
+    Some(SimulatedSchedule {
+        arrivals,
+        end_time: time,
+    })
+}
+
+/// The latest time a vehicle may arrive at `stop` without violating the time windows of the
+/// requests served there.
+fn latest_arrival(stop: &DrtStop, requests: &IntMap<Id<DrtRequest>, DrtRequest>) -> SimTime {
+    let pickups = stop
+        .pickups
+        .iter()
+        .map(|id| requests.get(id).unwrap().latest_start_time);
+    let dropoffs = stop
+        .dropoffs
+        .iter()
+        .map(|id| requests.get(id).unwrap().latest_arrival_time);
+    pickups.chain(dropoffs).min().unwrap_or(SimTime::max())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Insertion, InsertionCandidate, VirtualStop, find_best_insertion, simulate};
+    use crate::simulation::config::DrtInsertionHeuristic;
+    use crate::simulation::drt::{DrtRequest, DrtRouter, DrtSchedule, DrtStop};
+    use crate::simulation::id::Id;
+    use crate::simulation::scenario::Coordinate;
+    use crate::simulation::scenario::network::{Link, Network, Node};
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use nohash_hasher::IntMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const STOP_DURATION: Duration = Duration::from_secs(10);
+
+    #[deterministic_id_test]
+    fn inserts_request_into_empty_schedule() {
+        let mut router = router();
+        let mut schedule = DrtSchedule::new(Id::get_from_ext("l1"), SimTime::default());
+        let request = request("b", "l2", "l3", 100, 100);
+
+        let insertion = best_insertion(
+            DrtInsertionHeuristic::Extensive,
+            &request,
+            &schedule,
+            1,
+            &IntMap::default(),
+            &mut router,
+        );
+
+        // drive to l2 (10s), pick up (10s), drive to l3 (10s), drop off (10s)
+        let insertion = insertion.unwrap();
+        assert_eq!(
+            Insertion {
+                candidate: 0,
+                pickup_index: 0,
+                dropoff_index: 0,
+                cost: Duration::from_secs(40),
+            },
+            insertion
+        );
+        insertion.apply(&mut schedule, &request);
+        assert_eq!(
+            vec![
+                DrtStop::pickup(Id::get_from_ext("l2"), request.id.clone()),
+                DrtStop::dropoff(Id::get_from_ext("l3"), request.id.clone()),
+            ],
+            Vec::from(schedule.stops)
+        );
+    }
+
+    #[deterministic_id_test]
+    fn append_heuristic_only_inserts_at_end_of_schedule() {
+        let mut router = router();
+        let (schedule, requests) = schedule_with_request(100);
+
+        // b can only be served between the stops of a
+        let b = request("b", "l2", "l3", 100, 100);
+        let extensive = best_insertion(
+            DrtInsertionHeuristic::Extensive,
+            &b,
+            &schedule,
+            2,
+            &requests,
+            &mut router,
+        );
+        let append = best_insertion(
+            DrtInsertionHeuristic::Append,
+            &b,
+            &schedule,
+            2,
+            &requests,
+            &mut router,
+        );
+        assert_eq!(
+            Some((0, 1)),
+            extensive.map(|i| (i.pickup_index, i.dropoff_index))
+        );
+        assert_eq!(None, append);
+
+        // c is picked up where a is dropped off
+        let c = request("c", "l4", "l5", 100, 100);
+        let append = best_insertion(
+            DrtInsertionHeuristic::Append,
+            &c,
+            &schedule,
+            2,
+            &requests,
+            &mut router,
+        );
+        assert_eq!(
+            Some(Insertion {
+                candidate: 0,
+                pickup_index: 2,
+                dropoff_index: 2,
+                cost: Duration::from_secs(20),
+            }),
+            append
+        );
+    }
+
+    #[deterministic_id_test]
+    fn insertion_respects_capacity() {
+        let mut router = router();
+        let (schedule, requests) = schedule_with_request(100);
+        let b = request("b", "l2", "l3", 100, 100);
+
+        // a and b would be on board at the same time
+        let one_seat = best_insertion(
+            DrtInsertionHeuristic::Extensive,
+            &b,
+            &schedule,
+            1,
+            &requests,
+            &mut router,
+        );
+        let two_seats = best_insertion(
+            DrtInsertionHeuristic::Extensive,
+            &b,
+            &schedule,
+            2,
+            &requests,
+            &mut router,
+        );
+
+        assert_eq!(None, one_seat);
+        assert_eq!(Some(Duration::from_secs(10)), two_seats.map(|i| i.cost));
+    }
+
+    #[deterministic_id_test]
+    fn insertion_respects_max_wait_time() {
+        let mut router = router();
+        let schedule = DrtSchedule::new(Id::get_from_ext("l1"), SimTime::default());
+
+        // the vehicle reaches l3 after 20s
+        let too_early = request("b", "l3", "l4", 19, 100);
+        let in_time = request("c", "l3", "l4", 20, 100);
+
+        for (request, expected) in [(too_early, false), (in_time, true)] {
+            let insertion = best_insertion(
+                DrtInsertionHeuristic::Extensive,
+                &request,
+                &schedule,
+                1,
+                &IntMap::default(),
+                &mut router,
+            );
+            assert_eq!(expected, insertion.is_some());
+        }
+    }
+
+    #[deterministic_id_test]
+    fn insertion_rejects_detours_beyond_time_windows() {
+        let mut router = router();
+
+        // a is dropped off at l4 after 40s without b, serving b delays it by 10s
+        let (tight_schedule, tight_requests) = schedule_with_request(40);
+        let b = request("b", "l2", "l3", 100, 100);
+        let insertion = best_insertion(
+            DrtInsertionHeuristic::Extensive,
+            &b,
+            &tight_schedule,
+            2,
+            &tight_requests,
+            &mut router,
+        );
+        assert_eq!(None, insertion);
+
+        // b would be dropped off at l3 after 30s
+        let (schedule, requests) = schedule_with_request(100);
+        let late = request("c", "l2", "l3", 100, 29);
+        let insertion = best_insertion(
+            DrtInsertionHeuristic::Extensive,
+            &late,
+            &schedule,
+            2,
+            &requests,
+            &mut router,
+        );
+        assert_eq!(None, insertion);
+    }
+
+    #[deterministic_id_test]
+    fn simulate_serves_consecutive_stops_on_same_link_at_once() {
+        let mut router = router();
+        let (mut schedule, mut requests) = schedule_with_request(100);
+        let b = request("b", "l2", "l4", 100, 100);
+        schedule
+            .stops
+            .insert(1, DrtStop::pickup(b.from_link.clone(), b.id.clone()));
+        requests.insert(b.id.clone(), b.clone());
+        let candidate = candidate(&schedule, 2);
+        let stops: Vec<_> = (0..3).map(VirtualStop::Existing).collect();
+
+        let result = simulate(
+            &candidate,
+            &stops,
+            None,
+            &b,
+            &requests,
+            STOP_DURATION,
+            SimTime::default(),
+            &mut router,
+        )
+        .unwrap();
+
+        let secs = |secs| SimTime::from_secs(secs);
+        assert_eq!(vec![secs(10), secs(10), secs(40)], result.arrivals);
+        assert_eq!(secs(50), result.end_time);
+    }
+
+    #[deterministic_id_test]
+    fn simulate_fails_for_unreachable_stop() {
+        let mut router = router();
+        let schedule = DrtSchedule::new(Id::get_from_ext("l3"), SimTime::default());
+        let b = request("b", "l2", "l4", 100, 100);
+
+        let result = simulate(
+            &candidate(&schedule, 1),
+            &[VirtualStop::Pickup, VirtualStop::Dropoff],
+            None,
+            &b,
+            &IntMap::default(),
+            STOP_DURATION,
+            SimTime::default(),
+            &mut router,
+        );
+
+        assert!(result.is_none());
+    }
+
+    fn best_insertion(
+        heuristic: DrtInsertionHeuristic,
+        request: &DrtRequest,
+        schedule: &DrtSchedule,
+        capacity: usize,
+        requests: &IntMap<Id<DrtRequest>, DrtRequest>,
+        router: &mut DrtRouter,
+    ) -> Option<Insertion> {
+        find_best_insertion(
+            heuristic,
+            request,
+            &[candidate(schedule, capacity)],
+            requests,
+            STOP_DURATION,
+            SimTime::default(),
+            router,
+        )
+    }
+
+    fn candidate(schedule: &DrtSchedule, capacity: usize) -> InsertionCandidate<'_> {
+        InsertionCandidate {
+            schedule,
+            capacity,
+            service_end: SimTime::max(),
+        }
+    }
+
+    /// A schedule of a vehicle at l1, which picks up request a at l2 and drops it off at l4.
+    fn schedule_with_request(
+        latest_arrival: u64,
+    ) -> (DrtSchedule, IntMap<Id<DrtRequest>, DrtRequest>) {
+        let a = request("a", "l2", "l4", 100, latest_arrival);
+        let mut schedule = DrtSchedule::new(Id::get_from_ext("l1"), SimTime::default());
+        schedule
+            .stops
+            .push_back(DrtStop::pickup(a.from_link.clone(), a.id.clone()));
+        schedule
+            .stops
+            .push_back(DrtStop::dropoff(a.to_link.clone(), a.id.clone()));
+        let mut requests = IntMap::default();
+        requests.insert(a.id.clone(), a);
+        (schedule, requests)
+    }
+
+    fn request(
+        id: &str,
+        from: &str,
+        to: &str,
+        latest_start: u64,
+        latest_arrival: u64,
+    ) -> DrtRequest {
+        DrtRequest {
+            id: Id::create(id),
+            person: Id::create(id),
+            mode: Id::create("drt"),
+            from_link: Id::get_from_ext(from),
+            to_link: Id::get_from_ext(to),
+            submission_time: SimTime::default(),
+            latest_start_time: SimTime::from_secs(latest_start),
+            latest_arrival_time: SimTime::from_secs(latest_arrival),
+            direct_ride_time: Duration::ZERO,
+        }
+    }
+
+    /// A router on the one-way line l1 -> l2 -> ... -> l5, where each link takes 10s.
+    fn router() -> DrtRouter {
+        let mut network = Network::new();
+        let nodes: Vec<_> = (0..6)
+            .map(|i| {
+                Node::new(
+                    Id::create(&format!("n{}", i + 1)),
+                    Coordinate::new_2d(i as f64 * 100., 0.),
+                    0,
+                    1,
+                )
+            })
+            .collect();
+        let links: Vec<_> = nodes
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let mut link =
+                    Link::new_with_default(Id::create(&format!("l{}", i + 1)), &pair[0], &pair[1]);
+                link.freespeed = 10.;
+                link
+            })
+            .collect();
+        for node in nodes {
+            network.add_node(node);
+        }
+        for link in links {
+            network.add_link(link);
+        }
+        DrtRouter::new(Arc::new(network), &Id::create("car"))
+    }
+}
@@ -0,0 +1,187 @@
+use crate::simulation::id::Id;
+use crate::simulation::replanning::routing::a_star::Alt;
+use crate::simulation::replanning::routing::least_cost_path_calculator::{
+    FreeSpeedTravelTimeAndDisutility, LeastCostPathCalculator, LeastCostPathRequestBuilder,
+};
+use crate::simulation::scenario::network::{Link, Network};
+use crate::simulation::scenario::population::InternalPerson;
+use crate::simulation::time::SimTime;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod insertion;
+
+/// A request of an agent to be transported by a DRT service from `from_link` to `to_link`. The
+/// request is submitted when the agent departs on its DRT leg.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrtRequest {
+    pub id: Id<DrtRequest>,
+    pub person: Id<InternalPerson>,
+    pub mode: Id<String>,
+    pub from_link: Id<Link>,
+    pub to_link: Id<Link>,
+    pub submission_time: SimTime,
+    /// The passenger has to be picked up at the latest at this time.
+    pub latest_start_time: SimTime,
+    /// The passenger has to be dropped off at the latest at this time.
+    pub latest_arrival_time: SimTime,
+    /// Duration of the direct ride from `from_link` to `to_link`.
+    pub direct_ride_time: Duration,
+}
+
+/// A stop in the schedule of a DRT vehicle. All pickups and dropoffs of a stop happen at once,
+/// afterward the vehicle dwells at the stop for the stop duration of its service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrtStop {
+    pub link: Id<Link>,
+    pub pickups: Vec<Id<DrtRequest>>,
+    pub dropoffs: Vec<Id<DrtRequest>>,
+}
+
+impl DrtStop {
+    pub fn pickup(link: Id<Link>, request: Id<DrtRequest>) -> Self {
+        DrtStop {
+            link,
+            pickups: vec![request],
+            dropoffs: Vec::new(),
+        }
+    }
+
+    pub fn dropoff(link: Id<Link>, request: Id<DrtRequest>) -> Self {
+        DrtStop {
+            link,
+            pickups: Vec::new(),
+            dropoffs: vec![request],
+        }
+    }
+
+    /// Number of passengers on board after the stop minus the number of passengers before.
+    pub fn occupancy_change(&self) -> isize {
+        self.pickups.len() as isize - self.dropoffs.len() as isize
+    }
+}
+
+/// The part of a vehicle's schedule which can still be changed by the dispatcher. It starts when
+/// the vehicle leaves the stop it is currently driving to or dwelling at, or immediately if the
+/// vehicle is idle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrtSchedule {
+    /// The link at which the vehicle is available for its next stop.
+    pub start_link: Id<Link>,
+    /// The (estimated) time at which the vehicle is available for its next stop.
+    pub start_time: SimTime,
+    /// The number of passengers on board when the vehicle leaves `start_link`.
+    pub start_occupancy: usize,
+    /// Stops which are yet to be served, in order.
+    pub stops: VecDeque<DrtStop>,
+}
+
+impl DrtSchedule {
+    pub fn new(start_link: Id<Link>, start_time: SimTime) -> Self {
+        DrtSchedule {
+            start_link,
+            start_time,
+            start_occupancy: 0,
+            stops: VecDeque::new(),
+        }
+    }
+}
+
+/// A path of a DRT vehicle. `links` starts with the link the vehicle is currently on and ends with
+/// the link of the next stop. Travel time and distance exclude the first link, since the vehicle
+/// starts at its end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrtPath {
+    pub links: Vec<Id<Link>>,
+    pub travel_time: Duration,
+    pub distance: f64,
+}
+
+/// Calculates the path from `from` to `to` with the given router. Returns `None` if `to` can't be
+/// reached from `from`.
+pub fn calc_drt_path(
+    router: &dyn LeastCostPathCalculator,
+    network: &Network,
+    from: &Id<Link>,
+    to: &Id<Link>,
+    departure_time: SimTime,
+) -> Option<DrtPath> {
+    if from == to {
+        return Some(DrtPath {
+            links: vec![from.clone()],
+            travel_time: Duration::ZERO,
+            distance: 0.,
+        });
+    }
+
+    let request = LeastCostPathRequestBuilder::default()
+        .from(from.clone())
+        .to(to.clone())
+        .departure_time(departure_time)
+        .build()
+        .unwrap();
+    let path = router.calc_least_cost_path(request)?;
+
+    // the router excludes the to-link from the travel time, but the vehicle has to traverse it.
+    let to_link = network.get_link(to);
+    let to_link_freespeed = to_link.freespeed_at(departure_time.saturating_add(path.travel_time));
+    let travel_time =
+        path.travel_time + Duration::from_secs_f64(to_link.length / to_link_freespeed);
+
+    let mut links = Vec::with_capacity(path.path.len() + 2);
+    links.push(from.clone());
+    links.extend(path.path);
+    links.push(to.clone());
+    let distance = links[1..]
+        .iter()
+        .map(|link| network.get_link(link).length)
+        .sum();
+
+    Some(DrtPath {
+        links,
+        travel_time,
+        distance,
+    })
+}
+
+/// Free speed router for the vehicles of one DRT service. Paths are cached, as the dispatcher asks
+/// for the same pairs of links over and over again while evaluating insertions.
+pub struct DrtRouter {
+    network: Arc<Network>,
+    router: Alt,
+    cache: HashMap<(Id<Link>, Id<Link>), Option<DrtPath>>,
+}
+
+impl DrtRouter {
+    /// Creates a router on all links of `network` which allow `network_mode`.
+    pub fn new(network: Arc<Network>, network_mode: &Id<String>) -> Self {
+        let travel_time = Arc::new(FreeSpeedTravelTimeAndDisutility);
+        let router = Alt::new(
+            network.clone(),
+            Some(network_mode.clone()),
+            travel_time.clone(),
+            travel_time,
+        )
+        .unwrap_or_else(|e| panic!("Failed to create DRT router for mode {network_mode}: {e}"));
+
+        DrtRouter {
+            network,
+            router,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn path(&mut self, from: &Id<Link>, to: &Id<Link>) -> Option<&DrtPath> {
+        let network = &self.network;
+        let router = &self.router;
+        self.cache
+            .entry((from.clone(), to.clone()))
+            .or_insert_with(|| calc_drt_path(router, network, from, to, SimTime::default()))
+            .as_ref()
+    }
+
+    pub fn travel_time(&mut self, from: &Id<Link>, to: &Id<Link>) -> Option<Duration> {
+        self.path(from, to).map(|path| path.travel_time)
+    }
+}
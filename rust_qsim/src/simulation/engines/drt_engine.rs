@@ -0,0 +1,948 @@
+use crate::simulation::Identifiable;
+use crate::simulation::agents::agent::SimulationAgent;
+use crate::simulation::agents::drt_driver::DrtDriverLogic;
+use crate::simulation::agents::{
+    AgentEvent, EndTime, EnvironmentalEventObserver, SimulationAgentLogic,
+};
+use crate::simulation::config::{DrtInsertionHeuristic, DrtService};
+use crate::simulation::controller::ThreadLocalComputationalEnvironment;
+use crate::simulation::drt::insertion::{InsertionCandidate, find_best_insertion};
+use crate::simulation::drt::{DrtRequest, DrtRouter, DrtSchedule, DrtStop};
+use crate::simulation::events::{
+    DrtRequestSubmittedEventBuilder, PassengerDroppedOffEventBuilder,
+    PassengerPickedUpEventBuilder, PassengerRequestRejectedEventBuilder, PersonArrivalEventBuilder,
+    PersonDepartureEventBuilder, PersonEntersVehicleEventBuilder, PersonLeavesVehicleEventBuilder,
+};
+use crate::simulation::id::Id;
+use crate::simulation::scenario::ScenarioCore;
+use crate::simulation::scenario::network::Link;
+use crate::simulation::scenario::vehicles::{Garage, InternalVehicle};
+use crate::simulation::time::{SimClock, SimTime, Tick};
+use crate::simulation::time_queue::TimeQueue;
+use crate::simulation::vehicles::SimulationVehicle;
+use nohash_hasher::IntMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) const DVRP_MODE_ATTRIBUTE: &str = "dvrpMode";
+const START_LINK_ATTRIBUTE: &str = "startLink";
+const SERVICE_BEGIN_TIME_ATTRIBUTE: &str = "serviceBeginTime";
+const SERVICE_END_TIME_ATTRIBUTE: &str = "serviceEndTime";
+
+/// Engine for demand responsive transport (DRT). For every configured DRT service, it operates the
+/// fleet of vehicles whose `dvrpMode` attribute equals the service's mode.
+///
+/// Agents departing on a leg of a service's mode submit a request, which is inserted into the
+/// schedule of one of the vehicles or rejected if no vehicle can serve it within its time window.
+/// Vehicles drive from stop to stop on the network; picking up and dropping off passengers at
+/// their stops is done by this engine.
+///
+/// Vehicles are dispatched by this engine only, so DRT is restricted to a single partition. Runs
+/// with more partitions are rejected by
+/// [`ControllerBuilder::build`](crate::simulation::controller::controller::ControllerBuilder::build).
+pub(crate) struct DrtEngine {
+    operators: Vec<DrtOperator>,
+    operator_by_mode: IntMap<Id<String>, usize>,
+    /// Operator and fleet index of each DRT vehicle.
+    vehicle_index: IntMap<Id<InternalVehicle>, (usize, usize)>,
+    garage: Arc<Garage>,
+    dwelling: TimeQueue<DwellingVehicle, InternalVehicle>,
+    /// Passengers which have been dropped off and finished their leg.
+    arrived: Vec<SimulationAgent>,
+    /// Agents whose request has been rejected. They are stuck until the end of the simulation.
+    rejected: Vec<SimulationAgent>,
+    comp_env: ThreadLocalComputationalEnvironment,
+    clock: SimClock,
+}
+
+struct DrtOperator {
+    mode: Id<String>,
+    stop_duration: Duration,
+    max_wait_time: Duration,
+    max_travel_time_alpha: f64,
+    max_travel_time_beta: Duration,
+    insertion: DrtInsertionHeuristic,
+    router: DrtRouter,
+    fleet: Vec<FleetVehicle>,
+    /// Requests which are scheduled but not yet dropped off.
+    requests: IntMap<Id<DrtRequest>, DrtRequest>,
+    /// Agents waiting to be picked up, by their request.
+    waiting: IntMap<Id<DrtRequest>, SimulationAgent>,
+    request_count: u64,
+}
+
+struct FleetVehicle {
+    id: Id<InternalVehicle>,
+    capacity: usize,
+    service_begin: SimTime,
+    service_end: SimTime,
+    schedule: DrtSchedule,
+    /// The stop the vehicle is currently driving to.
+    current_stop: Option<DrtStop>,
+    /// Idle vehicles are parked at the start link of their schedule and have no stops to serve.
+    idle: bool,
+}
+
+struct DwellingVehicle {
+    operator: usize,
+    vehicle: usize,
+    passengers: Vec<SimulationAgent>,
+    leave_time: SimTime,
+}
+
+impl EndTime for DwellingVehicle {
+    fn end_time(&self, _now: SimTime) -> SimTime {
+        self.leave_time
+    }
+}
+
+impl DrtEngine {
+    pub fn new(
+        scenario: &ScenarioCore,
+        comp_env: ThreadLocalComputationalEnvironment,
+        clock: SimClock,
+    ) -> Self {
+        let services = &scenario.config.drt().services;
+        let mut operators = Vec::with_capacity(services.len());
+        let mut operator_by_mode = IntMap::default();
+        let mut vehicle_index = IntMap::default();
+        for (index, service) in services.iter().enumerate() {
+            let operator = DrtOperator::new(service, scenario);
+            for (i, vehicle) in operator.fleet.iter().enumerate() {
+                vehicle_index.insert(vehicle.id.clone(), (index, i));
+            }
+            operator_by_mode.insert(operator.mode.clone(), index);
+            operators.push(operator);
+        }
+
+        DrtEngine {
+            operators,
+            operator_by_mode,
+            vehicle_index,
+            garage: scenario.garage.clone(),
+            dwelling: TimeQueue::new(),
+            arrived: Vec::new(),
+            rejected: Vec::new(),
+            comp_env,
+            clock,
+        }
+    }
+
+    pub(crate) fn drain(&mut self) -> Vec<SimulationAgent> {
+        self.dwelling
+            .drain()
+            .into_iter()
+            .flat_map(|dwelling| dwelling.passengers)
+            .chain(
+                self.operators
+                    .iter_mut()
+                    .flat_map(|operator| operator.waiting.drain().map(|(_, agent)| agent)),
+            )
+            .chain(self.arrived.drain(..))
+            .chain(self.rejected.drain(..))
+            .collect()
+    }
+
+    /// Whether the agent's current leg is served by one of the DRT services.
+    pub(crate) fn is_serving(&self, agent: &SimulationAgent) -> bool {
+        self.operator_by_mode.contains_key(&agent.curr_leg().mode)
+    }
+
+    /// Whether the vehicle belongs to the fleet of one of the DRT services.
+    pub(crate) fn is_fleet_vehicle(&self, vehicle: &SimulationVehicle) -> bool {
+        vehicle.driver().drt_driver().is_some()
+    }
+
+    /// Returns the passengers which have been dropped off. They continue with their next activity.
+    pub(crate) fn take_arrived_passengers(&mut self) -> Vec<SimulationAgent> {
+        std::mem::take(&mut self.arrived)
+    }
+
+    /// Receives an agent which starts a DRT leg and submits its request. The request is inserted
+    /// into the schedule of the vehicle which is delayed the least by it.
+    pub(crate) fn receive_request(&mut self, now: Tick, agent: SimulationAgent) {
+        let now_time = self.clock.tick_to_time(now);
+        let leg = agent.curr_leg();
+        let route = leg
+            .route
+            .as_ref()
+            .unwrap_or_else(|| panic!("Missing route for agent {} at leg {:?}", agent.id(), leg));
+        let from_link = route.start_link().clone();
+        let to_link = route.end_link().clone();
+
+        self.comp_env.events_manager_borrow_mut().process_event(
+            &PersonDepartureEventBuilder::default()
+                .time(now_time)
+                .person(agent.id().clone())
+                .link(from_link.clone())
+                .leg_mode(leg.mode.clone())
+                .routing_mode(leg.routing_mode.clone().unwrap_or(leg.mode.clone()))
+                .build()
+                .unwrap(),
+        );
+
+        let operator_index = *self.operator_by_mode.get(&leg.mode).unwrap();
+        let operator = &mut self.operators[operator_index];
+        let request_id: Id<DrtRequest> = Id::create(&format!(
+            "{}_{}",
+            operator.mode.external(),
+            operator.request_count
+        ));
+        operator.request_count += 1;
+
+        let Some(direct_ride) = operator.router.path(&from_link, &to_link).cloned() else {
+            let mode = operator.mode.clone();
+            self.reject(now_time, agent, mode, request_id, "no_path_found");
+            return;
+        };
+
+        let latest_arrival_time = now_time
+            .saturating_add(
+                direct_ride
+                    .travel_time
+                    .mul_f64(operator.max_travel_time_alpha),
+            )
+            .saturating_add(operator.max_travel_time_beta);
+        let request = DrtRequest {
+            id: request_id.clone(),
+            person: agent.id().clone(),
+            mode: operator.mode.clone(),
+            from_link,
+            to_link,
+            submission_time: now_time,
+            latest_start_time: now_time.saturating_add(operator.max_wait_time),
+            latest_arrival_time,
+            direct_ride_time: direct_ride.travel_time,
+        };
+
+        self.comp_env.events_manager_borrow_mut().process_event(
+            &DrtRequestSubmittedEventBuilder::default()
+                .time(now_time)
+                .person(request.person.clone())
+                .mode(request.mode.clone())
+                .request(request.id.clone())
+                .from_link(request.from_link.clone())
+                .to_link(request.to_link.clone())
+                .unshared_ride_time(direct_ride.travel_time.as_secs_f64())
+                .unshared_ride_distance(direct_ride.distance)
+                .build()
+                .unwrap(),
+        );
+
+        let (indices, candidates): (Vec<_>, Vec<_>) = operator
+            .fleet
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.service_begin <= now_time && now_time < v.service_end)
+            .map(|(i, v)| {
+                let candidate = InsertionCandidate {
+                    schedule: &v.schedule,
+                    capacity: v.capacity,
+                    service_end: v.service_end,
+                };
+                (i, candidate)
+            })
+            .unzip();
+
+        let insertion = find_best_insertion(
+            operator.insertion,
+            &request,
+            &candidates,
+            &operator.requests,
+            operator.stop_duration,
+            now_time,
+            &mut operator.router,
+        );
+        let Some(insertion) = insertion else {
+            let mode = operator.mode.clone();
+            self.reject(now_time, agent, mode, request_id, "no_insertion_found");
+            return;
+        };
+
+        let vehicle_index = indices[insertion.candidate];
+        let vehicle = &mut operator.fleet[vehicle_index];
+        insertion.apply(&mut vehicle.schedule, &request);
+        operator.requests.insert(request_id.clone(), request);
+        operator.waiting.insert(request_id, agent);
+
+        if vehicle.idle {
+            vehicle.idle = false;
+            self.dwelling.add(
+                DwellingVehicle {
+                    operator: operator_index,
+                    vehicle: vehicle_index,
+                    passengers: Vec::new(),
+                    leave_time: now_time,
+                },
+                now_time,
+            );
+        }
+    }
+
+    /// Starts the drives of all vehicles which are done at their current stop. The returned
+    /// vehicles have to be sent onto the network at their current link.
+    pub(crate) fn do_step(&mut self, now: Tick) -> Vec<SimulationVehicle> {
+        let now_time = self.clock.tick_to_time(now);
+        self.dwelling
+            .pop(now_time)
+            .into_iter()
+            .filter_map(|dwelling| self.depart(dwelling, now_time))
+            .collect()
+    }
+
+    /// Receives a vehicle which has reached the link of its next stop. Passengers are dropped off
+    /// and picked up, afterward the vehicle dwells at the stop for the stop duration.
+    pub(crate) fn receive_vehicle_at_stop(&mut self, now: Tick, vehicle: SimulationVehicle) {
+        let now_time = self.clock.tick_to_time(now);
+        let (operator, index) = *self
+            .vehicle_index
+            .get(vehicle.id())
+            .unwrap_or_else(|| panic!("Vehicle {} is not a DRT vehicle.", vehicle.id()));
+        let vehicle_id = vehicle.id().clone();
+        let link = vehicle.curr_link_id().unwrap().clone();
+
+        let mut agents = vehicle.into_agents();
+        let driver = agents.remove(0);
+        let mut events = self.comp_env.events_manager_borrow_mut();
+        events.process_event(
+            &PersonLeavesVehicleEventBuilder::default()
+                .time(now_time)
+                .person(driver.id().clone())
+                .vehicle(vehicle_id)
+                .build()
+                .unwrap(),
+        );
+        events.process_event(
+            &PersonArrivalEventBuilder::default()
+                .time(now_time)
+                .person(driver.id().clone())
+                .link(link)
+                .leg_mode(driver.curr_leg().mode.clone())
+                .build()
+                .unwrap(),
+        );
+        drop(events);
+
+        self.serve_stop(operator, index, agents, now_time);
+    }
+
+    fn depart(&mut self, dwelling: DwellingVehicle, now: SimTime) -> Option<SimulationVehicle> {
+        let operator = &mut self.operators[dwelling.operator];
+        let stop_duration = operator.stop_duration;
+        let vehicle = &mut operator.fleet[dwelling.vehicle];
+
+        let Some(stop) = vehicle.schedule.stops.pop_front() else {
+            assert!(
+                dwelling.passengers.is_empty(),
+                "DRT vehicle {} has no stops left but passengers on board.",
+                vehicle.id
+            );
+            vehicle.idle = true;
+            vehicle.schedule.start_time = now;
+            return None;
+        };
+
+        let path = operator
+            .router
+            .path(&vehicle.schedule.start_link, &stop.link)
+            .cloned()
+            .unwrap_or_else(|| {
+                panic!(
+                    "No path for DRT vehicle {} from link {} to link {}.",
+                    vehicle.id, vehicle.schedule.start_link, stop.link
+                )
+            });
+        vehicle.schedule.start_link = stop.link.clone();
+        vehicle.schedule.start_time = now
+            .saturating_add(path.travel_time)
+            .saturating_add(stop_duration);
+        vehicle.schedule.start_occupancy =
+            (vehicle.schedule.start_occupancy as isize + stop.occupancy_change()) as usize;
+        vehicle.current_stop = Some(stop);
+
+        // the next stop is on the current link, so there is no need to drive.
+        if path.links.len() == 1 {
+            self.serve_stop(
+                dwelling.operator,
+                dwelling.vehicle,
+                dwelling.passengers,
+                now,
+            );
+            return None;
+        }
+
+        let driver_id = Id::create(vehicle.id.external());
+        let driver = DrtDriverLogic::new(driver_id, &operator.mode, vehicle.id.clone(), &path, now);
+        let vehicle_id = vehicle.id.clone();
+
+        let mut events = self.comp_env.events_manager_borrow_mut();
+        events.process_event(
+            &PersonDepartureEventBuilder::default()
+                .time(now)
+                .person(driver.id().clone())
+                .link(path.links[0].clone())
+                .leg_mode(operator.mode.clone())
+                .routing_mode(operator.mode.clone())
+                .build()
+                .unwrap(),
+        );
+        events.process_event(
+            &PersonEntersVehicleEventBuilder::default()
+                .time(now)
+                .person(driver.id().clone())
+                .vehicle(vehicle_id.clone())
+                .build()
+                .unwrap(),
+        );
+        drop(events);
+
+        Some(self.garage.unpark_veh_with_passengers(
+            SimulationAgent::new(Box::new(driver)),
+            dwelling.passengers,
+            vehicle_id,
+        ))
+    }
+
+    /// Drops off and picks up the passengers of the vehicle's current stop and lets the vehicle
+    /// dwell at the stop. Following stops on the same link are served at once.
+    fn serve_stop(
+        &mut self,
+        operator_index: usize,
+        vehicle_index: usize,
+        mut passengers: Vec<SimulationAgent>,
+        now: SimTime,
+    ) {
+        let operator = &mut self.operators[operator_index];
+        let vehicle = &mut operator.fleet[vehicle_index];
+        let stop = vehicle
+            .current_stop
+            .take()
+            .unwrap_or_else(|| panic!("DRT vehicle {} has no stop to serve.", vehicle.id));
+        let mut stops = vec![stop];
+        while vehicle
+            .schedule
+            .stops
+            .front()
+            .is_some_and(|next| next.link == stops[0].link)
+        {
+            let next = vehicle.schedule.stops.pop_front().unwrap();
+            vehicle.schedule.start_occupancy =
+                (vehicle.schedule.start_occupancy as isize + next.occupancy_change()) as usize;
+            stops.push(next);
+        }
+        let leave_time = now.saturating_add(operator.stop_duration);
+        // the vehicle might have arrived at another time than estimated
+        vehicle.schedule.start_time = leave_time;
+
+        let mut events = self.comp_env.events_manager_borrow_mut();
+        for stop in &stops {
+            for request_id in &stop.dropoffs {
+                let request = operator.requests.remove(request_id).unwrap();
+                let position = passengers
+                    .iter()
+                    .position(|p| p.id() == &request.person)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Passenger {} of request {} is not on board of DRT vehicle {}.",
+                            request.person, request.id, vehicle.id
+                        )
+                    });
+                let mut passenger = passengers.remove(position);
+                passenger.notify_event(&mut AgentEvent::LeftDrtVehicle(), now);
+
+                events.process_event(
+                    &PersonLeavesVehicleEventBuilder::default()
+                        .time(now)
+                        .person(request.person.clone())
+                        .vehicle(vehicle.id.clone())
+                        .build()
+                        .unwrap(),
+                );
+                events.process_event(
+                    &PassengerDroppedOffEventBuilder::default()
+                        .time(now)
+                        .person(request.person.clone())
+                        .mode(request.mode.clone())
+                        .request(request.id.clone())
+                        .vehicle(vehicle.id.clone())
+                        .build()
+                        .unwrap(),
+                );
+                events.process_event(
+                    &PersonArrivalEventBuilder::default()
+                        .time(now)
+                        .person(request.person.clone())
+                        .link(stop.link.clone())
+                        .leg_mode(passenger.curr_leg().mode.clone())
+                        .build()
+                        .unwrap(),
+                );
+                self.arrived.push(passenger);
+            }
+
+            for request_id in &stop.pickups {
+                let request = operator.requests.get(request_id).unwrap();
+                let passenger = operator.waiting.remove(request_id).unwrap();
+                events.process_event(
+                    &PersonEntersVehicleEventBuilder::default()
+                        .time(now)
+                        .person(request.person.clone())
+                        .vehicle(vehicle.id.clone())
+                        .build()
+                        .unwrap(),
+                );
+                events.process_event(
+                    &PassengerPickedUpEventBuilder::default()
+                        .time(now)
+                        .person(request.person.clone())
+                        .mode(request.mode.clone())
+                        .request(request.id.clone())
+                        .vehicle(vehicle.id.clone())
+                        .build()
+                        .unwrap(),
+                );
+                passengers.push(passenger);
+            }
+        }
+        drop(events);
+
+        self.dwelling.add(
+            DwellingVehicle {
+                operator: operator_index,
+                vehicle: vehicle_index,
+                passengers,
+                leave_time,
+            },
+            now,
+        );
+    }
+
+    fn reject(
+        &mut self,
+        now: SimTime,
+        agent: SimulationAgent,
+        mode: Id<String>,
+        request: Id<DrtRequest>,
+        cause: &str,
+    ) {
+        self.comp_env.events_manager_borrow_mut().process_event(
+            &PassengerRequestRejectedEventBuilder::default()
+                .time(now)
+                .person(agent.id().clone())
+                .mode(mode)
+                .request(request)
+                .cause(cause.to_string())
+                .build()
+                .unwrap(),
+        );
+        self.rejected.push(agent);
+    }
+}
+
+impl DrtOperator {
+    fn new(service: &DrtService, scenario: &ScenarioCore) -> Self {
+        let mode = Id::create(&service.mode);
+        let mut fleet: Vec<_> = scenario
+            .garage
+            .vehicles
+            .values()
+            .filter(|v| {
+                v.attributes.get::<String>(DVRP_MODE_ATTRIBUTE).as_ref() == Some(&service.mode)
+            })
+            .map(|v| FleetVehicle::new(v, &scenario.garage))
+            .collect();
+        fleet.sort_by(|a, b| a.id.external().cmp(b.id.external()));
+
+        DrtOperator {
+            mode,
+            stop_duration: Duration::from_secs(service.stop_duration as u64),
+            max_wait_time: Duration::from_secs(service.max_wait_time as u64),
+            max_travel_time_alpha: service.max_travel_time_alpha,
+            max_travel_time_beta: Duration::from_secs(service.max_travel_time_beta as u64),
+            insertion: service.insertion,
+            router: DrtRouter::new(scenario.network.clone(), &Id::create(&service.network_mode)),
+            fleet,
+            requests: IntMap::default(),
+            waiting: IntMap::default(),
+            request_count: 0,
+        }
+    }
+}
+
+impl FleetVehicle {
+    fn new(vehicle: &InternalVehicle, garage: &Garage) -> Self {
+        let start_link: Id<Link> = vehicle
+            .attributes
+            .get::<String>(START_LINK_ATTRIBUTE)
+            .map(|link| Id::get_from_ext(&link))
+            .unwrap_or_else(|| {
+                panic!(
+                    "DRT vehicle {} has no {START_LINK_ATTRIBUTE} attribute.",
+                    vehicle.id
+                )
+            });
+        let service_begin = vehicle
+            .attributes
+            .get::<f64>(SERVICE_BEGIN_TIME_ATTRIBUTE)
+            .map(|t| SimTime::from_duration(Duration::from_secs_f64(t)))
+            .unwrap_or_default();
+        let service_end = vehicle
+            .attributes
+            .get::<f64>(SERVICE_END_TIME_ATTRIBUTE)
+            .map(|t| SimTime::from_duration(Duration::from_secs_f64(t)))
+            .unwrap_or(SimTime::max());
+        let capacity = garage
+            .vehicle_types
+            .get(&vehicle.vehicle_type)
+            .map(|veh_type| veh_type.passenger_capacity())
+            .unwrap_or_else(|| panic!("No vehicle type found for DRT vehicle {}", vehicle.id))
+            as usize;
+
+        FleetVehicle {
+            id: vehicle.id.clone(),
+            capacity,
+            service_begin,
+            service_end,
+            schedule: DrtSchedule::new(start_link, service_begin),
+            current_stop: None,
+            idle: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::InternalAttributes;
+    use crate::simulation::agents::agent::SimulationAgent;
+    use crate::simulation::config::{Config, DrtInsertionHeuristic, DrtService};
+    use crate::simulation::controller::{MobsimWorkerPool, MobsimWorkerPoolArgumentsBuilder};
+    use crate::simulation::events::{EventHandlerRegisterFn, EventsManager};
+    use crate::simulation::id::Id;
+    use crate::simulation::io::xml::events::XmlEventsWriter;
+    use crate::simulation::network::sim_network::SimNetworkPartition;
+    use crate::simulation::population::agent_source::PopulationAgentSource;
+    use crate::simulation::pt::TransitSchedule;
+    use crate::simulation::scenario::facilities::ActivityFacilities;
+    use crate::simulation::scenario::network::{Link, Network, Node};
+    use crate::simulation::scenario::population::{
+        InternalActivity, InternalGenericRoute, InternalLeg, InternalPerson, InternalPlan,
+        InternalRoute, Population,
+    };
+    use crate::simulation::scenario::vehicles::{Garage, InternalVehicle};
+    use crate::simulation::scenario::{
+        Coordinate, MobsimInput, MobsimScenarioPartition, PopulationShard, ScenarioCore,
+    };
+    use crate::simulation::time::SimTime;
+    use crate::test_utils::create_vehicle_type;
+    use macros::deterministic_id_test;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Barrier, Mutex};
+    use std::time::Duration;
+
+    const EVENTS: &[&str] = &[
+        "departure",
+        "DrtRequest submitted",
+        "PassengerRequest rejected",
+        "PersonEntersVehicle",
+        "passenger picked up",
+        "PersonLeavesVehicle",
+        "passenger dropped off",
+        "arrival",
+    ];
+
+    #[deterministic_id_test]
+    fn vehicle_picks_up_and_drops_off_passenger() {
+        let (events, agents) = run_drt_scenario(1, &[("p1", "l1", "l3", 5)], EVENTS);
+
+        assert_eq!(1, agents.len());
+        assert_eq!(
+            vec![
+                "<event time=\"5\" type=\"departure\" person=\"p1\" link=\"l1\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"5\" type=\"DrtRequest submitted\" person=\"p1\" mode=\"drt\" request=\"drt_0\" fromLink=\"l1\" toLink=\"l3\" unsharedRideTime=\"20\" unsharedRideDistance=\"200\"/>\n",
+                "<event time=\"5\" type=\"PersonEntersVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"5\" type=\"passenger picked up\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"15\" type=\"departure\" person=\"v1\" link=\"l1\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"15\" type=\"PersonEntersVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"PersonLeavesVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"arrival\" person=\"v1\" link=\"l3\" legMode=\"drt\"/>\n",
+                "<event time=\"37\" type=\"PersonLeavesVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"passenger dropped off\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"arrival\" person=\"p1\" link=\"l3\" legMode=\"drt\"/>\n",
+            ],
+            events
+        );
+    }
+
+    #[deterministic_id_test]
+    fn vehicle_drives_to_pickup() {
+        let (events, agents) = run_drt_scenario(1, &[("p1", "l2", "l3", 5)], EVENTS);
+
+        assert_eq!(1, agents.len());
+        assert_eq!(
+            vec![
+                "<event time=\"5\" type=\"departure\" person=\"p1\" link=\"l2\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"5\" type=\"DrtRequest submitted\" person=\"p1\" mode=\"drt\" request=\"drt_0\" fromLink=\"l2\" toLink=\"l3\" unsharedRideTime=\"10\" unsharedRideDistance=\"100\"/>\n",
+                "<event time=\"5\" type=\"departure\" person=\"v1\" link=\"l1\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"5\" type=\"PersonEntersVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"16\" type=\"PersonLeavesVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"16\" type=\"arrival\" person=\"v1\" link=\"l2\" legMode=\"drt\"/>\n",
+                "<event time=\"16\" type=\"PersonEntersVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"16\" type=\"passenger picked up\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"26\" type=\"departure\" person=\"v1\" link=\"l2\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"26\" type=\"PersonEntersVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"PersonLeavesVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"arrival\" person=\"v1\" link=\"l3\" legMode=\"drt\"/>\n",
+                "<event time=\"37\" type=\"PersonLeavesVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"passenger dropped off\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"arrival\" person=\"p1\" link=\"l3\" legMode=\"drt\"/>\n",
+            ],
+            events
+        );
+    }
+
+    #[deterministic_id_test]
+    fn passengers_share_vehicle() {
+        let (events, agents) =
+            run_drt_scenario(2, &[("p1", "l1", "l3", 5), ("p2", "l2", "l3", 6)], EVENTS);
+
+        assert_eq!(2, agents.len());
+        assert_eq!(
+            vec![
+                "<event time=\"5\" type=\"departure\" person=\"p1\" link=\"l1\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"5\" type=\"DrtRequest submitted\" person=\"p1\" mode=\"drt\" request=\"drt_0\" fromLink=\"l1\" toLink=\"l3\" unsharedRideTime=\"20\" unsharedRideDistance=\"200\"/>\n",
+                "<event time=\"5\" type=\"PersonEntersVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"5\" type=\"passenger picked up\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"6\" type=\"departure\" person=\"p2\" link=\"l2\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"6\" type=\"DrtRequest submitted\" person=\"p2\" mode=\"drt\" request=\"drt_1\" fromLink=\"l2\" toLink=\"l3\" unsharedRideTime=\"10\" unsharedRideDistance=\"100\"/>\n",
+                "<event time=\"15\" type=\"departure\" person=\"v1\" link=\"l1\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"15\" type=\"PersonEntersVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"26\" type=\"PersonLeavesVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"26\" type=\"arrival\" person=\"v1\" link=\"l2\" legMode=\"drt\"/>\n",
+                "<event time=\"26\" type=\"PersonEntersVehicle\" person=\"p2\" vehicle=\"v1\"/>\n",
+                "<event time=\"26\" type=\"passenger picked up\" person=\"p2\" mode=\"drt\" request=\"drt_1\" vehicle=\"v1\"/>\n",
+                "<event time=\"36\" type=\"departure\" person=\"v1\" link=\"l2\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"36\" type=\"PersonEntersVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"47\" type=\"PersonLeavesVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"47\" type=\"arrival\" person=\"v1\" link=\"l3\" legMode=\"drt\"/>\n",
+                "<event time=\"47\" type=\"PersonLeavesVehicle\" person=\"p2\" vehicle=\"v1\"/>\n",
+                "<event time=\"47\" type=\"passenger dropped off\" person=\"p2\" mode=\"drt\" request=\"drt_1\" vehicle=\"v1\"/>\n",
+                "<event time=\"47\" type=\"arrival\" person=\"p2\" link=\"l3\" legMode=\"drt\"/>\n",
+                "<event time=\"47\" type=\"PersonLeavesVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"47\" type=\"passenger dropped off\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"47\" type=\"arrival\" person=\"p1\" link=\"l3\" legMode=\"drt\"/>\n",
+            ],
+            events
+        );
+    }
+
+    #[deterministic_id_test]
+    fn request_without_feasible_insertion_is_rejected() {
+        // The vehicle has one seat and can't return to l2 after dropping off p1 on l3.
+        let (events, agents) =
+            run_drt_scenario(1, &[("p1", "l1", "l3", 5), ("p2", "l2", "l3", 6)], EVENTS);
+
+        // p2 is stuck until the end of the simulation
+        assert_eq!(2, agents.len());
+        assert_eq!(
+            vec![
+                "<event time=\"5\" type=\"departure\" person=\"p1\" link=\"l1\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"5\" type=\"DrtRequest submitted\" person=\"p1\" mode=\"drt\" request=\"drt_0\" fromLink=\"l1\" toLink=\"l3\" unsharedRideTime=\"20\" unsharedRideDistance=\"200\"/>\n",
+                "<event time=\"5\" type=\"PersonEntersVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"5\" type=\"passenger picked up\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"6\" type=\"departure\" person=\"p2\" link=\"l2\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"6\" type=\"DrtRequest submitted\" person=\"p2\" mode=\"drt\" request=\"drt_1\" fromLink=\"l2\" toLink=\"l3\" unsharedRideTime=\"10\" unsharedRideDistance=\"100\"/>\n",
+                "<event time=\"6\" type=\"PassengerRequest rejected\" person=\"p2\" mode=\"drt\" request=\"drt_1\" cause=\"no_insertion_found\"/>\n",
+                "<event time=\"15\" type=\"departure\" person=\"v1\" link=\"l1\" legMode=\"drt\" computationalRoutingMode=\"drt\"/>\n",
+                "<event time=\"15\" type=\"PersonEntersVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"PersonLeavesVehicle\" person=\"v1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"arrival\" person=\"v1\" link=\"l3\" legMode=\"drt\"/>\n",
+                "<event time=\"37\" type=\"PersonLeavesVehicle\" person=\"p1\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"passenger dropped off\" person=\"p1\" mode=\"drt\" request=\"drt_0\" vehicle=\"v1\"/>\n",
+                "<event time=\"37\" type=\"arrival\" person=\"p1\" link=\"l3\" legMode=\"drt\"/>\n",
+            ],
+            events
+        );
+    }
+
+    /// Runs the mobsim with one DRT vehicle `v1` for `capacity` passengers starting on l1 and
+    /// returns the events of the given types ordered by time, as well as the agents returned by the
+    /// mobsim.
+    /// Each passenger is given as (id, from link, to link, departure time in seconds).
+    fn run_drt_scenario(
+        capacity: u32,
+        passengers: &[(&str, &str, &str, u64)],
+        event_types: &'static [&'static str],
+    ) -> (Vec<String>, Vec<SimulationAgent>) {
+        let mut config = Config::default();
+        config.qsim_mut().end_time = 200;
+        config.drt_mut().services.push(DrtService {
+            mode: "drt".to_string(),
+            network_mode: "car".to_string(),
+            stop_duration: 10,
+            max_wait_time: 60,
+            max_travel_time_alpha: 1.5,
+            max_travel_time_beta: 60,
+            insertion: DrtInsertionHeuristic::Extensive,
+        });
+        let config = Arc::new(config);
+
+        let mut garage = Garage::default();
+        let mut veh_type = create_vehicle_type(&Id::create("drt_type"), Id::create("car"));
+        // one more seat for the driver
+        veh_type.seats = capacity + 1;
+        garage.add_veh_type(veh_type);
+        let mut attributes = InternalAttributes::default();
+        attributes.insert("dvrpMode", "drt");
+        attributes.insert("startLink", "l1");
+        garage.add_veh(InternalVehicle {
+            id: Id::create("v1"),
+            max_v: 100.,
+            pce: 1.,
+            fef: 1.,
+            vehicle_type: Id::get_from_ext("drt_type"),
+            attributes,
+        });
+
+        let scenario = ScenarioCore {
+            network: Arc::new(create_network()),
+            garage: Arc::new(garage),
+            transit_schedule: Arc::new(TransitSchedule::default()),
+            transit_vehicles: Arc::new(Garage::default()),
+            facilities: Arc::new(ActivityFacilities::default()),
+            config: config.clone(),
+        };
+
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let handlers: HashMap<u32, Vec<Box<EventHandlerRegisterFn>>> =
+            HashMap::from([(0, vec![collect_events(collected.clone(), event_types)])]);
+
+        let args = MobsimWorkerPoolArgumentsBuilder::default()
+            .scenario_core(scenario.clone())
+            .agent_source(Arc::new(PopulationAgentSource))
+            .event_handler_per_partition(handlers)
+            .global_barrier(Arc::new(Barrier::new(1)))
+            .build()
+            .unwrap();
+        let mut pool = MobsimWorkerPool::spawn(args);
+
+        let population = Population::from_persons(
+            passengers
+                .iter()
+                .map(|(id, from, to, dep_time)| {
+                    drt_person(id, from, to, SimTime::from_secs(*dep_time))
+                })
+                .collect(),
+        );
+        let agents = pool.run_mobsim(0, true, vec![mobsim_input(&scenario, population)]);
+        pool.shutdown();
+
+        let mut events = collected.lock().unwrap().clone();
+        events.sort_by_key(|(time, _)| *time);
+        (events.into_iter().map(|(_, e)| e).collect(), agents)
+    }
+
+    fn collect_events(
+        collected: Arc<Mutex<Vec<(u64, String)>>>,
+        event_types: &'static [&'static str],
+    ) -> Box<EventHandlerRegisterFn> {
+        Box::new(move |events: &mut EventsManager| {
+            events.on_any(move |e| {
+                if event_types.contains(&e.type_()) {
+                    collected
+                        .lock()
+                        .unwrap()
+                        .push((e.time().as_nanos(), XmlEventsWriter::event_2_string(e)));
+                }
+            });
+        })
+    }
+
+    /// Creates a person which leaves home on `from` at `dep_time` and takes a DRT vehicle to work
+    /// on `to`.
+    fn drt_person(id: &str, from: &str, to: &str, dep_time: SimTime) -> InternalPerson {
+        let route = InternalRoute::Generic(InternalGenericRoute::new(
+            Id::get_from_ext(from),
+            Id::get_from_ext(to),
+            None,
+            None,
+            None,
+        ));
+
+        let mut plan = InternalPlan::default();
+        plan.add_act(InternalActivity::new(
+            Some(Coordinate::new_2d(0., 0.)),
+            "home",
+            Id::get_from_ext(from),
+            None,
+            Some(dep_time),
+            None,
+        ));
+        plan.add_leg(InternalLeg::new(
+            route,
+            "drt",
+            Duration::from_secs(20),
+            Some(dep_time),
+        ));
+        plan.add_act(InternalActivity::new(
+            Some(Coordinate::new_2d(300., 0.)),
+            "work",
+            Id::get_from_ext(to),
+            None,
+            None,
+            None,
+        ));
+        InternalPerson::new(Id::create(id), plan)
+    }
+
+    /// Creates the network n1 -l1-> n2 -l2-> n3 -l3-> n4 with links of 100m and a free speed of
+    /// 10m/s.
+    fn create_network() -> Network {
+        let mut network = Network::new();
+        let nodes: Vec<_> = (0..4)
+            .map(|i| {
+                Node::new(
+                    Id::create(&format!("n{}", i + 1)),
+                    Coordinate::new_2d(i as f64 * 100., 0.),
+                    0,
+                    1,
+                )
+            })
+            .collect();
+        let links: Vec<_> = nodes
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let mut link =
+                    Link::new_with_default(Id::create(&format!("l{}", i + 1)), &pair[0], &pair[1]);
+                link.capacity = 3600.;
+                link.freespeed = 10.;
+                link
+            })
+            .collect();
+        for node in nodes {
+            network.add_node(node);
+        }
+        for link in links {
+            network.add_link(link);
+        }
+        network
+    }
+
+    fn mobsim_input(scenario: &ScenarioCore, population: Population) -> MobsimInput {
+        let network_partition = SimNetworkPartition::from_network(
+            &scenario.network,
+            0,
+            scenario.config.qsim(),
+            scenario.config.computational_setup().random_seed,
+        );
+
+        MobsimInput {
+            partition: MobsimScenarioPartition {
+                rank: 0,
+                scenario: scenario.clone(),
+                network_partition,
+            },
+            population: PopulationShard { population },
+        }
+    }
+}
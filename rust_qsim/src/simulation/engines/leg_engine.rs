@@ -1,10 +1,11 @@
 use crate::simulation::Identifiable;
 use crate::simulation::agents::agent::SimulationAgent;
 use crate::simulation::agents::{SimulationAgentLogic, SimulationAgentState};
-use crate::simulation::config::QSim;
 use crate::simulation::controller::ThreadLocalComputationalEnvironment;
+use crate::simulation::engines::drt_engine::DrtEngine;
 use crate::simulation::engines::network_engine::NetworkEngine;
 use crate::simulation::engines::teleportation_engine::TeleportationEngine;
+use crate::simulation::engines::transit_engine::TransitEngine;
 use crate::simulation::events::{
     PersonArrivalEventBuilder, PersonDepartureEventBuilder, PersonEntersVehicleEventBuilder,
     PersonLeavesVehicleEventBuilder,
@@ -17,6 +18,7 @@ use crate::simulation::messaging::messages::InternalSyncMessage;
 use crate::simulation::messaging::sim_communication::SimCommunicator;
 use crate::simulation::messaging::sim_communication::message_broker::NetMessageBroker;
 use crate::simulation::network::sim_network::SimNetworkPartition;
+use crate::simulation::scenario::ScenarioCore;
 use crate::simulation::scenario::population::InternalRoute;
 use crate::simulation::scenario::vehicles::Garage;
 use crate::simulation::time::{SimClock, SimTime, Tick};
@@ -28,6 +30,8 @@ use tracing::instrument;
 pub struct LegEngine<C: SimCommunicator> {
     teleportation_engine: TeleportationEngine,
     network_engine: NetworkEngine,
+    transit_engine: TransitEngine,
+    drt_engine: DrtEngine,
     garage: Arc<Garage>,
     net_message_broker: NetMessageBroker<C>,
     departure_handler: VehicularDepartureHandler,
@@ -39,11 +43,11 @@ pub struct LegEngine<C: SimCommunicator> {
 impl<C: SimCommunicator> LegEngine<C> {
     pub fn new(
         network: SimNetworkPartition,
-        garage: Arc<Garage>,
+        scenario: &ScenarioCore,
         net_message_broker: NetMessageBroker<C>,
-        config: &QSim,
         comp_env: ThreadLocalComputationalEnvironment,
     ) -> Self {
+        let config = scenario.config.qsim();
         let clock = SimClock::new(config.ticks_per_second);
         let main_modes: IntSet<Id<String>> = config
             .main_modes
@@ -56,10 +60,21 @@ impl<C: SimCommunicator> LegEngine<C> {
             main_modes: main_modes.clone(),
         };
 
+        let transit_engine = TransitEngine::new(
+            scenario.transit_schedule.clone(),
+            scenario.transit_vehicles.clone(),
+            &network.get_link_ids(),
+            comp_env.clone(),
+            clock,
+        );
+        let drt_engine = DrtEngine::new(scenario, comp_env.clone(), clock);
+
         LegEngine {
             teleportation_engine: TeleportationEngine::new(comp_env.clone(), clock),
             network_engine: NetworkEngine::new(network, comp_env.clone(), clock),
-            garage,
+            transit_engine,
+            drt_engine,
+            garage: scenario.garage.clone(),
             net_message_broker,
             departure_handler,
             main_modes,
@@ -73,6 +88,8 @@ impl<C: SimCommunicator> LegEngine<C> {
             .drain()
             .into_iter()
             .chain(self.teleportation_engine.drain())
+            .chain(self.transit_engine.drain())
+            .chain(self.drt_engine.drain())
             .collect()
     }
 
@@ -85,21 +102,55 @@ impl<C: SimCommunicator> LegEngine<C> {
     /// Let's say, a vehicle's earliest exit time is `x`. The `move_links` call puts it into the buffer
     /// at time step `x` (assuming it is free), and the `move_nodes` call at time step `x+1` puts it onto the next link.
     /// The corresponding LinkEnter and LinkLeave events have time step `x+1`
+    ///
+    /// Transit vehicles are started before the nodes are moved. Vehicles reaching a stop during
+    /// `move_links` are handed to the transit engine, and vehicles done serving their stop are put
+    /// back onto their link afterwards.
+    ///
+    /// DRT vehicles leaving their stops are sent onto the network at the same point. Vehicles
+    /// reaching the link of their next stop are handed back to the DRT engine.
+    ///
+    /// Scheduled link changes, e.g., from network change events, are applied first, so that they
+    /// are in effect for the whole time step.
     #[instrument(level = "trace", skip(self, agents), fields(rank=self.net_message_broker.rank()))]
     pub(crate) fn do_step(
         &mut self,
         now: Tick,
         agents: Vec<SimulationAgent>,
     ) -> Vec<SimulationAgent> {
+        self.network_engine.apply_link_changes(now);
         self.receive_agents(now, agents);
 
         let teleported_vehicles = self.teleportation_engine.do_step(now);
 
+        for vehicle in self.transit_engine.start_departures(now) {
+            self.network_engine.receive_vehicle(now, vehicle, true);
+        }
+        for vehicle in self.drt_engine.do_step(now) {
+            self.network_engine.receive_vehicle(now, vehicle, true);
+        }
+
         self.network_engine.move_nodes(now);
-        let network_vehicles = self
+        let (network_vehicles, vehicles_at_stop) = self
             .network_engine
             .move_links(now, &mut self.net_message_broker);
 
+        for vehicle in vehicles_at_stop {
+            self.transit_engine.receive_vehicle_at_stop(now, vehicle);
+        }
+        for vehicle in self.transit_engine.leave_stops(now) {
+            self.network_engine.receive_vehicle_after_stop(vehicle);
+        }
+        let transit_passengers = self.transit_engine.take_arrived_passengers();
+
+        let (drt_vehicles, network_vehicles): (Vec<_>, Vec<_>) = network_vehicles
+            .into_iter()
+            .partition(|vehicle| self.drt_engine.is_fleet_vehicle(vehicle));
+        for vehicle in drt_vehicles {
+            self.drt_engine.receive_vehicle_at_stop(now, vehicle);
+        }
+        let drt_passengers = self.drt_engine.take_arrived_passengers();
+
         let sync_messages = self.send_recv(now);
 
         for mut msg in sync_messages {
@@ -117,6 +168,8 @@ impl<C: SimCommunicator> LegEngine<C> {
         let mut agents = vec![];
         agents.extend(self.publish_end_events(now, network_vehicles, true));
         agents.extend(self.publish_end_events(now, teleported_vehicles, false));
+        agents.extend(transit_passengers);
+        agents.extend(drt_passengers);
         agents
     }
 
@@ -176,7 +229,12 @@ impl<C: SimCommunicator> LegEngine<C> {
                     .unwrap(),
             );
 
-            agents.extend(veh.into_agents());
+            // Transit drivers are generated by the transit engine and are dropped after their leg.
+            agents.extend(
+                veh.into_agents()
+                    .into_iter()
+                    .filter(|agent| agent.transit_driver().is_none()),
+            );
         }
         agents
     }
@@ -185,6 +243,16 @@ impl<C: SimCommunicator> LegEngine<C> {
         let now_time = self.clock.tick_to_time(now);
         agent.advance_plan(now_time);
 
+        if self.transit_engine.is_serving(&agent) {
+            self.transit_engine.receive_passenger(now, agent);
+            return;
+        }
+
+        if self.drt_engine.is_serving(&agent) {
+            self.drt_engine.receive_request(now, agent);
+            return;
+        }
+
         let vehicle = self
             .departure_handler
             .handle_departure(now_time, agent, &self.garage);
@@ -195,6 +263,13 @@ impl<C: SimCommunicator> LegEngine<C> {
     }
 
     fn pass_vehicle_to_engine(&mut self, now: Tick, vehicle: SimulationVehicle, route_begin: bool) {
+        // Transit vehicles always drive along their network route, regardless of the main modes.
+        if vehicle.driver().transit_driver().is_some() {
+            self.network_engine
+                .receive_vehicle(now, vehicle, route_begin);
+            return;
+        }
+
         let leg = vehicle.driver().curr_leg();
 
         // If mode of leg is not main mode, teleport vehicle in every case
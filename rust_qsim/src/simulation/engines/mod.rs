@@ -7,9 +7,11 @@ use crate::simulation::time::SimTime;
 use crate::simulation::vehicles::SimulationVehicle;
 
 pub mod activity_engine;
+pub mod drt_engine;
 pub mod leg_engine;
 pub mod network_engine;
 pub mod teleportation_engine;
+pub mod transit_engine;
 
 fn emit_partition_leave_events(
     comp_env: &mut ThreadLocalComputationalEnvironment,
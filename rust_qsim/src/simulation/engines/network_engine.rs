@@ -48,6 +48,15 @@ impl NetworkEngine {
         self.network.send_veh_en_route(vehicle, events, now)
     }
 
+    /// Receives a vehicle which has finished serving a transit stop and continues its route.
+    pub(crate) fn receive_vehicle_after_stop(&mut self, vehicle: SimulationVehicle) {
+        self.network.continue_after_stop(vehicle)
+    }
+
+    pub(super) fn apply_link_changes(&mut self, now: Tick) {
+        self.network.apply_link_changes(now)
+    }
+
     #[instrument(level = "trace", skip(self), fields(rank = self.network.partition()))]
     pub(super) fn move_nodes(&mut self, now: Tick) {
         self.network.move_nodes(&mut self.comp_env, now)
@@ -58,7 +67,7 @@ impl NetworkEngine {
         &mut self,
         now: Tick,
         net_message_broker: &mut NetMessageBroker<C>,
-    ) -> Vec<SimulationVehicle> {
+    ) -> (Vec<SimulationVehicle>, Vec<SimulationVehicle>) {
         let move_links_result = self.network.move_links(&mut self.comp_env, now);
 
         for veh in move_links_result.vehicles_exit_partition {
@@ -74,6 +83,9 @@ impl NetworkEngine {
             net_message_broker.add_cap_update(cap, now);
         }
 
-        move_links_result.vehicles_end_leg
+        (
+            move_links_result.vehicles_end_leg,
+            move_links_result.vehicles_at_stop,
+        )
     }
 }
@@ -0,0 +1,844 @@
+use crate::simulation::Identifiable;
+use crate::simulation::agents::agent::SimulationAgent;
+use crate::simulation::agents::transit_driver::{TransitDriverLogic, TransitDriverStop};
+use crate::simulation::agents::{
+    AgentEvent, EndTime, EnvironmentalEventObserver, SimulationAgentLogic,
+};
+use crate::simulation::controller::ThreadLocalComputationalEnvironment;
+use crate::simulation::events::{
+    AgentWaitingForPtEventBuilder, PersonArrivalEventBuilder, PersonDepartureEventBuilder,
+    PersonEntersVehicleEventBuilder, PersonLeavesVehicleEventBuilder,
+    TransitDriverStartsEventBuilder, VehicleArrivesAtFacilityEventBuilder,
+    VehicleDepartsAtFacilityEventBuilder,
+};
+use crate::simulation::id::Id;
+use crate::simulation::pt::{TransitLine, TransitRoute, TransitSchedule, TransitStopFacility};
+use crate::simulation::scenario::network::Link;
+use crate::simulation::scenario::population::InternalPtRouteDescription;
+use crate::simulation::scenario::vehicles::{Garage, InternalVehicle};
+use crate::simulation::time::{SimClock, SimTime, Tick};
+use crate::simulation::time_queue::TimeQueue;
+use crate::simulation::vehicles::SimulationVehicle;
+use nohash_hasher::IntMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::error;
+
+/// Engine for scheduled public transport. It starts the departures of the transit schedule whose
+/// network route begins on this partition and handles the vehicles while they serve a stop.
+/// Driving between stops is done by the network engine.
+///
+/// Agents with a pt route on one of the scheduled transit routes wait at their access stop, board
+/// a vehicle of that route and alight at their egress stop.
+pub(crate) struct TransitEngine {
+    schedule: Arc<TransitSchedule>,
+    transit_vehicles: Arc<Garage>,
+    departures: TimeQueue<ScheduledDeparture, TransitRoute>,
+    dwelling: TimeQueue<DwellingVehicle, InternalVehicle>,
+    /// Agents waiting at a stop facility, in the order of their arrival at the stop.
+    waiting: IntMap<Id<TransitStopFacility>, Vec<SimulationAgent>>,
+    /// Passengers which have alighted at their egress stop and finished their leg.
+    arrived: Vec<SimulationAgent>,
+    /// Passengers whose access or egress stop is not part of the schedule. They are stuck until
+    /// the end of the simulation.
+    rejected: Vec<SimulationAgent>,
+    comp_env: ThreadLocalComputationalEnvironment,
+    clock: SimClock,
+}
+
+impl TransitEngine {
+    /// Creates the engine. Only departures whose network route starts on one of `link_ids` are
+    /// started by this engine.
+    pub fn new(
+        schedule: Arc<TransitSchedule>,
+        transit_vehicles: Arc<Garage>,
+        link_ids: &HashSet<Id<Link>>,
+        comp_env: ThreadLocalComputationalEnvironment,
+        clock: SimClock,
+    ) -> Self {
+        let mut departures = TimeQueue::new();
+        for line in schedule.lines().values() {
+            for route in line.routes.values() {
+                let starts_on_partition = route
+                    .network_route
+                    .first()
+                    .is_some_and(|link| link_ids.contains(link));
+                if !starts_on_partition {
+                    continue;
+                }
+                for (index, departure) in route.departures.iter().enumerate() {
+                    departures.add(
+                        ScheduledDeparture {
+                            line: line.id.clone(),
+                            route: route.id.clone(),
+                            index,
+                            time: departure.departure_time,
+                        },
+                        SimTime::default(),
+                    );
+                }
+            }
+        }
+
+        TransitEngine {
+            schedule,
+            transit_vehicles,
+            departures,
+            dwelling: TimeQueue::new(),
+            waiting: IntMap::default(),
+            arrived: Vec::new(),
+            rejected: Vec::new(),
+            comp_env,
+            clock,
+        }
+    }
+
+    pub(crate) fn drain(&mut self) -> Vec<SimulationAgent> {
+        self.dwelling
+            .drain()
+            .into_iter()
+            .flat_map(|dwelling| dwelling.vehicle.into_agents())
+            .chain(self.waiting.drain().flat_map(|(_, agents)| agents))
+            .chain(self.arrived.drain(..))
+            .chain(self.rejected.drain(..))
+            .collect()
+    }
+
+    /// Whether the agent's current leg is a pt leg on a transit route of the schedule. Other pt
+    /// legs are teleported.
+    pub(crate) fn is_serving(&self, agent: &SimulationAgent) -> bool {
+        let Some(route) = agent.curr_leg().route.as_ref().and_then(|r| r.as_pt()) else {
+            return false;
+        };
+        self.schedule.find_route(route.description()).is_some()
+    }
+
+    /// Receives an agent which starts a pt leg. The agent waits at its access stop until a vehicle
+    /// of its transit route arrives. Agents whose access or egress stop does not exist are
+    /// rejected.
+    pub(crate) fn receive_passenger(&mut self, now: Tick, agent: SimulationAgent) {
+        let now_time = self.clock.tick_to_time(now);
+        let leg = agent.curr_leg();
+        let description = pt_description(&agent);
+        let stops = self
+            .find_stop(&description.access_facility_id)
+            .and_then(|access| Ok((access, self.find_stop(&description.egress_facility_id)?)));
+        let (access, egress) = match stops {
+            Ok(stops) => stops,
+            Err(err) => {
+                error!(
+                    "Rejecting transit passenger {}, who stays stuck at its departure: {err}",
+                    agent.id()
+                );
+                self.rejected.push(agent);
+                return;
+            }
+        };
+
+        let mut events = self.comp_env.events_manager_borrow_mut();
+        events.process_event(
+            &PersonDepartureEventBuilder::default()
+                .time(now_time)
+                .person(agent.id().clone())
+                .link(leg.route.as_ref().unwrap().start_link().clone())
+                .leg_mode(leg.mode.clone())
+                .routing_mode(
+                    leg.routing_mode
+                        .as_ref()
+                        .unwrap_or_else(|| panic!("Missing routing mode for leg {:?}", leg))
+                        .clone(),
+                )
+                .build()
+                .unwrap(),
+        );
+        events.process_event(
+            &AgentWaitingForPtEventBuilder::default()
+                .time(now_time)
+                .person(agent.id().clone())
+                .waiting_at_stop(access.clone())
+                .destination_stop(egress)
+                .build()
+                .unwrap(),
+        );
+        drop(events);
+
+        self.waiting.entry(access).or_default().push(agent);
+    }
+
+    fn find_stop(&self, id: &str) -> Result<Id<TransitStopFacility>, String> {
+        Id::try_get_from_ext(id)
+            .filter(|id| self.schedule.facilities().contains_key(id))
+            .ok_or_else(|| format!("Stop {id} is not part of the transit schedule."))
+    }
+
+    /// Returns the passengers which have finished their pt leg. They continue with their next
+    /// activity.
+    pub(crate) fn take_arrived_passengers(&mut self) -> Vec<SimulationAgent> {
+        std::mem::take(&mut self.arrived)
+    }
+
+    /// Creates the drivers and vehicles of all departures which are due. The returned vehicles
+    /// have to be sent onto the network at the start link of their route.
+    pub(crate) fn start_departures(&mut self, now: Tick) -> Vec<SimulationVehicle> {
+        let now_time = self.clock.tick_to_time(now);
+        self.departures
+            .pop(now_time)
+            .into_iter()
+            .map(|scheduled| self.start_departure(scheduled, now_time))
+            .collect()
+    }
+
+    fn start_departure(
+        &mut self,
+        scheduled: ScheduledDeparture,
+        now: SimTime,
+    ) -> SimulationVehicle {
+        let line = self.schedule.get_line(&scheduled.line);
+        let route = line.routes.get(&scheduled.route).unwrap();
+        let departure = &route.departures[scheduled.index];
+        let vehicle_id: Id<InternalVehicle> = Id::get_from_ext(
+            departure
+                .vehicle_ref_id
+                .as_ref()
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Departure {} of transit route {} has no vehicle.",
+                        departure.id, route.id
+                    )
+                })
+                .external(),
+        );
+
+        let driver =
+            TransitDriverLogic::new(&self.schedule, line, route, departure, vehicle_id.clone());
+        let start_link = route.network_route.first().unwrap().clone();
+
+        let mut events = self.comp_env.events_manager_borrow_mut();
+        events.process_event(
+            &TransitDriverStartsEventBuilder::default()
+                .time(now)
+                .driver(driver.id().clone())
+                .vehicle(vehicle_id.clone())
+                .transit_line(line.id.clone())
+                .transit_route(route.id.clone())
+                .departure(departure.id.clone())
+                .build()
+                .unwrap(),
+        );
+        events.process_event(
+            &PersonDepartureEventBuilder::default()
+                .time(now)
+                .person(driver.id().clone())
+                .link(start_link)
+                .leg_mode(route.transport_mode.clone())
+                .routing_mode(route.transport_mode.clone())
+                .build()
+                .unwrap(),
+        );
+        events.process_event(
+            &PersonEntersVehicleEventBuilder::default()
+                .time(now)
+                .person(driver.id().clone())
+                .vehicle(vehicle_id.clone())
+                .build()
+                .unwrap(),
+        );
+        drop(events);
+
+        self.transit_vehicles
+            .unpark_veh(SimulationAgent::new(Box::new(driver)), vehicle_id)
+    }
+
+    /// Receives a vehicle which has reached its next stop. The vehicle dwells at the stop at least
+    /// for the minimum stop duration and, if the stop awaits the departure, until the scheduled
+    /// departure time.
+    pub(crate) fn receive_vehicle_at_stop(&mut self, now: Tick, mut vehicle: SimulationVehicle) {
+        let now_time = self.clock.tick_to_time(now);
+        let stop = Self::next_stop(&vehicle).clone();
+
+        self.comp_env.events_manager_borrow_mut().process_event(
+            &VehicleArrivesAtFacilityEventBuilder::default()
+                .time(now_time)
+                .vehicle(vehicle.id().clone())
+                .facility(stop.facility_id.clone())
+                .delay(delay(now_time, stop.arrival_time))
+                .build()
+                .unwrap(),
+        );
+
+        self.alight_passengers(now_time, &mut vehicle, &stop);
+        self.board_passengers(now_time, &mut vehicle, &stop);
+
+        let mut leave_time = now_time.saturating_add(stop.minimum_stop_duration);
+        if stop.await_departure {
+            leave_time = leave_time.max(stop.departure_time);
+        }
+
+        self.dwelling.add(
+            DwellingVehicle {
+                vehicle,
+                leave_time,
+            },
+            now_time,
+        );
+    }
+
+    /// Returns the vehicles which are done serving their stop. The vehicles have to be put back
+    /// onto their current link.
+    pub(crate) fn leave_stops(&mut self, now: Tick) -> Vec<SimulationVehicle> {
+        let now_time = self.clock.tick_to_time(now);
+        let mut vehicles = vec![];
+        for dwelling in self.dwelling.pop(now_time) {
+            let mut vehicle = dwelling.vehicle;
+            let stop = Self::next_stop(&vehicle).clone();
+
+            // agents might have arrived at the stop while the vehicle was dwelling
+            self.board_passengers(now_time, &mut vehicle, &stop);
+
+            self.comp_env.events_manager_borrow_mut().process_event(
+                &VehicleDepartsAtFacilityEventBuilder::default()
+                    .time(now_time)
+                    .vehicle(vehicle.id().clone())
+                    .facility(stop.facility_id.clone())
+                    .delay(delay(now_time, stop.departure_time))
+                    .build()
+                    .unwrap(),
+            );
+
+            vehicle.notify_event(&mut AgentEvent::DepartedFromStop(), now_time);
+            vehicles.push(vehicle);
+        }
+        vehicles
+    }
+
+    fn alight_passengers(
+        &mut self,
+        now: SimTime,
+        vehicle: &mut SimulationVehicle,
+        stop: &TransitDriverStop,
+    ) {
+        if !stop.allow_alighting {
+            return;
+        }
+
+        let alighting = vehicle.remove_passengers(|passenger| {
+            pt_description(passenger).egress_facility_id == stop.facility_id.external()
+        });
+
+        for mut passenger in alighting {
+            passenger.notify_event(&mut AgentEvent::LeftTransitVehicle(), now);
+
+            let mut events = self.comp_env.events_manager_borrow_mut();
+            events.process_event(
+                &PersonLeavesVehicleEventBuilder::default()
+                    .time(now)
+                    .person(passenger.id().clone())
+                    .vehicle(vehicle.id().clone())
+                    .build()
+                    .unwrap(),
+            );
+            events.process_event(
+                &PersonArrivalEventBuilder::default()
+                    .time(now)
+                    .person(passenger.id().clone())
+                    .link(stop.link_id.clone())
+                    .leg_mode(passenger.curr_leg().mode.clone())
+                    .build()
+                    .unwrap(),
+            );
+            drop(events);
+
+            self.arrived.push(passenger);
+        }
+    }
+
+    /// Boards the agents waiting at the stop for the vehicle's transit route, as long as the
+    /// vehicle has capacity left.
+    fn board_passengers(
+        &mut self,
+        now: SimTime,
+        vehicle: &mut SimulationVehicle,
+        stop: &TransitDriverStop,
+    ) {
+        if !stop.allow_boarding {
+            return;
+        }
+        let Some(waiting) = self.waiting.get_mut(&stop.facility_id) else {
+            return;
+        };
+
+        let driver = vehicle.driver().transit_driver().unwrap();
+        let line = driver.transit_line().clone();
+        let route = driver.transit_route().clone();
+        let capacity = self
+            .transit_vehicles
+            .vehicle_types
+            .get(&vehicle.internal_vehicle().vehicle_type)
+            .map(|veh_type| veh_type.passenger_capacity())
+            .unwrap_or_else(|| panic!("No vehicle type found for transit vehicle {}", vehicle.id()))
+            as usize;
+
+        let mut i = 0;
+        while i < waiting.len() && vehicle.passengers().len() < capacity {
+            let description = pt_description(&waiting[i]);
+            let is_waiting_for_vehicle = description.transit_line_id == line.external()
+                && description.transit_route_id == route.external();
+            if !is_waiting_for_vehicle {
+                i += 1;
+                continue;
+            }
+
+            let passenger = waiting.remove(i);
+            self.comp_env.events_manager_borrow_mut().process_event(
+                &PersonEntersVehicleEventBuilder::default()
+                    .time(now)
+                    .person(passenger.id().clone())
+                    .vehicle(vehicle.id().clone())
+                    .build()
+                    .unwrap(),
+            );
+            vehicle.add_passenger(passenger);
+        }
+    }
+
+    fn next_stop(vehicle: &SimulationVehicle) -> &TransitDriverStop {
+        vehicle
+            .driver()
+            .transit_driver()
+            .and_then(|driver| driver.next_stop())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Vehicle {} at a stop must be driven by a transit driver with a next stop.",
+                    vehicle.id()
+                )
+            })
+    }
+}
+
+fn pt_description(agent: &SimulationAgent) -> &InternalPtRouteDescription {
+    agent
+        .curr_leg()
+        .route
+        .as_ref()
+        .and_then(|route| route.as_pt())
+        .map(|route| route.description())
+        .unwrap_or_else(|| panic!("Transit passenger {} must have a pt route.", agent.id()))
+}
+
+/// Difference between `actual` and `scheduled` in seconds. Negative if the vehicle is early.
+fn delay(actual: SimTime, scheduled: SimTime) -> f64 {
+    actual.as_duration().as_secs_f64() - scheduled.as_duration().as_secs_f64()
+}
+
+struct ScheduledDeparture {
+    line: Id<TransitLine>,
+    route: Id<TransitRoute>,
+    index: usize,
+    time: SimTime,
+}
+
+impl EndTime for ScheduledDeparture {
+    fn end_time(&self, _now: SimTime) -> SimTime {
+        self.time
+    }
+}
+
+struct DwellingVehicle {
+    vehicle: SimulationVehicle,
+    leave_time: SimTime,
+}
+
+impl EndTime for DwellingVehicle {
+    fn end_time(&self, _now: SimTime) -> SimTime {
+        self.leave_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::agents::agent::SimulationAgent;
+    use crate::simulation::config::Config;
+    use crate::simulation::controller::{MobsimWorkerPool, MobsimWorkerPoolArgumentsBuilder};
+    use crate::simulation::events::{EventHandlerRegisterFn, EventsManager};
+    use crate::simulation::id::Id;
+    use crate::simulation::io::xml::events::XmlEventsWriter;
+    use crate::simulation::io::xml::transit::IOTransitSchedule;
+    use crate::simulation::network::sim_network::SimNetworkPartition;
+    use crate::simulation::population::agent_source::PopulationAgentSource;
+    use crate::simulation::pt::TransitSchedule;
+    use crate::simulation::scenario::facilities::ActivityFacilities;
+    use crate::simulation::scenario::network::{Link, Network, Node};
+    use crate::simulation::scenario::population::{
+        InternalActivity, InternalGenericRoute, InternalLeg, InternalPerson, InternalPlan,
+        InternalPtRoute, InternalPtRouteDescription, InternalRoute, Population,
+    };
+    use crate::simulation::scenario::vehicles::{Garage, InternalVehicle};
+    use crate::simulation::scenario::{
+        Coordinate, MobsimInput, MobsimScenarioPartition, PopulationShard, ScenarioCore,
+    };
+    use crate::simulation::time::SimTime;
+    use crate::test_utils::create_vehicle_type;
+    use macros::deterministic_id_test;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Barrier, Mutex};
+    use std::time::Duration;
+
+    const SCHEDULE: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <transitSchedule>\
+            <transitStops>\
+                <stopFacility id=\"s1\" x=\"50.0\" y=\"0.0\" linkRefId=\"l1\"/>\
+                <stopFacility id=\"s2\" x=\"250.0\" y=\"0.0\" linkRefId=\"l3\"/>\
+            </transitStops>\
+            <transitLine id=\"line1\">\
+                <transitRoute id=\"route1\">\
+                    <transportMode>bus</transportMode>\
+                    <routeProfile>\
+                        <stop refId=\"s1\" departureOffset=\"00:00:00\"/>\
+                        <stop refId=\"s2\" arrivalOffset=\"00:00:30\" departureOffset=\"00:00:40\" awaitDeparture=\"true\"/>\
+                    </routeProfile>\
+                    <route>\
+                        <link refId=\"l1\"/>\
+                        <link refId=\"l2\"/>\
+                        <link refId=\"l3\"/>\
+                    </route>\
+                    <departures>\
+                        <departure id=\"d1\" departureTime=\"00:00:10\" vehicleRefId=\"bus1\"/>\
+                    </departures>\
+                </transitRoute>\
+            </transitLine>\
+        </transitSchedule>";
+
+    const DRIVER_EVENTS: &[&str] = &[
+        "TransitDriverStarts",
+        "VehicleArrivesAtFacility",
+        "VehicleDepartsAtFacility",
+        "vehicle leaves traffic",
+    ];
+
+    const PASSENGER_EVENTS: &[&str] = &[
+        "departure",
+        "waitingForPt",
+        "PersonEntersVehicle",
+        "PersonLeavesVehicle",
+        "arrival",
+    ];
+
+    #[deterministic_id_test]
+    fn transit_vehicle_serves_stops() {
+        let (events, agents) = run_transit_scenario(1, &[], DRIVER_EVENTS);
+
+        // transit drivers are not part of the population
+        assert!(agents.is_empty());
+        assert_eq!(
+            vec![
+                "<event time=\"10\" type=\"TransitDriverStarts\" driverId=\"pt_bus1_d1\" vehicleId=\"bus1\" transitLineId=\"line1\" transitRouteId=\"route1\" departureId=\"d1\"/>\n",
+                "<event time=\"10\" type=\"VehicleArrivesAtFacility\" vehicle=\"bus1\" facility=\"s1\" delay=\"0\"/>\n",
+                "<event time=\"10\" type=\"VehicleDepartsAtFacility\" vehicle=\"bus1\" facility=\"s1\" delay=\"0\"/>\n",
+                "<event time=\"33\" type=\"VehicleArrivesAtFacility\" vehicle=\"bus1\" facility=\"s2\" delay=\"-7\"/>\n",
+                "<event time=\"50\" type=\"VehicleDepartsAtFacility\" vehicle=\"bus1\" facility=\"s2\" delay=\"0\"/>\n",
+                "<event time=\"51\" type=\"vehicle leaves traffic\" person=\"pt_bus1_d1\" link=\"l3\" vehicle=\"bus1\" networkMode=\"bus\" relativePosition=\"1\"/>\n",
+            ],
+            events
+        );
+    }
+
+    #[deterministic_id_test]
+    fn transit_vehicle_serves_stops_across_partitions() {
+        // The second partition owns l2 and l3, i.e. the vehicle is passed on after the first stop.
+        let (events, agents) = run_transit_scenario(2, &[], DRIVER_EVENTS);
+
+        assert!(agents.is_empty());
+        assert_eq!(
+            vec![
+                "<event time=\"10\" type=\"TransitDriverStarts\" driverId=\"pt_bus1_d1\" vehicleId=\"bus1\" transitLineId=\"line1\" transitRouteId=\"route1\" departureId=\"d1\"/>\n",
+                "<event time=\"10\" type=\"VehicleArrivesAtFacility\" vehicle=\"bus1\" facility=\"s1\" delay=\"0\"/>\n",
+                "<event time=\"10\" type=\"VehicleDepartsAtFacility\" vehicle=\"bus1\" facility=\"s1\" delay=\"0\"/>\n",
+                "<event time=\"33\" type=\"VehicleArrivesAtFacility\" vehicle=\"bus1\" facility=\"s2\" delay=\"-7\"/>\n",
+                "<event time=\"50\" type=\"VehicleDepartsAtFacility\" vehicle=\"bus1\" facility=\"s2\" delay=\"0\"/>\n",
+                "<event time=\"51\" type=\"vehicle leaves traffic\" person=\"pt_bus1_d1\" link=\"l3\" vehicle=\"bus1\" networkMode=\"bus\" relativePosition=\"1\"/>\n",
+            ],
+            events
+        );
+    }
+
+    #[deterministic_id_test]
+    fn passenger_waits_boards_and_alights() {
+        let (events, agents) = run_transit_scenario(1, &["p1"], PASSENGER_EVENTS);
+
+        assert_eq!(1, agents.len());
+        assert_eq!(expected_passenger_events(), events);
+    }
+
+    #[deterministic_id_test]
+    fn passenger_alights_on_other_partition() {
+        let (events, agents) = run_transit_scenario(2, &["p1"], PASSENGER_EVENTS);
+
+        assert_eq!(1, agents.len());
+        assert_eq!(expected_passenger_events(), events);
+    }
+
+    #[deterministic_id_test]
+    fn passengers_exceeding_capacity_keep_waiting() {
+        // the vehicle type has one seat besides the driver's, p1 has been waiting longer than p2
+        let (events, agents) = run_transit_scenario(1, &["p1", "p2"], &["PersonEntersVehicle"]);
+
+        // p2 is still waiting at the stop when the simulation ends
+        assert_eq!(2, agents.len());
+        assert_eq!(
+            vec![
+                "<event time=\"10\" type=\"PersonEntersVehicle\" person=\"pt_bus1_d1\" vehicle=\"bus1\"/>\n",
+                "<event time=\"10\" type=\"PersonEntersVehicle\" person=\"p1\" vehicle=\"bus1\"/>\n",
+            ],
+            events
+        );
+    }
+
+    #[deterministic_id_test]
+    fn passenger_with_unknown_stop_is_rejected() {
+        let (events, agents) = run_transit_scenario_with(
+            1,
+            || {
+                vec![pt_person_between(
+                    "p1",
+                    SimTime::from_secs(5),
+                    "s1",
+                    "unknown-stop",
+                )]
+            },
+            PASSENGER_EVENTS,
+        );
+
+        // p1 is stuck until the simulation ends, the bus serves its stops anyway
+        assert_eq!(1, agents.len());
+        assert!(events.iter().all(|event| !event.contains("\"p1\"")));
+        assert!(events.iter().any(|event| event.contains("\"pt_bus1_d1\"")));
+    }
+
+    fn expected_passenger_events() -> Vec<&'static str> {
+        vec![
+            "<event time=\"5\" type=\"departure\" person=\"p1\" link=\"l1\" legMode=\"pt\" computationalRoutingMode=\"pt\"/>\n",
+            "<event time=\"5\" type=\"waitingForPt\" agent=\"p1\" atStop=\"s1\" destinationStop=\"s2\"/>\n",
+            "<event time=\"10\" type=\"departure\" person=\"pt_bus1_d1\" link=\"l1\" legMode=\"bus\" computationalRoutingMode=\"bus\"/>\n",
+            "<event time=\"10\" type=\"PersonEntersVehicle\" person=\"pt_bus1_d1\" vehicle=\"bus1\"/>\n",
+            "<event time=\"10\" type=\"PersonEntersVehicle\" person=\"p1\" vehicle=\"bus1\"/>\n",
+            "<event time=\"33\" type=\"PersonLeavesVehicle\" person=\"p1\" vehicle=\"bus1\"/>\n",
+            "<event time=\"33\" type=\"arrival\" person=\"p1\" link=\"l3\" legMode=\"pt\"/>\n",
+            "<event time=\"51\" type=\"PersonLeavesVehicle\" person=\"pt_bus1_d1\" vehicle=\"bus1\"/>\n",
+            "<event time=\"51\" type=\"arrival\" person=\"pt_bus1_d1\" link=\"l3\" legMode=\"bus\"/>\n",
+        ]
+    }
+
+    /// Runs the mobsim with the schedule above and returns the events of the given types of all
+    /// partitions ordered by time, as well as the agents returned by the mobsim. `persons` are the
+    /// ids of passengers travelling from s1 to s2, who leave home one second after another starting at 5s.
+    fn run_transit_scenario(
+        num_parts: u32,
+        persons: &[&str],
+        event_types: &'static [&'static str],
+    ) -> (Vec<String>, Vec<SimulationAgent>) {
+        run_transit_scenario_with(
+            num_parts,
+            || {
+                persons
+                    .iter()
+                    .enumerate()
+                    .map(|(i, id)| pt_person(id, SimTime::from_secs(5 + i as u64)))
+                    .collect()
+            },
+            event_types,
+        )
+    }
+
+    /// Like [run_transit_scenario], but with the persons created by `persons` once the network
+    /// ids exist.
+    fn run_transit_scenario_with(
+        num_parts: u32,
+        persons: impl FnOnce() -> Vec<InternalPerson>,
+        event_types: &'static [&'static str],
+    ) -> (Vec<String>, Vec<SimulationAgent>) {
+        let mut config = Config::default();
+        config.partitioning_mut().num_parts = num_parts;
+        config.qsim_mut().end_time = 100;
+        let config = Arc::new(config);
+
+        let schedule =
+            TransitSchedule::from(quick_xml::de::from_str::<IOTransitSchedule>(SCHEDULE).unwrap());
+        let mut transit_vehicles = Garage::default();
+        let mut bus_type = create_vehicle_type(&Id::create("bus"), Id::create("bus"));
+        bus_type.seats = 2;
+        transit_vehicles.add_veh_type(bus_type);
+        transit_vehicles.add_veh(InternalVehicle {
+            id: Id::create("bus1"),
+            max_v: 100.,
+            pce: 1.,
+            fef: 1.,
+            vehicle_type: Id::get_from_ext("bus"),
+            attributes: Default::default(),
+        });
+
+        let scenario_core = ScenarioCore {
+            network: Arc::new(create_network(num_parts - 1)),
+            garage: Arc::new(Garage::default()),
+            transit_schedule: Arc::new(schedule),
+            transit_vehicles: Arc::new(transit_vehicles),
+            facilities: Arc::new(ActivityFacilities::default()),
+            config: config.clone(),
+        };
+
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let handlers: HashMap<u32, Vec<Box<EventHandlerRegisterFn>>> = (0..num_parts)
+            .map(|rank| (rank, vec![collect_events(collected.clone(), event_types)]))
+            .collect();
+
+        let args = MobsimWorkerPoolArgumentsBuilder::default()
+            .scenario_core(scenario_core.clone())
+            .agent_source(Arc::new(PopulationAgentSource))
+            .event_handler_per_partition(handlers)
+            .global_barrier(Arc::new(Barrier::new(1)))
+            .build()
+            .unwrap();
+        let mut pool = MobsimWorkerPool::spawn(args);
+
+        // all persons start on l1, which belongs to partition 0
+        let mut population = Some(Population::from_persons(persons()));
+        let inputs = (0..num_parts)
+            .map(|rank| {
+                let population = population.take().unwrap_or_default();
+                mobsim_input(&scenario_core, rank, population)
+            })
+            .collect();
+        let agents = pool.run_mobsim(0, true, inputs);
+        pool.shutdown();
+
+        let mut events = collected.lock().unwrap().clone();
+        events.sort_by_key(|(time, _)| *time);
+        (events.into_iter().map(|(_, e)| e).collect(), agents)
+    }
+
+    fn collect_events(
+        collected: Arc<Mutex<Vec<(u64, String)>>>,
+        event_types: &'static [&'static str],
+    ) -> Box<EventHandlerRegisterFn> {
+        Box::new(move |events: &mut EventsManager| {
+            events.on_any(move |e| {
+                if event_types.contains(&e.type_()) {
+                    collected
+                        .lock()
+                        .unwrap()
+                        .push((e.time().as_nanos(), XmlEventsWriter::event_2_string(e)));
+                }
+            });
+        })
+    }
+
+    /// Creates a person which leaves home on l1 at `dep_time` and takes route1 from s1 to s2 to work
+    /// on l3.
+    fn pt_person(id: &str, dep_time: SimTime) -> InternalPerson {
+        pt_person_between(id, dep_time, "s1", "s2")
+    }
+
+    /// Creates a person which leaves home on l1 at `dep_time` and takes route1 from `access` to
+    /// `egress` to work on l3.
+    fn pt_person_between(
+        id: &str,
+        dep_time: SimTime,
+        access: &str,
+        egress: &str,
+    ) -> InternalPerson {
+        let route = InternalRoute::Pt(InternalPtRoute {
+            generic_delegate: InternalGenericRoute::new(
+                Id::get_from_ext("l1"),
+                Id::get_from_ext("l3"),
+                Some(Duration::from_secs(30)),
+                Some(200.),
+                None,
+            ),
+            description: InternalPtRouteDescription {
+                transit_route_id: "route1".to_string(),
+                boarding_time: None,
+                transit_line_id: "line1".to_string(),
+                access_facility_id: access.to_string(),
+                egress_facility_id: egress.to_string(),
+            },
+        });
+
+        let mut plan = InternalPlan::default();
+        plan.add_act(InternalActivity::new(
+            Some(Coordinate::new_2d(100., 0.)),
+            "home",
+            Id::get_from_ext("l1"),
+            None,
+            Some(dep_time),
+            None,
+        ));
+        plan.add_leg(InternalLeg::new(
+            route,
+            "pt",
+            Duration::from_secs(30),
+            Some(dep_time),
+        ));
+        plan.add_act(InternalActivity::new(
+            Some(Coordinate::new_2d(300., 0.)),
+            "work",
+            Id::get_from_ext("l3"),
+            None,
+            None,
+            None,
+        ));
+        InternalPerson::new(Id::create(id), plan)
+    }
+
+    /// Creates the network n1 -l1-> n2 -l2-> n3 -l3-> n4 with links of 100m and a free speed of
+    /// 10m/s. Nodes n3 and n4 as well as l2 and l3 belong to `second_partition`.
+    fn create_network(second_partition: u32) -> Network {
+        let mut network = Network::new();
+        let nodes: Vec<_> = (0..4)
+            .map(|i| {
+                let part = if i < 2 { 0 } else { second_partition };
+                Node::new(
+                    Id::create(&format!("n{}", i + 1)),
+                    Coordinate::new_2d(i as f64 * 100., 0.),
+                    part,
+                    1,
+                )
+            })
+            .collect();
+        let links: Vec<_> = nodes
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let mut link =
+                    Link::new_with_default(Id::create(&format!("l{}", i + 1)), &pair[0], &pair[1]);
+                link.capacity = 3600.;
+                link.freespeed = 10.;
+                link.partition = pair[1].partition;
+                link
+            })
+            .collect();
+        for node in nodes {
+            network.add_node(node);
+        }
+        for link in links {
+            network.add_link(link);
+        }
+        network
+    }
+
+    fn mobsim_input(scenario: &ScenarioCore, rank: u32, population: Population) -> MobsimInput {
+        let network_partition = SimNetworkPartition::from_network(
+            &scenario.network,
+            rank,
+            scenario.config.qsim(),
+            scenario.config.computational_setup().random_seed,
+        );
+
+        MobsimInput {
+            partition: MobsimScenarioPartition {
+                rank,
+                scenario: scenario.clone(),
+                network_partition,
+            },
+            population: PopulationShard { population },
+        }
+    }
+}
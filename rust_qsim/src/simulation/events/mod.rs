@@ -2,7 +2,9 @@ mod comparison;
 pub mod utils;
 
 use crate::simulation::InternalAttributes;
+use crate::simulation::drt::DrtRequest;
 use crate::simulation::id::Id;
+use crate::simulation::pt::{TransitDeparture, TransitLine, TransitRoute, TransitStopFacility};
 use crate::simulation::scenario::Coordinate;
 use crate::simulation::scenario::network::Link;
 use crate::simulation::scenario::population::InternalPerson;
@@ -552,6 +554,238 @@ impl PtTeleportationArrivalEvent {
     }
 }
 
+#[event_struct]
+pub struct TransitDriverStartsEvent {
+    pub time: SimTime,
+    pub driver: Id<InternalPerson>,
+    pub vehicle: Id<InternalVehicle>,
+    pub transit_line: Id<TransitLine>,
+    pub transit_route: Id<TransitRoute>,
+    pub departure: Id<TransitDeparture>,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl TransitDriverStartsEvent {
+    pub const TYPE: &'static str = "TransitDriverStarts";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        TransitDriverStartsEventBuilder::default()
+            .time(time)
+            .driver(Id::create(&event.attributes["driver"].as_string()))
+            .vehicle(Id::create(&event.attributes["vehicle"].as_string()))
+            .transit_line(Id::create(&event.attributes["transit_line"].as_string()))
+            .transit_route(Id::create(&event.attributes["transit_route"].as_string()))
+            .departure(Id::create(&event.attributes["departure"].as_string()))
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
+#[event_struct]
+pub struct VehicleArrivesAtFacilityEvent {
+    pub time: SimTime,
+    pub vehicle: Id<InternalVehicle>,
+    pub facility: Id<TransitStopFacility>,
+    /// Difference between the actual and the scheduled arrival time in seconds.
+    pub delay: f64,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl VehicleArrivesAtFacilityEvent {
+    pub const TYPE: &'static str = "VehicleArrivesAtFacility";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        VehicleArrivesAtFacilityEventBuilder::default()
+            .time(time)
+            .vehicle(Id::create(&event.attributes["vehicle"].as_string()))
+            .facility(Id::create(&event.attributes["facility"].as_string()))
+            .delay(event.attributes["delay"].as_double())
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
+#[event_struct]
+pub struct VehicleDepartsAtFacilityEvent {
+    pub time: SimTime,
+    pub vehicle: Id<InternalVehicle>,
+    pub facility: Id<TransitStopFacility>,
+    /// Difference between the actual and the scheduled departure time in seconds.
+    pub delay: f64,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl VehicleDepartsAtFacilityEvent {
+    pub const TYPE: &'static str = "VehicleDepartsAtFacility";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        VehicleDepartsAtFacilityEventBuilder::default()
+            .time(time)
+            .vehicle(Id::create(&event.attributes["vehicle"].as_string()))
+            .facility(Id::create(&event.attributes["facility"].as_string()))
+            .delay(event.attributes["delay"].as_double())
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
+#[event_struct]
+pub struct AgentWaitingForPtEvent {
+    pub time: SimTime,
+    pub person: Id<InternalPerson>,
+    pub waiting_at_stop: Id<TransitStopFacility>,
+    pub destination_stop: Id<TransitStopFacility>,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl AgentWaitingForPtEvent {
+    pub const TYPE: &'static str = "waitingForPt";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        AgentWaitingForPtEventBuilder::default()
+            .time(time)
+            .person(Id::create(&event.attributes["person"].as_string()))
+            .waiting_at_stop(Id::create(&event.attributes["waiting_at_stop"].as_string()))
+            .destination_stop(Id::create(
+                &event.attributes["destination_stop"].as_string(),
+            ))
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
+#[event_struct]
+pub struct DrtRequestSubmittedEvent {
+    pub time: SimTime,
+    pub person: Id<InternalPerson>,
+    pub mode: Id<String>,
+    pub request: Id<DrtRequest>,
+    pub from_link: Id<Link>,
+    pub to_link: Id<Link>,
+    /// Travel time in seconds of the direct ride from the pickup to the dropoff link.
+    pub unshared_ride_time: f64,
+    /// Distance in meters of the direct ride from the pickup to the dropoff link.
+    pub unshared_ride_distance: f64,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl DrtRequestSubmittedEvent {
+    pub const TYPE: &'static str = "DrtRequest submitted";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        DrtRequestSubmittedEventBuilder::default()
+            .time(time)
+            .person(Id::create(&event.attributes["person"].as_string()))
+            .mode(Id::create(&event.attributes["mode"].as_string()))
+            .request(Id::create(&event.attributes["request"].as_string()))
+            .from_link(Id::create(&event.attributes["from_link"].as_string()))
+            .to_link(Id::create(&event.attributes["to_link"].as_string()))
+            .unshared_ride_time(event.attributes["unshared_ride_time"].as_double())
+            .unshared_ride_distance(event.attributes["unshared_ride_distance"].as_double())
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
+#[event_struct]
+pub struct PassengerRequestRejectedEvent {
+    pub time: SimTime,
+    pub person: Id<InternalPerson>,
+    pub mode: Id<String>,
+    pub request: Id<DrtRequest>,
+    pub cause: String,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl PassengerRequestRejectedEvent {
+    pub const TYPE: &'static str = "PassengerRequest rejected";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        PassengerRequestRejectedEventBuilder::default()
+            .time(time)
+            .person(Id::create(&event.attributes["person"].as_string()))
+            .mode(Id::create(&event.attributes["mode"].as_string()))
+            .request(Id::create(&event.attributes["request"].as_string()))
+            .cause(event.attributes["cause"].as_string())
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
+#[event_struct]
+pub struct PassengerPickedUpEvent {
+    pub time: SimTime,
+    pub person: Id<InternalPerson>,
+    pub mode: Id<String>,
+    pub request: Id<DrtRequest>,
+    pub vehicle: Id<InternalVehicle>,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl PassengerPickedUpEvent {
+    pub const TYPE: &'static str = "passenger picked up";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        PassengerPickedUpEventBuilder::default()
+            .time(time)
+            .person(Id::create(&event.attributes["person"].as_string()))
+            .mode(Id::create(&event.attributes["mode"].as_string()))
+            .request(Id::create(&event.attributes["request"].as_string()))
+            .vehicle(Id::create(&event.attributes["vehicle"].as_string()))
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
+#[event_struct]
+pub struct PassengerDroppedOffEvent {
+    pub time: SimTime,
+    pub person: Id<InternalPerson>,
+    pub mode: Id<String>,
+    pub request: Id<DrtRequest>,
+    pub vehicle: Id<InternalVehicle>,
+    #[builder(default)]
+    pub attributes: InternalAttributes,
+}
+
+impl PassengerDroppedOffEvent {
+    pub const TYPE: &'static str = "passenger dropped off";
+    pub fn from_proto_event(event: &crate::generated::events::GenericEvent, time: SimTime) -> Self {
+        let attrs = InternalAttributes::from(&event.attributes);
+        assert!(event.r#type.eq(Self::TYPE));
+        PassengerDroppedOffEventBuilder::default()
+            .time(time)
+            .person(Id::create(&event.attributes["person"].as_string()))
+            .mode(Id::create(&event.attributes["mode"].as_string()))
+            .request(Id::create(&event.attributes["request"].as_string()))
+            .vehicle(Id::create(&event.attributes["vehicle"].as_string()))
+            .attributes(attrs)
+            .build()
+            .unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::simulation::events::{
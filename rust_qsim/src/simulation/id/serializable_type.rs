@@ -1,3 +1,4 @@
+use crate::simulation::drt::DrtRequest;
 use crate::simulation::pt::{TransitDeparture, TransitLine, TransitRoute, TransitStopFacility};
 use crate::simulation::scenario::facilities::ActivityFacility;
 use crate::simulation::scenario::network::{Link, Node};
@@ -103,6 +104,12 @@ impl StableTypeId for ActivityFacility {
     }
 }
 
+impl StableTypeId for DrtRequest {
+    fn stable_type_id() -> u64 {
+        DRT_REQUEST_TYPE_ID
+    }
+}
+
 pub const STRING_TYPE_ID: u64 = 1;
 pub const PERSON_TYPE_ID: u64 = 2;
 pub const LINK_TYPE_ID: u64 = 3;
@@ -118,3 +125,4 @@ pub const TRANSIT_ROUTE_TYPE_ID: u64 = 12;
 pub const TRANSIT_STOP_FACILITY_TYPE_ID: u64 = 13;
 pub const TRANSIT_DEPARTURE_TYPE_ID: u64 = 14;
 pub const FACILITY_TYPE_ID: u64 = 15;
+pub const DRT_REQUEST_TYPE_ID: u64 = 16;
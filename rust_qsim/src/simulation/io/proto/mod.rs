@@ -1,4 +1,6 @@
 pub mod proto_events;
+pub mod proto_messages;
 pub mod proto_network;
 pub mod proto_population;
+pub mod proto_transit;
 pub mod proto_vehicles;
@@ -1,10 +1,13 @@
 use crate::generated::events::{GenericEvent, TimeStep};
 use crate::generated::general::AttributeValue;
 use crate::simulation::events::{
-    ActivityEndEvent, ActivityStartEvent, EventHandlerRegisterFn, EventTrait, EventsManager,
-    LinkEnterEvent, LinkLeaveEvent, PersonArrivalEvent, PersonDepartureEvent,
-    PersonEntersVehicleEvent, PersonLeavesVehicleEvent, PtTeleportationArrivalEvent,
-    TeleportationArrivalEvent, VehicleEntersTrafficEvent, VehicleLeavesTrafficEvent,
+    ActivityEndEvent, ActivityStartEvent, AgentWaitingForPtEvent, DrtRequestSubmittedEvent,
+    EventHandlerRegisterFn, EventTrait, EventsManager, LinkEnterEvent, LinkLeaveEvent,
+    PassengerDroppedOffEvent, PassengerPickedUpEvent, PassengerRequestRejectedEvent,
+    PersonArrivalEvent, PersonDepartureEvent, PersonEntersVehicleEvent, PersonLeavesVehicleEvent,
+    PtTeleportationArrivalEvent, TeleportationArrivalEvent, TransitDriverStartsEvent,
+    VehicleArrivesAtFacilityEvent, VehicleDepartsAtFacilityEvent, VehicleEntersTrafficEvent,
+    VehicleLeavesTrafficEvent,
 };
 use crate::simulation::time::SimTime;
 use prost::Message;
@@ -299,6 +302,212 @@ impl From<&VehicleLeavesTrafficEvent> for GenericEvent {
     }
 }
 
+impl From<&TransitDriverStartsEvent> for GenericEvent {
+    fn from(value: &TransitDriverStartsEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "driver".to_string(),
+            AttributeValue::from(value.driver.external()),
+        );
+        attributes.insert(
+            "vehicle".to_string(),
+            AttributeValue::from(value.vehicle.external()),
+        );
+        attributes.insert(
+            "transit_line".to_string(),
+            AttributeValue::from(value.transit_line.external()),
+        );
+        attributes.insert(
+            "transit_route".to_string(),
+            AttributeValue::from(value.transit_route.external()),
+        );
+        attributes.insert(
+            "departure".to_string(),
+            AttributeValue::from(value.departure.external()),
+        );
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
+impl From<&VehicleArrivesAtFacilityEvent> for GenericEvent {
+    fn from(value: &VehicleArrivesAtFacilityEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "vehicle".to_string(),
+            AttributeValue::from(value.vehicle.external()),
+        );
+        attributes.insert(
+            "facility".to_string(),
+            AttributeValue::from(value.facility.external()),
+        );
+        attributes.insert("delay".to_string(), AttributeValue::from(value.delay));
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
+impl From<&VehicleDepartsAtFacilityEvent> for GenericEvent {
+    fn from(value: &VehicleDepartsAtFacilityEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "vehicle".to_string(),
+            AttributeValue::from(value.vehicle.external()),
+        );
+        attributes.insert(
+            "facility".to_string(),
+            AttributeValue::from(value.facility.external()),
+        );
+        attributes.insert("delay".to_string(), AttributeValue::from(value.delay));
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
+impl From<&AgentWaitingForPtEvent> for GenericEvent {
+    fn from(value: &AgentWaitingForPtEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "person".to_string(),
+            AttributeValue::from(value.person.external()),
+        );
+        attributes.insert(
+            "waiting_at_stop".to_string(),
+            AttributeValue::from(value.waiting_at_stop.external()),
+        );
+        attributes.insert(
+            "destination_stop".to_string(),
+            AttributeValue::from(value.destination_stop.external()),
+        );
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
+impl From<&DrtRequestSubmittedEvent> for GenericEvent {
+    fn from(value: &DrtRequestSubmittedEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "person".to_string(),
+            AttributeValue::from(value.person.external()),
+        );
+        attributes.insert(
+            "mode".to_string(),
+            AttributeValue::from(value.mode.external()),
+        );
+        attributes.insert(
+            "request".to_string(),
+            AttributeValue::from(value.request.external()),
+        );
+        attributes.insert(
+            "from_link".to_string(),
+            AttributeValue::from(value.from_link.external()),
+        );
+        attributes.insert(
+            "to_link".to_string(),
+            AttributeValue::from(value.to_link.external()),
+        );
+        attributes.insert(
+            "unshared_ride_time".to_string(),
+            AttributeValue::from(value.unshared_ride_time),
+        );
+        attributes.insert(
+            "unshared_ride_distance".to_string(),
+            AttributeValue::from(value.unshared_ride_distance),
+        );
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
+impl From<&PassengerRequestRejectedEvent> for GenericEvent {
+    fn from(value: &PassengerRequestRejectedEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "person".to_string(),
+            AttributeValue::from(value.person.external()),
+        );
+        attributes.insert(
+            "mode".to_string(),
+            AttributeValue::from(value.mode.external()),
+        );
+        attributes.insert(
+            "request".to_string(),
+            AttributeValue::from(value.request.external()),
+        );
+        attributes.insert(
+            "cause".to_string(),
+            AttributeValue::from(value.cause.as_str()),
+        );
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
+impl From<&PassengerPickedUpEvent> for GenericEvent {
+    fn from(value: &PassengerPickedUpEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "person".to_string(),
+            AttributeValue::from(value.person.external()),
+        );
+        attributes.insert(
+            "mode".to_string(),
+            AttributeValue::from(value.mode.external()),
+        );
+        attributes.insert(
+            "request".to_string(),
+            AttributeValue::from(value.request.external()),
+        );
+        attributes.insert(
+            "vehicle".to_string(),
+            AttributeValue::from(value.vehicle.external()),
+        );
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
+impl From<&PassengerDroppedOffEvent> for GenericEvent {
+    fn from(value: &PassengerDroppedOffEvent) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "person".to_string(),
+            AttributeValue::from(value.person.external()),
+        );
+        attributes.insert(
+            "mode".to_string(),
+            AttributeValue::from(value.mode.external()),
+        );
+        attributes.insert(
+            "request".to_string(),
+            AttributeValue::from(value.request.external()),
+        );
+        attributes.insert(
+            "vehicle".to_string(),
+            AttributeValue::from(value.vehicle.external()),
+        );
+        GenericEvent {
+            r#type: value.type_().to_string(),
+            attributes,
+        }
+    }
+}
+
 impl From<&crate::simulation::events::GenericEvent> for GenericEvent {
     fn from(value: &crate::simulation::events::GenericEvent) -> Self {
         let mut attributes = HashMap::new();
@@ -380,10 +589,35 @@ impl ProtoEventsWriter {
             GenericEvent::from(event)
         } else if let Some(event) = event.as_any().downcast_ref::<PtTeleportationArrivalEvent>() {
             GenericEvent::from(event)
+        } else if let Some(event) = event.as_any().downcast_ref::<TransitDriverStartsEvent>() {
+            GenericEvent::from(event)
+        } else if let Some(event) = event
+            .as_any()
+            .downcast_ref::<VehicleArrivesAtFacilityEvent>()
+        {
+            GenericEvent::from(event)
+        } else if let Some(event) = event
+            .as_any()
+            .downcast_ref::<VehicleDepartsAtFacilityEvent>()
+        {
+            GenericEvent::from(event)
+        } else if let Some(event) = event.as_any().downcast_ref::<AgentWaitingForPtEvent>() {
+            GenericEvent::from(event)
         } else if let Some(event) = event.as_any().downcast_ref::<VehicleEntersTrafficEvent>() {
             GenericEvent::from(event)
         } else if let Some(event) = event.as_any().downcast_ref::<VehicleLeavesTrafficEvent>() {
             GenericEvent::from(event)
+        } else if let Some(event) = event.as_any().downcast_ref::<DrtRequestSubmittedEvent>() {
+            GenericEvent::from(event)
+        } else if let Some(event) = event
+            .as_any()
+            .downcast_ref::<PassengerRequestRejectedEvent>()
+        {
+            GenericEvent::from(event)
+        } else if let Some(event) = event.as_any().downcast_ref::<PassengerPickedUpEvent>() {
+            GenericEvent::from(event)
+        } else if let Some(event) = event.as_any().downcast_ref::<PassengerDroppedOffEvent>() {
+            GenericEvent::from(event)
         } else {
             // TODO use general event here and log warning
             panic!("Unknown event type: {:?}", event);
@@ -530,8 +764,16 @@ pub fn process_events(time: SimTime, events: &Vec<GenericEvent>, manager: &mut E
             PersonArrivalEvent::TYPE => Box::new(PersonArrivalEvent::from_proto_event(proto_event, time)),
             TeleportationArrivalEvent::TYPE => Box::new(TeleportationArrivalEvent::from_proto_event(proto_event, time)),
             PtTeleportationArrivalEvent::TYPE => Box::new(PtTeleportationArrivalEvent::from_proto_event(proto_event, time)),
+            TransitDriverStartsEvent::TYPE => Box::new(TransitDriverStartsEvent::from_proto_event(proto_event, time)),
+            VehicleArrivesAtFacilityEvent::TYPE => Box::new(VehicleArrivesAtFacilityEvent::from_proto_event(proto_event, time)),
+            VehicleDepartsAtFacilityEvent::TYPE => Box::new(VehicleDepartsAtFacilityEvent::from_proto_event(proto_event, time)),
+            AgentWaitingForPtEvent::TYPE => Box::new(AgentWaitingForPtEvent::from_proto_event(proto_event, time)),
             VehicleEntersTrafficEvent::TYPE => Box::new(VehicleEntersTrafficEvent::from_proto_event(proto_event, time)),
             VehicleLeavesTrafficEvent::TYPE => Box::new(VehicleLeavesTrafficEvent::from_proto_event(proto_event, time)),
+            DrtRequestSubmittedEvent::TYPE => Box::new(DrtRequestSubmittedEvent::from_proto_event(proto_event, time)),
+            PassengerRequestRejectedEvent::TYPE => Box::new(PassengerRequestRejectedEvent::from_proto_event(proto_event, time)),
+            PassengerPickedUpEvent::TYPE => Box::new(PassengerPickedUpEvent::from_proto_event(proto_event, time)),
+            PassengerDroppedOffEvent::TYPE => Box::new(PassengerDroppedOffEvent::from_proto_event(proto_event, time)),
             _ => panic!("Unknown event type: {:?}", type_),
         };
         manager.process_event(internal_event.as_ref());
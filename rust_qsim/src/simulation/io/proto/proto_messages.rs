@@ -0,0 +1,88 @@
+use crate::generated::messages::{SimulationVehicle, StorageUpdate, SyncMessage};
+use crate::generated::vehicles::Vehicle;
+use crate::simulation::agents::SimulationAgentLogic;
+use crate::simulation::agents::agent::SimulationAgent;
+use crate::simulation::id::Id;
+use crate::simulation::messaging::messages::InternalSyncMessage;
+use crate::simulation::network::sim_network;
+use crate::simulation::scenario::vehicles::InternalVehicle;
+use crate::simulation::time::Tick;
+use crate::simulation::vehicles;
+
+impl From<&InternalSyncMessage> for SyncMessage {
+    fn from(value: &InternalSyncMessage) -> Self {
+        SyncMessage {
+            time: value.time().value(),
+            from_process: value.from_process(),
+            to_process: value.to_process(),
+            vehicles: value
+                .vehicles()
+                .iter()
+                .map(SimulationVehicle::from)
+                .collect(),
+            storage_capacities: value
+                .storage_capacities()
+                .iter()
+                .map(StorageUpdate::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<SyncMessage> for InternalSyncMessage {
+    fn from(value: SyncMessage) -> Self {
+        let mut message =
+            InternalSyncMessage::new(Tick::new(value.time), value.from_process, value.to_process);
+        for vehicle in value.vehicles {
+            message.add_veh(vehicles::SimulationVehicle::from(vehicle));
+        }
+        for storage_cap in value.storage_capacities {
+            message.add_storage_cap(sim_network::StorageUpdate::from(storage_cap));
+        }
+        message
+    }
+}
+
+impl From<&vehicles::SimulationVehicle> for SimulationVehicle {
+    fn from(value: &vehicles::SimulationVehicle) -> Self {
+        SimulationVehicle {
+            vehicle: Some(Vehicle::from(value.internal_vehicle())),
+            driver: Some(value.driver().to_message()),
+            passengers: value.passengers().iter().map(|p| p.to_message()).collect(),
+        }
+    }
+}
+
+impl From<SimulationVehicle> for vehicles::SimulationVehicle {
+    fn from(value: SimulationVehicle) -> Self {
+        vehicles::SimulationVehicle::new(
+            InternalVehicle::from(value.vehicle.expect("Vehicle message without vehicle.")),
+            value.driver.map(SimulationAgent::from),
+            value
+                .passengers
+                .into_iter()
+                .map(SimulationAgent::from)
+                .collect(),
+        )
+    }
+}
+
+impl From<&sim_network::StorageUpdate> for StorageUpdate {
+    fn from(value: &sim_network::StorageUpdate) -> Self {
+        StorageUpdate {
+            link_id: value.link_id.external().to_string(),
+            from_part: value.from_part,
+            released: value.released,
+        }
+    }
+}
+
+impl From<StorageUpdate> for sim_network::StorageUpdate {
+    fn from(value: StorageUpdate) -> Self {
+        sim_network::StorageUpdate {
+            link_id: Id::get_from_ext(&value.link_id),
+            from_part: value.from_part,
+            released: value.released,
+        }
+    }
+}
@@ -94,7 +94,7 @@ impl Person {
         Self {
             id: value.id().external().to_string(),
             plan: value.plans().iter().map(Plan::from).collect(),
-            attributes: Default::default(),
+            attributes: value.attributes().as_cloned_map(),
             subpopulation: Some(value.subpopulation().external().to_string()),
         }
     }
@@ -121,6 +121,7 @@ impl Activity {
                 y: c.y,
                 z: c.z,
             }),
+            facility_id: value.facility_id.as_ref().map(|f| f.external().to_string()),
             start_time_ns: value.start_time.map(SimTime::as_nanos),
             end_time_ns: value.end_time.map(SimTime::as_nanos),
             max_dur_ns: value.max_dur.map(duration_to_u64_nanos),
@@ -0,0 +1,362 @@
+use crate::generated;
+use crate::generated::general::Coordinate;
+use crate::simulation::InternalAttributes;
+use crate::simulation::id::Id;
+use crate::simulation::pt::{
+    MinimalTransferTime, TransitDeparture, TransitLine, TransitRoute, TransitRouteStop,
+    TransitSchedule, TransitStopFacility,
+};
+use crate::simulation::scenario;
+use crate::simulation::time::SimTime;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+fn duration_to_u64_nanos(duration: Duration) -> u64 {
+    duration
+        .as_nanos()
+        .try_into()
+        .expect("duration exceeds u64::MAX nanoseconds for proto encoding")
+}
+
+pub(crate) fn load_from_proto(path: &Path) -> TransitSchedule {
+    info!("Start reading proto transit schedule from path: {path:?}");
+    let wire_schedule: generated::transit::TransitSchedule = generated::read_from_file(path);
+    let res = TransitSchedule::from(wire_schedule);
+    info!("Finished reading proto transit schedule from path: {path:?}");
+    res
+}
+
+pub(crate) fn write_to_proto(schedule: &TransitSchedule, path: &Path) {
+    info!("Start writing proto transit schedule to path: {path:?}");
+    let wire_schedule = generated::transit::TransitSchedule::from(schedule);
+    generated::write_to_file(wire_schedule, path);
+    info!("Finished writing proto transit schedule to path: {path:?}");
+}
+
+impl From<generated::transit::TransitSchedule> for TransitSchedule {
+    fn from(value: generated::transit::TransitSchedule) -> Self {
+        let facilities = value
+            .stop_facilities
+            .into_iter()
+            .map(TransitStopFacility::from)
+            .map(|facility| (facility.id.clone(), facility))
+            .collect();
+        let lines = value
+            .lines
+            .into_iter()
+            .map(TransitLine::from)
+            .map(|line| (line.id.clone(), line))
+            .collect();
+        let minimal_transfer_times = value
+            .minimal_transfer_times
+            .into_iter()
+            .map(|t| MinimalTransferTime {
+                from_stop: Id::create(&t.from_stop),
+                to_stop: Id::create(&t.to_stop),
+                transfer_time: t.transfer_time,
+            })
+            .collect();
+
+        TransitSchedule::new(
+            lines,
+            facilities,
+            minimal_transfer_times,
+            InternalAttributes::from(&value.attributes),
+        )
+    }
+}
+
+impl From<generated::transit::TransitStopFacility> for TransitStopFacility {
+    fn from(value: generated::transit::TransitStopFacility) -> Self {
+        Id::<String>::create(&value.id);
+        let coord = value.coordinate.unwrap_or_default();
+        TransitStopFacility {
+            id: Id::create(&value.id),
+            coord: scenario::Coordinate::new_3d(coord.x, coord.y, coord.z),
+            link_ref_id: value.link_ref_id.map(|id| Id::create(&id)),
+            name: value.name,
+            stop_area_id: value.stop_area_id,
+            is_blocking: value.is_blocking,
+            attributes: InternalAttributes::from(&value.attributes),
+        }
+    }
+}
+
+impl From<generated::transit::TransitLine> for TransitLine {
+    fn from(value: generated::transit::TransitLine) -> Self {
+        Id::<String>::create(&value.id);
+        let routes = value
+            .routes
+            .into_iter()
+            .map(TransitRoute::from)
+            .map(|route| (route.id.clone(), route))
+            .collect();
+        TransitLine {
+            id: Id::create(&value.id),
+            name: value.name,
+            routes,
+            attributes: InternalAttributes::from(&value.attributes),
+        }
+    }
+}
+
+impl From<generated::transit::TransitRoute> for TransitRoute {
+    fn from(value: generated::transit::TransitRoute) -> Self {
+        Id::<String>::create(&value.id);
+        TransitRoute {
+            id: Id::create(&value.id),
+            description: value.description,
+            transport_mode: Id::create(&value.transport_mode),
+            stops: value
+                .stops
+                .into_iter()
+                .map(TransitRouteStop::from)
+                .collect(),
+            network_route: value
+                .network_route
+                .iter()
+                .map(|link| Id::create(link))
+                .collect(),
+            departures: value
+                .departures
+                .into_iter()
+                .map(TransitDeparture::from)
+                .collect(),
+            attributes: InternalAttributes::from(&value.attributes),
+        }
+    }
+}
+
+impl From<generated::transit::TransitRouteStop> for TransitRouteStop {
+    fn from(value: generated::transit::TransitRouteStop) -> Self {
+        TransitRouteStop {
+            facility_id: Id::create(&value.facility_id),
+            arrival_offset: value.arrival_offset_ns.map(Duration::from_nanos),
+            departure_offset: value.departure_offset_ns.map(Duration::from_nanos),
+            await_departure: value.await_departure,
+            allow_boarding: value.allow_boarding,
+            allow_alighting: value.allow_alighting,
+            minimum_stop_duration: Duration::from_nanos(value.minimum_stop_duration_ns),
+        }
+    }
+}
+
+impl From<generated::transit::TransitDeparture> for TransitDeparture {
+    fn from(value: generated::transit::TransitDeparture) -> Self {
+        TransitDeparture {
+            id: Id::create(&value.id),
+            departure_time: SimTime::from_nanos(value.departure_time_ns),
+            vehicle_ref_id: value.vehicle_ref_id.map(|id| Id::create(&id)),
+            attributes: InternalAttributes::from(&value.attributes),
+        }
+    }
+}
+
+impl generated::transit::TransitSchedule {
+    pub fn from(schedule: &TransitSchedule) -> Self {
+        info!("Converting TransitSchedule into wire format");
+        let stop_facilities = schedule
+            .facilities()
+            .values()
+            .map(generated::transit::TransitStopFacility::from)
+            .collect();
+        let minimal_transfer_times = schedule
+            .minimal_transfer_times()
+            .iter()
+            .map(|t| generated::transit::MinimalTransferTime {
+                from_stop: t.from_stop.external().to_string(),
+                to_stop: t.to_stop.external().to_string(),
+                transfer_time: t.transfer_time,
+            })
+            .collect();
+        let lines = schedule
+            .lines()
+            .values()
+            .map(generated::transit::TransitLine::from)
+            .collect();
+
+        generated::transit::TransitSchedule {
+            stop_facilities,
+            minimal_transfer_times,
+            lines,
+            attributes: schedule.attributes().as_cloned_map(),
+        }
+    }
+}
+
+impl generated::transit::TransitStopFacility {
+    pub fn from(facility: &TransitStopFacility) -> Self {
+        Self {
+            id: facility.id.external().to_string(),
+            coordinate: Some(Coordinate {
+                x: facility.coord.x,
+                y: facility.coord.y,
+                z: facility.coord.z,
+            }),
+            link_ref_id: facility
+                .link_ref_id
+                .as_ref()
+                .map(|id| id.external().to_string()),
+            name: facility.name.clone(),
+            stop_area_id: facility.stop_area_id.clone(),
+            is_blocking: facility.is_blocking,
+            attributes: facility.attributes.as_cloned_map(),
+        }
+    }
+}
+
+impl generated::transit::TransitLine {
+    pub fn from(line: &TransitLine) -> Self {
+        Self {
+            id: line.id.external().to_string(),
+            name: line.name.clone(),
+            routes: line
+                .routes
+                .values()
+                .map(generated::transit::TransitRoute::from)
+                .collect(),
+            attributes: line.attributes.as_cloned_map(),
+        }
+    }
+}
+
+impl generated::transit::TransitRoute {
+    pub fn from(route: &TransitRoute) -> Self {
+        Self {
+            id: route.id.external().to_string(),
+            description: route.description.clone(),
+            transport_mode: route.transport_mode.external().to_string(),
+            stops: route
+                .stops
+                .iter()
+                .map(generated::transit::TransitRouteStop::from)
+                .collect(),
+            network_route: route
+                .network_route
+                .iter()
+                .map(|link| link.external().to_string())
+                .collect(),
+            departures: route
+                .departures
+                .iter()
+                .map(generated::transit::TransitDeparture::from)
+                .collect(),
+            attributes: route.attributes.as_cloned_map(),
+        }
+    }
+}
+
+impl generated::transit::TransitRouteStop {
+    pub fn from(stop: &TransitRouteStop) -> Self {
+        Self {
+            facility_id: stop.facility_id.external().to_string(),
+            arrival_offset_ns: stop.arrival_offset.map(duration_to_u64_nanos),
+            departure_offset_ns: stop.departure_offset.map(duration_to_u64_nanos),
+            await_departure: stop.await_departure,
+            allow_boarding: stop.allow_boarding,
+            allow_alighting: stop.allow_alighting,
+            minimum_stop_duration_ns: duration_to_u64_nanos(stop.minimum_stop_duration),
+        }
+    }
+}
+
+impl generated::transit::TransitDeparture {
+    pub fn from(departure: &TransitDeparture) -> Self {
+        Self {
+            id: departure.id.external().to_string(),
+            departure_time_ns: departure.departure_time.as_nanos(),
+            vehicle_ref_id: departure
+                .vehicle_ref_id
+                .as_ref()
+                .map(|id| id.external().to_string()),
+            attributes: departure.attributes.as_cloned_map(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::io::xml::transit::IOTransitSchedule;
+    use crate::simulation::pt::TransitSchedule;
+    use macros::deterministic_id_test;
+    use std::path::PathBuf;
+
+    const SCHEDULE: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <transitSchedule>\
+            <attributes>\
+                <attribute name=\"startDate\" class=\"java.lang.String\">2023-01-11</attribute>\
+            </attributes>\
+            <transitStops>\
+                <stopFacility id=\"s1\" x=\"1.0\" y=\"2.0\" z=\"3.0\" linkRefId=\"l-1\" name=\"Stop 1\" stopAreaId=\"area-1\" isBlocking=\"false\">\
+                    <attributes>\
+                        <attribute name=\"stopFilter\" class=\"java.lang.String\">station</attribute>\
+                    </attributes>\
+                </stopFacility>\
+                <stopFacility id=\"s2\" x=\"4.0\" y=\"5.0\" linkRefId=\"l-2\"/>\
+            </transitStops>\
+            <minimalTransferTimes>\
+                <relation fromStop=\"s1\" toStop=\"s2\" transferTime=\"120\"/>\
+            </minimalTransferTimes>\
+            <transitLine id=\"line-1\" name=\"Blue\">\
+                <attributes>\
+                    <attribute name=\"operator\" class=\"java.lang.String\">DB</attribute>\
+                </attributes>\
+                <transitRoute id=\"route-1\">\
+                    <description>test route</description>\
+                    <transportMode>train</transportMode>\
+                    <routeProfile>\
+                        <stop refId=\"s1\" departureOffset=\"00:00:00\" awaitDepartureTime=\"true\"/>\
+                        <stop refId=\"s2\" arrivalOffset=\"00:05:00.5\" departureOffset=\"00:05:30\"/>\
+                    </routeProfile>\
+                    <route>\
+                        <link refId=\"l-1\"/>\
+                        <link refId=\"l-2\"/>\
+                    </route>\
+                    <departures>\
+                        <departure id=\"dep-1\" departureTime=\"06:00:00\" vehicleRefId=\"veh-1\">\
+                            <attributes>\
+                                <attribute name=\"run\" class=\"java.lang.Integer\">7</attribute>\
+                            </attributes>\
+                        </departure>\
+                    </departures>\
+                    <attributes>\
+                        <attribute name=\"routeAttr\" class=\"java.lang.Double\">0.5</attribute>\
+                    </attributes>\
+                </transitRoute>\
+            </transitLine>\
+        </transitSchedule>";
+
+    #[deterministic_id_test]
+    fn schedule_round_trip_keeps_transfer_times_and_attributes() {
+        let schedule =
+            TransitSchedule::from(quick_xml::de::from_str::<IOTransitSchedule>(SCHEDULE).unwrap());
+        let file = PathBuf::from(
+            "./test_output/simulation/pt/proto_transit/schedule_round_trip/transitSchedule.binpb",
+        );
+
+        schedule.to_file(&file);
+        let round_trip = TransitSchedule::from_file(&file);
+
+        assert_eq!(1, round_trip.minimal_transfer_times().len());
+        assert_eq!(
+            Some(String::from("2023-01-11")),
+            round_trip.attributes().get("startDate")
+        );
+        assert_eq!(schedule, round_trip);
+    }
+
+    #[deterministic_id_test]
+    fn tutorial_schedule_round_trip() {
+        let schedule =
+            TransitSchedule::from_file("./assets/pt_tutorial/transitschedule.xml".as_ref());
+        let file = PathBuf::from(
+            "./test_output/simulation/pt/proto_transit/tutorial_schedule_round_trip/transitSchedule.binpb",
+        );
+
+        schedule.to_file(&file);
+        let round_trip = TransitSchedule::from_file(&file);
+
+        assert_eq!(schedule, round_trip);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::generated;
 use crate::generated::vehicles::{Vehicle, VehicleType, VehiclesContainer};
+use crate::simulation::id::Id;
 use crate::simulation::scenario::vehicles::Garage;
 use crate::simulation::scenario::vehicles::{InternalVehicle, InternalVehicleType};
 use std::path::Path;
@@ -24,22 +25,19 @@ pub(crate) fn write_to_proto(garage: &Garage, path: &Path) {
 
 pub(crate) fn load_from_proto(path: &Path) -> Garage {
     let wire_garage: VehiclesContainer = generated::read_from_file(path);
-    let vehicles = wire_garage
-        .vehicles
-        .into_iter()
-        .map(InternalVehicle::from)
-        .map(|v| (v.id.clone(), v))
-        .collect();
-    let vehicle_types = wire_garage
-        .vehicle_types
-        .into_iter()
-        .map(InternalVehicleType::from)
-        .map(|v_type| (v_type.id.clone(), v_type))
-        .collect();
-    Garage {
-        vehicles,
-        vehicle_types,
+    let mut garage = Garage::new();
+    for veh_type in wire_garage.vehicle_types {
+        garage.add_veh_type(InternalVehicleType::from(veh_type));
     }
+    for vehicle in wire_garage.vehicles {
+        let veh_type = garage
+            .vehicle_types
+            .get(&Id::<InternalVehicleType>::get(vehicle.r#type))
+            .expect("Vehicle type of vehicle not found. There has to be a vehicle type defined before a vehicle can be added.");
+        let vehicle = InternalVehicle::from_proto(vehicle, veh_type);
+        garage.add_veh(vehicle);
+    }
+    garage
 }
 
 impl Vehicle {
@@ -49,6 +47,7 @@ impl Vehicle {
             r#type: vehicle.vehicle_type.internal(),
             max_v: vehicle.max_v,
             pce: vehicle.pce,
+            fef: Some(vehicle.fef),
             attributes: vehicle.attributes.as_cloned_map(),
         }
     }
@@ -64,18 +63,26 @@ impl VehicleType {
             pce: vehicle.pce,
             fef: vehicle.fef,
             net_mode: vehicle.net_mode.internal(),
+            seats: vehicle.seats,
+            standing_room: vehicle.standing_room,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::generated;
+    use crate::generated::vehicles::{Vehicle, VehicleType, VehiclesContainer};
     use crate::simulation::InternalAttributes;
     use crate::simulation::id::Id;
     use crate::simulation::scenario::vehicles::Garage;
-    use crate::simulation::scenario::vehicles::{InternalVehicleType, from_file, to_file};
+    use crate::simulation::scenario::vehicles::{
+        InternalVehicle, InternalVehicleType, from_file, to_file,
+    };
+    use crate::simulation::vehicles::SimulationVehicle;
     use macros::deterministic_id_test;
-    use std::path::PathBuf;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
 
     #[deterministic_id_test]
     fn test_to_from_file_proto() {
@@ -91,6 +98,8 @@ mod tests {
             max_v: 1000.0,
             pce: 20.0,
             fef: 0.3,
+            seats: 4,
+            standing_room: 2,
             net_mode: Id::<String>::create("some network type 🚕"),
             attributes: InternalAttributes::default(),
         });
@@ -117,6 +126,8 @@ mod tests {
             max_v: 1000.0,
             pce: 20.0,
             fef: 0.3,
+            seats: 4,
+            standing_room: 2,
             net_mode: Id::<String>::create("some-network-type"),
             attributes: InternalAttributes::default(),
         });
@@ -127,4 +138,76 @@ mod tests {
 
         assert_eq!(garage.vehicle_types, loaded_garage.vehicle_types);
     }
+
+    #[deterministic_id_test]
+    fn vehicle_without_fef_uses_fef_of_vehicle_type() {
+        let file = &PathBuf::from(
+            "./test_output/simulation/vehicles/io/vehicle_without_fef_uses_fef_of_vehicle_type/vehicles.binpb",
+        );
+        let id = Id::<InternalVehicle>::create("vehicle-without-fef");
+        write_container(file, vehicle_type(0.5), vehicle(&id, None));
+
+        let garage = from_file(file);
+
+        let vehicle = SimulationVehicle::new(garage.vehicles[&id].clone(), None, Vec::new());
+        assert_eq!(0.5, vehicle.internal_vehicle().fef);
+        assert_eq!(4., vehicle.flow_consumption());
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "Flow efficiency factor of vehicle type some-type")]
+    fn vehicle_type_with_zero_fef_panics() {
+        let file = &PathBuf::from(
+            "./test_output/simulation/vehicles/io/vehicle_type_with_zero_fef_panics/vehicles.binpb",
+        );
+        let id = Id::<InternalVehicle>::create("some-vehicle");
+        write_container(file, vehicle_type(0.), vehicle(&id, None));
+
+        from_file(file);
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "Flow efficiency factor of vehicle some-vehicle")]
+    fn vehicle_with_negative_fef_panics() {
+        let file = &PathBuf::from(
+            "./test_output/simulation/vehicles/io/vehicle_with_negative_fef_panics/vehicles.binpb",
+        );
+        let id = Id::<InternalVehicle>::create("some-vehicle");
+        write_container(file, vehicle_type(1.), vehicle(&id, Some(-1.)));
+
+        from_file(file);
+    }
+
+    fn vehicle_type(fef: f64) -> VehicleType {
+        VehicleType {
+            id: Id::<InternalVehicleType>::create("some-type").internal(),
+            length: 10.,
+            width: 2.,
+            max_v: 10.,
+            pce: 2.,
+            fef,
+            net_mode: Id::<String>::create("car").internal(),
+            seats: 4,
+            standing_room: 0,
+        }
+    }
+
+    fn vehicle(id: &Id<InternalVehicle>, fef: Option<f64>) -> Vehicle {
+        Vehicle {
+            id: id.internal(),
+            r#type: Id::<InternalVehicleType>::get_from_ext("some-type").internal(),
+            max_v: 10.,
+            pce: 2.,
+            attributes: HashMap::new(),
+            fef,
+        }
+    }
+
+    fn write_container(file: &Path, vehicle_type: VehicleType, vehicle: Vehicle) {
+        let container = VehiclesContainer {
+            vehicle_types: vec![vehicle_type],
+            vehicles: vec![vehicle],
+        };
+        generated::write_to_file(container, file);
+    }
 }
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::simulation::io::xml;
+
+pub(crate) fn load_from_xml(path: &Path) -> IOCounts {
+    let io_counts: IOCounts = xml::read_from_file(path);
+
+    info!(
+        "Finished reading counts. It contains {} count stations.",
+        io_counts.counts.len()
+    );
+
+    io_counts
+}
+
+/// Observed link volumes in MATSim's `counts_v1` format.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(rename = "counts")]
+pub struct IOCounts {
+    #[serde(rename = "@name")]
+    pub name: Option<String>,
+    #[serde(rename = "@desc")]
+    pub desc: Option<String>,
+    #[serde(rename = "@year")]
+    pub year: Option<u32>,
+    #[serde(rename = "@layer")]
+    pub layer: Option<String>,
+    #[serde(rename = "count", default)]
+    pub counts: Vec<IOCount>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOCount {
+    #[serde(rename = "@loc_id")]
+    pub loc_id: String,
+    #[serde(rename = "@cs_id")]
+    pub cs_id: Option<String>,
+    #[serde(rename = "volume", default)]
+    pub volumes: Vec<IOVolume>,
+}
+
+/// The volume of hour `h`, where `h = 1` is the hour from 0:00 to 1:00.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
+pub struct IOVolume {
+    #[serde(rename = "@h")]
+    pub h: u32,
+    #[serde(rename = "@val")]
+    pub val: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::de::from_str;
+
+    use crate::simulation::io::xml::counts::{IOCounts, IOVolume};
+
+    #[test]
+    fn parse_counts() {
+        let xml = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+                <counts xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+                    xsi:noNamespaceSchemaLocation=\"http://matsim.org/files/dtd/counts_v1.xsd\" \
+                    name=\"loops\" desc=\"loop detectors\" year=\"2024\" layer=\"0\">
+                    <count loc_id=\"1\" cs_id=\"station 1\">
+                        <volume h=\"1\" val=\"10.0\" />
+                        <volume h=\"8\" val=\"120.5\" />
+                    </count>
+                    <count loc_id=\"2\">
+                        <volume h=\"24\" val=\"3\" />
+                    </count>
+                </counts>";
+
+        let counts: IOCounts = from_str(xml).unwrap();
+
+        assert_eq!("loops", counts.name.as_deref().unwrap());
+        assert_eq!("loop detectors", counts.desc.as_deref().unwrap());
+        assert_eq!(Some(2024), counts.year);
+        assert_eq!(2, counts.counts.len());
+        assert_eq!("1", counts.counts[0].loc_id);
+        assert_eq!("station 1", counts.counts[0].cs_id.as_deref().unwrap());
+        assert_eq!(
+            vec![IOVolume { h: 1, val: 10.0 }, IOVolume { h: 8, val: 120.5 }],
+            counts.counts[0].volumes
+        );
+        assert_eq!(None, counts.counts[1].cs_id);
+        assert_eq!(vec![IOVolume { h: 24, val: 3.0 }], counts.counts[1].volumes);
+    }
+
+    #[test]
+    fn parse_empty_counts() {
+        let xml = "<?xml version=\"1.0\" encoding=\"utf-8\"?><counts name=\"empty\" />";
+
+        let counts: IOCounts = from_str(xml).unwrap();
+
+        assert!(counts.counts.is_empty());
+    }
+}
@@ -12,17 +12,25 @@ use xml::reader::XmlEvent;
 use zstd::stream::read::Decoder as ZstdDecoder;
 use zstd::stream::write::Encoder as ZstdEncoder;
 
+use crate::simulation::drt::DrtRequest;
 use crate::simulation::events::{
     ActivityEndEvent, ActivityEndEventBuilder, ActivityStartEvent, ActivityStartEventBuilder,
-    EventHandlerRegisterFn, EventTrait, EventsManager, GenericEvent, LinkEnterEvent,
-    LinkEnterEventBuilder, LinkLeaveEvent, LinkLeaveEventBuilder, PersonArrivalEvent,
-    PersonArrivalEventBuilder, PersonDepartureEvent, PersonDepartureEventBuilder,
-    PersonEntersVehicleEvent, PersonEntersVehicleEventBuilder, PersonLeavesVehicleEvent,
-    PersonLeavesVehicleEventBuilder, PtTeleportationArrivalEvent, TeleportationArrivalEvent,
-    TeleportationArrivalEventBuilder, VehicleEntersTrafficEvent, VehicleEntersTrafficEventBuilder,
-    VehicleLeavesTrafficEvent, VehicleLeavesTrafficEventBuilder,
+    AgentWaitingForPtEvent, AgentWaitingForPtEventBuilder, DrtRequestSubmittedEvent,
+    DrtRequestSubmittedEventBuilder, EventHandlerRegisterFn, EventTrait, EventsManager,
+    GenericEvent, LinkEnterEvent, LinkEnterEventBuilder, LinkLeaveEvent, LinkLeaveEventBuilder,
+    PassengerDroppedOffEvent, PassengerDroppedOffEventBuilder, PassengerPickedUpEvent,
+    PassengerPickedUpEventBuilder, PassengerRequestRejectedEvent,
+    PassengerRequestRejectedEventBuilder, PersonArrivalEvent, PersonArrivalEventBuilder,
+    PersonDepartureEvent, PersonDepartureEventBuilder, PersonEntersVehicleEvent,
+    PersonEntersVehicleEventBuilder, PersonLeavesVehicleEvent, PersonLeavesVehicleEventBuilder,
+    PtTeleportationArrivalEvent, TeleportationArrivalEvent, TeleportationArrivalEventBuilder,
+    TransitDriverStartsEvent, TransitDriverStartsEventBuilder, VehicleArrivesAtFacilityEvent,
+    VehicleArrivesAtFacilityEventBuilder, VehicleDepartsAtFacilityEvent,
+    VehicleDepartsAtFacilityEventBuilder, VehicleEntersTrafficEvent,
+    VehicleEntersTrafficEventBuilder, VehicleLeavesTrafficEvent, VehicleLeavesTrafficEventBuilder,
 };
 use crate::simulation::id::Id;
+use crate::simulation::pt::{TransitDeparture, TransitLine, TransitRoute, TransitStopFacility};
 use crate::simulation::scenario::Coordinate;
 use crate::simulation::scenario::network::Link;
 use crate::simulation::scenario::population::InternalPerson;
@@ -153,6 +161,44 @@ impl XmlEventsWriter {
                 ev.line,
                 ev.route
             )
+        } else if let Some(ev) = e.as_any().downcast_ref::<TransitDriverStartsEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" driverId=\"{}\" vehicleId=\"{}\" transitLineId=\"{}\" transitRouteId=\"{}\" departureId=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.driver,
+                ev.vehicle,
+                ev.transit_line,
+                ev.transit_route,
+                ev.departure
+            )
+        } else if let Some(ev) = e.as_any().downcast_ref::<VehicleArrivesAtFacilityEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" vehicle=\"{}\" facility=\"{}\" delay=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.vehicle,
+                ev.facility,
+                ev.delay
+            )
+        } else if let Some(ev) = e.as_any().downcast_ref::<VehicleDepartsAtFacilityEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" vehicle=\"{}\" facility=\"{}\" delay=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.vehicle,
+                ev.facility,
+                ev.delay
+            )
+        } else if let Some(ev) = e.as_any().downcast_ref::<AgentWaitingForPtEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" agent=\"{}\" atStop=\"{}\" destinationStop=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.person,
+                ev.waiting_at_stop,
+                ev.destination_stop
+            )
         } else if let Some(ev) = e.as_any().downcast_ref::<VehicleLeavesTrafficEvent>() {
             format!(
                 "<event time=\"{}\" type=\"{}\" person=\"{}\" link=\"{}\" vehicle=\"{}\" networkMode=\"{}\" relativePosition=\"{}\"/>\n",
@@ -175,6 +221,49 @@ impl XmlEventsWriter {
                 ev.network_mode,
                 ev.relative_position
             )
+        } else if let Some(ev) = e.as_any().downcast_ref::<DrtRequestSubmittedEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" person=\"{}\" mode=\"{}\" request=\"{}\" fromLink=\"{}\" toLink=\"{}\" unsharedRideTime=\"{}\" unsharedRideDistance=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.person,
+                ev.mode,
+                ev.request,
+                ev.from_link,
+                ev.to_link,
+                ev.unshared_ride_time,
+                ev.unshared_ride_distance
+            )
+        } else if let Some(ev) = e.as_any().downcast_ref::<PassengerRequestRejectedEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" person=\"{}\" mode=\"{}\" request=\"{}\" cause=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.person,
+                ev.mode,
+                ev.request,
+                ev.cause
+            )
+        } else if let Some(ev) = e.as_any().downcast_ref::<PassengerPickedUpEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" person=\"{}\" mode=\"{}\" request=\"{}\" vehicle=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.person,
+                ev.mode,
+                ev.request,
+                ev.vehicle
+            )
+        } else if let Some(ev) = e.as_any().downcast_ref::<PassengerDroppedOffEvent>() {
+            format!(
+                "<event time=\"{}\" type=\"{}\" person=\"{}\" mode=\"{}\" request=\"{}\" vehicle=\"{}\"/>\n",
+                ev.time().format_decimal_seconds(),
+                ev.type_(),
+                ev.person,
+                ev.mode,
+                ev.request,
+                ev.vehicle
+            )
         } else {
             panic!("Unknown event type");
         }
@@ -274,6 +363,14 @@ fn handle(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
         "left link" => handle_link_leave(attr),
         "vehicle enters traffic" => handle_vehicle_enters_traffic(attr),
         "vehicle leaves traffic" => handle_vehicle_leaves_traffic(attr),
+        "TransitDriverStarts" => handle_transit_driver_starts(attr),
+        "VehicleArrivesAtFacility" => handle_vehicle_arrives_at_facility(attr),
+        "VehicleDepartsAtFacility" => handle_vehicle_departs_at_facility(attr),
+        "waitingForPt" => handle_agent_waiting_for_pt(attr),
+        "DrtRequest submitted" => handle_drt_request_submitted(attr),
+        "PassengerRequest rejected" => handle_passenger_request_rejected(attr),
+        "passenger picked up" => handle_passenger_picked_up(attr),
+        "passenger dropped off" => handle_passenger_dropped_off(attr),
         _ => panic!("Unknown event type {ev_type}"),
     }
 }
@@ -469,6 +566,163 @@ fn handle_link_leave(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
     )
 }
 
+fn handle_transit_driver_starts(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let driver: Id<InternalPerson> = Id::create(value_from_name(&attr, "driverId").unwrap());
+    let vehicle: Id<InternalVehicle> = Id::create(value_from_name(&attr, "vehicleId").unwrap());
+    let transit_line: Id<TransitLine> =
+        Id::create(value_from_name(&attr, "transitLineId").unwrap());
+    let transit_route: Id<TransitRoute> =
+        Id::create(value_from_name(&attr, "transitRouteId").unwrap());
+    let departure: Id<TransitDeparture> =
+        Id::create(value_from_name(&attr, "departureId").unwrap());
+    Box::new(
+        TransitDriverStartsEventBuilder::default()
+            .time(time)
+            .driver(driver)
+            .vehicle(vehicle)
+            .transit_line(transit_line)
+            .transit_route(transit_route)
+            .departure(departure)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn handle_vehicle_arrives_at_facility(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let vehicle: Id<InternalVehicle> = Id::create(value_from_name(&attr, "vehicle").unwrap());
+    let facility: Id<TransitStopFacility> = Id::create(value_from_name(&attr, "facility").unwrap());
+    let delay: f64 = value_from_name(&attr, "delay").unwrap().parse().unwrap();
+    Box::new(
+        VehicleArrivesAtFacilityEventBuilder::default()
+            .time(time)
+            .vehicle(vehicle)
+            .facility(facility)
+            .delay(delay)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn handle_vehicle_departs_at_facility(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let vehicle: Id<InternalVehicle> = Id::create(value_from_name(&attr, "vehicle").unwrap());
+    let facility: Id<TransitStopFacility> = Id::create(value_from_name(&attr, "facility").unwrap());
+    let delay: f64 = value_from_name(&attr, "delay").unwrap().parse().unwrap();
+    Box::new(
+        VehicleDepartsAtFacilityEventBuilder::default()
+            .time(time)
+            .vehicle(vehicle)
+            .facility(facility)
+            .delay(delay)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn handle_agent_waiting_for_pt(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let person: Id<InternalPerson> = Id::create(value_from_name(&attr, "agent").unwrap());
+    let waiting_at_stop: Id<TransitStopFacility> =
+        Id::create(value_from_name(&attr, "atStop").unwrap());
+    let destination_stop: Id<TransitStopFacility> =
+        Id::create(value_from_name(&attr, "destinationStop").unwrap());
+    Box::new(
+        AgentWaitingForPtEventBuilder::default()
+            .time(time)
+            .person(person)
+            .waiting_at_stop(waiting_at_stop)
+            .destination_stop(destination_stop)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn handle_drt_request_submitted(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let person: Id<InternalPerson> = Id::create(value_from_name(&attr, "person").unwrap());
+    let mode: Id<String> = Id::create(value_from_name(&attr, "mode").unwrap());
+    let request: Id<DrtRequest> = Id::create(value_from_name(&attr, "request").unwrap());
+    let from_link: Id<Link> = Id::create(value_from_name(&attr, "fromLink").unwrap());
+    let to_link: Id<Link> = Id::create(value_from_name(&attr, "toLink").unwrap());
+    let unshared_ride_time: f64 = value_from_name(&attr, "unsharedRideTime")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let unshared_ride_distance: f64 = value_from_name(&attr, "unsharedRideDistance")
+        .unwrap()
+        .parse()
+        .unwrap();
+    Box::new(
+        DrtRequestSubmittedEventBuilder::default()
+            .time(time)
+            .person(person)
+            .mode(mode)
+            .request(request)
+            .from_link(from_link)
+            .to_link(to_link)
+            .unshared_ride_time(unshared_ride_time)
+            .unshared_ride_distance(unshared_ride_distance)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn handle_passenger_request_rejected(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let person: Id<InternalPerson> = Id::create(value_from_name(&attr, "person").unwrap());
+    let mode: Id<String> = Id::create(value_from_name(&attr, "mode").unwrap());
+    let request: Id<DrtRequest> = Id::create(value_from_name(&attr, "request").unwrap());
+    let cause = value_from_name(&attr, "cause").unwrap().clone();
+    Box::new(
+        PassengerRequestRejectedEventBuilder::default()
+            .time(time)
+            .person(person)
+            .mode(mode)
+            .request(request)
+            .cause(cause)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn handle_passenger_picked_up(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let person: Id<InternalPerson> = Id::create(value_from_name(&attr, "person").unwrap());
+    let mode: Id<String> = Id::create(value_from_name(&attr, "mode").unwrap());
+    let request: Id<DrtRequest> = Id::create(value_from_name(&attr, "request").unwrap());
+    let vehicle: Id<InternalVehicle> = Id::create(value_from_name(&attr, "vehicle").unwrap());
+    Box::new(
+        PassengerPickedUpEventBuilder::default()
+            .time(time)
+            .person(person)
+            .mode(mode)
+            .request(request)
+            .vehicle(vehicle)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn handle_passenger_dropped_off(attr: Vec<OwnedAttribute>) -> Box<dyn EventTrait> {
+    let time = SimTime::parse_decimal_seconds(value_from_name(&attr, "time").unwrap()).unwrap();
+    let person: Id<InternalPerson> = Id::create(value_from_name(&attr, "person").unwrap());
+    let mode: Id<String> = Id::create(value_from_name(&attr, "mode").unwrap());
+    let request: Id<DrtRequest> = Id::create(value_from_name(&attr, "request").unwrap());
+    let vehicle: Id<InternalVehicle> = Id::create(value_from_name(&attr, "vehicle").unwrap());
+    Box::new(
+        PassengerDroppedOffEventBuilder::default()
+            .time(time)
+            .person(person)
+            .mode(mode)
+            .request(request)
+            .vehicle(vehicle)
+            .build()
+            .unwrap(),
+    )
+}
+
 fn value_from_name<'a>(attr: &'a Vec<OwnedAttribute>, name: &str) -> Option<&'a String> {
     attr.iter()
         .find(|&a| a.name.local_name.eq(name))
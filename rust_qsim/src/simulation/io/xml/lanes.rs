@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::simulation::io::xml;
+
+pub(crate) fn load_from_xml(path: &Path) -> IOLaneDefinitions {
+    let io_lanes: IOLaneDefinitions = xml::read_from_file(path);
+
+    info!(
+        "Finished reading lanes. It contains lanes for {} links.",
+        io_lanes.lanes_to_link_assignments.len()
+    );
+
+    io_lanes
+}
+
+/// Lanes at the downstream end of links in MATSim's `laneDefinitions` v2.0 format.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(rename = "laneDefinitions")]
+pub struct IOLaneDefinitions {
+    #[serde(rename = "lanesToLinkAssignment", default)]
+    pub lanes_to_link_assignments: Vec<IOLanesToLinkAssignment>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOLanesToLinkAssignment {
+    #[serde(rename = "@linkIdRef")]
+    pub link_id_ref: String,
+    #[serde(rename = "lane", default)]
+    pub lanes: Vec<IOLane>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOLane {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "leadsTo")]
+    pub leads_to: IOLeadsTo,
+    pub capacity: Option<IOLaneCapacity>,
+    #[serde(rename = "startsAt")]
+    pub starts_at: IOStartsAt,
+    pub alignment: Option<i32>,
+    #[serde(rename = "representedLanes")]
+    pub represented_lanes: Option<IORepresentedLanes>,
+}
+
+/// A lane either leads to other lanes of the same link, like the original lane at the upstream
+/// end of a link, or to links starting at the end of the link.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOLeadsTo {
+    #[serde(rename = "toLane", default)]
+    pub to_lanes: Vec<IOToLane>,
+    #[serde(rename = "toLink", default)]
+    pub to_links: Vec<IOToLink>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOToLane {
+    #[serde(rename = "@laneId")]
+    pub lane_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOToLink {
+    #[serde(rename = "@refId")]
+    pub ref_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
+pub struct IOLaneCapacity {
+    #[serde(rename = "@vehiclesPerHour")]
+    pub vehicles_per_hour: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
+pub struct IOStartsAt {
+    #[serde(rename = "@meterFromLinkEnd")]
+    pub meter_from_link_end: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
+pub struct IORepresentedLanes {
+    #[serde(rename = "@number")]
+    pub number: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::de::from_str;
+
+    use crate::simulation::io::xml::lanes::{
+        IOLaneCapacity, IOLaneDefinitions, IORepresentedLanes, IOStartsAt, IOToLane, IOToLink,
+    };
+
+    #[test]
+    fn parse_lanes() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+                <laneDefinitions xmlns=\"http://www.matsim.org/files/dtd\" \
+                    xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+                    xsi:schemaLocation=\"http://www.matsim.org/files/dtd http://www.matsim.org/files/dtd/laneDefinitions_v2.0.xsd\">
+                    <lanesToLinkAssignment linkIdRef=\"1\">
+                        <lane id=\"1.ol\">
+                            <leadsTo>
+                                <toLane laneId=\"1.left\"/>
+                                <toLane laneId=\"1.straight\"/>
+                            </leadsTo>
+                            <capacity vehiclesPerHour=\"3600.0\"/>
+                            <startsAt meterFromLinkEnd=\"100.0\"/>
+                            <alignment>0</alignment>
+                            <representedLanes number=\"2.0\"/>
+                            <attributes/>
+                        </lane>
+                        <lane id=\"1.left\">
+                            <leadsTo>
+                                <toLink refId=\"2\"/>
+                            </leadsTo>
+                            <capacity vehiclesPerHour=\"900.0\"/>
+                            <startsAt meterFromLinkEnd=\"45.0\"/>
+                            <alignment>1</alignment>
+                        </lane>
+                        <lane id=\"1.straight\">
+                            <leadsTo>
+                                <toLink refId=\"3\"/>
+                                <toLink refId=\"4\"/>
+                            </leadsTo>
+                            <startsAt meterFromLinkEnd=\"45.0\"/>
+                        </lane>
+                    </lanesToLinkAssignment>
+                </laneDefinitions>";
+
+        let lanes: IOLaneDefinitions = from_str(xml).unwrap();
+
+        assert_eq!(1, lanes.lanes_to_link_assignments.len());
+        let assignment = &lanes.lanes_to_link_assignments[0];
+        assert_eq!("1", assignment.link_id_ref);
+        assert_eq!(3, assignment.lanes.len());
+
+        let original = &assignment.lanes[0];
+        assert_eq!("1.ol", original.id);
+        assert_eq!(
+            vec![
+                IOToLane {
+                    lane_id: "1.left".to_string()
+                },
+                IOToLane {
+                    lane_id: "1.straight".to_string()
+                }
+            ],
+            original.leads_to.to_lanes
+        );
+        assert!(original.leads_to.to_links.is_empty());
+        assert_eq!(
+            Some(IOLaneCapacity {
+                vehicles_per_hour: 3600.
+            }),
+            original.capacity
+        );
+        assert_eq!(Some(0), original.alignment);
+        assert_eq!(
+            Some(IORepresentedLanes { number: 2. }),
+            original.represented_lanes
+        );
+
+        let left = &assignment.lanes[1];
+        assert_eq!(
+            vec![IOToLink {
+                ref_id: "2".to_string()
+            }],
+            left.leads_to.to_links
+        );
+        assert_eq!(
+            IOStartsAt {
+                meter_from_link_end: 45.
+            },
+            left.starts_at
+        );
+        assert_eq!(None, left.represented_lanes);
+
+        let straight = &assignment.lanes[2];
+        assert_eq!(2, straight.leads_to.to_links.len());
+        assert_eq!(None, straight.capacity);
+    }
+
+    #[test]
+    fn parse_empty_lanes() {
+        let xml = "<laneDefinitions></laneDefinitions>";
+
+        let lanes: IOLaneDefinitions = from_str(xml).unwrap();
+
+        assert!(lanes.lanes_to_link_assignments.is_empty());
+    }
+}
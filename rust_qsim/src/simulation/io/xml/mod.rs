@@ -9,9 +9,12 @@ use serde::de::DeserializeOwned;
 use tracing::info;
 
 pub mod attributes;
+pub mod counts;
 pub mod events;
 pub mod facilities;
+pub mod lanes;
 pub mod network;
+pub mod network_change_events;
 pub mod population;
 pub mod transit;
 pub mod vehicles;
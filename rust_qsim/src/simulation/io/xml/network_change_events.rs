@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::simulation::io::xml;
+
+pub(crate) fn load_from_xml(path: &Path) -> IONetworkChangeEvents {
+    let io_events: IONetworkChangeEvents = xml::read_from_file(path);
+
+    info!(
+        "Finished reading network change events. It contains {} events.",
+        io_events.events.len()
+    );
+
+    io_events
+}
+
+/// Scheduled changes of link attributes in MATSim's `networkChangeEvents` format.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(rename = "networkChangeEvents")]
+pub struct IONetworkChangeEvents {
+    #[serde(rename = "networkChangeEvent", default)]
+    pub events: Vec<IONetworkChangeEvent>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IONetworkChangeEvent {
+    #[serde(rename = "@startTime")]
+    pub start_time: String,
+    #[serde(rename = "link", default)]
+    pub links: Vec<IOLinkRef>,
+    /// The flow capacity in vehicles per second, unlike the capacity of network links.
+    #[serde(rename = "flowCapacity")]
+    pub flow_capacity: Option<IOChangeValue>,
+    pub freespeed: Option<IOChangeValue>,
+    pub lanes: Option<IOChangeValue>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOLinkRef {
+    #[serde(rename = "@refId")]
+    pub ref_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOChangeValue {
+    #[serde(rename = "@type")]
+    pub change_type: IOChangeType,
+    #[serde(rename = "@value")]
+    pub value: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
+pub enum IOChangeType {
+    #[serde(rename = "absolute")]
+    Absolute,
+    #[serde(rename = "scaleFactor")]
+    ScaleFactor,
+    #[serde(rename = "offsetInSameUnits")]
+    Offset,
+}
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::de::from_str;
+
+    use crate::simulation::io::xml::network_change_events::{
+        IOChangeType, IOChangeValue, IOLinkRef, IONetworkChangeEvents,
+    };
+
+    #[test]
+    fn parse_network_change_events() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+                <networkChangeEvents xmlns=\"http://www.matsim.org/files/dtd\" \
+                    xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+                    xsi:schemaLocation=\"http://www.matsim.org/files/dtd http://www.matsim.org/files/dtd/networkChangeEvents.xsd\">
+                    <networkChangeEvent startTime=\"08:00:00\">
+                        <link refId=\"1\"/>
+                        <link refId=\"2\"/>
+                        <flowCapacity type=\"absolute\" value=\"0.5\"/>
+                        <freespeed type=\"scaleFactor\" value=\"0.25\"/>
+                    </networkChangeEvent>
+                    <networkChangeEvent startTime=\"10:30:00\">
+                        <link refId=\"1\"/>
+                        <lanes type=\"offsetInSameUnits\" value=\"-1\"/>
+                    </networkChangeEvent>
+                </networkChangeEvents>";
+
+        let events: IONetworkChangeEvents = from_str(xml).unwrap();
+
+        assert_eq!(2, events.events.len());
+        let first = &events.events[0];
+        assert_eq!("08:00:00", first.start_time);
+        assert_eq!(
+            vec![
+                IOLinkRef {
+                    ref_id: "1".to_string()
+                },
+                IOLinkRef {
+                    ref_id: "2".to_string()
+                }
+            ],
+            first.links
+        );
+        assert_eq!(
+            Some(IOChangeValue {
+                change_type: IOChangeType::Absolute,
+                value: 0.5
+            }),
+            first.flow_capacity
+        );
+        assert_eq!(
+            Some(IOChangeValue {
+                change_type: IOChangeType::ScaleFactor,
+                value: 0.25
+            }),
+            first.freespeed
+        );
+        assert_eq!(None, first.lanes);
+        assert_eq!(
+            Some(IOChangeValue {
+                change_type: IOChangeType::Offset,
+                value: -1.
+            }),
+            events.events[1].lanes
+        );
+    }
+
+    #[test]
+    fn parse_empty_network_change_events() {
+        let xml = "<networkChangeEvents></networkChangeEvents>";
+
+        let events: IONetworkChangeEvents = from_str(xml).unwrap();
+
+        assert!(events.events.is_empty());
+    }
+}
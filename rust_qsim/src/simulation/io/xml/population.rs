@@ -166,6 +166,8 @@ pub struct IOActivity {
     pub x: Option<f64>,
     #[serde(rename = "@y")]
     pub y: Option<f64>,
+    #[serde(rename = "@facility", skip_serializing_if = "Option::is_none")]
+    pub facility: Option<String>,
     #[serde(rename = "@start_time", skip_serializing_if = "Option::is_none")]
     pub start_time: Option<String>,
     #[serde(rename = "@end_time", skip_serializing_if = "Option::is_none")]
@@ -187,6 +189,10 @@ impl From<&InternalActivity> for IOActivity {
             link: Some(activity.link_id.external().to_string()),
             x: activity.coord.as_ref().map(|c| c.x),
             y: activity.coord.as_ref().map(|c| c.y),
+            facility: activity
+                .facility_id
+                .as_ref()
+                .map(|f| f.external().to_string()),
             start_time: activity.start_time.map(|t| t.format_hh_mm_ss_trimmed()),
             end_time: activity.end_time.map(|t| t.format_hh_mm_ss_trimmed()),
             max_dur: activity
@@ -20,7 +20,11 @@ pub(crate) fn write_to_xml(garage: &Garage, path: &Path) {
         .map(|t| IOVehicleType {
             id: t.id.external().to_owned(),
             description: None,
-            capacity: None,
+            capacity: Some(IOCapacity {
+                seats: Some(t.seats),
+                standing_room_in_persons: Some(t.standing_room),
+                ..Default::default()
+            }),
             length: Some(IODimension { meter: t.length }),
             width: Some(IODimension { meter: t.width }),
             maximum_velocity: Some(IOVelocity {
@@ -87,9 +91,41 @@ pub struct IOVehicleType {
     pub attributes: Option<IOAttributes>,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+/// Vehicle definitions v2 store the capacity as attributes, v1 as child elements.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Clone)]
 pub struct IOCapacity {
-    // leave emtpy for now
+    #[serde(rename = "@seats", skip_serializing_if = "Option::is_none")]
+    pub(crate) seats: Option<u32>,
+    #[serde(
+        rename = "@standingRoomInPersons",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) standing_room_in_persons: Option<u32>,
+    #[serde(rename = "seats", skip_serializing_if = "Option::is_none")]
+    pub(crate) seats_v1: Option<IOPersons>,
+    #[serde(rename = "standingRoom", skip_serializing_if = "Option::is_none")]
+    pub(crate) standing_room_v1: Option<IOPersons>,
+}
+
+impl IOCapacity {
+    /// Like MATSim, a vehicle type has one seat if nothing else is specified.
+    pub(crate) fn seats(&self) -> u32 {
+        self.seats
+            .or(self.seats_v1.as_ref().map(|s| s.persons))
+            .unwrap_or(1)
+    }
+
+    pub(crate) fn standing_room(&self) -> u32 {
+        self.standing_room_in_persons
+            .or(self.standing_room_v1.as_ref().map(|s| s.persons))
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IOPersons {
+    #[serde(rename = "@persons")]
+    pub(crate) persons: u32,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -271,6 +307,36 @@ mod test {
         assert_eq!("84000", attrs.find_or_else("serviceEndTime", || ""));
     }
 
+    #[test]
+    fn from_string_capacity() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+                            <vehicleDefinitions xmlns=\"http://www.matsim.org/files/dtd\">\
+                                <vehicleType id=\"v2\">\
+                                    <capacity seats=\"30\" standingRoomInPersons=\"50\"/>\
+                                </vehicleType>\
+                                <vehicleType id=\"v1\">\
+                                    <capacity>\
+                                        <seats persons=\"20\"/>\
+                                        <standingRoom persons=\"40\"/>\
+                                    </capacity>\
+                                </vehicleType>\
+                                <vehicleType id=\"none\">\
+                                </vehicleType>\
+                            </vehicleDefinitions>\
+                        ";
+        let veh_def: IOVehicleDefinitions = from_str(xml).unwrap();
+
+        let capacities: Vec<_> = veh_def
+            .veh_types
+            .iter()
+            .map(|t| {
+                let capacity = t.capacity.clone().unwrap_or_default();
+                (capacity.seats(), capacity.standing_room())
+            })
+            .collect();
+        assert_eq!(vec![(30, 50), (20, 40), (1, 0)], capacities);
+    }
+
     #[deterministic_id_test]
     fn test_to_from_file_xml() {
         let file = &PathBuf::from(
@@ -285,6 +351,8 @@ mod test {
             max_v: 1000.0,
             pce: 20.0,
             fef: 0.3,
+            seats: 4,
+            standing_room: 2,
             net_mode: Id::<String>::create("some network type 🚕"),
             attributes: InternalAttributes::default(),
         });
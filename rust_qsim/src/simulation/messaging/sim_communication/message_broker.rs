@@ -357,6 +357,7 @@ mod tests {
             sample_size: 0.0,
             stuck_threshold: 0,
             main_modes: vec![],
+            ..config::QSim::default()
         };
         let partition = SimNetworkPartition::from_network(
             &create_network(),
@@ -485,6 +486,8 @@ mod tests {
             modes: Default::default(),
             partition,
             attributes: Default::default(),
+            changes: Vec::new(),
+            lanes: Vec::new(),
         }
     }
 }
@@ -4,6 +4,7 @@ use std::collections::{HashMap, HashSet};
 
 pub mod local_communicator;
 pub mod message_broker;
+pub mod socket_communicator;
 
 pub trait SimCommunicator {
     fn send_receive_vehicles<F>(
@@ -0,0 +1,496 @@
+use crate::generated::messages::sim_message::Payload;
+use crate::generated::messages::{Barrier, SimMessage, SyncMessage};
+use crate::simulation::messaging::messages::InternalSyncMessage;
+use crate::simulation::messaging::sim_communication::SimCommunicator;
+use crate::simulation::time::Tick;
+use nohash_hasher::IntMap;
+use prost::Message;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// The address a rank listens on for connections of the other ranks. Addresses are written as
+/// `host:port` for TCP sockets and as `unix:<path>` for Unix domain sockets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketAddress {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for SocketAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        #[cfg(unix)]
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(SocketAddress::Unix(PathBuf::from(path)));
+        }
+        s.parse()
+            .map(SocketAddress::Tcp)
+            .map_err(|e| format!("Invalid socket address {s}: {e}"))
+    }
+}
+
+impl Display for SocketAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SocketAddress::Tcp(addr) => write!(f, "{addr}"),
+            #[cfg(unix)]
+            SocketAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn bind(address: &SocketAddress) -> std::io::Result<Self> {
+        match address {
+            SocketAddress::Tcp(addr) => TcpListener::bind(addr).map(Listener::Tcp),
+            #[cfg(unix)]
+            SocketAddress::Unix(path) => {
+                // remove the socket file of a previous run
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+                UnixListener::bind(path).map(Listener::Unix)
+            }
+        }
+    }
+
+    fn accept(&self) -> std::io::Result<Stream> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.accept().map(|(s, _)| Stream::Unix(s)),
+        }
+    }
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    fn connect(address: &SocketAddress) -> std::io::Result<Self> {
+        match address {
+            SocketAddress::Tcp(addr) => {
+                let stream = TcpStream::connect(addr)?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            SocketAddress::Unix(path) => UnixStream::connect(path).map(Stream::Unix),
+        }
+    }
+
+    fn try_clone(&self) -> std::io::Result<Self> {
+        match self {
+            Stream::Tcp(s) => s.try_clone().map(Stream::Tcp),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.try_clone().map(Stream::Unix),
+        }
+    }
+
+    fn write_all(&self, buf: &[u8]) -> std::io::Result<()> {
+        match self {
+            Stream::Tcp(s) => (&*s).write_all(buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => (&*s).write_all(buf),
+        }
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        match self {
+            Stream::Tcp(s) => s.read_exact(buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.read_exact(buf),
+        }
+    }
+
+    fn shutdown(&self) {
+        // the peer might have shut down the connection already.
+        let _ = match self {
+            Stream::Tcp(s) => s.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.shutdown(Shutdown::Both),
+        };
+    }
+
+    /// Writes `message` prefixed with its length as little endian u32.
+    fn write_message(&self, message: &SimMessage) -> std::io::Result<()> {
+        let len = message.encoded_len();
+        let mut buf = Vec::with_capacity(4 + len);
+        buf.extend_from_slice(&(len as u32).to_le_bytes());
+        message
+            .encode(&mut buf)
+            .expect("Vec has enough capacity to encode message.");
+        self.write_all(&buf)
+    }
+
+    /// Reads a message written by `write_message`.
+    fn read_message(&mut self) -> std::io::Result<SimMessage> {
+        let mut len = [0u8; 4];
+        self.read_exact(&mut len)?;
+        let mut buf = vec![0u8; u32::from_le_bytes(len) as usize];
+        self.read_exact(&mut buf)?;
+        SimMessage::decode(buf.as_slice())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Communicator for partitions which are simulated in different processes, possibly on different
+/// machines. Every rank is connected to every other rank by a TCP or Unix domain socket.
+/// Messages are serialized with the protobuf messages in `messages.proto`.
+///
+/// Incoming messages are read by one thread per connection and forwarded to the simulation
+/// thread, so that ranks never block each other while sending.
+pub struct SocketSimCommunicator {
+    rank: u32,
+    size: u32,
+    streams: IntMap<u32, Stream>,
+    sync_receiver: Receiver<SyncMessage>,
+    barrier_receiver: Receiver<u32>,
+}
+
+impl SocketSimCommunicator {
+    /// Connects `rank` to all other ranks. `addresses` contains the listening address of every
+    /// rank, ordered by rank. Each rank connects to all ranks with a lower rank and accepts
+    /// connections of all ranks with a higher rank. Connection attempts are retried until `timeout`
+    /// has passed, so that the processes of a run can be started in any order.
+    pub fn connect(
+        rank: u32,
+        addresses: &[SocketAddress],
+        timeout: Duration,
+    ) -> std::io::Result<Self> {
+        let size = addresses.len() as u32;
+        assert!(
+            rank < size,
+            "Rank {rank} is out of range for {size} socket addresses."
+        );
+        info!(
+            "Rank #{rank} listening on {} for {} other ranks.",
+            addresses[rank as usize],
+            size - 1
+        );
+
+        let listener = Listener::bind(&addresses[rank as usize])?;
+        let mut streams = IntMap::default();
+
+        for peer in 0..rank {
+            let stream = connect_with_retry(&addresses[peer as usize], timeout)?;
+            stream.write_all(&rank.to_le_bytes())?;
+            streams.insert(peer, stream);
+            debug!("Rank #{rank} connected to rank #{peer}.");
+        }
+
+        for _ in rank + 1..size {
+            let mut stream = listener.accept()?;
+            let mut peer = [0u8; 4];
+            stream.read_exact(&mut peer)?;
+            let peer = u32::from_le_bytes(peer);
+            debug!("Rank #{rank} accepted connection of rank #{peer}.");
+            streams.insert(peer, stream);
+        }
+
+        let (sync_sender, sync_receiver) = channel();
+        let (barrier_sender, barrier_receiver) = channel();
+        for (peer, stream) in &streams {
+            let stream = stream.try_clone()?;
+            let sync_sender = sync_sender.clone();
+            let barrier_sender = barrier_sender.clone();
+            let peer = *peer;
+            thread::Builder::new()
+                .name(format!("socket-{rank}-{peer}"))
+                .spawn(move || receive_messages(stream, peer, sync_sender, barrier_sender))?;
+        }
+
+        info!("Rank #{rank} is connected to all other ranks.");
+        Ok(SocketSimCommunicator {
+            rank,
+            size,
+            streams,
+            sync_receiver,
+            barrier_receiver,
+        })
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn send(&self, target: u32, message: &SimMessage) {
+        self.streams
+            .get(&target)
+            .unwrap_or_else(|| panic!("Rank #{} has no connection to rank {target}", self.rank))
+            .write_message(message)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Error while sending message to rank {} with error {}",
+                    target, e
+                )
+            });
+    }
+}
+
+impl SimCommunicator for SocketSimCommunicator {
+    fn send_receive_vehicles<F>(
+        &self,
+        vehicles: HashMap<u32, InternalSyncMessage>,
+        expected_vehicle_messages: &mut HashSet<u32>,
+        now: Tick,
+        mut on_msg: F,
+    ) where
+        F: FnMut(InternalSyncMessage),
+    {
+        // send messages to everyone
+        for (target, msg) in vehicles {
+            if target == self.rank {
+                // messages to ourselves don't go through a socket.
+                if msg.time() == now {
+                    expected_vehicle_messages.remove(&target);
+                }
+                on_msg(msg);
+                continue;
+            }
+            let message = SimMessage {
+                payload: Some(Payload::Sync(SyncMessage::from(&msg))),
+            };
+            self.send(target, &message);
+        }
+
+        // receive messages from everyone
+        while !expected_vehicle_messages.is_empty() {
+            let received_msg = InternalSyncMessage::from(
+                self.sync_receiver
+                    .recv()
+                    .expect("Error while receiving messages"),
+            );
+            let from_rank = received_msg.from_process();
+
+            // Same as in the channel communicator: a message for this very time step means that
+            // the sender has nothing more to send for this time step.
+            if received_msg.time() == now {
+                expected_vehicle_messages.remove(&from_rank);
+            }
+
+            on_msg(received_msg);
+        }
+    }
+
+    fn barrier(&self) {
+        let message = SimMessage {
+            payload: Some(Payload::Barrier(Barrier {})),
+        };
+        for peer in self.streams.keys() {
+            self.send(*peer, &message);
+        }
+        // Only the number of received messages is counted, not their senders. A peer which has
+        // already passed this barrier may send its message for the next barrier before the message
+        // of another peer for this barrier arrives. That is fine, because a peer only passes a
+        // barrier once all ranks have reached it. Either way, all peers have reached this barrier.
+        for _ in 0..self.streams.len() {
+            self.barrier_receiver
+                .recv()
+                .expect("Error while waiting for barrier messages");
+        }
+    }
+
+    fn rank(&self) -> u32 {
+        self.rank
+    }
+}
+
+impl Drop for SocketSimCommunicator {
+    fn drop(&mut self) {
+        // ends the receiving threads of this and the other ranks.
+        for stream in self.streams.values() {
+            stream.shutdown();
+        }
+    }
+}
+
+fn connect_with_retry(address: &SocketAddress, timeout: Duration) -> std::io::Result<Stream> {
+    let start = Instant::now();
+    loop {
+        match Stream::connect(address) {
+            Ok(stream) => return Ok(stream),
+            Err(e) if start.elapsed() >= timeout => return Err(e),
+            Err(_) => thread::sleep(Duration::from_millis(50)),
+        }
+    }
+}
+
+fn receive_messages(
+    mut stream: Stream,
+    peer: u32,
+    sync_sender: Sender<SyncMessage>,
+    barrier_sender: Sender<u32>,
+) {
+    // The loop ends when the connection is closed, or when the communicator was dropped and
+    // the receivers are gone.
+    while let Ok(message) = stream.read_message() {
+        let sent = match message.payload {
+            Some(Payload::Sync(sync)) => sync_sender.send(sync).is_ok(),
+            Some(Payload::Barrier(_)) => barrier_sender.send(peer).is_ok(),
+            None => panic!("Received message without payload from rank {peer}."),
+        };
+        if !sent {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::Identifiable;
+    use crate::simulation::agents::SimulationAgentLogic;
+    use crate::simulation::id::Id;
+    use crate::simulation::messaging::messages::InternalSyncMessage;
+    use crate::simulation::messaging::sim_communication::SimCommunicator;
+    use crate::simulation::messaging::sim_communication::socket_communicator::{
+        SocketAddress, SocketSimCommunicator,
+    };
+    use crate::simulation::network::sim_network::StorageUpdate;
+    use crate::simulation::scenario::network::Link;
+    use crate::simulation::scenario::vehicles::InternalVehicle;
+    use crate::simulation::time::Tick;
+    use crate::simulation::vehicles::SimulationVehicle;
+    use crate::test_utils::create_agent;
+    use macros::deterministic_id_test;
+    use std::collections::{HashMap, HashSet};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_socket_address() {
+        assert_eq!(
+            SocketAddress::Tcp("127.0.0.1:9000".parse().unwrap()),
+            "127.0.0.1:9000".parse().unwrap()
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            SocketAddress::Unix("/tmp/rank-0.sock".into()),
+            "unix:/tmp/rank-0.sock".parse().unwrap()
+        );
+        assert!("localhost".parse::<SocketAddress>().is_err());
+    }
+
+    #[deterministic_id_test]
+    #[cfg(unix)]
+    fn exchange_messages_over_unix_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        let addresses: Vec<_> = (0..3)
+            .map(|rank| SocketAddress::Unix(dir.path().join(format!("rank-{rank}.sock"))))
+            .collect();
+        exchange_messages(addresses);
+    }
+
+    #[deterministic_id_test]
+    fn exchange_messages_over_tcp_sockets() {
+        // let the os pick free ports
+        let addresses: Vec<_> = (0..3)
+            .map(|_| {
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                SocketAddress::Tcp(listener.local_addr().unwrap())
+            })
+            .collect();
+        exchange_messages(addresses);
+    }
+
+    /// Every rank sends a message with a storage update to every other rank. Additionally, rank 0
+    /// sends a vehicle to rank 2. Then, all ranks meet at a barrier.
+    fn exchange_messages(addresses: Vec<SocketAddress>) {
+        Id::<Link>::create("link");
+
+        thread::scope(|scope| {
+            for rank in 0..3 {
+                let addresses = &addresses;
+                scope.spawn(move || {
+                    let comm =
+                        SocketSimCommunicator::connect(rank, addresses, Duration::from_secs(10))
+                            .unwrap();
+                    assert_eq!(rank, comm.rank());
+                    assert_eq!(3, comm.size());
+
+                    let now = Tick::new(7);
+                    let mut messages = HashMap::new();
+                    for target in (0..3).filter(|t| *t != rank) {
+                        let mut msg = InternalSyncMessage::new(now, rank, target);
+                        msg.add_storage_cap(StorageUpdate {
+                            link_id: Id::get_from_ext("link"),
+                            from_part: rank,
+                            released: 1.5,
+                        });
+                        if rank == 0 && target == 2 {
+                            let agent = create_agent(1, vec!["link", "link"]);
+                            msg.add_veh(SimulationVehicle::new(
+                                InternalVehicle::new(0, 0, 10., 1.),
+                                Some(agent),
+                                vec![],
+                            ));
+                        }
+                        messages.insert(target, msg);
+                    }
+
+                    let mut expected: HashSet<u32> = (0..3).filter(|t| *t != rank).collect();
+                    let mut received = Vec::new();
+                    comm.send_receive_vehicles(messages, &mut expected, now, |msg| {
+                        received.push(msg)
+                    });
+                    comm.barrier();
+
+                    assert!(expected.is_empty());
+                    received.sort_by_key(|msg| msg.from_process());
+                    let senders: Vec<_> = received.iter().map(|m| m.from_process()).collect();
+                    let expected_senders: Vec<_> = (0..3).filter(|t| *t != rank).collect();
+                    assert_eq!(expected_senders, senders);
+
+                    for msg in &received {
+                        assert_eq!(now, msg.time());
+                        assert_eq!(rank, msg.to_process());
+                        let cap = &msg.storage_capacities()[0];
+                        assert_eq!(Id::get_from_ext("link"), cap.link_id);
+                        assert_eq!(msg.from_process(), cap.from_part);
+                        assert_eq!(1.5, cap.released);
+
+                        if rank == 2 && msg.from_process() == 0 {
+                            assert_eq!(1, msg.vehicles().len());
+                            let vehicle = &msg.vehicles()[0];
+                            assert_eq!(&Id::get_from_ext("0"), vehicle.id());
+                            assert_eq!(&Id::get_from_ext("1"), vehicle.driver().id());
+                            assert_eq!(
+                                Some(&Id::get_from_ext("link")),
+                                vehicle.driver().curr_link_id()
+                            );
+                        } else {
+                            assert!(msg.vehicles().is_empty());
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
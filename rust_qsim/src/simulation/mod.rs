@@ -13,10 +13,12 @@ use std::fmt::Debug;
 use tracing::warn;
 
 pub mod agents;
+pub mod analysis;
 #[allow(deprecated)]
 pub mod config;
 pub mod controller;
 pub mod data_structures;
+pub mod drt;
 pub mod engines;
 pub mod events;
 pub mod framework_events;
@@ -31,6 +33,7 @@ pub mod pt;
 pub mod random;
 pub mod replanning;
 pub mod scenario;
+pub mod scoring;
 #[allow(clippy::module_inception)]
 pub mod simulation;
 pub mod time;
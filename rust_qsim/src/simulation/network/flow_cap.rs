@@ -40,6 +40,19 @@ impl Flowcap {
         self.remaining_capacity -= by;
     }
 
+    /// Changes the capacity, e.g., because of a network change event. Capacity which has already
+    /// accumulated is kept, as long as it does not exceed the new capacity per tick.
+    pub(super) fn change_capacity(
+        &mut self,
+        capacity_h: f64,
+        sample_size: f64,
+        capacity_per_tick: f64,
+    ) {
+        self.capacity_per_second = capacity_h * sample_size / 3600.;
+        self.capacity_per_tick = capacity_per_tick;
+        self.remaining_capacity = f64::min(self.remaining_capacity, capacity_per_tick);
+    }
+
     pub(super) fn capacity_per_tick(&self) -> f64 {
         self.capacity_per_tick
     }
@@ -96,4 +109,20 @@ mod tests {
         flowcap.update_capacity(SimTime::from_secs(5));
         assert!(flowcap.has_capacity_left());
     }
+
+    #[test]
+    fn flowcap_change_capacity() {
+        let mut flowcap = Flowcap::new(3600., 1., 1.0);
+
+        flowcap.change_capacity(900., 1., 0.25);
+        assert_eq!(0.25, flowcap.capacity_per_tick());
+        assert_eq!(0.25, flowcap.remaining_capacity());
+
+        flowcap.consume(1.0);
+        flowcap.update_capacity(SimTime::from_secs(3));
+        assert!(!flowcap.has_capacity_left());
+
+        flowcap.update_capacity(SimTime::from_secs(4));
+        assert!(flowcap.has_capacity_left());
+    }
 }
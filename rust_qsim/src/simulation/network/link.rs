@@ -1,6 +1,7 @@
 use crate::simulation::Identifiable;
 use crate::simulation::agents::SimulationAgentLogic;
 use crate::simulation::config;
+use crate::simulation::config::{LinkDynamics, TrafficDynamics};
 use crate::simulation::controller::ThreadLocalComputationalEnvironment;
 use crate::simulation::events::{
     VehicleEntersTrafficEventBuilder, VehicleLeavesTrafficEventBuilder,
@@ -9,8 +10,8 @@ use crate::simulation::id::Id;
 use crate::simulation::network::flow_cap::Flowcap;
 use crate::simulation::network::storage_cap::StorageCap;
 use crate::simulation::network::stuck_timer::StuckTimer;
-use crate::simulation::scenario::network::Link;
 use crate::simulation::scenario::network::Node;
+use crate::simulation::scenario::network::{Lane, Link, LinkState};
 use crate::simulation::time::{SimClock, Tick};
 use crate::simulation::vehicles::SimulationVehicle;
 use std::collections::VecDeque;
@@ -77,6 +78,28 @@ impl SimLink {
         }
     }
 
+    /// The flow capacity per tick of one of the link's outlets, see [LocalLink::outlets].
+    pub fn outlet_flow_cap(&self, outlet: usize) -> f64 {
+        match self {
+            SimLink::Local(l) => l.outlet(outlet).1.capacity_per_tick(),
+            SimLink::In(il) => il.local_link.outlet(outlet).1.capacity_per_tick(),
+            SimLink::Out(_) => {
+                panic!("no flow cap for out links")
+            }
+        }
+    }
+
+    /// The number of outlets through which vehicles leave the link, see [LocalLink::outlets].
+    pub fn outlets(&self) -> usize {
+        match self {
+            SimLink::Local(l) => l.outlets(),
+            SimLink::In(il) => il.local_link.outlets(),
+            SimLink::Out(_) => {
+                panic!("Out links don't offer vehicles. ")
+            }
+        }
+    }
+
     pub fn offers_veh(&self, now: impl Into<Tick>) -> Option<&SimulationVehicle> {
         let now = now.into();
         match self {
@@ -88,11 +111,26 @@ impl SimLink {
         }
     }
 
-    pub fn is_veh_stuck(&self, now: impl Into<Tick>) -> bool {
+    pub fn offers_veh_from(
+        &self,
+        outlet: usize,
+        now: impl Into<Tick>,
+    ) -> Option<&SimulationVehicle> {
+        let now = now.into();
+        match self {
+            SimLink::Local(ll) => ll.offers_veh_from(outlet, now),
+            SimLink::In(il) => il.local_link.offers_veh_from(outlet, now),
+            SimLink::Out(_) => {
+                panic!("can't query out links to offer vehicles.")
+            }
+        }
+    }
+
+    pub fn is_veh_stuck(&self, outlet: usize, now: impl Into<Tick>) -> bool {
         let now = now.into();
         match self {
-            SimLink::Local(ll) => ll.stuck_timer.is_stuck(now),
-            SimLink::In(il) => il.local_link.stuck_timer.is_stuck(now),
+            SimLink::Local(ll) => ll.outlet(outlet).2.is_stuck(now),
+            SimLink::In(il) => il.local_link.outlet(outlet).2.is_stuck(now),
             SimLink::Out(_) => {
                 panic!("Out links don't offer vehicles. ")
             }
@@ -140,11 +178,21 @@ impl SimLink {
         let now = now.into();
         match self {
             SimLink::Local(l) => l.push_veh(vehicle, now, position),
-            SimLink::In(il) => il.local_link.push_veh(vehicle, now, position),
+            SimLink::In(il) => il.push_veh(vehicle, now, position),
             SimLink::Out(ol) => ol.push_veh(vehicle, position),
         }
     }
 
+    /// Changes the capacity, freespeed and lanes of the link, e.g., because of a network change
+    /// event. Vehicles already on the link keep their earliest exit time.
+    pub(super) fn apply_state(&mut self, state: &LinkState) {
+        match self {
+            SimLink::Local(ll) => ll.apply_state(state),
+            SimLink::In(il) => il.local_link.apply_state(state),
+            SimLink::Out(ol) => ol.apply_state(state),
+        }
+    }
+
     pub fn pop_veh(&mut self) -> Option<SimulationVehicle> {
         match self {
             SimLink::Local(ll) => ll.pop_veh(),
@@ -155,6 +203,30 @@ impl SimLink {
         }
     }
 
+    pub fn pop_veh_from(&mut self, outlet: usize) -> Option<SimulationVehicle> {
+        match self {
+            SimLink::Local(ll) => ll.pop_veh_from(outlet),
+            SimLink::In(il) => il.local_link.pop_veh_from(outlet),
+            SimLink::Out(_) => {
+                panic!("Can't pop vehicle from out link")
+            }
+        }
+    }
+
+    pub(super) fn push_veh_leaving_stop(&mut self, vehicle: SimulationVehicle) {
+        match self {
+            SimLink::Local(ll) => ll.push_veh_leaving_stop(vehicle),
+            SimLink::In(il) => il.local_link.push_veh_leaving_stop(vehicle),
+            SimLink::Out(ol) => {
+                panic!(
+                    "Vehicle {} can't leave a stop on out link {}.",
+                    vehicle.id(),
+                    ol.id
+                )
+            }
+        }
+    }
+
     pub(super) fn drain(&mut self) -> Vec<SimulationVehicle> {
         match self {
             SimLink::Local(ll) => ll.drain(),
@@ -170,10 +242,23 @@ pub struct LocalLink {
     q: VecDeque<VehicleQEntry>,
     buffer: VecDeque<SimulationVehicle>,
     waiting_list: VecDeque<SimulationVehicle>,
+    /// Vehicles which have reached a transit stop on this link. They are handed over to the transit engine until they leave the stop.
+    stopping: Vec<SimulationVehicle>,
+    /// Vehicles which have served a transit stop on this link and wait for flow capacity to move into the buffer.
+    leaving_stop: VecDeque<SimulationVehicle>,
     length: f64,
     free_speed: f64,
     storage_cap: StorageCap,
     flow_cap: Flowcap,
+    sample_size: f64,
+    effective_cell_size: f64,
+    /// The time a hole needs to travel from the downstream to the upstream end of the link. Only
+    /// set with [TrafficDynamics::KinematicWaves].
+    hole_travel_time: Option<Tick>,
+    link_dynamics: LinkDynamics,
+    /// Lanes at the downstream end of the link. If there are lanes, vehicles leave the link
+    /// through the buffer of their lane instead of the link's buffer.
+    lanes: Vec<SimLane>,
     stuck_timer: StuckTimer,
     clock: SimClock,
     pub from: Id<Node>,
@@ -186,9 +271,73 @@ struct VehicleQEntry {
     earliest_exit_time: Tick,
 }
 
+/// A lane at the downstream end of a link. Vehicles move from the link's queue into the lane
+/// leading to their next link, so that vehicles turning into different directions queue
+/// separately. Each lane has its own storage and flow capacity.
+#[derive(Debug)]
+struct SimLane {
+    to_links: Vec<Id<Link>>,
+    length: f64,
+    q: VecDeque<VehicleQEntry>,
+    buffer: VecDeque<SimulationVehicle>,
+    storage_cap: StorageCap,
+    flow_cap: Flowcap,
+    stuck_timer: StuckTimer,
+}
+
+impl SimLane {
+    fn new(lane: &Lane, effective_cell_size: f64, config: &config::QSim, clock: SimClock) -> Self {
+        let capacity_per_tick =
+            LocalLink::capacity_per_tick(lane.capacity, config.sample_size, clock);
+        SimLane {
+            to_links: lane.to_links.clone(),
+            length: lane.length,
+            q: VecDeque::new(),
+            buffer: VecDeque::new(),
+            storage_cap: StorageCap::build(
+                lane.length,
+                lane.permlanes,
+                lane.capacity,
+                config.sample_size,
+                effective_cell_size,
+            ),
+            flow_cap: Flowcap::new(lane.capacity, config.sample_size, capacity_per_tick),
+            stuck_timer: StuckTimer::new(clock.secs_to_tick(config.stuck_threshold as u64)),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.q.is_empty() && self.buffer.is_empty()
+    }
+}
+
+/// Inserts a vehicle into the queue of a link or lane according to the link dynamics.
+fn insert_into_queue(
+    q: &mut VecDeque<VehicleQEntry>,
+    entry: VehicleQEntry,
+    link_dynamics: LinkDynamics,
+) {
+    match link_dynamics {
+        LinkDynamics::Fifo => q.push_back(entry),
+        LinkDynamics::PassingQueue => {
+            // Vehicles with the same earliest exit time keep the order in which they entered.
+            let index = q.partition_point(|e| e.earliest_exit_time <= entry.earliest_exit_time);
+            q.insert(index, entry);
+        }
+    }
+}
+
+/// A vehicle may move into a buffer as long as some flow capacity is left after the vehicles
+/// already in the buffer. Its full flow consumption is deducted when it leaves the link, which may
+/// make the remaining capacity negative and delays the following vehicles.
+fn has_flow_capacity_left(flow_cap: &Flowcap, buffer: &VecDeque<SimulationVehicle>) -> bool {
+    let buffer_cap = buffer.iter().map(|v| v.flow_consumption()).sum::<f64>();
+    flow_cap.remaining_capacity() - buffer_cap > 0.0
+}
+
 impl LocalLink {
     pub fn from_link(link: &Link, effective_cell_size: f64, config: &config::QSim) -> Self {
-        LocalLink::build(
+        let mut local_link = LocalLink::build(
             link.id.clone(),
             link.capacity,
             link.freespeed,
@@ -198,7 +347,13 @@ impl LocalLink {
             config,
             link.from.clone(),
             link.to.clone(),
-        )
+        );
+        local_link.lanes = link
+            .lanes
+            .iter()
+            .map(|lane| SimLane::new(lane, effective_cell_size, config, local_link.clock))
+            .collect();
+        local_link
     }
 
     pub fn new_with_defaults(id: Id<Link>, from: Id<Node>, to: Id<Node>) -> Self {
@@ -208,10 +363,17 @@ impl LocalLink {
             q: VecDeque::new(),
             buffer: VecDeque::new(),
             waiting_list: VecDeque::new(),
+            stopping: Vec::new(),
+            leaving_stop: VecDeque::new(),
             length: 1.0,
             free_speed: 1.0,
             storage_cap: StorageCap::build(0., 1., 1., 1.0, 7.5),
             flow_cap: Flowcap::new(3600., 1.0, 1.0),
+            sample_size: 1.0,
+            effective_cell_size: 7.5,
+            hole_travel_time: None,
+            link_dynamics: LinkDynamics::Fifo,
+            lanes: Vec::new(),
             stuck_timer: StuckTimer::new(Tick::new(u32::MAX as u64)),
             clock,
             from,
@@ -231,8 +393,7 @@ impl LocalLink {
         to: Id<Node>,
     ) -> Self {
         let clock = SimClock::new(config.ticks_per_second);
-        let capacity_per_tick =
-            (capacity_h * config.sample_size / 3600.) * clock.tick_length().as_secs_f64();
+        let capacity_per_tick = Self::capacity_per_tick(capacity_h, config.sample_size, clock);
         let storage_cap = StorageCap::build(
             length,
             perm_lanes,
@@ -240,16 +401,29 @@ impl LocalLink {
             config.sample_size,
             effective_cell_size,
         );
+        let hole_travel_time = match config.traffic_dynamics {
+            TrafficDynamics::Queue => None,
+            TrafficDynamics::KinematicWaves => {
+                Some(clock.secs_to_ticks_floor(length / (config.hole_speed / 3.6)))
+            }
+        };
 
         LocalLink {
             id,
             q: VecDeque::new(),
             buffer: VecDeque::new(),
             waiting_list: VecDeque::new(),
+            stopping: Vec::new(),
+            leaving_stop: VecDeque::new(),
             length,
             free_speed,
             storage_cap,
             flow_cap: Flowcap::new(capacity_h, config.sample_size, capacity_per_tick),
+            sample_size: config.sample_size,
+            effective_cell_size,
+            hole_travel_time,
+            link_dynamics: config.link_dynamics,
+            lanes: Vec::new(),
             stuck_timer: StuckTimer::new(clock.secs_to_tick(config.stuck_threshold as u64)),
             clock,
             from,
@@ -257,6 +431,30 @@ impl LocalLink {
         }
     }
 
+    fn capacity_per_tick(capacity_h: f64, sample_size: f64, clock: SimClock) -> f64 {
+        (capacity_h * sample_size / 3600.) * clock.tick_length().as_secs_f64()
+    }
+
+    /// Changes the capacity, freespeed and lanes of the link. The new freespeed applies to
+    /// vehicles entering the link from now on. Lanes at the downstream end keep their capacities.
+    pub fn apply_state(&mut self, state: &LinkState) {
+        self.free_speed = state.freespeed;
+        let capacity_per_tick =
+            Self::capacity_per_tick(state.capacity, self.sample_size, self.clock);
+        self.flow_cap
+            .change_capacity(state.capacity, self.sample_size, capacity_per_tick);
+        self.storage_cap.set_max(
+            StorageCap::build(
+                self.length,
+                state.permlanes,
+                state.capacity,
+                self.sample_size,
+                self.effective_cell_size,
+            )
+            .max(),
+        );
+    }
+
     pub fn push_veh(
         &mut self,
         vehicle: SimulationVehicle,
@@ -271,28 +469,90 @@ impl LocalLink {
     }
 
     fn push_veh_to_queue(&mut self, vehicle: SimulationVehicle, now: Tick) {
-        let speed = self.free_speed.min(vehicle.max_v());
-        let duration = self.clock.secs_to_ticks_floor(self.length / speed as f64);
-        let earliest_exit_time = now.saturating_add(duration);
+        // vehicles using a lane leave the link's queue at the start of the lane
+        let lane_length = self
+            .lane_for(&vehicle)
+            .map_or(0., |lane| self.lanes[lane].length);
+        let earliest_exit_time = self.earliest_exit_time(&vehicle, self.length - lane_length, now);
 
         // update state
         self.storage_cap.consume(vehicle.pce());
-        self.q.push_back(VehicleQEntry {
+        let entry = VehicleQEntry {
             vehicle,
             earliest_exit_time,
-        });
+        };
+        insert_into_queue(&mut self.q, entry, self.link_dynamics);
     }
 
-    /// Push a vehicle into the waiting list.
+    fn push_veh_to_lane(&mut self, lane: usize, vehicle: SimulationVehicle, now: Tick) {
+        let earliest_exit_time = self.earliest_exit_time(&vehicle, self.lanes[lane].length, now);
+        let lane = &mut self.lanes[lane];
+        lane.storage_cap.consume(vehicle.pce());
+        let entry = VehicleQEntry {
+            vehicle,
+            earliest_exit_time,
+        };
+        insert_into_queue(&mut lane.q, entry, self.link_dynamics);
+    }
+
+    fn earliest_exit_time(&self, vehicle: &SimulationVehicle, length: f64, now: Tick) -> Tick {
+        let speed = self.free_speed.min(vehicle.max_v());
+        let duration = self.clock.secs_to_ticks_floor(length / speed);
+        now.saturating_add(duration)
+    }
+
+    /// The lane a vehicle uses to leave the link. Among the lanes leading to the vehicle's next
+    /// link, this is the one with the least used storage capacity. Vehicles which end their leg
+    /// or serve a transit stop on the link don't use lanes. Neither do vehicles turning into a
+    /// link no lane leads to, e.g., a U-turn left out of the lanes file. They leave the link
+    /// through its own buffer.
+    fn lane_for(&self, vehicle: &SimulationVehicle) -> Option<usize> {
+        if self.lanes.is_empty()
+            || vehicle.driver().is_wanting_to_arrive_on_current_link()
+            || vehicle.driver().is_wanting_to_stop_on_current_link()
+        {
+            return None;
+        }
+        let next_link = vehicle.peek_next_route_element()?;
+        self.lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, lane)| lane.to_links.contains(next_link))
+            .min_by(|(_, a), (_, b)| a.storage_cap.used().total_cmp(&b.storage_cap.used()))
+            .map(|(index, _)| index)
+    }
+
+    /// Push a vehicle into the waiting list. Vehicles which serve a transit stop on this link
+    /// occupy storage capacity from now on until they leave the stop.
     pub fn push_veh_to_waiting_list(&mut self, vehicle: SimulationVehicle) {
+        if vehicle.driver().is_wanting_to_stop_on_current_link() {
+            self.storage_cap.consume(vehicle.pce());
+        }
         self.waiting_list.push_back(vehicle);
     }
 
-    /// This method fills the buffer from two sources with priority:
-    /// 1. Check if there are vehicles in the waiting list and move them to the buffer.
-    /// 2. Check if there are vehicles in the queue that have reached their earliest exit time and move them to the buffer.
+    /// Push a vehicle which has finished serving a transit stop on this link. Such vehicles still
+    /// use storage capacity on the link and are moved into the buffer with priority.
+    pub fn push_veh_leaving_stop(&mut self, vehicle: SimulationVehicle) {
+        self.leaving_stop.push_back(vehicle);
+    }
+
+    /// Returns the vehicles which have reached a transit stop during the last sim step.
+    pub fn take_stopping_vehicles(&mut self) -> Vec<SimulationVehicle> {
+        std::mem::take(&mut self.stopping)
+    }
+
+    /// This method fills the buffer from three sources with priority:
+    /// 1. Check if there are vehicles which have served a transit stop and move them to the buffer.
+    /// 2. Check if there are vehicles in the waiting list and move them to the buffer.
+    /// 3. Check if there are vehicles in the queue that have reached their earliest exit time and move them to the buffer.
+    ///
+    /// On links with lanes, vehicles use the buffer of their lane. Vehicles in the queue first
+    /// move into their lane and from there into the lane's buffer once they have reached the end
+    /// of the lane.
     ///
-    /// Both is done only if the flow capacity allows this.
+    /// All is done only if the flow capacity allows this. Vehicles which want to serve a transit
+    /// stop on this link are collected separately, see [LocalLink::take_stopping_vehicles].
     ///
     /// Returns the vehicles that end their leg on the link
     pub fn do_sim_step(
@@ -303,7 +563,10 @@ impl LocalLink {
         let now = now.into();
         let now_time = self.clock.tick_to_time(now);
         self.update_flow_cap(now);
-        let mut ending_vehicles = self.add_waiting_to_buffer(comp_env, now);
+        self.storage_cap.update_holes(now);
+        let mut ending_vehicles = self.add_leaving_stop_to_buffer(now);
+        ending_vehicles.append(&mut self.add_waiting_to_buffer(comp_env, now));
+        self.add_lanes_to_buffer(now);
         ending_vehicles.append(&mut self.add_queue_to_buffer(now));
 
         for v in &ending_vehicles {
@@ -335,8 +598,9 @@ impl LocalLink {
 
             let veh = option.unwrap();
 
+            let stop = veh.vehicle.driver().is_wanting_to_stop_on_current_link();
             let arrive = veh.vehicle.driver().is_wanting_to_arrive_on_current_link();
-            let capacity_left = self.has_flow_capacity_left(&veh.vehicle);
+            let capacity_left = self.has_flow_capacity_left();
             let exit = veh.earliest_exit_time <= now;
 
             // If the earliest exit time has not passed, nothing to do
@@ -344,27 +608,80 @@ impl LocalLink {
                 break;
             }
 
+            // If the vehicle wants to serve a transit stop, remove it from the queue. Stops are
+            // served before the vehicle may arrive on the link. The vehicle keeps its storage
+            // capacity until it leaves the stop.
+            if stop {
+                let veh = self.q.pop_front().unwrap().vehicle;
+                self.stopping.push(veh);
+                continue;
+            }
+
             // If the vehicle wants to arrive, remove it from the queue
             if arrive {
                 let veh = self.q.pop_front().unwrap().vehicle;
-                self.storage_cap.release(veh.pce());
+                self.release_storage(veh.pce(), now);
                 released_vehicles.push(veh);
                 continue;
             }
 
-            // If the vehicle wants to move to another link, put it into buffer
-            if capacity_left {
-                let veh = self.q.pop_front().unwrap().vehicle;
-                self.storage_cap.release(veh.pce());
-                self.buffer.push_back(veh);
-            } else {
+            if !capacity_left {
                 break;
             }
+
+            // If the vehicle wants to move to another link, put it into the buffer or into the
+            // lane leading to that link. A full lane blocks the queue.
+            match self.lane_for(&veh.vehicle) {
+                None => {
+                    let veh = self.q.pop_front().unwrap().vehicle;
+                    self.release_storage(veh.pce(), now);
+                    self.buffer.push_back(veh);
+                }
+                Some(lane) if self.lanes[lane].storage_cap.is_available() => {
+                    let veh = self.q.pop_front().unwrap().vehicle;
+                    self.flow_cap.consume(veh.flow_consumption());
+                    self.push_veh_to_lane(lane, veh, now);
+                }
+                Some(_) => break,
+            }
         }
 
         released_vehicles
     }
 
+    /// Moves vehicles which have reached the end of their lane into the lane's buffer, if the
+    /// lane's flow capacity allows this. Like vehicles moving from the queue into the link's
+    /// buffer, they release their storage capacity on the link.
+    fn add_lanes_to_buffer(&mut self, now: Tick) {
+        let mut released = 0.;
+        for lane in &mut self.lanes {
+            while let Some(entry) = lane.q.front()
+                && entry.earliest_exit_time <= now
+                && has_flow_capacity_left(&lane.flow_cap, &lane.buffer)
+            {
+                let veh = lane.q.pop_front().unwrap().vehicle;
+                lane.storage_cap.release(veh.pce());
+                released += veh.pce();
+                lane.buffer.push_back(veh);
+            }
+        }
+        if released > 0. {
+            self.release_storage(released, now);
+        }
+    }
+
+    /// Releases the storage capacity of a vehicle leaving the queue. With kinematic waves, the
+    /// capacity is only released when the hole left by the vehicle reaches the upstream end of
+    /// the link.
+    fn release_storage(&mut self, pce: f64, now: Tick) {
+        match self.hole_travel_time {
+            None => self.storage_cap.release(pce),
+            Some(hole_travel_time) => self
+                .storage_cap
+                .release_at(pce, now.saturating_add(hole_travel_time)),
+        }
+    }
+
     fn add_waiting_to_buffer(
         &mut self,
         comp_env: &mut ThreadLocalComputationalEnvironment,
@@ -380,6 +697,18 @@ impl LocalLink {
                 break;
             }
 
+            // If the vehicle wants to serve a transit stop on its first link, remove it from waiting list.
+            // Its storage capacity was consumed when it was pushed into the waiting list.
+            if option
+                .unwrap()
+                .driver()
+                .is_wanting_to_stop_on_current_link()
+            {
+                let vehicle = self.pop_from_waiting(comp_env, now);
+                self.stopping.push(vehicle);
+                continue;
+            }
+
             // If arrival on link, remove from waiting list and put into buffer
             if option
                 .unwrap()
@@ -391,9 +720,41 @@ impl LocalLink {
             }
 
             // If not arriving on link, check if flow capacity allows to move vehicle to buffer
-            if self.is_accepting_from_wait(option.unwrap()) {
+            if self.has_flow_capacity_left_for(option.unwrap()) {
                 let vehicle = self.pop_from_waiting(comp_env, now);
-                self.buffer.push_back(vehicle);
+                self.push_veh_to_buffer(vehicle);
+            } else {
+                break;
+            }
+        }
+
+        released_vehicles
+    }
+
+    /// Vehicles which have served a stop release their storage capacity once they end their leg
+    /// or move into a buffer, like vehicles coming from the queue.
+    fn add_leaving_stop_to_buffer(&mut self, now: Tick) -> Vec<SimulationVehicle> {
+        let mut released_vehicles = vec![];
+
+        while let Some(vehicle) = self.leaving_stop.front() {
+            // A vehicle might serve another stop on the same link
+            if vehicle.driver().is_wanting_to_stop_on_current_link() {
+                let vehicle = self.leaving_stop.pop_front().unwrap();
+                self.stopping.push(vehicle);
+                continue;
+            }
+
+            if vehicle.driver().is_wanting_to_arrive_on_current_link() {
+                let vehicle = self.leaving_stop.pop_front().unwrap();
+                self.release_storage(vehicle.pce(), now);
+                released_vehicles.push(vehicle);
+                continue;
+            }
+
+            if self.has_flow_capacity_left_for(vehicle) {
+                let vehicle = self.leaving_stop.pop_front().unwrap();
+                self.release_storage(vehicle.pce(), now);
+                self.push_veh_to_buffer(vehicle);
             } else {
                 break;
             }
@@ -422,21 +783,74 @@ impl LocalLink {
         vehicle
     }
 
-    fn is_accepting_from_wait(&self, veh: &SimulationVehicle) -> bool {
-        self.has_flow_capacity_left(veh)
+    fn has_flow_capacity_left(&self) -> bool {
+        has_flow_capacity_left(&self.flow_cap, &self.buffer)
+    }
+
+    /// Whether the buffer of the vehicle's lane, or the link's buffer, can take the vehicle.
+    fn has_flow_capacity_left_for(&self, vehicle: &SimulationVehicle) -> bool {
+        match self.lane_for(vehicle) {
+            None => self.has_flow_capacity_left(),
+            Some(lane) => {
+                has_flow_capacity_left(&self.lanes[lane].flow_cap, &self.lanes[lane].buffer)
+            }
+        }
+    }
+
+    fn push_veh_to_buffer(&mut self, vehicle: SimulationVehicle) {
+        match self.lane_for(&vehicle) {
+            None => self.buffer.push_back(vehicle),
+            Some(lane) => self.lanes[lane].buffer.push_back(vehicle),
+        }
+    }
+
+    /// Vehicles leave the link through one of its outlets. These are the buffers of the lanes,
+    /// followed by the link's buffer. If the link has lanes, its buffer is only an outlet while it
+    /// holds vehicles which no lane leads to their next link. Each outlet has its own flow capacity
+    /// and stuck timer.
+    pub(super) fn outlets(&self) -> usize {
+        if self.lanes.is_empty() || !self.buffer.is_empty() {
+            self.lanes.len() + 1
+        } else {
+            self.lanes.len()
+        }
+    }
+
+    fn outlet(&self, outlet: usize) -> (&VecDeque<SimulationVehicle>, &Flowcap, &StuckTimer) {
+        if outlet == self.lanes.len() {
+            (&self.buffer, &self.flow_cap, &self.stuck_timer)
+        } else {
+            let lane = &self.lanes[outlet];
+            (&lane.buffer, &lane.flow_cap, &lane.stuck_timer)
+        }
     }
 
-    fn has_flow_capacity_left(&self, _veh: &SimulationVehicle) -> bool {
-        let buffer_cap = self.buffer.iter().map(|v| v.pce()).sum::<f64>();
-        self.flow_cap.remaining_capacity() - buffer_cap > 0.0
+    fn outlet_mut(
+        &mut self,
+        outlet: usize,
+    ) -> (&mut VecDeque<SimulationVehicle>, &mut Flowcap, &StuckTimer) {
+        if outlet == self.lanes.len() {
+            (&mut self.buffer, &mut self.flow_cap, &self.stuck_timer)
+        } else {
+            let lane = &mut self.lanes[outlet];
+            (&mut lane.buffer, &mut lane.flow_cap, &lane.stuck_timer)
+        }
     }
 
-    /// This method returns the next/first vehicle from the buffer and removes it from the buffer.
+    /// This method returns the first vehicle of the first outlet which has one and removes it from
+    /// the outlet's buffer.
     fn pop_veh(&mut self) -> Option<SimulationVehicle> {
-        if let Some(veh) = self.buffer.pop_front() {
-            // self.storage_cap.release(veh.pce);
-            self.flow_cap.consume(veh.pce());
-            self.stuck_timer.reset();
+        let outlet = (0..self.outlets()).find(|&outlet| !self.outlet(outlet).0.is_empty())?;
+        self.pop_veh_from(outlet)
+    }
+
+    /// This method returns the next/first vehicle from the outlet's buffer and removes it from the
+    /// buffer.
+    fn pop_veh_from(&mut self, outlet: usize) -> Option<SimulationVehicle> {
+        let (buffer, flow_cap, stuck_timer) = self.outlet_mut(outlet);
+        if let Some(veh) = buffer.pop_front() {
+            flow_cap.consume(veh.flow_consumption());
+            stuck_timer.reset();
             return Some(veh);
         }
         None
@@ -444,17 +858,28 @@ impl LocalLink {
 
     fn update_flow_cap(&mut self, now: Tick) {
         // increase flow cap if new time step
-        self.flow_cap.update_capacity(self.clock.tick_to_time(now));
+        let now = self.clock.tick_to_time(now);
+        self.flow_cap.update_capacity(now);
+        for lane in &mut self.lanes {
+            lane.flow_cap.update_capacity(now);
+        }
     }
 
-    /// This method returns the next vehicle allowed to leave the connection and checks
-    /// whether flow capacity is available.
+    /// This method returns the next vehicle allowed to leave the connection through any of its
+    /// outlets.
     fn offers_veh(&self, now: impl Into<Tick>) -> Option<&SimulationVehicle> {
         let now = now.into();
-        if let Some(entry) = self.buffer.front()
-            && self.flow_cap.has_capacity_left()
+        (0..self.outlets()).find_map(|outlet| self.offers_veh_from(outlet, now))
+    }
+
+    /// This method returns the next vehicle allowed to leave the outlet and checks whether flow
+    /// capacity is available.
+    fn offers_veh_from(&self, outlet: usize, now: Tick) -> Option<&SimulationVehicle> {
+        let (buffer, flow_cap, stuck_timer) = self.outlet(outlet);
+        if let Some(entry) = buffer.front()
+            && flow_cap.has_capacity_left()
         {
-            self.stuck_timer.start(now);
+            stuck_timer.start(now);
             return Some(entry);
         }
 
@@ -463,7 +888,16 @@ impl LocalLink {
 
     #[cfg(test)]
     pub(super) fn veh_count(&self) -> usize {
-        self.q.len() + self.waiting_list.len() + self.buffer.len()
+        let on_lanes: usize = self
+            .lanes
+            .iter()
+            .map(|lane| lane.q.len() + lane.buffer.len())
+            .sum();
+        self.q.len()
+            + self.waiting_list.len()
+            + self.buffer.len()
+            + self.leaving_stop.len()
+            + on_lanes
     }
 
     pub fn is_available(&self) -> bool {
@@ -471,17 +905,29 @@ impl LocalLink {
     }
 
     fn drain(&mut self) -> Vec<SimulationVehicle> {
-        let mut vehicles =
-            Vec::with_capacity(self.q.len() + self.buffer.len() + self.waiting_list.len());
+        let mut vehicles = Vec::with_capacity(
+            self.q.len() + self.buffer.len() + self.waiting_list.len() + self.leaving_stop.len(),
+        );
         vehicles.extend(self.q.drain(..).map(|entry| entry.vehicle));
         vehicles.extend(self.buffer.drain(..));
         vehicles.extend(self.waiting_list.drain(..));
+        vehicles.extend(self.leaving_stop.drain(..));
+        for lane in &mut self.lanes {
+            vehicles.extend(lane.q.drain(..).map(|entry| entry.vehicle));
+            vehicles.extend(lane.buffer.drain(..));
+        }
         vehicles
     }
 
-    /// A link is active, if either the queue, waiting_list or buffer is not empty.
+    /// A link is active, if either the queue, waiting_list, leaving_stop list, buffer or one of its
+    /// lanes is not empty, or if holes still have to reach the upstream end of the link.
     pub(super) fn is_active(&self) -> bool {
-        !self.q.is_empty() || !self.waiting_list.is_empty() || !self.buffer.is_empty()
+        !self.q.is_empty()
+            || !self.waiting_list.is_empty()
+            || !self.leaving_stop.is_empty()
+            || !self.buffer.is_empty()
+            || self.storage_cap.has_holes()
+            || self.lanes.iter().any(|lane| !lane.is_empty())
     }
 
     fn from(&self) -> &Id<Node> {
@@ -506,6 +952,9 @@ pub struct SplitOutLink {
     pub to_part: u32,
     q: VecDeque<SimulationVehicle>,
     storage_cap: StorageCap,
+    length: f64,
+    sample_size: f64,
+    effective_cell_size: f64,
 }
 
 impl SplitOutLink {
@@ -528,9 +977,27 @@ impl SplitOutLink {
             to_part,
             q: VecDeque::default(),
             storage_cap,
+            length: link.length,
+            sample_size,
+            effective_cell_size,
         }
     }
 
+    /// Keeps the storage capacity in line with the changed downstream [LocalLink], so that the
+    /// upstream node only moves vehicles onto the link if they fit.
+    pub fn apply_state(&mut self, state: &LinkState) {
+        self.storage_cap.set_max(
+            StorageCap::build(
+                self.length,
+                state.permlanes,
+                state.capacity,
+                self.sample_size,
+                self.effective_cell_size,
+            )
+            .max(),
+        );
+    }
+
     pub fn apply_storage_cap_update(&mut self, released: f64) {
         self.storage_cap.consume(-released);
     }
@@ -558,6 +1025,10 @@ impl SplitOutLink {
 pub struct SplitInLink {
     pub from_part: u32,
     pub local_link: LocalLink,
+    /// Storage capacity consumed by vehicles which entered the link on this partition, i.e.
+    /// transit vehicles serving a stop on the first link of their route. The upstream partition
+    /// has not been informed about it yet.
+    unreported_consumption: f64,
 }
 
 impl SplitInLink {
@@ -565,23 +1036,44 @@ impl SplitInLink {
         SplitInLink {
             from_part,
             local_link,
+            unreported_consumption: 0.,
         }
     }
 
     pub(super) fn occupied_storage(&self) -> f64 {
         self.local_link.storage_cap.used()
     }
+
+    fn push_veh(&mut self, vehicle: SimulationVehicle, now: Tick, position: LinkPosition) {
+        // vehicles from the upstream partition are already accounted for on its SplitOutLink
+        let starts_here = matches!(position, LinkPosition::Waiting);
+        let before = self.occupied_storage();
+        self.local_link.push_veh(vehicle, now, position);
+        if starts_here {
+            self.unreported_consumption += self.occupied_storage() - before;
+        }
+    }
+
+    /// Returns the storage capacity consumed on this partition since the last call.
+    pub(super) fn take_unreported_consumption(&mut self) -> f64 {
+        std::mem::take(&mut self.unreported_consumption)
+    }
 }
 
 #[cfg(test)]
 mod sim_link_tests {
+    use crate::simulation::agents::{AgentEvent, EnvironmentalEventObserver};
     use crate::simulation::config;
+    use crate::simulation::config::{LinkDynamics, TrafficDynamics};
     use crate::simulation::id::Id;
     use crate::simulation::network::link::LinkPosition::QStart;
     use crate::simulation::network::link::{LocalLink, SimLink};
+    use crate::simulation::scenario::network::{Lane, Link};
+    use crate::simulation::scenario::vehicles::InternalVehicle;
+    use crate::simulation::time::SimTime;
     use crate::simulation::vehicles::SimulationVehicle;
     use crate::test_utils;
-    use crate::test_utils::create_agent_without_route;
+    use crate::test_utils::{create_agent, create_agent_without_route, create_bus};
     use assert_approx_eq::assert_approx_eq;
     use macros::deterministic_id_test;
 
@@ -639,6 +1131,88 @@ mod sim_link_tests {
         assert_eq!(0., link.used_storage());
     }
 
+    #[deterministic_id_test]
+    fn storage_cap_released_with_holes() {
+        // holes travel with 10 m/s and need 10 seconds to traverse the link
+        let config = config::QSim {
+            traffic_dynamics: TrafficDynamics::KinematicWaves,
+            hole_speed: 36.,
+            ..test_utils::config()
+        };
+        let mut link = SimLink::Local(LocalLink::build(
+            Id::create("0"),
+            3600.,
+            10.,
+            3.,
+            100.,
+            7.5,
+            &config,
+            Id::create("0"),
+            Id::create("0"),
+        ));
+        let agent = create_agent_without_route(1);
+        let vehicle = SimulationVehicle::from_parts(1, 0, 10., 1.5, agent);
+        link.push_veh(vehicle, QStart, 0);
+
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(10, &mut Default::default());
+        let _vehicle = link.pop_veh().unwrap();
+
+        // the vehicle has left, but its hole has not yet reached the upstream end
+        assert_eq!(1.5, link.used_storage());
+        assert!(link.is_active());
+
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(19, &mut Default::default());
+        assert_eq!(1.5, link.used_storage());
+
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(20, &mut Default::default());
+        assert_eq!(0., link.used_storage());
+        assert!(!link.is_active());
+    }
+
+    #[deterministic_id_test]
+    fn storage_cap_released_when_vehicle_leaves_stop() {
+        let mut link = SimLink::Local(LocalLink::build(
+            Id::create("0"),
+            3600.,
+            10.,
+            3.,
+            10.,
+            7.5,
+            &test_utils::config(),
+            Id::create("0"),
+            Id::create("0"),
+        ));
+        link.push_veh(create_bus(1, 1.5, vec!["0", "1"]), QStart, 0);
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+
+        // the bus reaches its stop, but still occupies the link
+        l.do_sim_step(1, &mut Default::default());
+        let mut stopping = l.take_stopping_vehicles();
+        assert_eq!(1, stopping.len());
+        assert_eq!(1.5, l.storage_cap.used());
+
+        let mut bus = stopping.pop().unwrap();
+        bus.notify_event(&mut AgentEvent::DepartedFromStop(), SimTime::from_secs(5));
+        l.push_veh_leaving_stop(bus);
+        assert_eq!(1.5, l.storage_cap.used());
+
+        // the bus moves into the buffer and releases its storage capacity
+        l.do_sim_step(5, &mut Default::default());
+        assert_eq!(0., link.used_storage());
+        assert_eq!("1", link.pop_veh().unwrap().id().external());
+    }
+
     #[deterministic_id_test]
     fn flow_cap_accumulates() {
         let mut link = SimLink::Local(LocalLink::build(
@@ -768,6 +1342,299 @@ mod sim_link_tests {
         assert_eq!(id2.to_string(), popped_vehicle2.id().external());
     }
 
+    #[deterministic_id_test]
+    fn flow_efficient_vehicles_increase_throughput() {
+        // cars with a fef of 1 leave one per second
+        assert_eq!(
+            vec![1, 2, 3, 4],
+            exit_times(&[(1., 1.), (1., 1.), (1., 1.), (1., 1.)])
+        );
+        // automated vehicles with a fef of 2 leave two per second
+        assert_eq!(
+            vec![1, 1, 2, 2],
+            exit_times(&[(1., 2.), (1., 2.), (1., 2.), (1., 2.)])
+        );
+    }
+
+    #[deterministic_id_test]
+    fn heavy_vehicles_decrease_throughput() {
+        // trucks with a pce of 2 use the capacity of two seconds
+        assert_eq!(vec![1, 3, 5], exit_times(&[(2., 1.), (2., 1.), (2., 1.)]));
+        // a truck with a fef of 2 uses as much flow capacity as a car
+        assert_eq!(vec![1, 2, 3], exit_times(&[(2., 2.), (2., 2.), (2., 2.)]));
+    }
+
+    #[deterministic_id_test]
+    fn throughput_of_mixed_fleet() {
+        // A car, two automated vehicles, a truck and another car. The automated vehicles share one
+        // second, the truck delays the following car by one second.
+        assert_eq!(
+            vec![1, 2, 2, 3, 5],
+            exit_times(&[(1., 1.), (1., 2.), (1., 2.), (2., 1.), (1., 1.)])
+        );
+    }
+
+    #[deterministic_id_test]
+    fn storage_cap_consumed_by_pce_regardless_of_fef() {
+        let mut link = short_link();
+        link.push_veh(vehicle_with_fef(1, 1.5, 3.), QStart, 0);
+
+        assert_eq!(1.5, link.used_storage());
+    }
+
+    /// Pushes vehicles with the given pce and fef onto a link with a flow capacity of one vehicle
+    /// per second and returns the second at which each of them leaves the link.
+    fn exit_times(pce_and_fef: &[(f64, f64)]) -> Vec<u32> {
+        let mut link = short_link();
+        for (id, (pce, fef)) in pce_and_fef.iter().enumerate() {
+            link.push_veh(vehicle_with_fef(id as u64, *pce, *fef), QStart, 0);
+        }
+
+        let mut exit_times = Vec::new();
+        for now in 1..100 {
+            let SimLink::Local(l) = &mut link else {
+                unreachable!()
+            };
+            l.do_sim_step(now, &mut Default::default());
+            while link.offers_veh(now).is_some() {
+                let vehicle = link.pop_veh().unwrap();
+                assert_eq!(
+                    exit_times.len().to_string(),
+                    vehicle.id().external(),
+                    "Vehicles should leave in the order in which they entered the link."
+                );
+                exit_times.push(now);
+            }
+        }
+        assert_eq!(pce_and_fef.len(), exit_times.len());
+        exit_times
+    }
+
+    /// A link of 10 m with a freespeed of 10 m/s and a flow capacity of one vehicle per second.
+    fn short_link() -> SimLink {
+        SimLink::Local(LocalLink::build(
+            Id::create("0"),
+            3600.,
+            10.,
+            3.,
+            10.,
+            7.5,
+            &test_utils::config(),
+            Id::create("0"),
+            Id::create("0"),
+        ))
+    }
+
+    fn vehicle_with_fef(id: u64, pce: f64, fef: f64) -> SimulationVehicle {
+        let mut vehicle = InternalVehicle::new(id, 0, 10., pce);
+        vehicle.fef = fef;
+        let agent = create_agent_without_route(id);
+        SimulationVehicle::new(vehicle, Some(agent), Vec::new())
+    }
+
+    #[deterministic_id_test]
+    fn slow_vehicle_blocks_fast_vehicle_in_fifo() {
+        let mut link = link_with_dynamics(LinkDynamics::Fifo);
+        push_slow_and_fast_vehicle(&mut link);
+
+        // the fast vehicle could leave after 11 s, but waits behind the slow one
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(11, &mut Default::default());
+        assert!(link.offers_veh(11).is_none());
+
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(50, &mut Default::default());
+        assert_eq!("1", link.pop_veh().unwrap().id().external());
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(51, &mut Default::default());
+        assert_eq!("2", link.pop_veh().unwrap().id().external());
+    }
+
+    #[deterministic_id_test]
+    fn fast_vehicle_passes_slow_vehicle_in_passing_queue() {
+        let mut link = link_with_dynamics(LinkDynamics::PassingQueue);
+        push_slow_and_fast_vehicle(&mut link);
+
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(11, &mut Default::default());
+        assert_eq!("2", link.pop_veh().unwrap().id().external());
+
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(49, &mut Default::default());
+        assert!(link.offers_veh(49).is_none());
+
+        let SimLink::Local(l) = &mut link else {
+            unreachable!()
+        };
+        l.do_sim_step(50, &mut Default::default());
+        assert_eq!("1", link.pop_veh().unwrap().id().external());
+    }
+
+    #[deterministic_id_test]
+    fn passing_queue_keeps_entry_order_for_equal_exit_times() {
+        let mut link = link_with_dynamics(LinkDynamics::PassingQueue);
+        for id in 1..=3 {
+            let agent = create_agent_without_route(id);
+            let vehicle = SimulationVehicle::from_parts(id, 0, 10., 1., agent);
+            link.push_veh(vehicle, QStart, 0);
+        }
+
+        for (now, id) in [(10, "1"), (11, "2"), (12, "3")] {
+            let SimLink::Local(l) = &mut link else {
+                unreachable!()
+            };
+            l.do_sim_step(now, &mut Default::default());
+            assert_eq!(id, link.pop_veh().unwrap().id().external());
+        }
+    }
+
+    /// A link of 100 m with a freespeed of 10 m/s and a flow capacity of one vehicle per second.
+    fn link_with_dynamics(link_dynamics: LinkDynamics) -> SimLink {
+        let config = config::QSim {
+            link_dynamics,
+            ..test_utils::config()
+        };
+        SimLink::Local(LocalLink::build(
+            Id::create("0"),
+            3600.,
+            10.,
+            3.,
+            100.,
+            7.5,
+            &config,
+            Id::create("0"),
+            Id::create("0"),
+        ))
+    }
+
+    /// Pushes a bike with 2 m/s at 0 s and a car with 10 m/s at 1 s. The bike could leave the link
+    /// at 50 s, the car at 11 s.
+    fn push_slow_and_fast_vehicle(link: &mut SimLink) {
+        let agent1 = create_agent_without_route(1);
+        let bike = SimulationVehicle::from_parts(1, 0, 2., 0.25, agent1);
+        link.push_veh(bike, QStart, 0);
+        let agent2 = create_agent_without_route(2);
+        let car = SimulationVehicle::from_parts(2, 1, 10., 1., agent2);
+        link.push_veh(car, QStart, 1);
+    }
+
+    #[deterministic_id_test]
+    fn vehicles_queue_per_lane() {
+        let mut link = link_with_lanes();
+        push_turning_vehicle(&mut link, 1, "left");
+        push_turning_vehicle(&mut link, 2, "left");
+        push_turning_vehicle(&mut link, 3, "straight");
+
+        // Vehicles reach the lanes after 92.5 m, i.e., after 9 s. They leave the link's queue one
+        // per second and pass the lanes of 7.5 m within the same second.
+        for now in 9..=12 {
+            let SimLink::Local(l) = &mut link else {
+                unreachable!()
+            };
+            l.do_sim_step(now, &mut Default::default());
+        }
+
+        assert_eq!(2, link.outlets());
+        assert_eq!("1", link.offers_veh_from(0, 12).unwrap().id().external());
+        assert_eq!("3", link.offers_veh_from(1, 12).unwrap().id().external());
+
+        // the vehicle going straight leaves, although the left lane is not emptied
+        assert_eq!("3", link.pop_veh_from(1).unwrap().id().external());
+        assert!(link.offers_veh_from(1, 12).is_none());
+        assert_eq!("1", link.offers_veh_from(0, 12).unwrap().id().external());
+    }
+
+    #[deterministic_id_test]
+    fn full_lane_blocks_queue() {
+        let mut link = link_with_lanes();
+        push_turning_vehicle(&mut link, 1, "left");
+        push_turning_vehicle(&mut link, 2, "left");
+        push_turning_vehicle(&mut link, 3, "left");
+        push_turning_vehicle(&mut link, 4, "straight");
+
+        // The left lane holds one vehicle, and its buffer one more. The third vehicle turning
+        // left waits in the link's queue and blocks the vehicle going straight.
+        for now in 9..=20 {
+            let SimLink::Local(l) = &mut link else {
+                unreachable!()
+            };
+            l.do_sim_step(now, &mut Default::default());
+        }
+        assert!(link.offers_veh_from(1, 20).is_none());
+
+        assert_eq!("1", link.pop_veh_from(0).unwrap().id().external());
+        for now in 21..=23 {
+            let SimLink::Local(l) = &mut link else {
+                unreachable!()
+            };
+            l.do_sim_step(now, &mut Default::default());
+        }
+        assert_eq!("4", link.offers_veh_from(1, 23).unwrap().id().external());
+    }
+
+    #[deterministic_id_test]
+    fn vehicle_without_lane_leaves_through_link_buffer() {
+        let mut link = link_with_lanes();
+        push_turning_vehicle(&mut link, 1, "other");
+        assert_eq!(2, link.outlets());
+
+        // without a lane, the vehicle travels the whole link
+        for now in 0..=10 {
+            let SimLink::Local(l) = &mut link else {
+                unreachable!()
+            };
+            l.do_sim_step(now, &mut Default::default());
+        }
+        assert_eq!(3, link.outlets());
+        assert!(link.offers_veh_from(0, 10).is_none());
+        assert!(link.offers_veh_from(1, 10).is_none());
+        assert_eq!("1", link.pop_veh_from(2).unwrap().id().external());
+        assert_eq!(2, link.outlets());
+    }
+
+    /// A link of 100 m with a freespeed of 10 m/s and a flow capacity of one vehicle per second.
+    /// Its last 7.5 m are split into a lane to the link `left` and one to the link `straight`,
+    /// each of which can store one vehicle.
+    fn link_with_lanes() -> SimLink {
+        let mut link = Link::new(
+            Id::create("link"),
+            Id::create("from"),
+            Id::create("to"),
+            100.,
+            3600.,
+            10.,
+            1.,
+            Default::default(),
+            0,
+        );
+        link.lanes = ["left", "straight"]
+            .into_iter()
+            .map(|to_link| Lane {
+                length: 7.5,
+                capacity: 3600.,
+                permlanes: 1.,
+                to_links: vec![Id::create(to_link)],
+            })
+            .collect();
+        SimLink::Local(LocalLink::from_link(&link, 7.5, &test_utils::config()))
+    }
+
+    fn push_turning_vehicle(link: &mut SimLink, id: u64, next_link: &str) {
+        let agent = create_agent(id, vec!["link", next_link]);
+        let vehicle = SimulationVehicle::from_parts(id, 0, 10., 1., agent);
+        link.push_veh(vehicle, QStart, 0);
+    }
+
     #[deterministic_id_test]
     pub fn stuck_time() {
         let stuck_threshold = 10;
@@ -778,6 +1645,7 @@ mod sim_link_tests {
             sample_size: 1.0,
             stuck_threshold,
             main_modes: vec![],
+            ..config::QSim::default()
         };
         let mut link = SimLink::Local(LocalLink::build(
             Id::create("stuck-link"),
@@ -829,6 +1697,7 @@ mod sim_link_tests {
             sample_size: 1.0,
             stuck_threshold,
             main_modes: vec![],
+            ..config::QSim::default()
         };
         let mut link = SimLink::Local(LocalLink::build(
             Id::create("stuck-link"),
@@ -898,6 +1767,9 @@ mod out_link_tests {
             to_part: 1,
             q: Default::default(),
             storage_cap: StorageCap::build(100., 1., 1., 1., 1.),
+            length: 100.,
+            sample_size: 1.,
+            effective_cell_size: 1.,
         });
         let id1 = 42;
         let id2 = 43;
@@ -939,6 +1811,9 @@ mod out_link_tests {
             to_part: 1,
             q: Default::default(),
             storage_cap: cap,
+            length: 100.,
+            sample_size: 1.,
+            effective_cell_size: 1.,
         };
 
         assert_eq!(2., out_link.storage_cap.used());
@@ -6,7 +6,7 @@ use crate::simulation::events::{EventsManager, LinkEnterEventBuilder, LinkLeaveE
 use crate::simulation::id::Id;
 use crate::simulation::id::serializable_type::StableTypeId;
 use crate::simulation::network::link::LinkPosition::{QStart, Waiting};
-use crate::simulation::scenario::network::{Link, Network, Node};
+use crate::simulation::scenario::network::{Link, LinkState, Network, Node};
 use crate::simulation::time::{SimClock, Tick};
 use crate::simulation::vehicles::SimulationVehicle;
 use crate::simulation::{config, random};
@@ -15,7 +15,7 @@ use nohash_hasher::{IntMap, IntSet};
 use rand::Rng;
 use rand::rngs::SmallRng;
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::hash::Hasher;
 use std::rc::Rc;
 
@@ -83,6 +83,15 @@ pub struct SimNetworkPartition {
     veh_counter: usize,
     partition: u32,
     clock: SimClock,
+    /// Scheduled changes of the links of this partition, ordered by time.
+    link_changes: VecDeque<ScheduledLinkChange>,
+}
+
+#[derive(Debug)]
+struct ScheduledLinkChange {
+    tick: Tick,
+    link: Id<Link>,
+    state: LinkState,
 }
 
 #[derive(Debug)]
@@ -134,7 +143,24 @@ impl SimNetworkPartition {
             .map(|n| (n.id.clone(), Self::create_sim_node(n)))
             .collect();
 
-        SimNetworkPartition::build(sim_nodes, sim_links, partition, base_seed, clock)
+        let mut link_changes: Vec<_> = sim_links
+            .keys()
+            .map(|id| global_network.get_link(id))
+            .flat_map(|link| {
+                link.states().map(|(time, state)| ScheduledLinkChange {
+                    tick: clock.time_to_tick(time),
+                    link: link.id.clone(),
+                    state,
+                })
+            })
+            .collect();
+        // the sort is stable, so the changes of one link keep their order
+        link_changes.sort_by_key(|change| change.tick);
+
+        let mut sim_net =
+            SimNetworkPartition::build(sim_nodes, sim_links, partition, base_seed, clock);
+        sim_net.link_changes = link_changes.into();
+        sim_net
     }
 
     pub(crate) fn drain(&mut self) -> Vec<SimulationAgent> {
@@ -205,6 +231,7 @@ impl SimNetworkPartition {
             veh_counter: 0,
             partition,
             clock,
+            link_changes: VecDeque::new(),
         }
     }
 
@@ -311,6 +338,51 @@ impl SimNetworkPartition {
         self.active_links.activate(link.id().clone());
     }
 
+    /// Puts a vehicle which has finished serving a transit stop back onto its current link. The
+    /// vehicle is moved into the link's buffer with priority, once the flow capacity allows it.
+    pub fn continue_after_stop(&mut self, vehicle: SimulationVehicle) {
+        let link_id = vehicle
+            .curr_link_id()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Vehicle {} at a stop must have a current link",
+                    vehicle.id()
+                )
+            })
+            .clone();
+        self.links
+            .get_mut(&link_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "#{} Couldn't find link {} for vehicle {} leaving a stop.",
+                    self.partition,
+                    link_id,
+                    vehicle.id()
+                )
+            })
+            .push_veh_leaving_stop(vehicle);
+
+        self.veh_counter += 1;
+        self.active_links.activate(link_id);
+    }
+
+    /// Applies the scheduled link changes which take effect until `now`, e.g., from network change
+    /// events.
+    pub fn apply_link_changes(&mut self, now: impl Into<Tick>) {
+        let now = now.into();
+        while self
+            .link_changes
+            .front()
+            .is_some_and(|change| change.tick <= now)
+        {
+            let change = self.link_changes.pop_front().unwrap();
+            self.links
+                .get_mut(&change.link)
+                .unwrap()
+                .apply_state(&change.state);
+        }
+    }
+
     pub fn apply_storage_cap_updates(&mut self, storage_caps: Vec<StorageUpdate>) {
         for cap in storage_caps {
             if let SimLink::Out(link) = self.links.get_mut(&cap.link_id).unwrap() {
@@ -332,6 +404,7 @@ impl SimNetworkPartition {
         let mut deactivate: IntSet<_> = IntSet::default();
 
         let mut vehicles_end_leg = vec![];
+        let mut vehicles_at_stop = vec![];
         for id in &self.active_links {
             let link = self.links.get_mut(id).unwrap();
             let mut res = match link {
@@ -353,6 +426,7 @@ impl SimNetworkPartition {
             }
 
             vehicles_end_leg.append(&mut res.vehicles_end_leg);
+            vehicles_at_stop.append(&mut res.vehicles_at_stop);
         }
 
         // bookkeeping. Empty links are no longer active.
@@ -362,10 +436,12 @@ impl SimNetworkPartition {
         // vehicles leaving this partition are no longer part of the veh count
         self.veh_counter -= vehicles_exit_partition.len();
         self.veh_counter -= vehicles_end_leg.len();
+        self.veh_counter -= vehicles_at_stop.len();
 
         MoveAllLinksResult {
             vehicles_exit_partition,
             vehicles_end_leg,
+            vehicles_at_stop,
             storage_cap_updates,
         }
     }
@@ -377,6 +453,7 @@ impl SimNetworkPartition {
         comp_env: &mut ThreadLocalComputationalEnvironment,
     ) -> MoveSingleLinkResult {
         let vehicles_end_leg = link.do_sim_step(now, comp_env);
+        let vehicles_at_stop = link.take_stopping_vehicles();
         if link.to_nodes_active(now) {
             active_nodes.activate(link.to.clone());
         }
@@ -386,6 +463,7 @@ impl SimNetworkPartition {
 
         MoveSingleLinkResult {
             vehicles_end_leg,
+            vehicles_at_stop,
             is_active,
         }
     }
@@ -398,18 +476,13 @@ impl SimNetworkPartition {
         events: &mut ThreadLocalComputationalEnvironment,
     ) -> MoveSingleLinkResult {
         // if anything has changed on the link, we want to report the updated storage capacity to the
-        // upstream partition.
+        // upstream partition. Storage consumed by transit vehicles which started to serve a stop
+        // on this link is reported as negative release.
         let before = link.occupied_storage();
         let result = Self::move_local_link(&mut link.local_link, active_nodes, now, events);
-        let diff = before - link.occupied_storage();
-
-        assert!(
-            diff.partial_cmp(&0.0).unwrap().is_ge(),
-            "The occupied storage on link {:?} cannot increase when moving vehicles.",
-            link.local_link.id
-        );
+        let diff = before - link.occupied_storage() - link.take_unreported_consumption();
 
-        if diff > 0. {
+        if diff != 0. {
             storage_cap_updates.push(StorageUpdate {
                 link_id: link.local_link.id.clone(),
                 from_part: link.from_part,
@@ -486,16 +559,16 @@ impl SimNetworkPartition {
 
                 // take the not exhausted link and check whether it could release a vehicle and if
                 // that vehicle can move to the next link
-                let link_id = active.get(i).unwrap();
-                if Self::should_veh_move_out(link_id, &self.links, now) {
+                let (link_id, outlet) = active.get(i).unwrap();
+                if Self::should_veh_move_out(link_id, *outlet, &self.links, now) {
                     // the vehicle can move. Increase the selected capacity by the link's capacity
                     // this way it becomes more and more likely that a link can release vehicles,
                     // links with more capacity are more likely to release vehicles first though.
                     let in_link = self.links.get_mut(link_id).unwrap();
-                    sel_cap += in_link.flow_cap();
+                    sel_cap += in_link.outlet_flow_cap(*outlet);
 
                     if sel_cap >= rnd_num {
-                        let veh = in_link.pop_veh().expect("No vehicle on link");
+                        let veh = in_link.pop_veh_from(*outlet).expect("No vehicle on link");
                         Self::move_vehicle(
                             veh,
                             &mut self.links,
@@ -511,7 +584,7 @@ impl SimNetworkPartition {
                     // other links to be able to release vehicles.
                     exhausted_links[i] = Some(());
                     let link = self.links.get(link_id).unwrap();
-                    avail_capacity -= link.flow_cap();
+                    avail_capacity -= link.outlet_flow_cap(*outlet);
                 }
             }
         }
@@ -519,19 +592,23 @@ impl SimNetworkPartition {
         Self::any_link_offers(&active, &self.links, now.next())
     }
 
+    /// Returns the outlets of the active in links, i.e., the lanes of links with lanes and the
+    /// links themselves otherwise, together with their accumulated flow capacity.
     fn get_active_in_links(
         in_links: &Vec<Id<Link>>,
         active_links: &ActiveCache<Link>,
         links: &IntMap<Id<Link>, SimLink>,
-    ) -> (Vec<Id<Link>>, f64) {
+    ) -> (Vec<(Id<Link>, usize)>, f64) {
         let mut active = Vec::new();
         let mut acc_cap = 0.;
 
         for id in in_links {
             if active_links.contains(id) {
-                active.push(id.clone());
                 let link = links.get(id).unwrap();
-                acc_cap += link.flow_cap();
+                for outlet in 0..link.outlets() {
+                    active.push((id.clone(), outlet));
+                    acc_cap += link.outlet_flow_cap(outlet);
+                }
             }
         }
 
@@ -539,19 +616,27 @@ impl SimNetworkPartition {
     }
 
     fn any_link_offers(
-        link_ids: &[Id<Link>],
+        outlets: &[(Id<Link>, usize)],
         links: &IntMap<Id<Link>, SimLink>,
         time: Tick,
     ) -> bool {
-        link_ids
-            .iter()
-            .map(|id| links.get(id).unwrap())
-            .any(|link| link.offers_veh(time).is_some())
+        outlets.iter().any(|(id, outlet)| {
+            links
+                .get(id)
+                .unwrap()
+                .offers_veh_from(*outlet, time)
+                .is_some()
+        })
     }
 
-    fn should_veh_move_out(in_id: &Id<Link>, links: &IntMap<Id<Link>, SimLink>, now: Tick) -> bool {
+    fn should_veh_move_out(
+        in_id: &Id<Link>,
+        outlet: usize,
+        links: &IntMap<Id<Link>, SimLink>,
+        now: Tick,
+    ) -> bool {
         let in_link = links.get(in_id).unwrap();
-        if let Some(veh_ref) = in_link.offers_veh(now) {
+        if let Some(veh_ref) = in_link.offers_veh_from(outlet, now) {
             return if let Some(next_id) = veh_ref.peek_next_route_element() {
                 // if the vehicle has a next link id, it should move out of the current link.
                 // if the vehicle has reached its stuck threshold, we push it to the next link regardless of the available
@@ -563,7 +648,7 @@ impl SimNetworkPartition {
                         veh_ref.driver().curr_leg()
                     )
                 });
-                in_link.is_veh_stuck(now) || out_link.is_available()
+                in_link.is_veh_stuck(outlet, now) || out_link.is_available()
             } else {
                 panic!(
                     "Vehicle {:?} is offered by link {:?} but has no next link. This should not happen. Leg ends are handled in move_links, not move_nodes.",
@@ -627,20 +712,24 @@ impl SimNetworkPartition {
 pub struct MoveAllLinksResult {
     pub vehicles_exit_partition: Vec<SimulationVehicle>,
     pub vehicles_end_leg: Vec<SimulationVehicle>,
+    /// Vehicles which have reached a transit stop and are handed over to the transit engine.
+    pub vehicles_at_stop: Vec<SimulationVehicle>,
     pub storage_cap_updates: Vec<StorageUpdate>,
 }
 
 #[derive(Default)]
 struct MoveSingleLinkResult {
     vehicles_end_leg: Vec<SimulationVehicle>,
+    vehicles_at_stop: Vec<SimulationVehicle>,
     is_active: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::SimNetworkPartition;
+    use crate::simulation::agents::{AgentEvent, EnvironmentalEventObserver};
     use crate::simulation::config;
-    use crate::simulation::config::{MetisOptions, PartitionMethod};
+    use crate::simulation::config::{MetisOptions, PartitionMethod, TrafficDynamics};
     use crate::simulation::controller::ThreadLocalComputationalEnvironment;
     use crate::simulation::id::Id;
     use crate::simulation::io::xml::events::XmlEventsWriter;
@@ -648,7 +737,10 @@ mod tests {
     use crate::simulation::network::link::SimLink;
     use crate::simulation::network::link::SimLink::Local;
     use crate::simulation::scenario::Coordinate;
-    use crate::simulation::scenario::network::{Link, Network, Node};
+    use crate::simulation::scenario::network::{
+        ChangeValue, Lane, Link, LinkChange, Network, Node,
+    };
+    use crate::simulation::time::SimTime;
     use crate::simulation::vehicles::SimulationVehicle;
     use crate::test_utils;
     use assert_approx_eq::assert_approx_eq;
@@ -1051,6 +1143,8 @@ mod tests {
             modes: Default::default(),
             partition: 0,
             attributes: Default::default(),
+            changes: Vec::new(),
+            lanes: Vec::new(),
         });
         net.add_link(Link {
             id: Id::create("link2"),
@@ -1063,6 +1157,8 @@ mod tests {
             modes: Default::default(),
             partition: 0,
             attributes: Default::default(),
+            changes: Vec::new(),
+            lanes: Vec::new(),
         });
         net.add_link(Link {
             id: Id::create("link3"),
@@ -1075,6 +1171,8 @@ mod tests {
             modes: Default::default(),
             partition: 0,
             attributes: Default::default(),
+            changes: Vec::new(),
+            lanes: Vec::new(),
         });
         net.add_link(Link {
             id: Id::create("link4"),
@@ -1087,6 +1185,8 @@ mod tests {
             modes: Default::default(),
             partition: 0,
             attributes: Default::default(),
+            changes: Vec::new(),
+            lanes: Vec::new(),
         });
         let mut sim_net = SimNetworkPartition::from_network(
             &net,
@@ -1192,6 +1292,81 @@ mod tests {
         assert_approx_eq!(100., storage_cap.released, 0.00001);
     }
 
+    /// With kinematic waves, the upstream partition is informed about released storage capacity
+    /// only when the hole reaches the upstream end of the split link.
+    #[deterministic_id_test]
+    fn storage_cap_over_boundaries_with_holes() {
+        let mut network = Network::new();
+        create_three_node_sim_network_with_partition(&mut network);
+        // holes travel with 10 m/s and need 10 seconds to traverse the 100 m of link2
+        let config = config::QSim {
+            traffic_dynamics: TrafficDynamics::KinematicWaves,
+            hole_speed: 36.,
+            ..test_utils::config()
+        };
+        let mut net2 =
+            SimNetworkPartition::from_network(&network, 1, &config, config::DEFAULT_RANDOM_SEED);
+
+        let split_link_id: Id<Link> = Id::get_from_ext("link2");
+        let agent = test_utils::create_agent(1, vec![split_link_id.external()]);
+        let vehicle = SimulationVehicle::from_parts(1, 0, 10., 100., agent);
+        net2.links
+            .get_mut(&split_link_id)
+            .unwrap()
+            .push_veh(vehicle, QStart, 0);
+        net2.active_links.activate(split_link_id.clone());
+        net2.veh_counter = 1;
+
+        // the vehicle leaves the link after 10 seconds, but the storage remains occupied by its hole
+        let res = net2.move_links(&mut Default::default(), 10);
+        assert_eq!(1, res.vehicles_end_leg.len());
+        assert!(res.storage_cap_updates.is_empty());
+        assert_eq!(1, net2.active_links());
+
+        let res = net2.move_links(&mut Default::default(), 19);
+        assert!(res.storage_cap_updates.is_empty());
+
+        let res = net2.move_links(&mut Default::default(), 20);
+        assert_eq!(1, res.storage_cap_updates.len());
+        let storage_cap = res.storage_cap_updates.first().unwrap();
+        assert_eq!(split_link_id, storage_cap.link_id);
+        assert_approx_eq!(100., storage_cap.released, 0.00001);
+        assert_eq!(0, net2.active_links());
+    }
+
+    /// A transit line starting at a stop on a split link occupies storage on the downstream
+    /// partition. The upstream partition is informed about the consumption and the later release.
+    #[deterministic_id_test]
+    fn storage_cap_over_boundaries_with_stop_on_first_link() {
+        let mut network = Network::new();
+        let mut sim_nets = create_three_node_sim_network_with_partition(&mut network);
+        let split_link_id: Id<Link> = Id::get_from_ext("link2");
+
+        // the bus departs on the partition which owns the split link
+        let bus = test_utils::create_bus(1, 1.5, vec![split_link_id.external()]);
+        sim_nets[1].send_veh_en_route(bus, None, 0);
+
+        let res = sim_nets[1].move_links(&mut Default::default(), 0);
+        assert_eq!(1, res.vehicles_at_stop.len());
+        assert_eq!(1, res.storage_cap_updates.len());
+        assert_approx_eq!(-1.5, res.storage_cap_updates[0].released, 0.00001);
+        sim_nets[0].apply_storage_cap_updates(res.storage_cap_updates);
+        let out_link = sim_nets[0].links.get(&split_link_id).unwrap();
+        assert_approx_eq!(1.5, out_link.used_storage(), 0.00001);
+
+        // the bus leaves the stop and ends its route on the split link
+        let mut bus = res.vehicles_at_stop.into_iter().next().unwrap();
+        bus.notify_event(&mut AgentEvent::DepartedFromStop(), SimTime::from_secs(1));
+        sim_nets[1].continue_after_stop(bus);
+        let res = sim_nets[1].move_links(&mut Default::default(), 1);
+        assert_eq!(1, res.vehicles_end_leg.len());
+        assert_eq!(1, res.storage_cap_updates.len());
+        assert_approx_eq!(1.5, res.storage_cap_updates[0].released, 0.00001);
+        sim_nets[0].apply_storage_cap_updates(res.storage_cap_updates);
+        let out_link = sim_nets[0].links.get(&split_link_id).unwrap();
+        assert_approx_eq!(0., out_link.used_storage(), 0.00001);
+    }
+
     #[deterministic_id_test]
     fn neighbors() {
         let mut net = Network::new();
@@ -1242,6 +1417,178 @@ mod tests {
         assert!(!neighbors.contains(&4));
     }
 
+    #[deterministic_id_test]
+    fn apply_link_changes() {
+        let mut network = Network::new();
+        create_three_node_sim_network_with_partition(&mut network);
+        let change = LinkChange {
+            time: SimTime::from_secs(10),
+            capacity: Some(ChangeValue::Factor(0.5)),
+            freespeed: Some(ChangeValue::Absolute(5.)),
+            permlanes: None,
+        };
+        for id in ["link1", "link2"] {
+            network
+                .get_link_mut(&Id::get_from_ext(id))
+                .changes
+                .push(change.clone());
+        }
+        let mut sim_net_1 = SimNetworkPartition::from_network(
+            &network,
+            0,
+            &test_utils::config(),
+            config::DEFAULT_RANDOM_SEED,
+        );
+        let mut sim_net_2 = SimNetworkPartition::from_network(
+            &network,
+            1,
+            &test_utils::config(),
+            config::DEFAULT_RANDOM_SEED,
+        );
+        let local_link: Id<Link> = Id::get_from_ext("link1");
+        let in_link: Id<Link> = Id::get_from_ext("link2");
+
+        sim_net_1.apply_link_changes(9);
+        sim_net_2.apply_link_changes(9);
+        assert_eq!(1., sim_net_1.links.get(&local_link).unwrap().flow_cap());
+        assert_eq!(1., sim_net_2.links.get(&in_link).unwrap().flow_cap());
+
+        sim_net_1.apply_link_changes(10);
+        sim_net_2.apply_link_changes(10);
+        assert_eq!(0.5, sim_net_1.links.get(&local_link).unwrap().flow_cap());
+        assert_eq!(0.5, sim_net_2.links.get(&in_link).unwrap().flow_cap());
+        assert!(sim_net_1.link_changes.is_empty());
+        assert!(sim_net_2.link_changes.is_empty());
+    }
+
+    /// A vehicle entering a link after a freespeed change travels with the new freespeed.
+    #[deterministic_id_test]
+    fn vehicle_travels_with_changed_freespeed() {
+        let mut env = ThreadLocalComputationalEnvironment::default();
+        let mut global_net = Network::from_file(
+            "./assets/3-links/3-links-network.xml",
+            1,
+            &PartitionMethod::Metis(MetisOptions::default()),
+        );
+        // halve the freespeed of link2 when the vehicle enters it
+        global_net
+            .get_link_mut(&Id::get_from_ext("link2"))
+            .changes
+            .push(LinkChange {
+                time: SimTime::from_secs(1),
+                capacity: None,
+                freespeed: Some(ChangeValue::Factor(0.5)),
+                permlanes: None,
+            });
+        let mut network = SimNetworkPartition::from_network(
+            &global_net,
+            0,
+            &test_utils::config(),
+            config::DEFAULT_RANDOM_SEED,
+        );
+        let agent = test_utils::create_agent(1, vec!["link1", "link2", "link3"]);
+        let vehicle = SimulationVehicle::from_parts(1, 0, 10., 1., agent);
+        network.send_veh_en_route(vehicle, None, 0);
+
+        let mut arrival = None;
+        for i in 0..300 {
+            network.apply_link_changes(i);
+            network.move_nodes(&mut env, i);
+            let result = network.move_links(&mut Default::default(), i);
+            if !result.vehicles_end_leg.is_empty() {
+                arrival = Some(i);
+                break;
+            }
+        }
+
+        // without the change, the vehicle arrives at 112, see vehicle_travels_local. Traversing
+        // link2 now takes 200 instead of 100 seconds.
+        assert_eq!(Some(212), arrival);
+    }
+
+    #[deterministic_id_test]
+    fn lanes_let_through_traffic_pass_blocked_turn() {
+        // without lanes, the vehicle turning left blocks the vehicle going straight
+        assert!(arrivals_behind_blocked_left_turn(false).is_empty());
+        assert_eq!(vec!["2"], arrivals_behind_blocked_left_turn(true));
+    }
+
+    /// Sends a vehicle turning left and a vehicle going straight over the link `in`. The link
+    /// `left` is occupied by a vehicle which doesn't move. Returns the vehicles which have
+    /// arrived after 100 s.
+    fn arrivals_behind_blocked_left_turn(with_lanes: bool) -> Vec<String> {
+        let mut network = Network::new();
+        let nodes = [
+            ("node0", -100., 0.),
+            ("node1", 0., 0.),
+            ("node2", 100., 0.),
+            ("node3", 100., 7.5),
+            ("node4", 200., 0.),
+        ];
+        for (id, x, y) in nodes {
+            network.add_node(Node::new(Id::create(id), Coordinate::new_2d(x, y), 0, 1));
+        }
+        for (id, from, to) in [
+            ("start", "node0", "node1"),
+            ("in", "node1", "node2"),
+            ("left", "node2", "node3"),
+            ("straight", "node2", "node4"),
+        ] {
+            let mut link = Link::new_with_default(
+                Id::create(id),
+                network.get_node(&Id::get_from_ext(from)),
+                network.get_node(&Id::get_from_ext(to)),
+            );
+            link.capacity = 3600.;
+            link.freespeed = 10.;
+            network.add_link(link);
+        }
+        if with_lanes {
+            network.get_link_mut(&Id::get_from_ext("in")).lanes = ["left", "straight"]
+                .into_iter()
+                .map(|to_link| Lane {
+                    length: 30.,
+                    capacity: 3600.,
+                    permlanes: 1.,
+                    to_links: vec![Id::get_from_ext(to_link)],
+                })
+                .collect();
+        }
+        let mut sim_net = SimNetworkPartition::from_network(
+            &network,
+            0,
+            &test_utils::config(),
+            config::DEFAULT_RANDOM_SEED,
+        );
+
+        // the link left can store one vehicle, which would need 7500 s to leave it
+        let agent = test_utils::create_agent(0, vec!["left"]);
+        let blocker = SimulationVehicle::from_parts(0, 0, 0.001, 1., agent);
+        let left = sim_net.links.get_mut(&Id::get_from_ext("left")).unwrap();
+        left.push_veh(blocker, QStart, 0);
+        assert!(!left.is_available());
+
+        for (id, next_link) in [(1, "left"), (2, "straight")] {
+            let agent = test_utils::create_agent(id, vec!["start", "in", next_link]);
+            let vehicle = SimulationVehicle::from_parts(id, 0, 10., 1., agent);
+            sim_net.send_veh_en_route(vehicle, None, 0);
+        }
+
+        let mut env = ThreadLocalComputationalEnvironment::default();
+        let mut arrivals = Vec::new();
+        for now in 0..100 {
+            sim_net.move_nodes(&mut env, now);
+            let result = sim_net.move_links(&mut env, now);
+            arrivals.extend(
+                result
+                    .vehicles_end_leg
+                    .iter()
+                    .map(|vehicle| vehicle.id().external().to_string()),
+            );
+        }
+        arrivals
+    }
+
     fn create_three_node_sim_network_with_partition(
         network: &mut Network,
     ) -> Vec<SimNetworkPartition> {
@@ -1,3 +1,6 @@
+use crate::simulation::time::Tick;
+use std::collections::VecDeque;
+
 /// StorageCap tracks changes in storage capacity for a link.
 /// First of all it stores the maximum available storage capacity for a link.
 /// Also, consumed and released capacity during a simulation time step is tracked
@@ -13,10 +16,21 @@
 /// The consumed and released capacities are also tracked, so that we can figure out which
 /// SplitInLinks must send storage capacity updates to upstream partitions. This logic
 /// can be found in SimNetwork::move_links.
+///
+/// With kinematic waves, released capacity is not available immediately. It is stored as a hole,
+/// which travels from the downstream to the upstream end of the link, and is only freed once the
+/// hole has arrived, see 'release_at' and 'update_holes'.
 #[derive(Debug, Clone)]
 pub struct StorageCap {
     max: f64,
     used: f64,
+    holes: VecDeque<Hole>,
+}
+
+#[derive(Debug, Clone)]
+struct Hole {
+    arrival: Tick,
+    size: f64,
 }
 
 impl StorageCap {
@@ -38,9 +52,20 @@ impl StorageCap {
         Self {
             max: max_storage_cap,
             used: 0.0,
+            holes: VecDeque::new(),
         }
     }
 
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Changes the maximum storage capacity. Vehicles already on the link remain there, even if
+    /// they exceed the new maximum.
+    pub fn set_max(&mut self, max: f64) {
+        self.max = max;
+    }
+
     pub fn used(&self) -> f64 {
         self.used
     }
@@ -62,6 +87,33 @@ impl StorageCap {
         self.used -= value;
     }
 
+    /// Releases storage capacity once the hole left by a vehicle at the downstream end of the link
+    /// reaches the upstream end at 'arrival'. Until then, the capacity remains used.
+    ///
+    /// Holes must be added in the order of their arrival.
+    pub fn release_at(&mut self, value: f64, arrival: Tick) {
+        self.holes.push_back(Hole {
+            arrival,
+            size: value,
+        });
+    }
+
+    /// Releases the storage capacity of all holes which have reached the upstream end of the link
+    /// until 'now'.
+    pub fn update_holes(&mut self, now: Tick) {
+        while let Some(hole) = self.holes.front()
+            && hole.arrival <= now
+        {
+            self.used -= hole.size;
+            self.holes.pop_front();
+        }
+    }
+
+    /// Whether there are holes which have not yet reached the upstream end of the link.
+    pub fn has_holes(&self) -> bool {
+        !self.holes.is_empty()
+    }
+
     /// Tests whether there is storage capacity available on the link.
     pub fn is_available(&self) -> bool {
         let available_cap = self.max - self.used;
@@ -72,6 +124,7 @@ impl StorageCap {
 #[cfg(test)]
 mod test {
     use crate::simulation::network::storage_cap::StorageCap;
+    use crate::simulation::time::Tick;
 
     #[test]
     fn init_default() {
@@ -85,4 +138,26 @@ mod test {
         // we expect a storage size of 20. because it the flow cap/s is 20 (36000 * 0.2 / 3600)
         assert_eq!(20., cap.max);
     }
+
+    #[test]
+    fn release_with_holes() {
+        let mut cap = StorageCap::build(15., 1., 1., 1., 7.5);
+        cap.consume(1.);
+        cap.consume(1.);
+        assert!(!cap.is_available());
+
+        cap.release_at(1., Tick::new(3));
+        cap.release_at(1., Tick::new(5));
+        cap.update_holes(Tick::new(2));
+        assert!(!cap.is_available());
+        assert!(cap.has_holes());
+
+        cap.update_holes(Tick::new(3));
+        assert_eq!(1., cap.used());
+        assert!(cap.is_available());
+
+        cap.update_holes(Tick::new(10));
+        assert_eq!(0., cap.used());
+        assert!(!cap.has_holes());
+    }
 }
@@ -121,6 +121,8 @@ mod tests {
     use crate::simulation::config::Config;
     use crate::simulation::id::Id;
     use crate::simulation::network::sim_network::SimNetworkPartition;
+    use crate::simulation::pt::TransitSchedule;
+    use crate::simulation::scenario::facilities::ActivityFacilities;
     use crate::simulation::scenario::network::{Link, Network};
     use crate::simulation::scenario::population::{
         InternalActivity, InternalPerson, InternalPlan, Population,
@@ -195,6 +197,9 @@ mod tests {
             scenario: ScenarioCore {
                 network,
                 garage: Arc::new(Garage::default()),
+                transit_schedule: Arc::new(TransitSchedule::default()),
+                transit_vehicles: Arc::new(Garage::default()),
+                facilities: Arc::new(ActivityFacilities::default()),
                 config,
             },
             network_partition,
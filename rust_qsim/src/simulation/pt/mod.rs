@@ -1,13 +1,16 @@
 use crate::simulation::InternalAttributes;
 use crate::simulation::id::Id;
+use crate::simulation::io::proto::proto_transit;
 use crate::simulation::io::xml::attributes::{IOAttribute, IOAttributes};
 use crate::simulation::io::xml::transit;
 use crate::simulation::io::xml::transit::{
-    IODeparture, IOMinimalTransferRelation, IORouteStop, IOStopFacility, IOTransitLine,
-    IOTransitRoute, IOTransitSchedule,
+    IODeparture, IODepartures, IOMinimalTransferRelation, IOMinimalTransferTimes, IONetworkRoute,
+    IORouteLink, IORouteProfile, IORouteStop, IOStopFacility, IOTransitLine, IOTransitRoute,
+    IOTransitSchedule, IOTransitStops,
 };
 use crate::simulation::scenario::Coordinate;
 use crate::simulation::scenario::network::Link;
+use crate::simulation::scenario::population::InternalPtRouteDescription;
 use crate::simulation::time::SimTime;
 use nohash_hasher::IntMap;
 use std::path::Path;
@@ -71,7 +74,7 @@ pub struct TransitStopFacility {
     pub attributes: InternalAttributes,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone)]
 pub struct TransitSchedule {
     lines: IntMap<Id<TransitLine>, TransitLine>,
     facilities: IntMap<Id<TransitStopFacility>, TransitStopFacility>,
@@ -80,9 +83,32 @@ pub struct TransitSchedule {
 }
 
 impl TransitSchedule {
+    pub(crate) fn new(
+        lines: IntMap<Id<TransitLine>, TransitLine>,
+        facilities: IntMap<Id<TransitStopFacility>, TransitStopFacility>,
+        minimal_transfer_times: Vec<MinimalTransferTime>,
+        attributes: InternalAttributes,
+    ) -> Self {
+        for time in &minimal_transfer_times {
+            assert!(
+                time.transfer_time >= 0.,
+                "Minimal transfer time from stop {} to stop {} must not be negative, but is {}.",
+                time.from_stop.external(),
+                time.to_stop.external(),
+                time.transfer_time
+            );
+        }
+        TransitSchedule {
+            lines,
+            facilities,
+            minimal_transfer_times,
+            attributes,
+        }
+    }
+
     pub fn from_file(file_path: &Path) -> Self {
         info!("Reading transit schedule");
-        let schedule = TransitSchedule::from(transit::load_from_xml(file_path));
+        let schedule = from_file(file_path);
         info!(
             "Finished reading transit schedule. Found {} lines, {} routes and {} facilities.",
             schedule.lines.len(),
@@ -92,6 +118,10 @@ impl TransitSchedule {
         schedule
     }
 
+    pub fn to_file(&self, file_path: &Path) {
+        to_file(self, file_path);
+    }
+
     pub fn lines(&self) -> &IntMap<Id<TransitLine>, TransitLine> {
         &self.lines
     }
@@ -116,11 +146,49 @@ impl TransitSchedule {
         self.facilities.get(id).unwrap()
     }
 
+    /// Looks up the transit route a pt route of a plan refers to. Returns `None` if the line or the
+    /// route is not part of this schedule.
+    pub fn find_route(&self, description: &InternalPtRouteDescription) -> Option<&TransitRoute> {
+        let line_id = Id::<TransitLine>::try_get_from_ext(&description.transit_line_id)?;
+        let route_id = Id::<TransitRoute>::try_get_from_ext(&description.transit_route_id)?;
+        self.lines.get(&line_id)?.routes.get(&route_id)
+    }
+
     pub fn num_routes(&self) -> usize {
         self.lines.values().map(|line| line.routes.len()).sum()
     }
 }
 
+fn from_file(path: &Path) -> TransitSchedule {
+    if path.extension().unwrap().eq("binpb") {
+        proto_transit::load_from_proto(path)
+    } else if path.extension().unwrap().eq("xml")
+        || path.extension().unwrap().eq("gz")
+        || path.extension().unwrap().eq("zst")
+    {
+        TransitSchedule::from(transit::load_from_xml(path))
+    } else {
+        panic!(
+            "Tried to load {path:?}. File format not supported. Either use `.xml`, `.xml.gz`, `.xml.zst`, or `.binpb` as extension"
+        );
+    }
+}
+
+fn to_file(schedule: &TransitSchedule, path: &Path) {
+    if path.extension().unwrap().eq("binpb") {
+        proto_transit::write_to_proto(schedule, path);
+    } else if path.extension().unwrap().eq("xml")
+        || path.extension().unwrap().eq("gz")
+        || path.extension().unwrap().eq("zst")
+    {
+        transit::write_to_xml(schedule, path);
+    } else {
+        panic!(
+            "Tried to write {path:?} . File format not supported. Either use `.xml`, `.xml.gz`, `.xml.zst`, or `.binpb` as extension"
+        );
+    }
+}
+
 impl From<IOTransitSchedule> for TransitSchedule {
     fn from(io: IOTransitSchedule) -> Self {
         let facilities = io
@@ -158,6 +226,35 @@ impl From<IOTransitSchedule> for TransitSchedule {
     }
 }
 
+impl From<&TransitSchedule> for IOTransitSchedule {
+    fn from(schedule: &TransitSchedule) -> Self {
+        let minimal_transfer_times = if schedule.minimal_transfer_times.is_empty() {
+            None
+        } else {
+            Some(IOMinimalTransferTimes {
+                relations: schedule
+                    .minimal_transfer_times
+                    .iter()
+                    .map(IOMinimalTransferRelation::from)
+                    .collect(),
+            })
+        };
+
+        IOTransitSchedule {
+            attributes: IOAttributes::from_internal_none_if_empty(&schedule.attributes),
+            transit_stops: IOTransitStops {
+                stop_facilities: schedule
+                    .facilities
+                    .values()
+                    .map(IOStopFacility::from)
+                    .collect(),
+            },
+            minimal_transfer_times,
+            transit_lines: schedule.lines.values().map(IOTransitLine::from).collect(),
+        }
+    }
+}
+
 impl From<IOTransitLine> for TransitLine {
     fn from(io: IOTransitLine) -> Self {
         Id::<String>::create(&io.id);
@@ -177,6 +274,17 @@ impl From<IOTransitLine> for TransitLine {
     }
 }
 
+impl From<&TransitLine> for IOTransitLine {
+    fn from(line: &TransitLine) -> Self {
+        IOTransitLine {
+            id: line.id.external().to_string(),
+            name: Some(line.name.clone()),
+            attributes: IOAttributes::from_internal_none_if_empty(&line.attributes),
+            transit_routes: line.routes.values().map(IOTransitRoute::from).collect(),
+        }
+    }
+}
+
 impl From<IOTransitRoute> for TransitRoute {
     fn from(io: IOTransitRoute) -> Self {
         Id::<String>::create(&io.id);
@@ -207,6 +315,32 @@ impl From<IOTransitRoute> for TransitRoute {
     }
 }
 
+impl From<&TransitRoute> for IOTransitRoute {
+    fn from(route: &TransitRoute) -> Self {
+        IOTransitRoute {
+            id: route.id.external().to_string(),
+            description: route.description.clone(),
+            transport_mode: route.transport_mode.external().to_string(),
+            route_profile: IORouteProfile {
+                stops: route.stops.iter().map(IORouteStop::from).collect(),
+            },
+            route: IONetworkRoute {
+                links: route
+                    .network_route
+                    .iter()
+                    .map(|link| IORouteLink {
+                        ref_id: link.external().to_string(),
+                    })
+                    .collect(),
+            },
+            departures: IODepartures {
+                departures: route.departures.iter().map(IODeparture::from).collect(),
+            },
+            attributes: IOAttributes::from_internal_none_if_empty(&route.attributes),
+        }
+    }
+}
+
 impl From<IORouteStop> for TransitRouteStop {
     fn from(io: IORouteStop) -> Self {
         let await_departure = io
@@ -273,6 +407,20 @@ impl From<IODeparture> for TransitDeparture {
     }
 }
 
+impl From<&TransitDeparture> for IODeparture {
+    fn from(departure: &TransitDeparture) -> Self {
+        IODeparture {
+            id: departure.id.external().to_string(),
+            departure_time: format_time(departure.departure_time),
+            vehicle_ref_id: departure
+                .vehicle_ref_id
+                .as_ref()
+                .map(|id| id.external().to_string()),
+            attributes: IOAttributes::from_internal_none_if_empty(&departure.attributes),
+        }
+    }
+}
+
 impl From<IOMinimalTransferRelation> for MinimalTransferTime {
     fn from(io: IOMinimalTransferRelation) -> Self {
         MinimalTransferTime {
@@ -283,6 +431,16 @@ impl From<IOMinimalTransferRelation> for MinimalTransferTime {
     }
 }
 
+impl From<&MinimalTransferTime> for IOMinimalTransferRelation {
+    fn from(time: &MinimalTransferTime) -> Self {
+        IOMinimalTransferRelation {
+            from_stop: time.from_stop.external().to_string(),
+            to_stop: time.to_stop.external().to_string(),
+            transfer_time: time.transfer_time,
+        }
+    }
+}
+
 impl From<IOStopFacility> for TransitStopFacility {
     fn from(io: IOStopFacility) -> Self {
         Id::<String>::create(&io.id);
@@ -298,6 +456,25 @@ impl From<IOStopFacility> for TransitStopFacility {
     }
 }
 
+impl From<&TransitStopFacility> for IOStopFacility {
+    fn from(facility: &TransitStopFacility) -> Self {
+        IOStopFacility {
+            id: facility.id.external().to_string(),
+            x: facility.coord.x,
+            y: facility.coord.y,
+            z: Some(facility.coord.z),
+            link_ref_id: facility
+                .link_ref_id
+                .as_ref()
+                .map(|id| id.external().to_string()),
+            name: facility.name.clone(),
+            stop_area_id: facility.stop_area_id.clone(),
+            is_blocking: facility.is_blocking,
+            attributes: IOAttributes::from_internal_none_if_empty(&facility.attributes),
+        }
+    }
+}
+
 fn parse_duration_opt(value: &Option<String>) -> Option<Duration> {
     value.as_deref().and_then(parse_duration)
 }
@@ -315,7 +492,6 @@ fn parse_time(value: &str) -> Option<SimTime> {
     SimTime::parse(value).ok()
 }
 
-#[allow(dead_code)]
 fn format_time(value: SimTime) -> String {
     value.format_hh_mm_ss_trimmed()
 }
@@ -356,11 +532,13 @@ mod tests {
     use super::{format_duration, format_time, parse_duration, parse_time};
     use crate::simulation::id::Id;
     use crate::simulation::pt::{
-        TransitDeparture, TransitLine, TransitRoute, TransitSchedule, TransitStopFacility,
+        MinimalTransferTime, TransitDeparture, TransitLine, TransitRoute, TransitSchedule,
+        TransitStopFacility,
     };
     use crate::simulation::scenario::network::Link;
     use crate::simulation::time::SimTime;
     use macros::deterministic_id_test;
+    use std::path::PathBuf;
     use std::time::Duration;
 
     #[deterministic_id_test]
@@ -467,6 +645,20 @@ mod tests {
         );
     }
 
+    #[deterministic_id_test]
+    fn tutorial_schedule_xml_round_trip() {
+        let schedule =
+            TransitSchedule::from_file("./assets/pt_tutorial/transitschedule.xml".as_ref());
+        let file = PathBuf::from(
+            "./test_output/simulation/pt/tutorial_schedule_xml_round_trip/transitSchedule.xml.gz",
+        );
+
+        schedule.to_file(&file);
+        let round_trip = TransitSchedule::from_file(&file);
+
+        assert_eq!(schedule, round_trip);
+    }
+
     #[test]
     fn transit_time_parsing_and_formatting_preserves_subseconds() {
         let parsed = parse_time("20:10:00.123456789").unwrap();
@@ -480,4 +672,19 @@ mod tests {
         assert_eq!(Duration::from_nanos(300_123_456_789), parsed);
         assert_eq!("00:05:00.123456789", format_duration(parsed));
     }
+
+    #[test]
+    #[should_panic(expected = "must not be negative")]
+    fn negative_minimal_transfer_time_panics() {
+        TransitSchedule::new(
+            Default::default(),
+            Default::default(),
+            vec![MinimalTransferTime {
+                from_stop: Id::create("s1"),
+                to_stop: Id::create("s2"),
+                transfer_time: -1.,
+            }],
+            Default::default(),
+        );
+    }
 }
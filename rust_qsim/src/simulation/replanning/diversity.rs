@@ -0,0 +1,251 @@
+use crate::simulation::config::DiversityGeneratingPlansRemoverParams;
+use crate::simulation::id::Id;
+use crate::simulation::replanning::{PlanSelector, ReplanningContext, plan_is_worse};
+use crate::simulation::scenario::network::Link;
+use crate::simulation::scenario::population::{InternalActivity, InternalPerson, InternalPlan};
+use crate::simulation::scenario::prepare_for_sim::resolve_main_mode;
+use crate::simulation::scenario::trip_structure_utils::{get_trip_spans_default, same_location};
+use nohash_hasher::IntSet;
+
+/// Selects the plan to remove, like MATSim's `DiversityGeneratingPlansRemover`. Each plan is
+/// penalised for its similarity to the other plans of the person, and the plan with the lowest
+/// penalised score is removed. Thereby, one of several near-identical plans is dropped before a
+/// slightly worse, but different plan. Like with `WorstScore`, the selected plan is only removed if
+/// it is the only plan, and unscored plans are removed first.
+pub(super) struct DiversityGeneratingPlansRemover {
+    pub(super) params: DiversityGeneratingPlansRemoverParams,
+}
+
+impl PlanSelector for DiversityGeneratingPlansRemover {
+    fn select(&self, person: &InternalPerson, _context: &ReplanningContext) -> usize {
+        let plans: Vec<_> = person.plans().iter().map(PlanSummary::new).collect();
+        let prefer_unselected = person.plans().iter().any(|plan| !plan.selected);
+
+        let penalised_score = |index: usize| {
+            let penalty: f64 = plans
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, other)| self.similarity(&plans[index], other))
+                .sum();
+            person.plans()[index].score.map(|score| score - penalty)
+        };
+
+        let mut worst: Option<(usize, Option<f64>)> = None;
+        for (index, plan) in person.plans().iter().enumerate() {
+            if prefer_unselected && plan.selected {
+                continue;
+            }
+            let score = penalised_score(index);
+            if worst.is_none_or(|(_, worst_score)| plan_is_worse(score, worst_score)) {
+                worst = Some((index, score));
+            }
+        }
+
+        worst
+            .map(|(index, _)| index)
+            .expect("DiversityGeneratingPlansRemover could not find a removable plan.")
+    }
+}
+
+impl DiversityGeneratingPlansRemover {
+    /// Sums up the penalties of all aspects, weighted by how much the plans share of each.
+    fn similarity(&self, a: &PlanSummary, b: &PlanSummary) -> f64 {
+        let act_types = share_of_equal(&a.activities, &b.activities, |a, b| {
+            a.act_type == b.act_type
+        });
+        let locations = share_of_equal(&a.activities, &b.activities, |a, b| same_location(a, b));
+        let modes = share_of_equal(&a.trip_modes, &b.trip_modes, |a, b| a == b);
+        let route_overlap = a.route_links.intersection(&b.route_links).count() as f64
+            / a.route_links.len().max(b.route_links.len()).max(1) as f64;
+
+        self.params.act_type_penalty * act_types
+            + self.params.location_penalty * locations
+            + self.params.mode_penalty * modes
+            + self.params.route_penalty * route_overlap
+    }
+}
+
+/// The parts of a plan which are compared. Stage activities are left out, as they follow from the
+/// modes and routes.
+struct PlanSummary<'a> {
+    activities: Vec<&'a InternalActivity>,
+    trip_modes: Vec<Option<Id<String>>>,
+    route_links: IntSet<Id<Link>>,
+}
+
+impl<'a> PlanSummary<'a> {
+    fn new(plan: &'a InternalPlan) -> Self {
+        let activities = plan
+            .acts()
+            .into_iter()
+            .filter(|act| !act.is_interaction())
+            .collect();
+        let trip_modes = get_trip_spans_default(&plan.elements)
+            .iter()
+            .map(|span| resolve_main_mode(&span.legs(&plan.elements).collect::<Vec<_>>()).ok())
+            .collect();
+        let route_links = plan
+            .legs()
+            .iter()
+            .filter_map(|leg| leg.route.as_ref()?.as_network())
+            .flat_map(|route| route.route().iter().cloned())
+            .collect();
+
+        Self {
+            activities,
+            trip_modes,
+            route_links,
+        }
+    }
+}
+
+/// Share of the positions at which both sequences have equal elements, relative to the longer one.
+fn share_of_equal<T>(a: &[T], b: &[T], equal: impl Fn(&T, &T) -> bool) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    let equal_count = a.iter().zip(b).filter(|(a, b)| equal(a, b)).count();
+    equal_count as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiversityGeneratingPlansRemover, PlanSummary};
+    use crate::simulation::InternalAttributes;
+    use crate::simulation::config::DiversityGeneratingPlansRemoverParams;
+    use crate::simulation::id::Id;
+    use crate::simulation::replanning::PlanSelector;
+    use crate::simulation::replanning::tests::context;
+    use crate::simulation::scenario::population::{
+        InternalActivity, InternalGenericRoute, InternalLeg, InternalNetworkRoute, InternalPerson,
+        InternalPlan, InternalPlanElement, InternalRoute,
+    };
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+
+    #[deterministic_id_test]
+    fn removes_one_of_two_identical_plans_before_a_worse_different_one() {
+        let remover = remover();
+        // plan 0 and plan 1 are the same, plan 2 goes shopping instead of working
+        let mut person = person(vec![
+            plan(&[("home", "1"), ("work", "2")], "car", &["1", "2"], 10.0),
+            plan(&[("home", "1"), ("work", "2")], "car", &["1", "2"], 11.0),
+            plan(&[("home", "1"), ("shop", "3")], "walk", &[], 8.0),
+        ]);
+        person.plans_mut()[1].selected = true;
+
+        assert_eq!(0, remover.select(&person, &context()));
+    }
+
+    #[deterministic_id_test]
+    fn keeps_selected_plan() {
+        let remover = remover();
+        let mut person = person(vec![
+            plan(&[("home", "1"), ("work", "2")], "car", &["1", "2"], 10.0),
+            plan(&[("home", "1"), ("work", "2")], "car", &["1", "2"], 9.0),
+        ]);
+        person.plans_mut()[1].selected = true;
+
+        assert_eq!(0, remover.select(&person, &context()));
+    }
+
+    #[deterministic_id_test]
+    fn removes_unscored_plans_first() {
+        let remover = remover();
+        let mut person = person(vec![
+            plan(&[("home", "1"), ("work", "2")], "car", &["1"], 10.0),
+            plan(&[("home", "1"), ("shop", "3")], "walk", &[], 0.0),
+            plan(&[("home", "1"), ("work", "2")], "car", &["1"], 10.0),
+        ]);
+        person.plans_mut()[1].score = None;
+        person.plans_mut()[2].selected = true;
+
+        assert_eq!(1, remover.select(&person, &context()));
+    }
+
+    #[deterministic_id_test]
+    fn similarity_considers_route_overlap() {
+        let remover = remover();
+        let a = plan(
+            &[("home", "1"), ("work", "2")],
+            "car",
+            &["1", "4", "2"],
+            0.0,
+        );
+        let same_route = plan(
+            &[("home", "1"), ("work", "2")],
+            "car",
+            &["1", "4", "2"],
+            0.0,
+        );
+        let other_route = plan(
+            &[("home", "1"), ("work", "2")],
+            "car",
+            &["1", "5", "2"],
+            0.0,
+        );
+
+        let same = remover.similarity(&PlanSummary::new(&a), &PlanSummary::new(&same_route));
+        let other = remover.similarity(&PlanSummary::new(&a), &PlanSummary::new(&other_route));
+
+        assert_eq!(20.0, same);
+        assert!((other - (15.0 + 5.0 * 2.0 / 3.0)).abs() < 1e-9);
+    }
+
+    fn remover() -> DiversityGeneratingPlansRemover {
+        DiversityGeneratingPlansRemover {
+            params: DiversityGeneratingPlansRemoverParams::default(),
+        }
+    }
+
+    fn person(plans: Vec<InternalPlan>) -> InternalPerson {
+        let mut plans = plans.into_iter();
+        let mut person = InternalPerson::new(Id::create("person"), plans.next().unwrap());
+        person.plans_mut().extend(plans);
+        person
+    }
+
+    /// Creates an unselected plan visiting the given activities at the given links. All legs use
+    /// `mode` and, if `route` is not empty, a network route along its links.
+    fn plan(activities: &[(&str, &str)], mode: &str, route: &[&str], score: f64) -> InternalPlan {
+        let mut plan = InternalPlan {
+            score: Some(score),
+            selected: false,
+            elements: Vec::new(),
+        };
+        for (index, (act_type, link)) in activities.iter().enumerate() {
+            if index > 0 {
+                let (_, from) = activities[index - 1];
+                let generic =
+                    InternalGenericRoute::new(Id::create(from), Id::create(link), None, None, None);
+                let route = if route.is_empty() {
+                    InternalRoute::Generic(generic)
+                } else {
+                    InternalRoute::Network(InternalNetworkRoute::new(
+                        generic,
+                        route.iter().map(|link| Id::create(link)).collect(),
+                    ))
+                };
+                plan.elements.push(InternalPlanElement::Leg(InternalLeg {
+                    mode: Id::create(mode),
+                    routing_mode: Some(Id::create(mode)),
+                    dep_time: None,
+                    trav_time: None,
+                    route: Some(route),
+                    attributes: InternalAttributes::default(),
+                }));
+            }
+            plan.add_act(InternalActivity::new(
+                None,
+                act_type,
+                Id::create(link),
+                None,
+                Some(SimTime::from_secs(3600 * (index as u64 + 1))),
+                None,
+            ));
+        }
+        plan
+    }
+}
@@ -1,23 +1,52 @@
 use crate::simulation::config;
 use crate::simulation::id::Id;
 use crate::simulation::random::get_rng;
-use crate::simulation::scenario::population::{DEFAULT_SUBPOPULATION, InternalPerson, Population};
+use crate::simulation::replanning::diversity::DiversityGeneratingPlansRemover;
+use crate::simulation::replanning::mode_choice::{ChangeTripModeModule, SubtourModeChoiceModule};
+use crate::simulation::replanning::registry::StrategyRegistry;
+use crate::simulation::replanning::routing::TripRouter;
+use crate::simulation::scenario::ScenarioCore;
+use crate::simulation::scenario::population::{
+    DEFAULT_SUBPOPULATION, InternalPerson, InternalPlanElement, Population,
+};
+use crate::simulation::scenario::prepare_for_sim::{
+    PrepareForSimContext, resolve_main_mode, route_trip,
+};
+use crate::simulation::scenario::trip_structure_utils::get_trip_spans_default;
+use crate::simulation::time::SimTime;
+use crate::simulation::time::time_interpretation::TimeInterpretation;
 use ahash::HashMap;
 use derive_builder::Builder;
 use rand::Rng;
+use rand::rngs::SmallRng;
 use rayon::prelude::*;
 use std::fmt;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
 
+mod diversity;
+mod mode_choice;
+pub(crate) mod registry;
 pub mod routing;
 
 const STRATEGY_RNG_PURPOSE: &str = "replanning.strategy";
 const RANDOM_SELECTOR_RNG_PURPOSE: &str = "replanning.selector.random";
+const CHANGE_EXP_BETA_RNG_PURPOSE: &str = "replanning.selector.change_exp_beta";
+const SELECT_EXP_BETA_RNG_PURPOSE: &str = "replanning.selector.select_exp_beta";
+const TIME_ALLOCATION_MUTATOR_RNG_PURPOSE: &str = "replanning.time_allocation_mutator";
 pub const KEEP_LAST_SELECTED_STRATEGY_NAME: &str = "KeepLastSelected";
 pub const BEST_SCORE_STRATEGY_NAME: &str = "BestScore";
 pub const SELECT_RANDOM_STRATEGY_NAME: &str = "SelectRandom";
 pub const WORST_SCORE_STRATEGY_NAME: &str = "WorstScore";
+pub const CHANGE_EXP_BETA_STRATEGY_NAME: &str = "ChangeExpBeta";
+pub const SELECT_EXP_BETA_STRATEGY_NAME: &str = "SelectExpBeta";
+pub const DIVERSITY_GENERATING_PLANS_REMOVER_NAME: &str = "DiversityGeneratingPlansRemover";
 pub const RE_ROUTE_STRATEGY_NAME: &str = "ReRoute";
+pub const TIME_ALLOCATION_MUTATOR_STRATEGY_NAME: &str = "TimeAllocationMutator";
+pub const CHANGE_TRIP_MODE_STRATEGY_NAME: &str = "ChangeTripMode";
+pub const SUBTOUR_MODE_CHOICE_STRATEGY_NAME: &str = "SubtourModeChoice";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DefaultSelector {
@@ -25,6 +54,9 @@ pub enum DefaultSelector {
     BestScore,
     SelectRandom,
     WorstScore,
+    ChangeExpBeta,
+    SelectExpBeta,
+    DiversityGeneratingPlansRemover,
 }
 
 impl DefaultSelector {
@@ -34,24 +66,36 @@ impl DefaultSelector {
             Self::BestScore => BEST_SCORE_STRATEGY_NAME,
             Self::SelectRandom => SELECT_RANDOM_STRATEGY_NAME,
             Self::WorstScore => WORST_SCORE_STRATEGY_NAME,
+            Self::ChangeExpBeta => CHANGE_EXP_BETA_STRATEGY_NAME,
+            Self::SelectExpBeta => SELECT_EXP_BETA_STRATEGY_NAME,
+            Self::DiversityGeneratingPlansRemover => DIVERSITY_GENERATING_PLANS_REMOVER_NAME,
         }
     }
 
-    fn as_plan_selector(self) -> Box<dyn PlanSelector> {
+    fn as_plan_selector(self, replanning: &config::Replanning) -> Box<dyn PlanSelector> {
         match self {
             Self::KeepLastSelected => Box::new(KeepLastSelector),
             Self::BestScore => Box::new(BestScoreSelector),
             Self::SelectRandom => Box::new(RandomSelector),
             Self::WorstScore => Box::new(WorstScoreSelector),
+            Self::ChangeExpBeta => Box::new(ExpBetaPlanChanger {
+                beta: replanning.brain_exp_beta,
+            }),
+            Self::SelectExpBeta => Box::new(ExpBetaPlanSelector {
+                beta: replanning.brain_exp_beta,
+            }),
+            Self::DiversityGeneratingPlansRemover => Box::new(DiversityGeneratingPlansRemover {
+                params: replanning.diversity_generating_plans_remover.clone(),
+            }),
         }
     }
 
-    fn as_generic_plan_strategy(self) -> Box<dyn PlanStrategy> {
+    fn as_generic_plan_strategy(self, replanning: &config::Replanning) -> Box<dyn PlanStrategy> {
         let name = Id::create(self.as_str());
 
         Box::new(GenericPlanStrategy {
             name,
-            selector: self.as_plan_selector(),
+            selector: self.as_plan_selector(replanning),
             modules: Vec::new(),
         })
     }
@@ -72,6 +116,9 @@ impl FromStr for DefaultSelector {
             BEST_SCORE_STRATEGY_NAME => Ok(Self::BestScore),
             SELECT_RANDOM_STRATEGY_NAME => Ok(Self::SelectRandom),
             WORST_SCORE_STRATEGY_NAME => Ok(Self::WorstScore),
+            CHANGE_EXP_BETA_STRATEGY_NAME => Ok(Self::ChangeExpBeta),
+            SELECT_EXP_BETA_STRATEGY_NAME => Ok(Self::SelectExpBeta),
+            DIVERSITY_GENERATING_PLANS_REMOVER_NAME => Ok(Self::DiversityGeneratingPlansRemover),
             _ => Err(format!("Unknown DefaultSelector: {value}")),
         }
     }
@@ -80,22 +127,78 @@ impl FromStr for DefaultSelector {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DefaultStrategy {
     ReRoute,
+    TimeAllocationMutator,
+    ChangeTripMode,
+    SubtourModeChoice,
 }
 
 impl DefaultStrategy {
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::ReRoute => RE_ROUTE_STRATEGY_NAME,
+            Self::TimeAllocationMutator => TIME_ALLOCATION_MUTATOR_STRATEGY_NAME,
+            Self::ChangeTripMode => CHANGE_TRIP_MODE_STRATEGY_NAME,
+            Self::SubtourModeChoice => SUBTOUR_MODE_CHOICE_STRATEGY_NAME,
         }
     }
 
-    fn as_generic_plan_strategy(self) -> Box<dyn PlanStrategy> {
+    fn as_generic_plan_strategy(
+        self,
+        environment: &ReplanningEnvironment,
+    ) -> Box<dyn PlanStrategy> {
         match self {
             Self::ReRoute => Box::new(GenericPlanStrategy {
                 name: Id::create(self.as_str()),
                 selector: Box::new(KeepLastSelector),
-                modules: vec![Box::new(ReRouteModule {})],
+                modules: vec![Box::new(ReRouteModule {
+                    scenario: environment.scenario.clone(),
+                    trip_router: environment.trip_router.clone(),
+                })],
             }),
+            Self::TimeAllocationMutator => {
+                let params = &environment
+                    .scenario
+                    .config
+                    .replanning()
+                    .time_allocation_mutator;
+                Box::new(GenericPlanStrategy {
+                    name: Id::create(self.as_str()),
+                    selector: Box::new(KeepLastSelector),
+                    modules: vec![Box::new(TimeAllocationMutatorModule {
+                        mutation_range: Duration::from_secs(params.mutation_range as u64),
+                        affecting_duration: params.affecting_duration,
+                    })],
+                })
+            }
+            Self::ChangeTripMode => {
+                let params = &environment.scenario.config.replanning().change_trip_mode;
+                Box::new(GenericPlanStrategy {
+                    name: Id::create(self.as_str()),
+                    selector: Box::new(KeepLastSelector),
+                    modules: vec![Box::new(ChangeTripModeModule {
+                        scenario: environment.scenario.clone(),
+                        trip_router: environment.trip_router.clone(),
+                        modes: params.modes.iter().map(|mode| Id::create(mode)).collect(),
+                    })],
+                })
+            }
+            Self::SubtourModeChoice => {
+                let params = &environment.scenario.config.replanning().subtour_mode_choice;
+                Box::new(GenericPlanStrategy {
+                    name: Id::create(self.as_str()),
+                    selector: Box::new(KeepLastSelector),
+                    modules: vec![Box::new(SubtourModeChoiceModule {
+                        scenario: environment.scenario.clone(),
+                        trip_router: environment.trip_router.clone(),
+                        modes: params.modes.iter().map(|mode| Id::create(mode)).collect(),
+                        chain_based_modes: params
+                            .chain_based_modes
+                            .iter()
+                            .map(|mode| Id::create(mode))
+                            .collect(),
+                    })],
+                })
+            }
         }
     }
 }
@@ -112,11 +215,32 @@ impl FromStr for DefaultStrategy {
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
             RE_ROUTE_STRATEGY_NAME => Ok(Self::ReRoute),
+            TIME_ALLOCATION_MUTATOR_STRATEGY_NAME => Ok(Self::TimeAllocationMutator),
+            CHANGE_TRIP_MODE_STRATEGY_NAME => Ok(Self::ChangeTripMode),
+            SUBTOUR_MODE_CHOICE_STRATEGY_NAME => Ok(Self::SubtourModeChoice),
             _ => Err(format!("Unknown DefaultStrategy: {value}")),
         }
     }
 }
 
+/// Controller state which strategy modules need in order to adapt plans.
+#[derive(Debug, Clone)]
+pub struct ReplanningEnvironment {
+    pub(crate) scenario: ScenarioCore,
+    pub(crate) trip_router: Arc<TripRouter>,
+}
+
+impl ReplanningEnvironment {
+    pub fn scenario(&self) -> &ScenarioCore {
+        &self.scenario
+    }
+
+    /// The trip router uses the travel times of the last iteration.
+    pub fn trip_router(&self) -> &Arc<TripRouter> {
+        &self.trip_router
+    }
+}
+
 pub(crate) fn replan_population(
     population: Population,
     iteration: u32,
@@ -148,8 +272,11 @@ pub(crate) struct StrategyManager {
     max_memory_size: usize,
     #[builder(default = "default_plan_remover()")]
     plan_remover: Box<dyn PlanSelector>,
-    #[builder(default = "default_strategies()")]
+    #[builder(default = "default_strategies(&config::Replanning::default())")]
     strategies: HashMap<Id<String>, Box<dyn PlanStrategy>>,
+    /// Names of registered selectors, which are non-innovative like the default selectors.
+    #[builder(default)]
+    registered_selectors: Vec<Id<String>>,
 }
 
 impl StrategyManager {
@@ -157,17 +284,28 @@ impl StrategyManager {
         StrategyManagerBuilder::default()
     }
 
-    pub(crate) fn from_replanning_config(replanning: &config::Replanning) -> Self {
+    pub(crate) fn from_replanning_config(
+        replanning: &config::Replanning,
+        environment: &ReplanningEnvironment,
+        registry: &StrategyRegistry,
+    ) -> Self {
         let weights_per_subpopulation =
-            weights_per_subpopulation_from_settings(&replanning.strategy_settings);
+            weights_per_subpopulation_from_settings(&replanning.strategy_settings, registry);
+        let mut strategies = default_strategies(replanning);
+        strategies.extend(default_innovative_strategies(environment));
+        strategies.extend(registry.create_strategies(environment));
 
         StrategyManager::builder()
             .weights_per_subpopulation(weights_per_subpopulation)
             .max_memory_size(replanning.max_agent_plan_memory as usize)
             .plan_remover(plan_selector_from_config_name(
                 &replanning.plan_selector_for_removal,
+                replanning,
+                environment,
+                registry,
             ))
-            .strategies(default_strategies())
+            .strategies(strategies)
+            .registered_selectors(registry.selector_names())
             .build()
             .unwrap()
     }
@@ -196,16 +334,12 @@ impl StrategyManager {
         person: &InternalPerson,
     ) -> Option<&dyn PlanStrategy> {
         let weights = self.weights_per_subpopulation.get(person.subpopulation())?;
-        let allowed_entries = weights
-            .entries
-            .iter()
-            .filter(|entry| entry.weight > 0.0)
-            .filter(|entry| {
-                !context.innovation_disabled || is_non_innovative_strategy(&entry.strategy_name)
-            })
+        let allowed_entries = self
+            .effective_weights(weights, context.iteration, context.innovation_disabled)
+            .filter(|(_, weight)| *weight > 0.0)
             .collect::<Vec<_>>();
 
-        let total_weight: f64 = allowed_entries.iter().map(|entry| entry.weight).sum();
+        let total_weight: f64 = allowed_entries.iter().map(|(_, weight)| weight).sum();
         if total_weight <= 0.0 {
             return None;
         }
@@ -221,20 +355,59 @@ impl StrategyManager {
 
         // Weighted random selection over positive strategy weights.
         let mut draw = rng.random_range(0.0..total_weight);
-        for entry in &allowed_entries {
-            if draw < entry.weight {
-                return Some(self.strategy_by_name(&entry.strategy_name));
+        for (strategy_name, weight) in &allowed_entries {
+            if draw < *weight {
+                return Some(self.strategy_by_name(strategy_name));
             }
-            draw -= entry.weight;
+            draw -= weight;
         }
 
         allowed_entries
             .into_iter()
             .rev()
-            .map(|entry| self.strategy_by_name(&entry.strategy_name))
+            .map(|(strategy_name, _)| self.strategy_by_name(strategy_name))
             .next()
     }
 
+    /// Weights of the strategies in `iteration`, after applying the weight schedules and disable
+    /// iterations. Innovative strategies have weight zero once innovation is disabled.
+    fn effective_weights<'a>(
+        &'a self,
+        weights: &'a StrategyWeights,
+        iteration: u32,
+        innovation_disabled: bool,
+    ) -> impl Iterator<Item = (&'a Id<String>, f64)> {
+        weights.entries.iter().map(move |entry| {
+            let weight =
+                if innovation_disabled && !self.is_non_innovative_strategy(&entry.strategy_name) {
+                    0.0
+                } else {
+                    entry.weight_at(iteration)
+                };
+            (&entry.strategy_name, weight)
+        })
+    }
+
+    /// Effective weights of all strategies in `iteration` as `(subpopulation, strategy, weight)`,
+    /// ordered by subpopulation and in the order of the strategy settings.
+    pub(crate) fn effective_weights_per_subpopulation(
+        &self,
+        iteration: u32,
+        innovation_disabled: bool,
+    ) -> Vec<(Id<String>, Id<String>, f64)> {
+        let mut subpopulations: Vec<_> = self.weights_per_subpopulation.iter().collect();
+        subpopulations.sort_by(|(a, _), (b, _)| a.external().cmp(b.external()));
+        subpopulations
+            .into_iter()
+            .flat_map(|(subpopulation, weights)| {
+                self.effective_weights(weights, iteration, innovation_disabled)
+                    .map(|(strategy_name, weight)| {
+                        (subpopulation.clone(), strategy_name.clone(), weight)
+                    })
+            })
+            .collect()
+    }
+
     fn remove_plans_if_needed(&self, person: &mut InternalPerson, context: &ReplanningContext) {
         while person.plans().len() > self.max_memory_size {
             let index = self.plan_remover.select(person, context);
@@ -242,6 +415,11 @@ impl StrategyManager {
         }
     }
 
+    fn is_non_innovative_strategy(&self, strategy_name: &Id<String>) -> bool {
+        DefaultSelector::from_str(strategy_name.external()).is_ok()
+            || self.registered_selectors.contains(strategy_name)
+    }
+
     fn strategy_by_name(&self, strategy_name: &Id<String>) -> &dyn PlanStrategy {
         self.strategies
             .get(strategy_name)
@@ -276,23 +454,39 @@ fn default_plan_remover() -> Box<dyn PlanSelector> {
     Box::new(WorstScoreSelector)
 }
 
-fn default_strategies() -> HashMap<Id<String>, Box<dyn PlanStrategy>> {
+fn default_strategies(
+    replanning: &config::Replanning,
+) -> HashMap<Id<String>, Box<dyn PlanStrategy>> {
     let mut strategies = HashMap::default();
     for selector in [
         DefaultSelector::KeepLastSelected,
         DefaultSelector::BestScore,
         DefaultSelector::SelectRandom,
         DefaultSelector::WorstScore,
+        DefaultSelector::ChangeExpBeta,
+        DefaultSelector::SelectExpBeta,
     ] {
         strategies.insert(
             Id::create(selector.as_str()),
-            selector.as_generic_plan_strategy(),
+            selector.as_generic_plan_strategy(replanning),
         );
     }
-    for strategy in [DefaultStrategy::ReRoute] {
+    strategies
+}
+
+fn default_innovative_strategies(
+    environment: &ReplanningEnvironment,
+) -> HashMap<Id<String>, Box<dyn PlanStrategy>> {
+    let mut strategies = HashMap::default();
+    for strategy in [
+        DefaultStrategy::ReRoute,
+        DefaultStrategy::TimeAllocationMutator,
+        DefaultStrategy::ChangeTripMode,
+        DefaultStrategy::SubtourModeChoice,
+    ] {
         strategies.insert(
             Id::create(strategy.as_str()),
-            strategy.as_generic_plan_strategy(),
+            strategy.as_generic_plan_strategy(environment),
         );
     }
     strategies
@@ -300,37 +494,48 @@ fn default_strategies() -> HashMap<Id<String>, Box<dyn PlanStrategy>> {
 
 fn weights_per_subpopulation_from_settings(
     settings: &[config::StrategySetting],
+    registry: &StrategyRegistry,
 ) -> HashMap<Id<String>, StrategyWeights> {
     let mut weights_per_subpopulation = HashMap::default();
     for setting in settings {
-        assert_known_strategy_name(&setting.name);
+        assert_known_strategy_name(&setting.name, registry);
         weights_per_subpopulation
             .entry(Id::create(&setting.subpopulation))
             .or_insert_with(|| StrategyWeights::new(Vec::new()))
             .entries
-            .push(StrategyWeight::new(
-                Id::create(&setting.name),
-                setting.weight,
-            ));
+            .push(StrategyWeight::from_setting(setting));
     }
     weights_per_subpopulation
 }
 
-fn assert_known_strategy_name(name: &str) {
-    if DefaultSelector::from_str(name).is_ok() || DefaultStrategy::from_str(name).is_ok() {
+fn assert_known_strategy_name(name: &str, registry: &StrategyRegistry) {
+    // the remover penalises similar plans to remove them, it never selects a plan to execute
+    assert_ne!(
+        name, DIVERSITY_GENERATING_PLANS_REMOVER_NAME,
+        "{DIVERSITY_GENERATING_PLANS_REMOVER_NAME} can only be configured as \
+        plan_selector_for_removal, not as replanning strategy."
+    );
+    if is_default_strategy_name(name) || registry.contains(name) {
         return;
     }
     panic!("Unknown replanning strategy or selector configured: {name}");
 }
 
-fn plan_selector_from_config_name(name: &str) -> Box<dyn PlanSelector> {
-    DefaultSelector::from_str(name)
-        .map(DefaultSelector::as_plan_selector)
-        .unwrap_or_else(|_| panic!("Unknown plan_selector_for_removal configured: {name}"))
+fn is_default_strategy_name(name: &str) -> bool {
+    DefaultSelector::from_str(name).is_ok() || DefaultStrategy::from_str(name).is_ok()
 }
 
-fn is_non_innovative_strategy(strategy_name: &Id<String>) -> bool {
-    DefaultSelector::from_str(strategy_name.external()).is_ok()
+fn plan_selector_from_config_name(
+    name: &str,
+    replanning: &config::Replanning,
+    environment: &ReplanningEnvironment,
+    registry: &StrategyRegistry,
+) -> Box<dyn PlanSelector> {
+    DefaultSelector::from_str(name)
+        .map(|selector| selector.as_plan_selector(replanning))
+        .ok()
+        .or_else(|| registry.create_selector(name, environment))
+        .unwrap_or_else(|| panic!("Unknown plan_selector_for_removal configured: {name}"))
 }
 
 struct StrategyWeights {
@@ -346,6 +551,8 @@ impl StrategyWeights {
 struct StrategyWeight {
     strategy_name: Id<String>,
     weight: f64,
+    disable_after_iteration: Option<u32>,
+    weight_schedule: Vec<config::WeightSchedulePoint>,
 }
 
 impl StrategyWeight {
@@ -353,6 +560,53 @@ impl StrategyWeight {
         Self {
             strategy_name,
             weight,
+            disable_after_iteration: None,
+            weight_schedule: Vec::new(),
+        }
+    }
+
+    fn from_setting(setting: &config::StrategySetting) -> Self {
+        assert!(
+            setting
+                .weight_schedule
+                .is_sorted_by(|a, b| a.iteration < b.iteration),
+            "The weight schedule of strategy {} must be sorted by iteration.",
+            setting.name
+        );
+        Self {
+            strategy_name: Id::create(&setting.name),
+            weight: setting.weight,
+            disable_after_iteration: setting.disable_after_iteration,
+            weight_schedule: setting.weight_schedule.clone(),
+        }
+    }
+
+    fn weight_at(&self, iteration: u32) -> f64 {
+        if self
+            .disable_after_iteration
+            .is_some_and(|last_iteration| iteration > last_iteration)
+        {
+            return 0.0;
+        }
+
+        let next = self
+            .weight_schedule
+            .iter()
+            .position(|point| point.iteration > iteration);
+        match next {
+            // before the first point of the schedule, or no schedule at all
+            Some(0) => self.weight,
+            None => self
+                .weight_schedule
+                .last()
+                .map_or(self.weight, |point| point.weight),
+            Some(next) => {
+                let from = &self.weight_schedule[next - 1];
+                let to = &self.weight_schedule[next];
+                let progress =
+                    (iteration - from.iteration) as f64 / (to.iteration - from.iteration) as f64;
+                from.weight + progress * (to.weight - from.weight)
+            }
         }
     }
 }
@@ -364,9 +618,8 @@ struct GenericPlanStrategy {
     modules: Vec<Box<dyn PlanStrategyModule + Send + Sync>>,
 }
 
-#[allow(dead_code)]
-// This is responsible for picking a plan, copying it and replanning it.
-trait PlanStrategy: Send + Sync {
+/// This is responsible for picking a plan, copying it and replanning it.
+pub trait PlanStrategy: Send + Sync {
     fn name(&self) -> &Id<String>;
     fn handle(&self, person: &mut InternalPerson, context: &ReplanningContext);
 }
@@ -379,6 +632,10 @@ impl PlanStrategy for GenericPlanStrategy {
     fn handle(&self, person: &mut InternalPerson, context: &ReplanningContext) {
         let plan_index = self.selector.select(person, context);
         if self.modules.is_empty() {
+            // strategies without modules only change which plan is executed
+            for (index, plan) in person.plans_mut().iter_mut().enumerate() {
+                plan.selected = index == plan_index;
+            }
             return;
         }
         let mut new_plan = person
@@ -394,36 +651,169 @@ impl PlanStrategy for GenericPlanStrategy {
         let new_plan_index = person.plans().len() - 1;
 
         for module in &self.modules {
-            module.handle(person, new_plan_index);
+            module.handle(person, new_plan_index, context);
         }
     }
 }
 
-#[allow(dead_code)]
-// This is the smallest replanning unit (e.g., routes a plan).
-trait PlanStrategyModule: Send + Sync {
-    fn handle(&self, person: &mut InternalPerson, plan_index: usize);
+/// This is the smallest replanning unit (e.g., routes a plan).
+pub trait PlanStrategyModule: Send + Sync {
+    fn handle(&self, person: &mut InternalPerson, plan_index: usize, context: &ReplanningContext);
 }
 
-#[allow(dead_code)]
+// Routes every trip of a plan again, using the travel times the trip router currently holds.
 struct ReRouteModule {
-    // hold reference to scenario
-    // hold reference to router
+    scenario: ScenarioCore,
+    trip_router: Arc<TripRouter>,
 }
 
 impl PlanStrategyModule for ReRouteModule {
-    fn handle(&self, _person: &mut InternalPerson, _plan_index: usize) {
-        unimplemented!("ReRouteModule is a placeholder and does not implement routing yet.")
+    fn handle(&self, person: &mut InternalPerson, plan_index: usize, _context: &ReplanningContext) {
+        route_trips(
+            &self.scenario,
+            &self.trip_router,
+            person,
+            plan_index,
+            |_, mode| Some(mode.clone()),
+        );
+    }
+}
+
+/// Routes trips of a plan again. `mode_for_trip` is called with the index and the current main
+/// mode of each trip and returns the mode the trip is routed with, or `None` if the trip is kept.
+/// Trips are routed one after another, as the departure time of a trip depends on the routed
+/// travel times of all trips before. Trips which cannot be routed keep their old elements.
+fn route_trips<F>(
+    scenario: &ScenarioCore,
+    trip_router: &TripRouter,
+    person: &mut InternalPerson,
+    plan_index: usize,
+    mut mode_for_trip: F,
+) where
+    F: FnMut(usize, &Id<String>) -> Option<Id<String>>,
+{
+    let context = PrepareForSimContext {
+        network: &scenario.network,
+        garage: &scenario.garage,
+        transit_schedule: &scenario.transit_schedule,
+        facilities: &scenario.facilities,
+        config: &scenario.config,
+    };
+    let mut elements = std::mem::take(&mut person.plans_mut()[plan_index].elements);
+
+    // replacing the elements of a trip shifts the spans of all later trips
+    let mut offset = 0;
+    for (trip_index, span) in get_trip_spans_default(&elements).into_iter().enumerate() {
+        let span = span.shifted(offset);
+        let legs: Vec<_> = span.legs(&elements).collect();
+        let new_elements = resolve_main_mode(&legs)
+            .map(|mode| mode_for_trip(trip_index, &mode))
+            .and_then(|mode| {
+                mode.map(|mode| route_trip(&context, person, &elements, span, &mode, trip_router))
+                    .transpose()
+            });
+        match new_elements {
+            Ok(Some(new_elements)) => {
+                let new_span = span.replace_trip_elements(&mut elements, new_elements);
+                offset += new_span.destination_index() as isize - span.destination_index() as isize;
+            }
+            Ok(None) => {}
+            Err(err) => warn!(
+                "Failed to re-route trip {trip_index} of person {}, keeping its old route: {err}",
+                person.id().external()
+            ),
+        }
+    }
+
+    person.plans_mut()[plan_index].elements = elements;
+}
+
+// Shifts activity end times and, if enabled, maximum durations by random amounts within the
+// mutation range. Stage activities are left untouched. Afterward, the departure times of all legs
+// are updated along the plan, so that they match the new activity times.
+struct TimeAllocationMutatorModule {
+    mutation_range: Duration,
+    affecting_duration: bool,
+}
+
+impl TimeAllocationMutatorModule {
+    /// Adds a random shift within `[-mutation_range, mutation_range]` to `duration`, without going
+    /// below zero.
+    fn mutate(&self, duration: Duration, rng: &mut impl Rng) -> Duration {
+        let offset = Duration::from_secs(rng.random_range(0..=self.mutation_range.as_secs() * 2));
+        duration
+            .saturating_add(offset)
+            .saturating_sub(self.mutation_range)
     }
 }
 
-struct ReplanningContext {
+impl PlanStrategyModule for TimeAllocationMutatorModule {
+    fn handle(&self, person: &mut InternalPerson, plan_index: usize, context: &ReplanningContext) {
+        let mut rng = get_rng(
+            context.base_seed,
+            (
+                context.iteration,
+                person.id().external(),
+                TIME_ALLOCATION_MUTATOR_RNG_PURPOSE,
+            ),
+        );
+
+        let mut now = SimTime::default();
+        for element in &mut person.plans_mut()[plan_index].elements {
+            match element {
+                InternalPlanElement::Activity(activity) => {
+                    if !activity.is_interaction() {
+                        if let Some(end_time) = activity.end_time {
+                            // an activity must not end before it has started
+                            let end_time = self.mutate(end_time.as_duration(), &mut rng);
+                            activity.end_time = Some(SimTime::from_duration(end_time).max(now));
+                        }
+                        if self.affecting_duration
+                            && let Some(max_dur) = activity.max_dur
+                        {
+                            activity.max_dur = Some(self.mutate(max_dur, &mut rng));
+                        }
+                    }
+                    if let Some(end_time) =
+                        TimeInterpretation::decide_on_activity_end_time(activity, &now)
+                    {
+                        now = end_time;
+                    }
+                }
+                InternalPlanElement::Leg(leg) => {
+                    leg.dep_time = Some(now);
+                    if let Some(travel_time) = TimeInterpretation::decide_on_leg_travel_time(leg) {
+                        now = now.saturating_add(travel_time);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct ReplanningContext {
     iteration: u32,
     base_seed: u64,
     innovation_disabled: bool,
 }
 
-trait PlanSelector: Send + Sync {
+impl ReplanningContext {
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    /// Creates a random number generator for `person` in the current iteration. Strategies should
+    /// use a distinct `purpose`, so that their draws are independent of each other.
+    pub fn rng(&self, person: &InternalPerson, purpose: &str) -> SmallRng {
+        get_rng(
+            self.base_seed,
+            (self.iteration, person.id().external(), purpose),
+        )
+    }
+}
+
+/// Picks one of the plans of a person and returns its index.
+pub trait PlanSelector: Send + Sync {
     fn select(&self, person: &InternalPerson, context: &ReplanningContext) -> usize;
 }
 
@@ -509,6 +899,94 @@ impl PlanSelector for WorstScoreSelector {
     }
 }
 
+/// Switches from the selected plan to a randomly drawn plan with probability
+/// `0.01 * exp(beta / 2 * (other score - selected score))`, like MATSim's `ExpBetaPlanChanger`.
+/// An unscored selected plan is kept and an unscored drawn plan is switched to, so that all plans
+/// get a score.
+struct ExpBetaPlanChanger {
+    beta: f64,
+}
+
+impl PlanSelector for ExpBetaPlanChanger {
+    fn select(&self, person: &InternalPerson, context: &ReplanningContext) -> usize {
+        let plans = person.plans();
+        let current = plans
+            .iter()
+            .position(|plan| plan.selected)
+            .expect("ExpBetaPlanChanger could not find a selected plan.");
+        let mut rng = get_rng(
+            context.base_seed,
+            (
+                context.iteration,
+                person.id().external(),
+                CHANGE_EXP_BETA_RNG_PURPOSE,
+            ),
+        );
+        let other = rng.random_range(0..plans.len());
+
+        let Some(current_score) = plans[current].score else {
+            return current;
+        };
+        let Some(other_score) = plans[other].score else {
+            return other;
+        };
+
+        let weight = (0.5 * self.beta * (other_score - current_score)).exp();
+        if rng.random::<f64>() < 0.01 * weight {
+            other
+        } else {
+            current
+        }
+    }
+}
+
+/// Draws a plan with probability proportional to `exp(beta * score)`, i.e. by a multinomial logit
+/// model over the plan scores. Unscored plans are selected first, so that they get a score.
+struct ExpBetaPlanSelector {
+    beta: f64,
+}
+
+impl PlanSelector for ExpBetaPlanSelector {
+    fn select(&self, person: &InternalPerson, context: &ReplanningContext) -> usize {
+        let plans = person.plans();
+        assert!(
+            !plans.is_empty(),
+            "ExpBetaPlanSelector could not find a plan."
+        );
+        if let Some(unscored) = plans.iter().position(|plan| plan.score.is_none()) {
+            return unscored;
+        }
+
+        // shift by the best score, so that exp doesn't overflow
+        let best = plans
+            .iter()
+            .filter_map(|plan| plan.score)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<_> = plans
+            .iter()
+            .map(|plan| (self.beta * (plan.score.unwrap() - best)).exp())
+            .collect();
+        let total_weight: f64 = weights.iter().sum();
+
+        let mut rng = get_rng(
+            context.base_seed,
+            (
+                context.iteration,
+                person.id().external(),
+                SELECT_EXP_BETA_RNG_PURPOSE,
+            ),
+        );
+        let mut draw = rng.random_range(0.0..total_weight);
+        for (index, weight) in weights.iter().enumerate() {
+            if draw < *weight {
+                return index;
+            }
+            draw -= weight;
+        }
+        plans.len() - 1
+    }
+}
+
 fn plan_is_worse(candidate: Option<f64>, current: Option<f64>) -> bool {
     match (score_for_ordering(candidate), score_for_ordering(current)) {
         (None, Some(_)) => true,
@@ -524,12 +1002,32 @@ fn score_for_ordering(score: Option<f64>) -> Option<f64> {
 #[cfg(test)]
 mod tests {
     use super::{
-        DefaultSelector, GenericPlanStrategy, KeepLastSelector, PlanSelector, PlanStrategy,
-        PlanStrategyModule, RandomSelector, ReplanningContext, StrategyManager, WorstScoreSelector,
+        BestScoreSelector, DIVERSITY_GENERATING_PLANS_REMOVER_NAME, DefaultSelector,
+        DefaultStrategy, ExpBetaPlanChanger, ExpBetaPlanSelector, GenericPlanStrategy,
+        KeepLastSelector, PlanSelector, PlanStrategy, PlanStrategyModule, RandomSelector,
+        ReplanningContext, ReplanningEnvironment, StrategyManager, StrategyWeight,
+        TimeAllocationMutatorModule, WorstScoreSelector, replan_population,
     };
-    use crate::simulation::config::{Replanning, StrategySetting};
+    use crate::simulation::InternalAttributes;
+    use crate::simulation::config::{Config, Replanning, StrategySetting, WeightSchedulePoint};
     use crate::simulation::id::Id;
-    use crate::simulation::scenario::population::{InternalPerson, InternalPlan};
+    use crate::simulation::pt::TransitSchedule;
+    use crate::simulation::replanning::registry::StrategyRegistry;
+    use crate::simulation::replanning::routing::teleportation::TeleportationRoutingModule;
+    use crate::simulation::replanning::routing::{RoutingModule, TripRouter};
+    use crate::simulation::scenario::facilities::ActivityFacilities;
+    use crate::simulation::scenario::network::Network;
+    use crate::simulation::scenario::population::{
+        InternalActivity, InternalLeg, InternalPerson, InternalPlan, InternalPlanElement,
+        InternalRoute, Population,
+    };
+    use crate::simulation::scenario::vehicles::Garage;
+    use crate::simulation::scenario::{Coordinate, ScenarioCore};
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use nohash_hasher::IntMap;
+    use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn keep_last_selector_returns_selected_plan_index() {
@@ -580,8 +1078,10 @@ mod tests {
             DefaultSelector::KeepLastSelected,
             DefaultSelector::BestScore,
             DefaultSelector::SelectRandom,
+            DefaultSelector::ChangeExpBeta,
+            DefaultSelector::SelectExpBeta,
         ] {
-            let strategy = selector.as_generic_plan_strategy();
+            let strategy = selector.as_generic_plan_strategy(&Replanning::default());
 
             assert_eq!(&Id::create(selector.as_str()), strategy.name());
         }
@@ -599,6 +1099,87 @@ mod tests {
         assert!(first < person.plans().len());
     }
 
+    #[test]
+    fn change_exp_beta_keeps_or_selects_unscored_plans() {
+        let changer = ExpBetaPlanChanger { beta: 1.0 };
+
+        let person = person_with_scores([None, Some(100.0), Some(100.0)]);
+        assert_eq!(0, changer.select(&person, &context()));
+
+        // the unscored plan is switched to as soon as it is drawn
+        let person = person_with_scores([Some(1.0), None, None]);
+        let selected: Vec<_> = (0..20)
+            .map(|iteration| {
+                let context = ReplanningContext {
+                    iteration,
+                    ..context()
+                };
+                changer.select(&person, &context)
+            })
+            .collect();
+        assert!(selected.contains(&0));
+        assert!(selected.iter().any(|index| *index != 0));
+    }
+
+    #[test]
+    fn change_exp_beta_switches_depending_on_score_difference() {
+        let changer = ExpBetaPlanChanger { beta: 1.0 };
+        let better = person_with_scores([Some(0.0), Some(100.0)]);
+        let worse = person_with_scores([Some(100.0), Some(0.0)]);
+
+        let mut switched_to_better = 0;
+        for iteration in 0..50 {
+            let context = ReplanningContext {
+                iteration,
+                ..context()
+            };
+            // switching to a much worse plan is practically impossible
+            assert_eq!(0, changer.select(&worse, &context));
+            // switching to a much better plan is certain whenever it is drawn
+            if changer.select(&better, &context) == 1 {
+                switched_to_better += 1;
+            }
+        }
+        assert!(switched_to_better > 10);
+    }
+
+    #[test]
+    fn select_exp_beta_prefers_unscored_plans() {
+        let selector = ExpBetaPlanSelector { beta: 1.0 };
+        let person = person_with_scores([Some(10.0), None, Some(20.0)]);
+
+        assert_eq!(1, selector.select(&person, &context()));
+    }
+
+    #[test]
+    fn select_exp_beta_draws_plans_by_logit_probabilities() {
+        let selector = ExpBetaPlanSelector { beta: 1.0 };
+        // probabilities are 1 / (1 + e^-2) = 0.88 and 0.12
+        let person = person_with_scores([Some(0.0), Some(2.0)]);
+
+        let mut counts = [0; 2];
+        for iteration in 0..500 {
+            let context = ReplanningContext {
+                iteration,
+                ..context()
+            };
+            let selected = selector.select(&person, &context);
+            assert_eq!(selected, selector.select(&person, &context));
+            counts[selected] += 1;
+        }
+
+        assert!(counts[0] > 20, "counts: {counts:?}");
+        assert!(counts[1] > 380, "counts: {counts:?}");
+    }
+
+    #[test]
+    fn select_exp_beta_with_large_scores_does_not_overflow() {
+        let selector = ExpBetaPlanSelector { beta: 1.0 };
+        let person = person_with_scores([Some(1000.0), Some(2000.0)]);
+
+        assert_eq!(1, selector.select(&person, &context()));
+    }
+
     #[test]
     fn config_manager_uses_memory_limit_and_removal_selector() {
         let replanning = Replanning {
@@ -606,7 +1187,11 @@ mod tests {
             plan_selector_for_removal: DefaultSelector::BestScore.as_str().to_string(),
             ..Replanning::default()
         };
-        let manager = StrategyManager::from_replanning_config(&replanning);
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
         let mut person = person_with_scores([Some(1.0), Some(2.0)]);
 
         manager.run(0, 42, false, &mut person);
@@ -615,6 +1200,179 @@ mod tests {
         assert_eq!(Some(1.0), person.plans()[0].score);
     }
 
+    #[test]
+    #[should_panic(
+        expected = "DiversityGeneratingPlansRemover can only be configured as \
+        plan_selector_for_removal, not as replanning strategy."
+    )]
+    fn diversity_generating_plans_remover_as_strategy_panics() {
+        let replanning = Replanning {
+            strategy_settings: vec![StrategySetting {
+                name: DIVERSITY_GENERATING_PLANS_REMOVER_NAME.to_string(),
+                weight: 1.0,
+                subpopulation: "person".to_string(),
+                ..Default::default()
+            }],
+            ..Replanning::default()
+        };
+
+        StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
+    }
+
+    #[test]
+    fn strategy_weight_follows_schedule_until_disabled() {
+        let weight = StrategyWeight::from_setting(&StrategySetting {
+            name: "ReRoute".to_string(),
+            weight: 0.5,
+            subpopulation: "person".to_string(),
+            disable_after_iteration: Some(30),
+            weight_schedule: vec![
+                WeightSchedulePoint {
+                    iteration: 10,
+                    weight: 0.4,
+                },
+                WeightSchedulePoint {
+                    iteration: 20,
+                    weight: 0.0,
+                },
+                WeightSchedulePoint {
+                    iteration: 25,
+                    weight: 0.1,
+                },
+            ],
+        });
+
+        assert_eq!(0.5, weight.weight_at(0));
+        assert_eq!(0.4, weight.weight_at(10));
+        assert!((weight.weight_at(15) - 0.2).abs() < 1e-9);
+        assert_eq!(0.0, weight.weight_at(20));
+        assert_eq!(0.1, weight.weight_at(30));
+        assert_eq!(0.0, weight.weight_at(31));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The weight schedule of strategy ReRoute must be sorted by iteration."
+    )]
+    fn unsorted_weight_schedule_panics() {
+        StrategyWeight::from_setting(&StrategySetting {
+            name: "ReRoute".to_string(),
+            weight_schedule: vec![
+                WeightSchedulePoint {
+                    iteration: 10,
+                    weight: 0.4,
+                },
+                WeightSchedulePoint {
+                    iteration: 5,
+                    weight: 0.0,
+                },
+            ],
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn disabled_strategies_are_not_chosen() {
+        let replanning = Replanning {
+            strategy_settings: vec![
+                StrategySetting {
+                    name: "ReRoute".to_string(),
+                    weight: 1.0,
+                    subpopulation: "person".to_string(),
+                    disable_after_iteration: Some(5),
+                    ..Default::default()
+                },
+                StrategySetting {
+                    name: DefaultSelector::BestScore.as_str().to_string(),
+                    weight: 0.0,
+                    subpopulation: "person".to_string(),
+                    weight_schedule: vec![WeightSchedulePoint {
+                        iteration: 6,
+                        weight: 1.0,
+                    }],
+                    ..Default::default()
+                },
+            ],
+            ..Replanning::default()
+        };
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
+        let person = person_with_scores([Some(1.0)]);
+
+        let early = ReplanningContext {
+            iteration: 5,
+            ..context()
+        };
+        let late = ReplanningContext {
+            iteration: 6,
+            ..context()
+        };
+        let strategy = manager.choose_strategy(&early, &person).unwrap();
+        assert_eq!(&Id::create("ReRoute"), strategy.name());
+        let strategy = manager.choose_strategy(&late, &person).unwrap();
+        assert_eq!(&Id::create("BestScore"), strategy.name());
+    }
+
+    #[test]
+    fn effective_weights_include_disabled_innovation() {
+        let replanning = Replanning {
+            strategy_settings: vec![
+                StrategySetting {
+                    name: "ReRoute".to_string(),
+                    weight: 0.2,
+                    subpopulation: "person".to_string(),
+                    ..Default::default()
+                },
+                StrategySetting {
+                    name: DefaultSelector::BestScore.as_str().to_string(),
+                    weight: 0.8,
+                    subpopulation: "person".to_string(),
+                    ..Default::default()
+                },
+                StrategySetting {
+                    name: DefaultSelector::KeepLastSelected.as_str().to_string(),
+                    weight: 1.0,
+                    subpopulation: "freight".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Replanning::default()
+        };
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
+
+        let weights: Vec<_> = manager
+            .effective_weights_per_subpopulation(0, true)
+            .into_iter()
+            .map(|(subpopulation, strategy, weight)| {
+                (
+                    subpopulation.external().to_string(),
+                    strategy.external().to_string(),
+                    weight,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("freight".to_string(), "KeepLastSelected".to_string(), 1.0),
+                ("person".to_string(), "ReRoute".to_string(), 0.0),
+                ("person".to_string(), "BestScore".to_string(), 0.8),
+            ],
+            weights
+        );
+    }
+
     #[test]
     fn config_manager_groups_strategy_weights_by_subpopulation() {
         let replanning = Replanning {
@@ -623,16 +1381,22 @@ mod tests {
                     name: DefaultSelector::KeepLastSelected.as_str().to_string(),
                     weight: 0.3,
                     subpopulation: "person".to_string(),
+                    ..Default::default()
                 },
                 StrategySetting {
                     name: DefaultSelector::BestScore.as_str().to_string(),
                     weight: 0.7,
                     subpopulation: "freight".to_string(),
+                    ..Default::default()
                 },
             ],
             ..Replanning::default()
         };
-        let manager = StrategyManager::from_replanning_config(&replanning);
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
 
         let person_weights = manager
             .weights_per_subpopulation
@@ -657,16 +1421,22 @@ mod tests {
                     name: "ReRoute".to_string(),
                     weight: 1.0,
                     subpopulation: "person".to_string(),
+                    ..Default::default()
                 },
                 StrategySetting {
                     name: DefaultSelector::BestScore.as_str().to_string(),
                     weight: 1.0,
                     subpopulation: "person".to_string(),
+                    ..Default::default()
                 },
             ],
             ..Replanning::default()
         };
-        let manager = StrategyManager::from_replanning_config(&replanning);
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
         let person = person_with_scores([Some(1.0), Some(2.0)]);
         let context = ReplanningContext {
             innovation_disabled: true,
@@ -681,6 +1451,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mode_choice_strategies_are_selectable_per_subpopulation() {
+        let replanning = Replanning {
+            strategy_settings: vec![
+                StrategySetting {
+                    name: DefaultStrategy::ChangeTripMode.as_str().to_string(),
+                    weight: 1.0,
+                    subpopulation: "person".to_string(),
+                    ..Default::default()
+                },
+                StrategySetting {
+                    name: DefaultStrategy::SubtourModeChoice.as_str().to_string(),
+                    weight: 1.0,
+                    subpopulation: "freight".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Replanning::default()
+        };
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
+        let person = person_with_scores([Some(1.0)]);
+
+        let strategy = manager.choose_strategy(&context(), &person).unwrap();
+        assert_eq!(&Id::create("ChangeTripMode"), strategy.name());
+
+        let freight_weights = manager
+            .weights_per_subpopulation
+            .get(&Id::create("freight"))
+            .unwrap();
+        let strategy = manager.strategy_by_name(&freight_weights.entries[0].strategy_name);
+        assert_eq!(&Id::create("SubtourModeChoice"), strategy.name());
+    }
+
     #[test]
     fn default_strategy_is_named_generic_keep_last_selected() {
         let manager = StrategyManager::default();
@@ -712,6 +1519,51 @@ mod tests {
         assert_eq!(Some(1.0), person.plans()[0].score);
     }
 
+    #[test]
+    fn generic_strategy_without_modules_selects_chosen_plan() {
+        let strategy = GenericPlanStrategy {
+            name: Id::create("BestScore"),
+            selector: Box::new(BestScoreSelector),
+            modules: Vec::new(),
+        };
+        let mut person = person_with_scores([Some(1.0), Some(5.0), Some(3.0)]);
+
+        strategy.handle(&mut person, &context());
+
+        assert_eq!(3, person.plans().len());
+        let selected: Vec<_> = person.plans().iter().map(|plan| plan.selected).collect();
+        assert_eq!(vec![false, true, false], selected);
+    }
+
+    #[test]
+    fn change_exp_beta_strategy_switches_to_better_plan() {
+        let replanning = Replanning {
+            strategy_settings: vec![StrategySetting {
+                name: DefaultSelector::ChangeExpBeta.as_str().to_string(),
+                weight: 1.0,
+                subpopulation: "person".to_string(),
+                ..Default::default()
+            }],
+            ..Replanning::default()
+        };
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
+        let mut population =
+            Population::from_persons(vec![person_with_scores([Some(0.0), Some(100.0)])]);
+
+        for iteration in 0..20 {
+            population = replan_population(population, iteration, 42, &manager, false);
+        }
+
+        let person = population.persons.values().next().unwrap();
+        assert_eq!(2, person.plans().len());
+        assert!(!person.plans()[0].selected);
+        assert!(person.plans()[1].selected);
+    }
+
     #[test]
     fn generic_strategy_with_modules_copies_plan_and_runs_modules_on_copy() {
         let strategy = GenericPlanStrategy {
@@ -730,6 +1582,245 @@ mod tests {
         assert_eq!(Some(99.0), person.plans()[1].score);
     }
 
+    #[deterministic_id_test]
+    fn re_route_routes_trips_of_copied_plan() {
+        let manager = StrategyManager::from_replanning_config(
+            &Replanning::default(),
+            &environment(teleportation_router("walk")),
+            &StrategyRegistry::default(),
+        );
+        let strategy = manager.strategy_by_name(&Id::create(DefaultStrategy::ReRoute.as_str()));
+        let mut person = InternalPerson::new(Id::create("person"), unrouted_walk_plan());
+
+        strategy.handle(&mut person, &context());
+
+        assert_eq!(2, person.plans().len());
+        assert!(person.plans()[0].legs()[0].route.is_none());
+        let legs = person.plans()[1].legs();
+        assert_eq!(1, legs.len());
+        assert!(matches!(legs[0].route, Some(InternalRoute::Generic(_))));
+        assert_eq!(Some(SimTime::from_secs(10)), legs[0].dep_time);
+        assert_eq!(Some(Id::get_from_ext("walk")), legs[0].routing_mode);
+    }
+
+    #[deterministic_id_test]
+    fn re_route_keeps_trips_which_cannot_be_routed() {
+        let manager = StrategyManager::from_replanning_config(
+            &Replanning::default(),
+            &environment(teleportation_router("walk")),
+            &StrategyRegistry::default(),
+        );
+        let strategy = manager.strategy_by_name(&Id::create(DefaultStrategy::ReRoute.as_str()));
+        // the first trip has two stages, which are replaced by a single routed leg. There is no
+        // router for the bike trip afterward.
+        let mut plan = InternalPlan::default();
+        plan.add_act(activity("home", Some(8 * 3600), None));
+        plan.add_leg(walk_leg());
+        plan.add_act(activity("walk interaction", None, Some(0)));
+        plan.add_leg(walk_leg());
+        plan.add_act(activity("work", None, Some(8 * 3600)));
+        plan.add_leg(InternalLeg {
+            mode: Id::create("bike"),
+            routing_mode: Some(Id::create("bike")),
+            ..walk_leg()
+        });
+        plan.add_act(activity("home", None, None));
+        let mut person = InternalPerson::new(Id::create("person"), plan);
+
+        strategy.handle(&mut person, &context());
+
+        let plan = &person.plans()[1];
+        assert_eq!(5, plan.elements.len());
+        let legs = plan.legs();
+        assert!(matches!(legs[0].route, Some(InternalRoute::Generic(_))));
+        assert_eq!(Id::get_from_ext("bike"), legs[1].mode);
+        assert!(legs[1].route.is_none());
+    }
+
+    #[deterministic_id_test]
+    fn time_allocation_mutator_shifts_times_of_copied_plan() {
+        let manager = StrategyManager::from_replanning_config(
+            &Replanning::default(),
+            &environment(TripRouter::new(IntMap::default())),
+            &StrategyRegistry::default(),
+        );
+        let strategy =
+            manager.strategy_by_name(&Id::create(DefaultStrategy::TimeAllocationMutator.as_str()));
+        let mut person = InternalPerson::new(Id::create("person"), timed_plan());
+
+        strategy.handle(&mut person, &context());
+
+        assert_eq!(2, person.plans().len());
+        assert_eq!(timed_plan().elements, person.plans()[0].elements);
+
+        let activities = person.plans()[1].acts();
+        let end_time = activities[0].end_time.unwrap();
+        let max_dur = activities[1].max_dur.unwrap();
+        assert_ne!(SimTime::from_secs(8 * 3600), end_time);
+        assert!(end_time.as_secs().abs_diff(8 * 3600) <= 1800);
+        assert_ne!(Duration::from_secs(8 * 3600), max_dur);
+        assert!(max_dur.as_secs().abs_diff(8 * 3600) <= 1800);
+        assert_eq!(None, activities[2].end_time);
+
+        // departures follow the mutated activity times
+        let legs = person.plans()[1].legs();
+        assert_eq!(Some(end_time), legs[0].dep_time);
+        assert_eq!(
+            Some(
+                end_time
+                    .saturating_add(Duration::from_secs(600))
+                    .saturating_add(max_dur)
+            ),
+            legs[1].dep_time
+        );
+    }
+
+    #[test]
+    fn time_allocation_mutator_is_deterministic_for_same_context() {
+        let module = TimeAllocationMutatorModule {
+            mutation_range: Duration::from_secs(1800),
+            affecting_duration: true,
+        };
+        let mut first = InternalPerson::new(Id::create("person"), timed_plan());
+        let mut second = InternalPerson::new(Id::create("person"), timed_plan());
+
+        module.handle(&mut first, 0, &context());
+        module.handle(&mut second, 0, &context());
+
+        assert_eq!(first.plans()[0].elements, second.plans()[0].elements);
+    }
+
+    #[test]
+    fn time_allocation_mutator_keeps_durations_if_not_affecting_duration() {
+        let module = TimeAllocationMutatorModule {
+            mutation_range: Duration::from_secs(1800),
+            affecting_duration: false,
+        };
+        let mut person = InternalPerson::new(Id::create("person"), timed_plan());
+
+        module.handle(&mut person, 0, &context());
+
+        let activities = person.plans()[0].acts();
+        assert_ne!(Some(SimTime::from_secs(8 * 3600)), activities[0].end_time);
+        assert_eq!(Some(Duration::from_secs(8 * 3600)), activities[1].max_dur);
+    }
+
+    #[test]
+    fn time_allocation_mutator_does_not_end_activities_before_they_start() {
+        let module = TimeAllocationMutatorModule {
+            mutation_range: Duration::from_secs(1800),
+            affecting_duration: true,
+        };
+        // work ends right after the arrival at 08:10
+        let mut plan = timed_plan();
+        plan.elements[2] =
+            InternalPlanElement::Activity(activity("work", Some(8 * 3600 + 600), None));
+
+        for iteration in 0..10 {
+            let mut person = InternalPerson::new(Id::create("person"), plan.clone());
+            let context = ReplanningContext {
+                iteration,
+                ..context()
+            };
+            module.handle(&mut person, 0, &context);
+
+            let legs = person.plans()[0].legs();
+            let arrival = legs[0]
+                .dep_time
+                .unwrap()
+                .saturating_add(Duration::from_secs(600));
+            let work_end = person.plans()[0].acts()[1].end_time.unwrap();
+            assert!(work_end >= arrival);
+            assert_eq!(Some(work_end), legs[1].dep_time);
+        }
+    }
+
+    pub(super) fn environment(trip_router: TripRouter) -> ReplanningEnvironment {
+        ReplanningEnvironment {
+            scenario: ScenarioCore {
+                network: Arc::new(Network::new()),
+                garage: Arc::new(Garage::default()),
+                transit_schedule: Arc::new(TransitSchedule::default()),
+                transit_vehicles: Arc::new(Garage::default()),
+                facilities: Arc::new(ActivityFacilities::default()),
+                config: Arc::new(Config::default()),
+            },
+            trip_router: Arc::new(trip_router),
+        }
+    }
+
+    fn teleportation_router(mode: &str) -> TripRouter {
+        let mut modules: IntMap<Id<String>, Arc<dyn RoutingModule>> = IntMap::default();
+        let mode_id = Id::create(mode);
+        modules.insert(
+            mode_id.clone(),
+            Arc::new(TeleportationRoutingModule::new(mode_id, 1.0, 1.0)),
+        );
+        TripRouter::new(modules)
+    }
+
+    fn unrouted_walk_plan() -> InternalPlan {
+        let mut plan = InternalPlan::default();
+        plan.add_act(InternalActivity::new(
+            Some(Coordinate::new_2d(0.0, 0.0)),
+            "home",
+            Id::create("link-1"),
+            None,
+            Some(SimTime::from_secs(10)),
+            None,
+        ));
+        plan.elements.push(InternalPlanElement::Leg(InternalLeg {
+            mode: Id::create("walk"),
+            routing_mode: Some(Id::create("walk")),
+            dep_time: None,
+            trav_time: None,
+            route: None,
+            attributes: InternalAttributes::default(),
+        }));
+        plan.add_act(InternalActivity::new(
+            Some(Coordinate::new_2d(20.0, 0.0)),
+            "work",
+            Id::create("link-2"),
+            None,
+            None,
+            None,
+        ));
+        plan
+    }
+
+    // home until 08:00, walk 10 minutes, work for 8 hours, walk 10 minutes, home
+    fn timed_plan() -> InternalPlan {
+        let mut plan = InternalPlan::default();
+        plan.add_act(activity("home", Some(8 * 3600), None));
+        plan.add_leg(walk_leg());
+        plan.add_act(activity("work", None, Some(8 * 3600)));
+        plan.add_leg(walk_leg());
+        plan.add_act(activity("home", None, None));
+        plan
+    }
+
+    fn activity(act_type: &str, end_time: Option<u64>, max_dur: Option<u64>) -> InternalActivity {
+        InternalActivity::new(
+            Some(Coordinate::new_2d(0.0, 0.0)),
+            act_type,
+            Id::create("link-1"),
+            None,
+            end_time.map(SimTime::from_secs),
+            max_dur.map(Duration::from_secs),
+        )
+    }
+
+    fn walk_leg() -> InternalLeg {
+        InternalLeg {
+            mode: Id::create("walk"),
+            routing_mode: Some(Id::create("walk")),
+            dep_time: None,
+            trav_time: Some(Duration::from_secs(600)),
+            route: None,
+            attributes: InternalAttributes::default(),
+        }
+    }
+
     fn person_with_scores<const N: usize>(scores: [Option<f64>; N]) -> InternalPerson {
         let mut person = InternalPerson::new(Id::create("person"), plan(scores[0], true));
         for score in scores.into_iter().skip(1) {
@@ -746,7 +1837,7 @@ mod tests {
         }
     }
 
-    fn context() -> ReplanningContext {
+    pub(super) fn context() -> ReplanningContext {
         ReplanningContext {
             iteration: 7,
             base_seed: 42,
@@ -757,7 +1848,12 @@ mod tests {
     struct MarkCopiedPlanModule;
 
     impl PlanStrategyModule for MarkCopiedPlanModule {
-        fn handle(&self, person: &mut InternalPerson, plan_index: usize) {
+        fn handle(
+            &self,
+            person: &mut InternalPerson,
+            plan_index: usize,
+            _context: &ReplanningContext,
+        ) {
             person.plans_mut()[plan_index].score = Some(99.0);
         }
     }
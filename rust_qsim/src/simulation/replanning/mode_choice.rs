@@ -0,0 +1,399 @@
+use crate::simulation::id::Id;
+use crate::simulation::random::get_rng;
+use crate::simulation::replanning::routing::TripRouter;
+use crate::simulation::replanning::{PlanStrategyModule, ReplanningContext, route_trips};
+use crate::simulation::scenario::ScenarioCore;
+use crate::simulation::scenario::population::InternalPerson;
+use crate::simulation::scenario::prepare_for_sim::resolve_main_mode;
+use crate::simulation::scenario::trip_structure_utils::{
+    Subtour, get_subtours, get_trip_spans_default,
+};
+use rand::Rng;
+use std::sync::Arc;
+
+const CHANGE_TRIP_MODE_RNG_PURPOSE: &str = "replanning.change_trip_mode";
+const SUBTOUR_MODE_CHOICE_RNG_PURPOSE: &str = "replanning.subtour_mode_choice";
+
+// Changes the mode of every trip which uses one of the configured modes to another randomly drawn
+// one of them and routes the changed trips.
+pub(super) struct ChangeTripModeModule {
+    pub(super) scenario: ScenarioCore,
+    pub(super) trip_router: Arc<TripRouter>,
+    pub(super) modes: Vec<Id<String>>,
+}
+
+impl PlanStrategyModule for ChangeTripModeModule {
+    fn handle(&self, person: &mut InternalPerson, plan_index: usize, context: &ReplanningContext) {
+        let mut rng = get_rng(
+            context.base_seed,
+            (
+                context.iteration,
+                person.id().external(),
+                CHANGE_TRIP_MODE_RNG_PURPOSE,
+            ),
+        );
+
+        route_trips(
+            &self.scenario,
+            &self.trip_router,
+            person,
+            plan_index,
+            |_, mode| {
+                if !self.modes.contains(mode) {
+                    return None;
+                }
+                let candidates: Vec<_> = self.modes.iter().filter(|m| *m != mode).collect();
+                if candidates.is_empty() {
+                    return None;
+                }
+                Some(candidates[rng.random_range(0..candidates.len())].clone())
+            },
+        );
+    }
+}
+
+// Changes the mode of one randomly drawn subtour of a plan, together with its nested subtours, and
+// routes their trips. All of these trips get the same new mode. Chain-based modes are only chosen
+// if the vehicle can be brought back, see `is_chain_based_feasible`. Subtours with trips of modes
+// other than the configured ones are left untouched.
+pub(super) struct SubtourModeChoiceModule {
+    pub(super) scenario: ScenarioCore,
+    pub(super) trip_router: Arc<TripRouter>,
+    pub(super) modes: Vec<Id<String>>,
+    pub(super) chain_based_modes: Vec<Id<String>>,
+}
+
+impl SubtourModeChoiceModule {
+    /// Returns the trips of the given subtour and of all subtours nested in it.
+    fn trips_with_nested(subtours: &[Subtour], subtour: usize) -> Vec<usize> {
+        let mut trips = subtours[subtour].trips.clone();
+        for (index, nested) in subtours.iter().enumerate() {
+            let mut parent = nested.parent;
+            while let Some(p) = parent {
+                if p == subtour {
+                    trips.extend_from_slice(&subtours[index].trips);
+                    break;
+                }
+                parent = subtours[p].parent;
+            }
+        }
+        trips
+    }
+
+    /// A chain-based mode may only be used by the trips of a subtour if the subtour is closed, all
+    /// of its trips use that mode, and the subtour is not nested or its parent uses the same mode.
+    /// Thereby, the vehicle is available at the start of each subtour and is brought back to
+    /// where the plan starts.
+    fn is_chain_based_feasible(
+        &self,
+        subtours: &[Subtour],
+        trip_modes: &[Option<Id<String>>],
+    ) -> bool {
+        let all_trips_use = |subtour: &Subtour, mode: &Id<String>| {
+            subtour
+                .trips
+                .iter()
+                .all(|trip| trip_modes[*trip].as_ref() == Some(mode))
+        };
+
+        subtours.iter().all(|subtour| {
+            subtour
+                .trips
+                .iter()
+                .filter_map(|trip| trip_modes[*trip].as_ref())
+                .filter(|mode| self.chain_based_modes.contains(mode))
+                .all(|mode| {
+                    subtour.closed
+                        && all_trips_use(subtour, mode)
+                        && subtour
+                            .parent
+                            .is_none_or(|parent| all_trips_use(&subtours[parent], mode))
+                })
+        })
+    }
+}
+
+impl PlanStrategyModule for SubtourModeChoiceModule {
+    fn handle(&self, person: &mut InternalPerson, plan_index: usize, context: &ReplanningContext) {
+        let elements = &person.plans()[plan_index].elements;
+        let trip_modes: Vec<_> = get_trip_spans_default(elements)
+            .iter()
+            .map(|span| resolve_main_mode(&span.legs(elements).collect::<Vec<_>>()).ok())
+            .collect();
+        let subtours = get_subtours(elements);
+
+        let candidates: Vec<_> = (0..subtours.len())
+            .map(|subtour| Self::trips_with_nested(&subtours, subtour))
+            .filter(|trips| {
+                trips.iter().all(|trip| {
+                    trip_modes[*trip]
+                        .as_ref()
+                        .is_some_and(|mode| self.modes.contains(mode))
+                })
+            })
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+
+        let mut rng = get_rng(
+            context.base_seed,
+            (
+                context.iteration,
+                person.id().external(),
+                SUBTOUR_MODE_CHOICE_RNG_PURPOSE,
+            ),
+        );
+        let trips = &candidates[rng.random_range(0..candidates.len())];
+
+        let options: Vec<_> = self
+            .modes
+            .iter()
+            .filter(|mode| {
+                let mut new_modes = trip_modes.clone();
+                for trip in trips {
+                    new_modes[*trip] = Some((*mode).clone());
+                }
+                new_modes != trip_modes && self.is_chain_based_feasible(&subtours, &new_modes)
+            })
+            .collect();
+        if options.is_empty() {
+            return;
+        }
+        let new_mode = options[rng.random_range(0..options.len())];
+
+        route_trips(
+            &self.scenario,
+            &self.trip_router,
+            person,
+            plan_index,
+            |trip, _| trips.contains(&trip).then(|| new_mode.clone()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeTripModeModule, SubtourModeChoiceModule};
+    use crate::simulation::InternalAttributes;
+    use crate::simulation::id::Id;
+    use crate::simulation::replanning::routing::teleportation::TeleportationRoutingModule;
+    use crate::simulation::replanning::routing::{RoutingModule, TripRouter};
+    use crate::simulation::replanning::tests::{context, environment};
+    use crate::simulation::replanning::{PlanStrategyModule, ReplanningContext};
+    use crate::simulation::scenario::Coordinate;
+    use crate::simulation::scenario::population::{
+        InternalActivity, InternalLeg, InternalPerson, InternalPlan, InternalPlanElement,
+        InternalRoute,
+    };
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use nohash_hasher::IntMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[deterministic_id_test]
+    fn change_trip_mode_changes_and_routes_trips_with_configured_modes() {
+        let module = ChangeTripModeModule {
+            scenario: environment(TripRouter::new(IntMap::default())).scenario,
+            trip_router: Arc::new(router(&["walk", "bike"])),
+            modes: vec![Id::create("walk"), Id::create("bike")],
+        };
+        let mut person = InternalPerson::new(
+            Id::create("person"),
+            plan(
+                &[("home", "1"), ("work", "2"), ("shop", "3")],
+                &["walk", "pt"],
+            ),
+        );
+
+        module.handle(&mut person, 0, &context());
+
+        let legs = person.plans()[0].legs();
+        assert_eq!(2, legs.len());
+        assert_eq!(Id::get_from_ext("bike"), legs[0].mode);
+        assert!(matches!(legs[0].route, Some(InternalRoute::Generic(_))));
+        // pt is not one of the configured modes
+        assert_eq!(Id::get_from_ext("pt"), legs[1].mode);
+        assert!(legs[1].route.is_none());
+    }
+
+    #[deterministic_id_test]
+    fn subtour_mode_choice_keeps_chain_based_modes_consistent() {
+        let module = subtour_mode_choice();
+        let elements = [
+            ("home", "1"),
+            ("work", "2"),
+            ("lunch", "3"),
+            ("work", "2"),
+            ("home", "1"),
+        ];
+        let modes = ["walk"; 4];
+
+        let mut changed = 0;
+        for iteration in 0..20 {
+            let mut person = InternalPerson::new(Id::create("person"), plan(&elements, &modes));
+            let context = ReplanningContext {
+                iteration,
+                ..context()
+            };
+
+            module.handle(&mut person, 0, &context);
+
+            let modes: Vec<_> = person.plans()[0]
+                .legs()
+                .iter()
+                .map(|leg| leg.mode.external().to_string())
+                .collect();
+            if modes.iter().any(|mode| mode == "bike") {
+                changed += 1;
+            }
+            // the bike can only be used for the lunch trips if it was taken to work
+            if modes[1] == "bike" || modes[2] == "bike" {
+                assert_eq!(vec!["bike"; 4], modes);
+            }
+            assert_eq!(modes[0], modes[3]);
+            assert_eq!(modes[1], modes[2]);
+        }
+        assert!(changed > 0);
+    }
+
+    #[deterministic_id_test]
+    fn subtour_mode_choice_changes_nested_subtours_with_their_parent() {
+        let module = subtour_mode_choice();
+        let elements = [
+            ("home", "1"),
+            ("work", "2"),
+            ("lunch", "3"),
+            ("work", "2"),
+            ("home", "1"),
+        ];
+
+        for iteration in 0..20 {
+            let mut person = InternalPerson::new(
+                Id::create("person"),
+                plan(&elements, &["bike", "walk", "walk", "bike"]),
+            );
+            let context = ReplanningContext {
+                iteration,
+                ..context()
+            };
+
+            module.handle(&mut person, 0, &context);
+
+            let modes: Vec<_> = person.plans()[0]
+                .legs()
+                .iter()
+                .map(|leg| leg.mode.external().to_string())
+                .collect();
+            // either the lunch trips change to bike or the work trips change to walk
+            assert!(
+                modes == vec!["bike"; 4] || modes == vec!["walk"; 4],
+                "unexpected modes {modes:?}"
+            );
+        }
+    }
+
+    #[deterministic_id_test]
+    fn subtour_mode_choice_changes_parent_together_with_nested_subtours() {
+        let module = subtour_mode_choice();
+        let elements = [
+            ("home", "1"),
+            ("work", "2"),
+            ("lunch", "3"),
+            ("work", "2"),
+            ("home", "1"),
+        ];
+
+        let mut all_walk = 0;
+        for iteration in 0..20 {
+            let mut person =
+                InternalPerson::new(Id::create("person"), plan(&elements, &["bike"; 4]));
+            let context = ReplanningContext {
+                iteration,
+                ..context()
+            };
+
+            module.handle(&mut person, 0, &context);
+
+            let modes: Vec<_> = person.plans()[0]
+                .legs()
+                .iter()
+                .map(|leg| leg.mode.external().to_string())
+                .collect();
+            if modes == vec!["walk"; 4] {
+                all_walk += 1;
+            }
+            // the bike must not be left at work
+            if modes[0] == "walk" {
+                assert_eq!(vec!["walk"; 4], modes);
+            }
+        }
+        assert!(all_walk > 0);
+    }
+
+    #[deterministic_id_test]
+    fn subtour_mode_choice_does_not_use_chain_based_modes_on_open_subtours() {
+        let module = subtour_mode_choice();
+        let mut person = InternalPerson::new(
+            Id::create("person"),
+            plan(&[("home", "1"), ("shop", "3")], &["walk"]),
+        );
+
+        module.handle(&mut person, 0, &context());
+
+        let legs = person.plans()[0].legs();
+        assert_eq!(Id::get_from_ext("walk"), legs[0].mode);
+        assert!(legs[0].route.is_none());
+    }
+
+    fn subtour_mode_choice() -> SubtourModeChoiceModule {
+        SubtourModeChoiceModule {
+            scenario: environment(TripRouter::new(IntMap::default())).scenario,
+            trip_router: Arc::new(router(&["walk", "bike"])),
+            modes: vec![Id::create("walk"), Id::create("bike")],
+            chain_based_modes: vec![Id::create("bike")],
+        }
+    }
+
+    fn router(modes: &[&str]) -> TripRouter {
+        let mut modules: IntMap<Id<String>, Arc<dyn RoutingModule>> = IntMap::default();
+        for mode in modes {
+            let mode_id = Id::create(mode);
+            modules.insert(
+                mode_id.clone(),
+                Arc::new(TeleportationRoutingModule::new(mode_id, 1.0, 1.0)),
+            );
+        }
+        TripRouter::new(modules)
+    }
+
+    /// Creates a plan visiting the given activities at the given links with one unrouted leg of the
+    /// given mode between each pair of activities. Activities end on the hour.
+    fn plan(activities: &[(&str, &str)], modes: &[&str]) -> InternalPlan {
+        let mut plan = InternalPlan::default();
+        for (index, (act_type, link)) in activities.iter().enumerate() {
+            if index > 0 {
+                let mode = Id::create(modes[index - 1]);
+                plan.elements.push(InternalPlanElement::Leg(InternalLeg {
+                    mode: mode.clone(),
+                    routing_mode: Some(mode),
+                    dep_time: None,
+                    trav_time: Some(Duration::from_secs(600)),
+                    route: None,
+                    attributes: InternalAttributes::default(),
+                }));
+            }
+            let x = link.parse::<f64>().unwrap() * 100.;
+            plan.add_act(InternalActivity::new(
+                Some(Coordinate::new_2d(x, 0.0)),
+                act_type,
+                Id::create(link),
+                None,
+                Some(SimTime::from_secs(3600 * (index as u64 + 1))),
+                None,
+            ));
+        }
+        plan
+    }
+}
@@ -0,0 +1,240 @@
+use crate::simulation::id::Id;
+use crate::simulation::replanning::{
+    GenericPlanStrategy, KeepLastSelector, PlanSelector, PlanStrategy, PlanStrategyModule,
+    ReplanningEnvironment, is_default_strategy_name,
+};
+use ahash::HashMap;
+use std::sync::Arc;
+
+type PlanStrategyFactory =
+    Arc<dyn Fn(&ReplanningEnvironment) -> Box<dyn PlanStrategy> + Send + Sync>;
+type PlanSelectorFactory =
+    Arc<dyn Fn(&ReplanningEnvironment) -> Box<dyn PlanSelector> + Send + Sync>;
+
+/// Strategies and selectors which are registered in addition to the default ones, e.g., by
+/// downstream crates through the `ControllerBuilder`. They are created once the replanning is set
+/// up, so that they can access the scenario and the trip router. `Replanning::strategy_settings`
+/// and `Replanning::plan_selector_for_removal` reference them by their names.
+#[derive(Default, Clone)]
+pub(crate) struct StrategyRegistry {
+    strategies: HashMap<String, PlanStrategyFactory>,
+    selectors: HashMap<String, PlanSelectorFactory>,
+}
+
+impl StrategyRegistry {
+    /// Registers an innovative strategy.
+    pub(crate) fn register_strategy<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&ReplanningEnvironment) -> Box<dyn PlanStrategy> + Send + Sync + 'static,
+    {
+        self.assert_unregistered(name);
+        self.strategies.insert(name.to_string(), Arc::new(factory));
+    }
+
+    /// Registers an innovative strategy which copies the last selected plan and adapts the copy
+    /// with the module, like the default innovative strategies do.
+    pub(crate) fn register_strategy_module<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&ReplanningEnvironment) -> Box<dyn PlanStrategyModule> + Send + Sync + 'static,
+    {
+        let strategy_name = name.to_string();
+        self.register_strategy(name, move |environment| {
+            Box::new(GenericPlanStrategy {
+                name: Id::create(&strategy_name),
+                selector: Box::new(KeepLastSelector),
+                modules: vec![factory(environment)],
+            })
+        });
+    }
+
+    /// Registers a selector. Like the default selectors, it can be used as non-innovative
+    /// strategy and for plan removal.
+    pub(crate) fn register_selector<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&ReplanningEnvironment) -> Box<dyn PlanSelector> + Send + Sync + 'static,
+    {
+        self.assert_unregistered(name);
+        self.selectors.insert(name.to_string(), Arc::new(factory));
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.strategies.contains_key(name) || self.selectors.contains_key(name)
+    }
+
+    pub(crate) fn selector_names(&self) -> Vec<Id<String>> {
+        self.selectors.keys().map(|name| Id::create(name)).collect()
+    }
+
+    pub(crate) fn create_selector(
+        &self,
+        name: &str,
+        environment: &ReplanningEnvironment,
+    ) -> Option<Box<dyn PlanSelector>> {
+        self.selectors.get(name).map(|factory| factory(environment))
+    }
+
+    /// Creates all registered strategies. Selectors become strategies which only select a plan.
+    pub(crate) fn create_strategies(
+        &self,
+        environment: &ReplanningEnvironment,
+    ) -> HashMap<Id<String>, Box<dyn PlanStrategy>> {
+        let strategies = self
+            .strategies
+            .iter()
+            .map(|(name, factory)| (Id::create(name), factory(environment)));
+        let selectors = self.selectors.iter().map(|(name, factory)| {
+            let strategy: Box<dyn PlanStrategy> = Box::new(GenericPlanStrategy {
+                name: Id::create(name),
+                selector: factory(environment),
+                modules: Vec::new(),
+            });
+            (Id::create(name), strategy)
+        });
+        strategies.chain(selectors).collect()
+    }
+
+    fn assert_unregistered(&self, name: &str) {
+        assert!(
+            !is_default_strategy_name(name) && !self.contains(name),
+            "Replanning strategy or selector {name} is already registered."
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StrategyRegistry;
+    use crate::simulation::config::{Replanning, StrategySetting};
+    use crate::simulation::id::Id;
+    use crate::simulation::replanning::routing::TripRouter;
+    use crate::simulation::replanning::tests::environment;
+    use crate::simulation::replanning::{
+        PlanSelector, PlanStrategyModule, ReplanningContext, StrategyManager,
+    };
+    use crate::simulation::scenario::population::{InternalPerson, InternalPlan};
+    use nohash_hasher::IntMap;
+
+    struct ScorePlanModule;
+
+    impl PlanStrategyModule for ScorePlanModule {
+        fn handle(&self, person: &mut InternalPerson, plan_index: usize, _: &ReplanningContext) {
+            person.plans_mut()[plan_index].score = Some(42.0);
+        }
+    }
+
+    struct FirstPlanSelector;
+
+    impl PlanSelector for FirstPlanSelector {
+        fn select(&self, _: &InternalPerson, _: &ReplanningContext) -> usize {
+            0
+        }
+    }
+
+    #[test]
+    fn registered_strategy_module_can_be_configured() {
+        let mut registry = StrategyRegistry::default();
+        registry.register_strategy_module("ScorePlan", |_| Box::new(ScorePlanModule));
+        let manager = StrategyManager::from_replanning_config(
+            &replanning("ScorePlan"),
+            &environment(TripRouter::new(IntMap::default())),
+            &registry,
+        );
+        let mut innovated = person();
+        let mut not_innovated = person();
+
+        manager.run(0, 42, false, &mut innovated);
+        // innovative strategies are not chosen once innovation is disabled
+        manager.run(0, 42, true, &mut not_innovated);
+
+        let plans = innovated.plans();
+        assert_eq!(3, plans.len());
+        assert_eq!(Some(42.0), plans[2].score);
+        assert!(plans[2].selected);
+        assert_eq!(2, not_innovated.plans().len());
+    }
+
+    #[test]
+    fn registered_selector_as_strategy_switches_selected_plan() {
+        let mut registry = StrategyRegistry::default();
+        registry.register_selector("FirstPlan", |_| Box::new(FirstPlanSelector));
+        let manager = StrategyManager::from_replanning_config(
+            &replanning("FirstPlan"),
+            &environment(TripRouter::new(IntMap::default())),
+            &registry,
+        );
+        let mut person = person();
+
+        manager.run(0, 42, true, &mut person);
+
+        let plans = person.plans();
+        assert_eq!(2, plans.len());
+        assert!(plans[0].selected);
+        assert!(!plans[1].selected);
+    }
+
+    #[test]
+    fn registered_selector_can_be_configured_as_strategy_and_for_removal() {
+        let mut registry = StrategyRegistry::default();
+        registry.register_selector("FirstPlan", |_| Box::new(FirstPlanSelector));
+        let replanning = Replanning {
+            max_agent_plan_memory: 1,
+            plan_selector_for_removal: "FirstPlan".to_string(),
+            ..replanning("FirstPlan")
+        };
+        let manager = StrategyManager::from_replanning_config(
+            &replanning,
+            &environment(TripRouter::new(IntMap::default())),
+            &registry,
+        );
+        let mut person = person();
+
+        manager.run(0, 42, true, &mut person);
+
+        assert_eq!(1, person.plans().len());
+        assert_eq!(Some(2.0), person.plans()[0].score);
+    }
+
+    #[test]
+    #[should_panic(expected = "Replanning strategy or selector ReRoute is already registered.")]
+    fn registering_default_name_panics() {
+        let mut registry = StrategyRegistry::default();
+        registry.register_strategy_module("ReRoute", |_| Box::new(ScorePlanModule));
+    }
+
+    #[test]
+    #[should_panic(expected = "Replanning strategy or selector FirstPlan is already registered.")]
+    fn registering_name_twice_panics() {
+        let mut registry = StrategyRegistry::default();
+        registry.register_selector("FirstPlan", |_| Box::new(FirstPlanSelector));
+        registry.register_strategy_module("FirstPlan", |_| Box::new(ScorePlanModule));
+    }
+
+    fn replanning(strategy: &str) -> Replanning {
+        Replanning {
+            strategy_settings: vec![StrategySetting {
+                name: strategy.to_string(),
+                weight: 1.0,
+                subpopulation: "person".to_string(),
+                ..Default::default()
+            }],
+            ..Replanning::default()
+        }
+    }
+
+    fn person() -> InternalPerson {
+        let mut person = InternalPerson::new(
+            Id::create("person"),
+            InternalPlan {
+                score: Some(1.0),
+                selected: false,
+                elements: Vec::new(),
+            },
+        );
+        person.plans_mut().push(InternalPlan {
+            score: Some(2.0),
+            selected: true,
+            elements: Vec::new(),
+        });
+        person
+    }
+}
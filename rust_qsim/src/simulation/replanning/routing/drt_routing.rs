@@ -0,0 +1,82 @@
+use crate::simulation::drt::calc_drt_path;
+use crate::simulation::id::Id;
+use crate::simulation::replanning::routing::least_cost_path_calculator::LeastCostPathCalculator;
+use crate::simulation::replanning::routing::{RoutingError, RoutingModule, RoutingRequest};
+use crate::simulation::scenario::network::Network;
+use crate::simulation::scenario::population::{
+    InternalGenericRoute, InternalLeg, InternalPlanElement, InternalRoute,
+};
+use std::sync::Arc;
+
+/// Routes legs of a DRT service. The leg gets a generic route from the start to the end link,
+/// carrying the travel time and distance of the direct ride. The actual path is decided by the
+/// DRT engine when the request is served.
+pub struct DrtRoutingModule {
+    mode: Id<String>,
+    least_cost_path_calculator: Box<dyn LeastCostPathCalculator>,
+    network: Arc<Network>,
+}
+
+impl RoutingModule for DrtRoutingModule {
+    fn calc_route(
+        &self,
+        request: RoutingRequest,
+    ) -> Result<Vec<InternalPlanElement>, RoutingError> {
+        let start = request
+            .from
+            .modal_link(&self.mode)
+            .unwrap_or_else(|| request.from.link())
+            .clone();
+        let end = request
+            .to
+            .modal_link(&self.mode)
+            .unwrap_or_else(|| request.to.link())
+            .clone();
+
+        let path = calc_drt_path(
+            self.least_cost_path_calculator.as_ref(),
+            &self.network,
+            &start,
+            &end,
+            request.departure_time,
+        )
+        .ok_or_else(|| RoutingError::NoPath {
+            mode: self.mode.external().to_string(),
+            from: start.external().to_string(),
+            to: end.external().to_string(),
+        })?;
+
+        let route = InternalRoute::Generic(InternalGenericRoute::new(
+            start,
+            end,
+            Some(path.travel_time),
+            Some(path.distance),
+            None,
+        ));
+        let leg = InternalLeg::new(
+            route,
+            self.mode.external(),
+            path.travel_time,
+            Some(request.departure_time),
+        );
+        Ok(vec![InternalPlanElement::Leg(leg)])
+    }
+
+    fn mode(&self) -> &Id<String> {
+        &self.mode
+    }
+}
+
+impl DrtRoutingModule {
+    pub fn new(
+        mode: Id<String>,
+        least_cost_path_calculator: Box<dyn LeastCostPathCalculator>,
+        network: Arc<Network>,
+    ) -> Self {
+        Self {
+            mode,
+            least_cost_path_calculator,
+            network,
+        }
+    }
+}
@@ -52,8 +52,8 @@ pub trait TravelDisutility: Debug + Send + Sync {
 }
 
 /// An implementation of both `TravelTime` and `TravelDisutility`, purely based on freespeed travel
-/// times. The travel time is simply the link length divided by the freespeed, ignoring any given
-/// vehicle type and its max speed.
+/// times. The travel time is simply the link length divided by the freespeed at the departure time,
+/// ignoring any given vehicle type and its max speed.
 ///
 /// The travel disutility is equal to the travel time.
 #[derive(Clone, Debug)]
@@ -63,12 +63,12 @@ impl TravelTime for FreeSpeedTravelTimeAndDisutility {
     fn travel_time(
         &self,
         link: &Link,
-        _departure_time: SimTime,
+        departure_time: SimTime,
         _person: Option<&InternalPerson>,
         _vehicle: Option<&InternalVehicle>,
     ) -> Duration {
         // the given vehicle type is ignored => true freespeed
-        Duration::from_secs_f64(link.length / link.freespeed)
+        Duration::from_secs_f64(link.length / link.freespeed_at(departure_time))
     }
 }
 
@@ -86,9 +86,9 @@ impl TravelDisutility for FreeSpeedTravelTimeAndDisutility {
             * 1.0
         // travel DISutility is simply the travel time here, since higher time corresponds to lower utility
     }
-    // min travel disutility is equal to the travel disutility, since it does not depend on time, person or vehicle
+    // min travel disutility is the travel time at the highest freespeed, since it does not depend on person or vehicle
     fn get_link_min_travel_disutility(&self, link: &Link) -> Disutility {
-        self.travel_disutility(link, SimTime::from_secs(0), None, None)
+        link.length / link.max_freespeed()
     }
 }
 
@@ -103,15 +103,16 @@ impl TravelTime for FreeOrMaxSpeedTravelTimeAndDisutility {
     fn travel_time(
         &self,
         link: &Link,
-        _departure_time: SimTime,
+        departure_time: SimTime,
         _person: Option<&InternalPerson>,
         vehicle: Option<&InternalVehicle>,
     ) -> Duration {
         // respect the given vehicle type, if provided
+        let freespeed = link.freespeed_at(departure_time);
         let max_speed = if let Some(v) = vehicle {
-            v.max_v.min(link.freespeed)
+            v.max_v.min(freespeed)
         } else {
-            link.freespeed
+            freespeed
         };
 
         Duration::from_secs_f64(link.length / max_speed)
@@ -133,11 +134,9 @@ impl TravelDisutility for FreeOrMaxSpeedTravelTimeAndDisutility {
         // travel DISutility is simply the travel time here, since higher time corresponds to lower utility
     }
     fn get_link_min_travel_disutility(&self, link: &Link) -> Disutility {
-        // the min travel disutility is equal to freespeed travel time, which is obtained from
-        // the travel disutility function by not passing a vehicle (since that one simply calls
-        // the travel time function, which respects the vehicle's max speed if given, and otherwise
-        // uses the freespeed)
-        self.travel_disutility(link, SimTime::from_secs(0), None, None)
+        // the min travel disutility is equal to the travel time at the highest freespeed of the
+        // link, since a vehicle's max speed can only make the travel time longer
+        link.length / link.max_freespeed()
     }
 }
 
@@ -201,13 +200,13 @@ mod tests {
         get_triangle_test_network, net_to_graph,
     };
     use crate::simulation::replanning::routing::least_cost_path_calculator::{
-        Disutility, FreeOrMaxSpeedTravelTimeAndDisutility, LeastCostPath,
-        LeastCostPathRequestBuilder,
+        Disutility, FreeOrMaxSpeedTravelTimeAndDisutility, FreeSpeedTravelTimeAndDisutility,
+        LeastCostPath, LeastCostPathRequestBuilder,
     };
     use crate::simulation::replanning::routing::least_cost_path_calculator::{
         LeastCostPathCalculator, TravelDisutility, TravelTime,
     };
-    use crate::simulation::scenario::network::Link;
+    use crate::simulation::scenario::network::{ChangeValue, Link, LinkChange};
     use crate::simulation::scenario::vehicles::InternalVehicle;
     use crate::simulation::time::SimTime;
 
@@ -275,4 +274,40 @@ mod tests {
             10.0
         );
     }
+
+    /// Freespeed travel times follow the network change events of a link, while the min travel
+    /// disutility is based on the highest freespeed over the day.
+    #[deterministic_id_test]
+    fn test_time_variant_freespeed_travel_time() {
+        let mut network = get_triangle_test_network();
+        network
+            .get_link_mut(&Id::create("4"))
+            .changes
+            .push(LinkChange {
+                time: SimTime::from_secs(3600),
+                capacity: None,
+                freespeed: Some(ChangeValue::Factor(0.5)),
+                permlanes: None,
+            });
+        let graph = net_to_graph(&network);
+        let link = graph.edge(Id::create("4")).unwrap();
+
+        let fsttad = FreeSpeedTravelTimeAndDisutility;
+        let fomsttad = FreeOrMaxSpeedTravelTimeAndDisutility;
+        for (before, after) in [
+            (
+                fsttad.travel_time(link, SimTime::from_secs(3599), None, None),
+                fsttad.travel_time(link, SimTime::from_secs(3600), None, None),
+            ),
+            (
+                fomsttad.travel_time(link, SimTime::from_secs(3599), None, None),
+                fomsttad.travel_time(link, SimTime::from_secs(3600), None, None),
+            ),
+        ] {
+            assert_eq!(Duration::from_secs(4), before);
+            assert_eq!(Duration::from_secs(8), after);
+        }
+        assert_eq!(4.0, fsttad.get_link_min_travel_disutility(link));
+        assert_eq!(4.0, fomsttad.get_link_min_travel_disutility(link));
+    }
 }
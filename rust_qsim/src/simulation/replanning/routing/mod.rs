@@ -13,11 +13,14 @@ use thiserror::Error;
 pub mod a_star;
 mod a_star_core;
 pub mod alt_landmark_data;
+pub mod drt_routing;
 mod graph;
 pub mod least_cost_path_calculator;
 mod network_converter;
 pub mod network_routing;
+mod raptor;
 pub mod teleportation;
+pub mod transit_routing;
 pub mod travel_time_collector;
 
 #[derive(Debug)]
@@ -126,24 +129,6 @@ pub trait RoutingModule: Send + Sync {
     fn mode(&self) -> &Id<String>;
 }
 
-#[allow(dead_code)]
-struct TransitRoutingModule {}
-
-impl RoutingModule for TransitRoutingModule {
-    fn calc_route(
-        &self,
-        _request: RoutingRequest,
-    ) -> Result<Vec<InternalPlanElement>, RoutingError> {
-        Err(RoutingError::Unsupported {
-            mode: "pt".to_string(),
-        })
-    }
-
-    fn mode(&self) -> &Id<String> {
-        todo!()
-    }
-}
-
 impl Debug for dyn RoutingModule {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         // write the name of the module
@@ -249,10 +249,12 @@ mod tests {
     use crate::simulation::InternalAttributes;
     use crate::simulation::config::Config;
     use crate::simulation::id::Id;
+    use crate::simulation::pt::TransitSchedule;
     use crate::simulation::replanning::routing::a_star::Alt;
     use crate::simulation::replanning::routing::least_cost_path_calculator::FreeSpeedTravelTimeAndDisutility;
     use crate::simulation::replanning::routing::teleportation::TeleportationRoutingModule;
     use crate::simulation::replanning::routing::{RoutingModule, RoutingRequestBuilder};
+    use crate::simulation::scenario::facilities::ActivityFacilities;
     use crate::simulation::scenario::facilities::{ActivityFacility, Facility};
     use crate::simulation::scenario::network::{Link, Network};
     use crate::simulation::scenario::population::{
@@ -438,6 +440,9 @@ mod tests {
             ScenarioCore {
                 network,
                 garage: Arc::new(Garage::default()),
+                transit_schedule: Arc::new(TransitSchedule::default()),
+                transit_vehicles: Arc::new(Garage::default()),
+                facilities: Arc::new(ActivityFacilities::default()),
                 config: Arc::new(Config::default()),
             },
         );
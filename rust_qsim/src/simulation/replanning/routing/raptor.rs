@@ -0,0 +1,791 @@
+use crate::simulation::id::Id;
+use crate::simulation::pt::{TransitLine, TransitRoute, TransitSchedule, TransitStopFacility};
+use crate::simulation::scenario::Coordinate;
+use crate::simulation::scenario::network::Network;
+use crate::simulation::time::SimTime;
+use nohash_hasher::IntMap;
+use std::time::Duration;
+
+/// Parameters to create walking transfers between stops.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TransferParams {
+    /// Stops closer than this beeline distance are connected by a walking transfer.
+    pub(crate) max_beeline_distance: f64,
+    pub(crate) beeline_distance_factor: f64,
+    pub(crate) walk_speed: f64,
+}
+
+/// The transit schedule in an index based layout, as required by the RAPTOR algorithm
+/// (Delling et al., Round-Based Public Transit Routing, 2012). Stops, routes and trips are
+/// referenced by their position in the corresponding vectors.
+pub(crate) struct RaptorData {
+    stops: Vec<Id<TransitStopFacility>>,
+    coords: Vec<Coordinate>,
+    stop_index: IntMap<Id<TransitStopFacility>, usize>,
+    routes: Vec<RaptorRoute>,
+    /// Routes serving a stop together with the position of the stop on the route.
+    routes_per_stop: Vec<Vec<(usize, usize)>>,
+    /// Walking transfers starting at a stop.
+    transfers: Vec<Vec<Transfer>>,
+    /// Minimal time to change vehicles at the same stop.
+    change_times: Vec<Duration>,
+}
+
+struct RaptorRoute {
+    line: Id<TransitLine>,
+    route: Id<TransitRoute>,
+    stops: Vec<usize>,
+    allow_boarding: Vec<bool>,
+    allow_alighting: Vec<bool>,
+    /// Network distance from the first stop of the route to each stop.
+    distances: Vec<f64>,
+    /// Trips sorted by their departure at the first stop. Trips are assumed not to overtake each
+    /// other.
+    trips: Vec<RaptorTrip>,
+}
+
+struct RaptorTrip {
+    arrivals: Vec<SimTime>,
+    departures: Vec<SimTime>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Transfer {
+    to: usize,
+    duration: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RideLabel {
+    arrival: SimTime,
+    route: usize,
+    trip: usize,
+    board_pos: usize,
+    alight_pos: usize,
+    /// Whether the passenger boarded after a walk (access or transfer) or directly after
+    /// alighting from another vehicle at the same stop.
+    boarded_after_walk: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WalkLabel {
+    arrival: SimTime,
+    /// The stop the walk started from. `None` for the access walk.
+    from: Option<usize>,
+    duration: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LabelKind {
+    Ride,
+    Walk,
+}
+
+/// A stop reachable by walking from the origin or to the destination.
+#[derive(Debug, Clone)]
+pub(crate) struct StopAccess {
+    pub(crate) stop: Id<TransitStopFacility>,
+    pub(crate) duration: Duration,
+}
+
+/// The fastest connection found by the RAPTOR search.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Journey {
+    pub(crate) access_stop: Id<TransitStopFacility>,
+    pub(crate) egress_stop: Id<TransitStopFacility>,
+    pub(crate) parts: Vec<JourneyPart>,
+    /// Arrival at the destination, including the egress walk.
+    pub(crate) arrival: SimTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JourneyPart {
+    Ride {
+        line: Id<TransitLine>,
+        route: Id<TransitRoute>,
+        board_stop: Id<TransitStopFacility>,
+        alight_stop: Id<TransitStopFacility>,
+        /// Departure of the vehicle at the boarding stop.
+        departure: SimTime,
+        /// Arrival of the vehicle at the alighting stop.
+        arrival: SimTime,
+        distance: f64,
+    },
+    Transfer {
+        from: Id<TransitStopFacility>,
+        to: Id<TransitStopFacility>,
+        duration: Duration,
+    },
+}
+
+impl RaptorData {
+    pub(crate) fn new(
+        schedule: &TransitSchedule,
+        network: &Network,
+        transfer_params: TransferParams,
+    ) -> Self {
+        let mut stops: Vec<_> = schedule.facilities().keys().cloned().collect();
+        stops.sort_by_key(|id| id.internal());
+        let stop_index: IntMap<_, _> = stops
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.clone(), index))
+            .collect();
+        let coords: Vec<_> = stops
+            .iter()
+            .map(|id| schedule.get_facility(id).coord.clone())
+            .collect();
+
+        let mut lines: Vec<_> = schedule.lines().values().collect();
+        lines.sort_by_key(|line| line.id.internal());
+        let mut routes = Vec::new();
+        for line in lines {
+            let mut line_routes: Vec<_> = line.routes.values().collect();
+            line_routes.sort_by_key(|route| route.id.internal());
+            for route in line_routes {
+                if let Some(raptor_route) =
+                    RaptorRoute::new(schedule, network, &stop_index, line, route)
+                {
+                    routes.push(raptor_route);
+                }
+            }
+        }
+
+        let mut routes_per_stop = vec![Vec::new(); stops.len()];
+        for (route_index, route) in routes.iter().enumerate() {
+            for (pos, stop) in route.stops.iter().enumerate() {
+                routes_per_stop[*stop].push((route_index, pos));
+            }
+        }
+
+        let mut change_times = vec![Duration::ZERO; stops.len()];
+        let mut transfers = Self::create_walk_transfers(&coords, transfer_params);
+        for min_transfer in schedule.minimal_transfer_times() {
+            let (Some(&from), Some(&to)) = (
+                stop_index.get(&min_transfer.from_stop),
+                stop_index.get(&min_transfer.to_stop),
+            ) else {
+                continue;
+            };
+            let duration = Duration::from_secs_f64(min_transfer.transfer_time);
+            if from == to {
+                change_times[from] = duration;
+            } else if let Some(transfer) = transfers[from].iter_mut().find(|t| t.to == to) {
+                transfer.duration = duration;
+            } else {
+                transfers[from].push(Transfer { to, duration });
+            }
+        }
+
+        RaptorData {
+            stops,
+            coords,
+            stop_index,
+            routes,
+            routes_per_stop,
+            transfers,
+            change_times,
+        }
+    }
+
+    /// Connects all stops which are within walking distance of each other. Stops are swept in
+    /// order of their x coordinate, so that only stops within the distance on the x axis are
+    /// compared.
+    fn create_walk_transfers(coords: &[Coordinate], params: TransferParams) -> Vec<Vec<Transfer>> {
+        let mut transfers = vec![Vec::new(); coords.len()];
+        let mut by_x: Vec<usize> = (0..coords.len()).collect();
+        by_x.sort_by(|a, b| coords[*a].x.total_cmp(&coords[*b].x));
+
+        for (i, &from) in by_x.iter().enumerate() {
+            for &to in &by_x[i + 1..] {
+                if coords[to].x - coords[from].x > params.max_beeline_distance {
+                    break;
+                }
+                let distance = Coordinate::euclidean_distance(&coords[from], &coords[to]);
+                if distance > params.max_beeline_distance {
+                    continue;
+                }
+                let duration = Duration::from_secs_f64(
+                    distance * params.beeline_distance_factor / params.walk_speed,
+                );
+                transfers[from].push(Transfer { to, duration });
+                transfers[to].push(Transfer { to: from, duration });
+            }
+        }
+        transfers
+    }
+
+    /// Returns all served stops within the given radius around the coordinate. If there is none,
+    /// the stops within `extension_radius` of the nearest stop are returned.
+    pub(crate) fn find_stops(
+        &self,
+        coord: &Coordinate,
+        radius: f64,
+        extension_radius: f64,
+    ) -> Vec<&Id<TransitStopFacility>> {
+        let distances: Vec<_> = self
+            .coords
+            .iter()
+            .map(|stop_coord| Coordinate::euclidean_distance(coord, stop_coord))
+            .collect();
+        let nearest = distances
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.routes_per_stop[*index].is_empty())
+            .map(|(_, distance)| *distance)
+            .fold(f64::INFINITY, f64::min);
+        let radius = if nearest <= radius {
+            radius
+        } else {
+            nearest + extension_radius
+        };
+
+        distances
+            .iter()
+            .enumerate()
+            .filter(|(index, distance)| {
+                **distance <= radius && !self.routes_per_stop[*index].is_empty()
+            })
+            .map(|(index, _)| &self.stops[index])
+            .collect()
+    }
+
+    /// Calculates the connection with the earliest arrival at the destination, using at most
+    /// `max_transfers` transfers. Returns `None` if no connection exists.
+    pub(crate) fn calc_earliest_arrival(
+        &self,
+        access: &[StopAccess],
+        egress: &[StopAccess],
+        departure_time: SimTime,
+        max_transfers: u32,
+    ) -> Option<Journey> {
+        let num_stops = self.stops.len();
+        let rounds = max_transfers as usize + 1;
+        let mut rides: Vec<Vec<Option<RideLabel>>> = vec![vec![None; num_stops]; rounds + 1];
+        let mut walks: Vec<Vec<Option<WalkLabel>>> = vec![vec![None; num_stops]; rounds + 1];
+        let mut best = vec![SimTime::max(); num_stops];
+        let mut marked = vec![false; num_stops];
+
+        for access_stop in access {
+            let Some(&stop) = self.stop_index.get(&access_stop.stop) else {
+                continue;
+            };
+            let arrival = departure_time.saturating_add(access_stop.duration);
+            if arrival < best[stop] {
+                best[stop] = arrival;
+                walks[0][stop] = Some(WalkLabel {
+                    arrival,
+                    from: None,
+                    duration: access_stop.duration,
+                });
+                marked[stop] = true;
+            }
+        }
+
+        for k in 1..=rounds {
+            let queue = self.collect_routes(&mut marked);
+            if queue.is_empty() {
+                break;
+            }
+
+            let mut ride_marked = Vec::new();
+            for (route_index, start_pos) in queue {
+                self.scan_route(
+                    route_index,
+                    start_pos,
+                    k,
+                    &mut rides,
+                    &walks,
+                    &mut best,
+                    &mut ride_marked,
+                );
+            }
+
+            for &stop in &ride_marked {
+                marked[stop] = true;
+            }
+            for &from in &ride_marked {
+                let arrival = rides[k][from].unwrap().arrival;
+                for transfer in &self.transfers[from] {
+                    let walk_arrival = arrival.saturating_add(transfer.duration);
+                    if walk_arrival < best[transfer.to] {
+                        best[transfer.to] = walk_arrival;
+                        walks[k][transfer.to] = Some(WalkLabel {
+                            arrival: walk_arrival,
+                            from: Some(from),
+                            duration: transfer.duration,
+                        });
+                        marked[transfer.to] = true;
+                    }
+                }
+            }
+        }
+
+        // find the label with the earliest arrival at the destination. Round 0 is skipped, as it
+        // does not contain any pt leg. Rounds are visited in ascending order, so that connections
+        // with fewer transfers win ties.
+        let mut target: Option<(SimTime, usize, usize, LabelKind, Duration)> = None;
+        for k in 1..=rounds {
+            for egress_stop in egress {
+                let Some(&stop) = self.stop_index.get(&egress_stop.stop) else {
+                    continue;
+                };
+                let candidates = [
+                    rides[k][stop].map(|l| (l.arrival, LabelKind::Ride)),
+                    walks[k][stop].map(|l| (l.arrival, LabelKind::Walk)),
+                ];
+                for (arrival, kind) in candidates.into_iter().flatten() {
+                    let arrival = arrival.saturating_add(egress_stop.duration);
+                    if target.is_none_or(|(best_arrival, ..)| arrival < best_arrival) {
+                        target = Some((arrival, k, stop, kind, egress_stop.duration));
+                    }
+                }
+            }
+        }
+
+        let (arrival, k, stop, kind, _) = target?;
+        Some(self.create_journey(&rides, &walks, arrival, k, stop, kind))
+    }
+
+    /// Collects the routes serving marked stops, together with the first marked position on the
+    /// route. Resets the marks.
+    fn collect_routes(&self, marked: &mut [bool]) -> Vec<(usize, usize)> {
+        let mut first_pos: IntMap<usize, usize> = IntMap::default();
+        for (stop, is_marked) in marked.iter_mut().enumerate() {
+            if !*is_marked {
+                continue;
+            }
+            *is_marked = false;
+            for &(route, pos) in &self.routes_per_stop[stop] {
+                first_pos
+                    .entry(route)
+                    .and_modify(|p| *p = (*p).min(pos))
+                    .or_insert(pos);
+            }
+        }
+        let mut queue: Vec<_> = first_pos.into_iter().collect();
+        queue.sort_unstable();
+        queue
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn scan_route(
+        &self,
+        route_index: usize,
+        start_pos: usize,
+        k: usize,
+        rides: &mut [Vec<Option<RideLabel>>],
+        walks: &[Vec<Option<WalkLabel>>],
+        best: &mut [SimTime],
+        ride_marked: &mut Vec<usize>,
+    ) {
+        let route = &self.routes[route_index];
+        // (trip, boarding position, boarded after walk)
+        let mut current: Option<(usize, usize, bool)> = None;
+
+        for pos in start_pos..route.stops.len() {
+            let stop = route.stops[pos];
+
+            if let Some((trip, board_pos, boarded_after_walk)) = current {
+                let arrival = route.trips[trip].arrivals[pos];
+                if route.allow_alighting[pos] && arrival < best[stop] {
+                    best[stop] = arrival;
+                    if rides[k][stop].is_none() {
+                        ride_marked.push(stop);
+                    }
+                    rides[k][stop] = Some(RideLabel {
+                        arrival,
+                        route: route_index,
+                        trip,
+                        board_pos,
+                        alight_pos: pos,
+                        boarded_after_walk,
+                    });
+                }
+            }
+
+            if !route.allow_boarding[pos] {
+                continue;
+            }
+            let Some((ready, after_walk)) = self.ready_to_board(&rides[k - 1], &walks[k - 1], stop)
+            else {
+                continue;
+            };
+            let earliest_trip = route.trips[..current.map_or(route.trips.len(), |(t, ..)| t)]
+                .partition_point(|trip| trip.departures[pos] < ready);
+            if current.is_none_or(|(trip, ..)| earliest_trip < trip)
+                && earliest_trip < route.trips.len()
+            {
+                current = Some((earliest_trip, pos, after_walk));
+            }
+        }
+    }
+
+    /// Returns the earliest time a passenger can board a vehicle at the stop, based on the labels
+    /// of the previous round, and whether the passenger arrived walking.
+    fn ready_to_board(
+        &self,
+        rides: &[Option<RideLabel>],
+        walks: &[Option<WalkLabel>],
+        stop: usize,
+    ) -> Option<(SimTime, bool)> {
+        let after_ride =
+            rides[stop].map(|label| (label.arrival.saturating_add(self.change_times[stop]), false));
+        let after_walk = walks[stop].map(|label| (label.arrival, true));
+        match (after_ride, after_walk) {
+            (Some(ride), Some(walk)) => Some(if walk.0 <= ride.0 { walk } else { ride }),
+            (ride, walk) => ride.or(walk),
+        }
+    }
+
+    fn create_journey(
+        &self,
+        rides: &[Vec<Option<RideLabel>>],
+        walks: &[Vec<Option<WalkLabel>>],
+        arrival: SimTime,
+        mut k: usize,
+        mut stop: usize,
+        mut kind: LabelKind,
+    ) -> Journey {
+        let egress_stop = self.stops[stop].clone();
+        let mut parts = Vec::new();
+
+        loop {
+            match kind {
+                LabelKind::Walk => {
+                    let label = walks[k][stop].expect("walk label must exist");
+                    let Some(from) = label.from else {
+                        break;
+                    };
+                    parts.push(JourneyPart::Transfer {
+                        from: self.stops[from].clone(),
+                        to: self.stops[stop].clone(),
+                        duration: label.duration,
+                    });
+                    stop = from;
+                    kind = LabelKind::Ride;
+                }
+                LabelKind::Ride => {
+                    let label = rides[k][stop].expect("ride label must exist");
+                    let route = &self.routes[label.route];
+                    let trip = &route.trips[label.trip];
+                    let board_stop = route.stops[label.board_pos];
+                    parts.push(JourneyPart::Ride {
+                        line: route.line.clone(),
+                        route: route.route.clone(),
+                        board_stop: self.stops[board_stop].clone(),
+                        alight_stop: self.stops[stop].clone(),
+                        departure: trip.departures[label.board_pos],
+                        arrival: label.arrival,
+                        distance: route.distances[label.alight_pos]
+                            - route.distances[label.board_pos],
+                    });
+                    stop = board_stop;
+                    kind = if label.boarded_after_walk {
+                        LabelKind::Walk
+                    } else {
+                        LabelKind::Ride
+                    };
+                    k -= 1;
+                }
+            }
+        }
+
+        parts.reverse();
+        Journey {
+            access_stop: self.stops[stop].clone(),
+            egress_stop,
+            parts,
+            arrival,
+        }
+    }
+}
+
+impl RaptorRoute {
+    fn new(
+        schedule: &TransitSchedule,
+        network: &Network,
+        stop_index: &IntMap<Id<TransitStopFacility>, usize>,
+        line: &TransitLine,
+        route: &TransitRoute,
+    ) -> Option<Self> {
+        if route.stops.len() < 2 || route.departures.is_empty() {
+            return None;
+        }
+
+        let stops = route
+            .stops
+            .iter()
+            .map(|stop| stop_index[&stop.facility_id])
+            .collect();
+
+        let mut trips: Vec<_> = route
+            .departures
+            .iter()
+            .map(|departure| {
+                let start = departure.departure_time;
+                let arrivals = route
+                    .stops
+                    .iter()
+                    .map(|stop| {
+                        start.saturating_add(
+                            stop.arrival_offset
+                                .or(stop.departure_offset)
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                let departures = route
+                    .stops
+                    .iter()
+                    .map(|stop| {
+                        start.saturating_add(
+                            stop.departure_offset
+                                .or(stop.arrival_offset)
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                RaptorTrip {
+                    arrivals,
+                    departures,
+                }
+            })
+            .collect();
+        trips.sort_by_key(|trip: &RaptorTrip| trip.departures[0]);
+
+        Some(RaptorRoute {
+            line: line.id.clone(),
+            route: route.id.clone(),
+            stops,
+            allow_boarding: route.stops.iter().map(|s| s.allow_boarding).collect(),
+            allow_alighting: route.stops.iter().map(|s| s.allow_alighting).collect(),
+            distances: Self::stop_distances(schedule, network, route),
+            trips,
+        })
+    }
+
+    /// Calculates the network distance from the first stop to each stop along the network route.
+    /// Vehicles start at the end of the first link, so its length is not counted.
+    fn stop_distances(
+        schedule: &TransitSchedule,
+        network: &Network,
+        route: &TransitRoute,
+    ) -> Vec<f64> {
+        let mut cumulative = Vec::with_capacity(route.network_route.len());
+        let mut sum = 0.;
+        for (index, link) in route.network_route.iter().enumerate() {
+            if index > 0 {
+                sum += network.get_link(link).length;
+            }
+            cumulative.push(sum);
+        }
+
+        let mut cursor = 0;
+        route
+            .stops
+            .iter()
+            .map(|stop| {
+                let link = &schedule.get_facility(&stop.facility_id).link_ref_id;
+                if let Some(offset) = route.network_route[cursor..]
+                    .iter()
+                    .position(|l| Some(l) == link.as_ref())
+                {
+                    cursor += offset;
+                }
+                cumulative.get(cursor).copied().unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JourneyPart, RaptorData, StopAccess, TransferParams};
+    use crate::simulation::InternalAttributes;
+    use crate::simulation::id::Id;
+    use crate::simulation::pt::{
+        MinimalTransferTime, TransitDeparture, TransitLine, TransitRoute, TransitRouteStop,
+        TransitSchedule, TransitStopFacility,
+    };
+    use crate::simulation::scenario::Coordinate;
+    use crate::simulation::scenario::network::Network;
+    use crate::simulation::time::SimTime;
+    use macros::deterministic_id_test;
+    use std::time::Duration;
+
+    /// Stops a, b, b2, c and d on the x axis. Line red runs from a to b, line green from b to c
+    /// and line blue from b2 to d. b2 is 100m away from b.
+    fn schedule(minimal_transfer_times: Vec<MinimalTransferTime>) -> TransitSchedule {
+        let facilities = [
+            ("a", 0.),
+            ("b", 1000.),
+            ("b2", 1100.),
+            ("c", 2000.),
+            ("d", 3000.),
+        ]
+        .into_iter()
+        .map(|(id, x)| {
+            let facility = TransitStopFacility {
+                id: Id::create(id),
+                coord: Coordinate::new_2d(x, 0.),
+                link_ref_id: None,
+                name: None,
+                stop_area_id: None,
+                is_blocking: None,
+                attributes: InternalAttributes::default(),
+            };
+            (facility.id.clone(), facility)
+        })
+        .collect();
+        let lines = [
+            line("red", "a", "b", &[100]),
+            line("green", "b", "c", &[210, 400]),
+            line("blue", "b2", "d", &[300]),
+        ]
+        .into_iter()
+        .map(|line| (line.id.clone(), line))
+        .collect();
+
+        TransitSchedule::new(
+            lines,
+            facilities,
+            minimal_transfer_times,
+            InternalAttributes::default(),
+        )
+    }
+
+    /// A line with a single route between two stops, which takes 100s.
+    fn line(id: &str, from: &str, to: &str, departures: &[u64]) -> TransitLine {
+        let route = TransitRoute {
+            id: Id::create(id),
+            description: None,
+            transport_mode: Id::create("bus"),
+            stops: [(from, 0), (to, 100)]
+                .into_iter()
+                .map(|(stop, offset)| TransitRouteStop {
+                    facility_id: Id::create(stop),
+                    arrival_offset: Some(Duration::from_secs(offset)),
+                    departure_offset: Some(Duration::from_secs(offset)),
+                    await_departure: None,
+                    allow_boarding: true,
+                    allow_alighting: true,
+                    minimum_stop_duration: Duration::ZERO,
+                })
+                .collect(),
+            network_route: Vec::new(),
+            departures: departures
+                .iter()
+                .map(|time| TransitDeparture {
+                    id: Id::create(&format!("{id}_{time}")),
+                    departure_time: SimTime::from_secs(*time),
+                    vehicle_ref_id: None,
+                    attributes: InternalAttributes::default(),
+                })
+                .collect(),
+            attributes: InternalAttributes::default(),
+        };
+        TransitLine {
+            id: Id::create(id),
+            name: String::new(),
+            routes: [(route.id.clone(), route)].into_iter().collect(),
+            attributes: InternalAttributes::default(),
+        }
+    }
+
+    fn raptor(
+        minimal_transfer_times: Vec<MinimalTransferTime>,
+        max_beeline_distance: f64,
+    ) -> RaptorData {
+        RaptorData::new(
+            &schedule(minimal_transfer_times),
+            &Network::new(),
+            TransferParams {
+                max_beeline_distance,
+                beeline_distance_factor: 1.,
+                walk_speed: 1.,
+            },
+        )
+    }
+
+    fn at(stop: &str) -> Vec<StopAccess> {
+        vec![StopAccess {
+            stop: Id::get_from_ext(stop),
+            duration: Duration::ZERO,
+        }]
+    }
+
+    fn ride_lines(parts: &[JourneyPart]) -> Vec<&str> {
+        parts
+            .iter()
+            .filter_map(|part| match part {
+                JourneyPart::Ride { line, .. } => Some(line.external()),
+                JourneyPart::Transfer { .. } => None,
+            })
+            .collect()
+    }
+
+    #[deterministic_id_test]
+    fn max_transfers_limits_number_of_rides() {
+        let raptor = raptor(Vec::new(), 150.);
+
+        let journey = raptor
+            .calc_earliest_arrival(&at("a"), &at("c"), SimTime::from_secs(0), 1)
+            .unwrap();
+        assert_eq!(vec!["red", "green"], ride_lines(&journey.parts));
+        assert_eq!(SimTime::from_secs(310), journey.arrival);
+
+        let no_transfer =
+            raptor.calc_earliest_arrival(&at("a"), &at("c"), SimTime::from_secs(0), 0);
+        assert_eq!(None, no_transfer);
+    }
+
+    #[deterministic_id_test]
+    fn change_time_at_stop_delays_boarding() {
+        let raptor = raptor(
+            vec![MinimalTransferTime {
+                from_stop: Id::create("b"),
+                to_stop: Id::create("b"),
+                transfer_time: 30.,
+            }],
+            150.,
+        );
+
+        let journey = raptor
+            .calc_earliest_arrival(&at("a"), &at("c"), SimTime::from_secs(0), 1)
+            .unwrap();
+
+        // red arrives at b at 200s, so the green departure at 210s is missed
+        assert_eq!(vec!["red", "green"], ride_lines(&journey.parts));
+        assert_eq!(SimTime::from_secs(500), journey.arrival);
+    }
+
+    #[deterministic_id_test]
+    fn walk_transfer_connects_stops_within_max_beeline_distance() {
+        let raptor = raptor(Vec::new(), 150.);
+
+        let journey = raptor
+            .calc_earliest_arrival(&at("a"), &at("d"), SimTime::from_secs(0), 1)
+            .unwrap();
+
+        assert_eq!(vec!["red", "blue"], ride_lines(&journey.parts));
+        assert_eq!(
+            JourneyPart::Transfer {
+                from: Id::get_from_ext("b"),
+                to: Id::get_from_ext("b2"),
+                duration: Duration::from_secs(100),
+            },
+            journey.parts[1]
+        );
+        assert_eq!(SimTime::from_secs(400), journey.arrival);
+    }
+
+    #[deterministic_id_test]
+    fn no_walk_transfer_between_stops_beyond_max_beeline_distance() {
+        let raptor = raptor(Vec::new(), 50.);
+
+        let journey = raptor.calc_earliest_arrival(&at("a"), &at("d"), SimTime::from_secs(0), 1);
+
+        assert_eq!(None, journey);
+    }
+}
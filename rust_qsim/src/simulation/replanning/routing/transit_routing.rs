@@ -0,0 +1,462 @@
+use crate::simulation::id::Id;
+use crate::simulation::pt::TransitStopFacility;
+use crate::simulation::replanning::routing::raptor::{
+    Journey, JourneyPart, RaptorData, StopAccess, TransferParams,
+};
+use crate::simulation::replanning::routing::{
+    RoutingError, RoutingModule, RoutingRequest, RoutingRequestBuilder,
+};
+use crate::simulation::scenario::ScenarioCore;
+use crate::simulation::scenario::facilities::Facility;
+use crate::simulation::scenario::population::{
+    InternalActivity, InternalGenericRoute, InternalLeg, InternalPlanElement, InternalPtRoute,
+    InternalPtRouteDescription, InternalRoute,
+};
+use crate::simulation::time::SimTime;
+use crate::simulation::time::time_interpretation::TimeInterpretation;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Routes trips on the transit schedule. Connections are searched with RAPTOR, access and egress
+/// as well as transfers between stops are routed with the access/egress router.
+pub struct TransitRoutingModule {
+    mode: Id<String>,
+    access_egress_router: Arc<dyn RoutingModule>,
+    raptor: RaptorData,
+    scenario: ScenarioCore,
+}
+
+impl RoutingModule for TransitRoutingModule {
+    fn calc_route(
+        &self,
+        request: RoutingRequest,
+    ) -> Result<Vec<InternalPlanElement>, RoutingError> {
+        let params = &self.scenario.config.routing().transit_routing;
+        let departure_time = request.departure_time;
+
+        let access = self.stop_accesses(&request, request.from, true)?;
+        let egress = self.stop_accesses(&request, request.to, false)?;
+        let journey = self.raptor.calc_earliest_arrival(
+            &access,
+            &egress,
+            departure_time,
+            params.max_transfers,
+        );
+
+        // walk directly, if there is no connection or walking is faster.
+        let (direct_walk, direct_arrival) =
+            self.walk(&request, request.from, request.to, departure_time)?;
+        match journey {
+            Some(journey) if journey.arrival < direct_arrival => {
+                self.journey_to_elements(&request, journey)
+            }
+            _ => Ok(direct_walk),
+        }
+    }
+
+    fn mode(&self) -> &Id<String> {
+        &self.mode
+    }
+}
+
+impl TransitRoutingModule {
+    pub fn new(
+        mode: Id<String>,
+        access_egress: Arc<dyn RoutingModule>,
+        scenario: ScenarioCore,
+    ) -> Self {
+        let routing = scenario.config.routing();
+        let walk_params = routing
+            .teleported_mode_params
+            .iter()
+            .find(|p| p.mode == routing.access_egress_mode)
+            .or_else(|| {
+                routing
+                    .teleported_mode_params
+                    .iter()
+                    .find(|p| p.mode == "walk")
+            })
+            .expect("Teleported mode params must contain parameters for walking.");
+        let transfer_params = TransferParams {
+            max_beeline_distance: routing.transit_routing.max_beeline_walk_connection_distance,
+            beeline_distance_factor: walk_params.beeline_distance_factor,
+            walk_speed: walk_params.teleported_mode_speed,
+        };
+        let raptor = RaptorData::new(
+            &scenario.transit_schedule,
+            &scenario.network,
+            transfer_params,
+        );
+
+        TransitRoutingModule {
+            mode,
+            access_egress_router: access_egress,
+            raptor,
+            scenario,
+        }
+    }
+
+    /// Calculates the walking time between the facility and all stops around it. For access, the
+    /// walk starts at the facility, for egress it ends there.
+    fn stop_accesses(
+        &self,
+        request: &RoutingRequest,
+        facility: &Facility,
+        is_access: bool,
+    ) -> Result<Vec<StopAccess>, RoutingError> {
+        let params = &self.scenario.config.routing().transit_routing;
+        let mut result = Vec::new();
+        for stop in self.raptor.find_stops(
+            facility.coord(),
+            params.search_radius,
+            params.extension_radius,
+        ) {
+            let stop_facility = self.stop_facility(stop);
+            let (from, to) = if is_access {
+                (facility, &stop_facility)
+            } else {
+                (&stop_facility, facility)
+            };
+            let (_, arrival) = self.walk(request, from, to, request.departure_time)?;
+            result.push(StopAccess {
+                stop: stop.clone(),
+                duration: arrival.duration_since(request.departure_time),
+            });
+        }
+        Ok(result)
+    }
+
+    fn journey_to_elements(
+        &self,
+        request: &RoutingRequest,
+        journey: Journey,
+    ) -> Result<Vec<InternalPlanElement>, RoutingError> {
+        let mut result = Vec::with_capacity(journey.parts.len() * 2 + 3);
+
+        // ====== access walk to the first stop
+        let access_stop = self.stop_facility(&journey.access_stop);
+        let (access, mut now) =
+            self.walk(request, request.from, &access_stop, request.departure_time)?;
+        result.extend(access);
+        result.push(self.create_interaction_activity(&access_stop));
+
+        // ====== pt legs and transfers between them
+        for part in &journey.parts {
+            match part {
+                JourneyPart::Ride {
+                    line,
+                    route,
+                    board_stop,
+                    alight_stop,
+                    departure,
+                    arrival,
+                    distance,
+                } => {
+                    let board_facility = self.stop_facility(board_stop);
+                    let alight_facility = self.stop_facility(alight_stop);
+                    // the travel time includes waiting for the vehicle at the stop.
+                    let trav_time = arrival.duration_since(now);
+                    let generic = InternalGenericRoute::new(
+                        board_facility.link().clone(),
+                        alight_facility.link().clone(),
+                        Some(trav_time),
+                        Some(*distance),
+                        None,
+                    );
+                    let description = InternalPtRouteDescription {
+                        transit_route_id: route.external().to_string(),
+                        boarding_time: Some(departure.as_duration()),
+                        transit_line_id: line.external().to_string(),
+                        access_facility_id: board_stop.external().to_string(),
+                        egress_facility_id: alight_stop.external().to_string(),
+                    };
+                    let route = InternalRoute::Pt(InternalPtRoute {
+                        generic_delegate: generic,
+                        description,
+                    });
+                    result.push(InternalPlanElement::Leg(InternalLeg::new(
+                        route,
+                        self.mode.external(),
+                        trav_time,
+                        Some(now),
+                    )));
+                    result.push(self.create_interaction_activity(&alight_facility));
+                    now = *arrival;
+                }
+                JourneyPart::Transfer { from, to, duration } => {
+                    let from_facility = self.stop_facility(from);
+                    let to_facility = self.stop_facility(to);
+                    let (mut transfer, _) =
+                        self.walk(request, &from_facility, &to_facility, now)?;
+                    // the transfer takes the time of the schedule, which honours minimal transfer
+                    // times.
+                    for element in &mut transfer {
+                        if let InternalPlanElement::Leg(leg) = element {
+                            leg.trav_time = Some(*duration);
+                            if let Some(InternalRoute::Generic(route)) = leg.route.as_mut() {
+                                *route = InternalGenericRoute::new(
+                                    route.start_link().clone(),
+                                    route.end_link().clone(),
+                                    Some(*duration),
+                                    route.distance(),
+                                    route.vehicle().clone(),
+                                );
+                            }
+                        }
+                    }
+                    result.extend(transfer);
+                    result.push(self.create_interaction_activity(&to_facility));
+                    now = now.saturating_add(*duration);
+                }
+            }
+        }
+
+        // ====== egress walk from the last stop
+        let egress_stop = self.stop_facility(&journey.egress_stop);
+        let (egress, _) = self.walk(request, &egress_stop, request.to, now)?;
+        result.extend(egress);
+
+        Ok(result)
+    }
+
+    /// Routes a walk with the access/egress router and returns the elements together with the
+    /// arrival time.
+    fn walk(
+        &self,
+        original_request: &RoutingRequest,
+        from: &Facility,
+        to: &Facility,
+        departure_time: SimTime,
+    ) -> Result<(Vec<InternalPlanElement>, SimTime), RoutingError> {
+        let new_req = RoutingRequestBuilder::default()
+            .from(from)
+            .to(to)
+            .departure_time(departure_time)
+            .attributes(original_request.attributes.clone())
+            .person(original_request.person)
+            .build()
+            .unwrap();
+
+        let elements = self.access_egress_router.calc_route(new_req)?;
+        let arrival = TimeInterpretation::decide_on_elements_end_time(&elements, &departure_time)
+            .ok_or_else(|| RoutingError::MissingEndTime {
+            mode: self.mode.external().to_string(),
+        })?;
+        Ok((elements, arrival))
+    }
+
+    fn stop_facility(&self, id: &Id<TransitStopFacility>) -> Facility {
+        Facility::TransitFacility(self.scenario.transit_schedule.get_facility(id).clone())
+    }
+
+    fn create_interaction_activity(&self, stop: &Facility) -> InternalPlanElement {
+        InternalPlanElement::Activity(InternalActivity::new(
+            Some(stop.coord().clone()),
+            &format!("{} interaction", self.mode.external()),
+            stop.link().clone(),
+            None,
+            None,
+            Some(Duration::from_secs(0)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransitRoutingModule;
+    use crate::simulation::config::Config;
+    use crate::simulation::id::Id;
+    use crate::simulation::io::xml::transit::IOTransitSchedule;
+    use crate::simulation::pt::TransitSchedule;
+    use crate::simulation::replanning::routing::teleportation::TeleportationRoutingModule;
+    use crate::simulation::replanning::routing::{RoutingModule, RoutingRequestBuilder};
+    use crate::simulation::scenario::facilities::{
+        ActivityFacilities, Facility, LinkWrapperFacility,
+    };
+    use crate::simulation::scenario::network::Network;
+    use crate::simulation::scenario::population::{InternalLeg, InternalPlanElement};
+    use crate::simulation::scenario::vehicles::Garage;
+    use crate::simulation::scenario::{Coordinate, ScenarioCore};
+    use crate::simulation::time::SimTime;
+    use assert_approx_eq::assert_approx_eq;
+    use macros::deterministic_id_test;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Two lines on the tutorial network. Line A ends at stop 2a, line B starts at stop 2c, which
+    /// is 20m away but requires a transfer time of 10 minutes.
+    const TRANSFER_SCHEDULE: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <transitSchedule>\
+            <transitStops>\
+                <stopFacility id=\"1\" x=\"1050\" y=\"1050\" linkRefId=\"11\"/>\
+                <stopFacility id=\"2a\" x=\"2050\" y=\"2940\" linkRefId=\"12\"/>\
+                <stopFacility id=\"2c\" x=\"2050\" y=\"2960\" linkRefId=\"12\"/>\
+                <stopFacility id=\"3\" x=\"3950\" y=\"1050\" linkRefId=\"33\"/>\
+            </transitStops>\
+            <minimalTransferTimes>\
+                <relation fromStop=\"2a\" toStop=\"2c\" transferTime=\"600\"/>\
+            </minimalTransferTimes>\
+            <transitLine id=\"A\">\
+                <transitRoute id=\"1to2a\">\
+                    <transportMode>train</transportMode>\
+                    <routeProfile>\
+                        <stop refId=\"1\" departureOffset=\"00:00:00\"/>\
+                        <stop refId=\"2a\" arrivalOffset=\"00:03:20\"/>\
+                    </routeProfile>\
+                    <route>\
+                        <link refId=\"11\"/>\
+                        <link refId=\"12\"/>\
+                    </route>\
+                    <departures>\
+                        <departure id=\"1\" departureTime=\"06:00:00\"/>\
+                    </departures>\
+                </transitRoute>\
+            </transitLine>\
+            <transitLine id=\"B\">\
+                <transitRoute id=\"2cto3\">\
+                    <transportMode>train</transportMode>\
+                    <routeProfile>\
+                        <stop refId=\"2c\" departureOffset=\"00:00:00\"/>\
+                        <stop refId=\"3\" arrivalOffset=\"00:05:00\"/>\
+                    </routeProfile>\
+                    <route>\
+                        <link refId=\"12\"/>\
+                        <link refId=\"23\"/>\
+                        <link refId=\"33\"/>\
+                    </route>\
+                    <departures>\
+                        <departure id=\"1\" departureTime=\"06:05:00\"/>\
+                        <departure id=\"2\" departureTime=\"06:20:00\"/>\
+                    </departures>\
+                </transitRoute>\
+            </transitLine>\
+        </transitSchedule>";
+
+    #[deterministic_id_test]
+    fn routes_pt_trip_on_tutorial_schedule() {
+        let schedule =
+            TransitSchedule::from_file("./assets/pt_tutorial/transitschedule.xml".as_ref());
+        let elements = calc_route(schedule, SimTime::from_secs(6 * 3600 + 5 * 60));
+
+        assert_eq!(5, elements.len());
+        assert_eq!("walk", leg_at(&elements, 0).mode.external());
+        assert_eq!("walk", leg_at(&elements, 4).mode.external());
+
+        let pt = leg_at(&elements, 2);
+        assert_eq!("pt", pt.mode.external());
+        // walking to the stop takes no time, so the passenger waits at the stop until 06:15.
+        assert_eq!(Some(SimTime::from_secs(6 * 3600 + 5 * 60)), pt.dep_time);
+        assert_eq!(Some(Duration::from_secs(19 * 60)), pt.trav_time);
+
+        let route = pt.route.as_ref().unwrap().as_pt().unwrap();
+        assert_eq!("11", route.start_link().external());
+        assert_eq!("33", route.end_link().external());
+        assert_approx_eq!(6100., route.generic_delegate().distance().unwrap());
+
+        let description = route.description();
+        assert_eq!("Blue Line", description.transit_line_id);
+        assert_eq!("1to3", description.transit_route_id);
+        assert_eq!("1", description.access_facility_id);
+        assert_eq!("3", description.egress_facility_id);
+        assert_eq!(
+            Some(Duration::from_secs(6 * 3600 + 15 * 60)),
+            description.boarding_time
+        );
+    }
+
+    #[deterministic_id_test]
+    fn honours_minimal_transfer_time() {
+        let schedule = TransitSchedule::from(
+            quick_xml::de::from_str::<IOTransitSchedule>(TRANSFER_SCHEDULE).unwrap(),
+        );
+        let elements = calc_route(schedule, SimTime::from_secs(6 * 3600));
+
+        // walk, interaction, pt, interaction, walk, interaction, pt, interaction, walk
+        assert_eq!(9, elements.len());
+        let first = leg_at(&elements, 2)
+            .route
+            .as_ref()
+            .unwrap()
+            .as_pt()
+            .unwrap();
+        assert_eq!("A", first.description().transit_line_id);
+        assert_eq!("2a", first.description().egress_facility_id);
+
+        let transfer = leg_at(&elements, 4);
+        assert_eq!("walk", transfer.mode.external());
+        assert_eq!(Some(Duration::from_secs(600)), transfer.trav_time);
+
+        // the departure at 06:05 can't be reached, as the transfer takes until 06:13:20.
+        let second = leg_at(&elements, 6)
+            .route
+            .as_ref()
+            .unwrap()
+            .as_pt()
+            .unwrap();
+        assert_eq!("B", second.description().transit_line_id);
+        assert_eq!("2c", second.description().access_facility_id);
+        assert_eq!(
+            Some(Duration::from_secs(6 * 3600 + 20 * 60)),
+            second.description().boarding_time
+        );
+    }
+
+    #[deterministic_id_test]
+    fn walks_if_there_is_no_connection() {
+        let schedule =
+            TransitSchedule::from_file("./assets/pt_tutorial/transitschedule.xml".as_ref());
+        let elements = calc_route(schedule, SimTime::from_secs(23 * 3600 + 30 * 60));
+
+        assert_eq!(1, elements.len());
+        assert_eq!("walk", leg_at(&elements, 0).mode.external());
+    }
+
+    fn calc_route(schedule: TransitSchedule, departure_time: SimTime) -> Vec<InternalPlanElement> {
+        let network = Arc::new(Network::from_file_as_is(&PathBuf::from(
+            "./assets/pt_tutorial/multimodalnetwork.xml",
+        )));
+        let module = TransitRoutingModule::new(
+            Id::create("pt"),
+            Arc::new(TeleportationRoutingModule::new(
+                Id::create("walk"),
+                1.3,
+                3. / 3.6,
+            )),
+            ScenarioCore {
+                network,
+                garage: Arc::new(Garage::default()),
+                transit_schedule: Arc::new(schedule),
+                transit_vehicles: Arc::new(Garage::default()),
+                facilities: Arc::new(ActivityFacilities::default()),
+                config: Arc::new(Config::default()),
+            },
+        );
+
+        let from = facility(1050., 1050., "11");
+        let to = facility(3950., 1050., "33");
+        let request = RoutingRequestBuilder::default()
+            .from(&from)
+            .to(&to)
+            .departure_time(departure_time)
+            .build()
+            .unwrap();
+
+        module.calc_route(request).unwrap()
+    }
+
+    fn facility(x: f64, y: f64, link_id: &str) -> Facility {
+        Facility::LinkWrapperFacility(LinkWrapperFacility {
+            link_id: Id::create(link_id),
+            coord: Coordinate::new_2d(x, y),
+            mode_to_link: Default::default(),
+        })
+    }
+
+    fn leg_at(elements: &[InternalPlanElement], index: usize) -> &InternalLeg {
+        let InternalPlanElement::Leg(leg) = &elements[index] else {
+            panic!("Expected leg at index {index}");
+        };
+        leg
+    }
+}
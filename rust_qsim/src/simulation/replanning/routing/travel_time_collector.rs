@@ -1,38 +1,72 @@
+use crate::simulation::engines::drt_engine::DVRP_MODE_ATTRIBUTE;
 use crate::simulation::events::{
     EventHandlerRegisterFn, LinkEnterEvent, LinkLeaveEvent, PersonLeavesVehicleEvent,
 };
 use crate::simulation::id::Id;
+use crate::simulation::replanning::routing::least_cost_path_calculator::{
+    Disutility, TravelDisutility, TravelTime,
+};
 use crate::simulation::scenario::network::Link;
-use crate::simulation::scenario::vehicles::InternalVehicle;
+use crate::simulation::scenario::population::InternalPerson;
+use crate::simulation::scenario::vehicles::{Garage, InternalVehicle};
 use crate::simulation::time::SimTime;
-use nohash_hasher::IntMap;
-use std::cell::RefCell;
+use nohash_hasher::{IntMap, IntSet};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
-pub struct TravelTimeCollector {
-    travel_times_by_link: HashMap<Id<Link>, Vec<Duration>>,
-    cache_enter_time_by_vehicle: IntMap<Id<InternalVehicle>, SimTime>,
+/// Returns the network mode of every vehicle in `garage` whose vehicle type is driven on one of
+/// the routed `modes`. DRT vehicles are left out, as their travel times include the stops. Transit
+/// vehicles are kept in a garage of their own and are thus never part of the result.
+pub fn routed_vehicle_modes(
+    garage: &Garage,
+    modes: &IntSet<Id<String>>,
+) -> IntMap<Id<InternalVehicle>, Id<String>> {
+    garage
+        .vehicles
+        .values()
+        .filter(|vehicle| {
+            vehicle
+                .attributes
+                .get::<String>(DVRP_MODE_ATTRIBUTE)
+                .is_none()
+        })
+        .filter_map(|vehicle| {
+            let net_mode = &garage.vehicle_types.get(&vehicle.vehicle_type)?.net_mode;
+            modes
+                .contains(net_mode)
+                .then(|| (vehicle.id.clone(), net_mode.clone()))
+        })
+        .collect()
 }
 
-impl Default for TravelTimeCollector {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Collects the link travel times per network mode of the vehicles in `vehicle_modes`. All other
+/// vehicles, e.g., transit and DRT vehicles, are ignored, so that their dwell times at stops don't
+/// end up in the travel times of the routed modes. As the mode is looked up by vehicle, this holds
+/// on every partition a vehicle passes, not only on the one where it entered traffic.
+#[derive(Debug)]
+pub struct TravelTimeCollector {
+    vehicle_modes: Arc<IntMap<Id<InternalVehicle>, Id<String>>>,
+    // travel times are stored together with the link enter time, so that they can be binned later
+    travel_times_by_mode: IntMap<Id<String>, HashMap<Id<Link>, Vec<(SimTime, Duration)>>>,
+    cache_enter_time_by_vehicle: IntMap<Id<InternalVehicle>, SimTime>,
 }
 
 #[allow(dead_code)]
 impl TravelTimeCollector {
-    pub fn new() -> Self {
+    pub fn new(vehicle_modes: Arc<IntMap<Id<InternalVehicle>, Id<String>>>) -> Self {
         TravelTimeCollector {
-            travel_times_by_link: HashMap::new(),
+            vehicle_modes,
+            travel_times_by_mode: IntMap::default(),
             cache_enter_time_by_vehicle: IntMap::default(),
         }
     }
 
     fn process_link_enter_event(&mut self, event: &LinkEnterEvent) {
-        // link enter events will always be stored
+        // link enter events are stored for all vehicles of the collected modes
+        if !self.vehicle_modes.contains_key(&event.vehicle) {
+            return;
+        }
         let time = event.time;
         self.cache_enter_time_by_vehicle
             .insert(event.vehicle.clone(), time);
@@ -43,10 +77,14 @@ impl TravelTimeCollector {
         // if it's None, the LinkLeaveEvent is the begin of a leg, thus no travel time can be computed
         let time = event.time;
         if let Some(t) = self.cache_enter_time_by_vehicle.remove(&event.vehicle) {
-            self.travel_times_by_link
+            // only vehicles of the collected modes are cached
+            let mode = &self.vehicle_modes[&event.vehicle];
+            self.travel_times_by_mode
+                .entry(mode.clone())
+                .or_default()
                 .entry(event.link.clone())
                 .or_default()
-                .push(time.duration_since(t))
+                .push((t, time.duration_since(t)))
         }
     }
 
@@ -54,21 +92,27 @@ impl TravelTimeCollector {
         self.cache_enter_time_by_vehicle.remove(&event.vehicle);
     }
 
-    fn get_travel_time_of_link(&self, link: &Id<Link>) -> Option<u32> {
-        match self.travel_times_by_link.get(link) {
+    fn get_travel_time_of_link(&self, mode: &Id<String>, link: &Id<Link>) -> Option<u32> {
+        match self
+            .travel_times_by_mode
+            .get(mode)
+            .and_then(|links| links.get(link))
+        {
             None => None,
             Some(travel_times) => {
-                let sum: Duration = travel_times.iter().copied().sum();
+                let sum: Duration = travel_times.iter().map(|(_, duration)| *duration).sum();
                 let len = travel_times.len();
                 Some((sum / (len as u32)).as_secs() as u32)
             }
         }
     }
 
-    fn get_travel_times(&self) -> HashMap<Id<Link>, u32> {
-        self.travel_times_by_link
-            .keys()
-            .map(|id| (id.clone(), self.get_travel_time_of_link(id)))
+    fn get_travel_times(&self, mode: &Id<String>) -> HashMap<Id<Link>, u32> {
+        self.travel_times_by_mode
+            .get(mode)
+            .into_iter()
+            .flat_map(|links| links.keys())
+            .map(|id| (id.clone(), self.get_travel_time_of_link(mode, id)))
             .filter(|(_, travel_time)| travel_time.is_some())
             .map(|(id, travel_time)| (id, travel_time.unwrap()))
             .collect::<HashMap<Id<Link>, u32>>()
@@ -78,40 +122,233 @@ impl TravelTimeCollector {
         // Collected travel times will be dropped, but cached values not.
         // Vehicles of cached values haven't left the corresponding links yet.
         // A travel time of a link is considered when a vehicle leaves the link.
-        self.travel_times_by_link = HashMap::new();
+        self.travel_times_by_mode = IntMap::default();
     }
 
-    pub fn register() -> Box<EventHandlerRegisterFn> {
-        Box::new(move |e| {
-            let ttc = Rc::new(RefCell::new(TravelTimeCollector::new()));
-
-            let ttc1 = ttc.clone();
-            let ttc2 = ttc.clone();
+    fn reset(&mut self) {
+        self.flush();
+        self.cache_enter_time_by_vehicle.clear();
+    }
 
+    /// Registers the given collector with an events manager. The collector is reset at the start
+    /// of every iteration, so that it only holds the travel times of the current iteration.
+    pub fn register(collector: Arc<Mutex<TravelTimeCollector>>) -> Box<EventHandlerRegisterFn> {
+        Box::new(move |e| {
+            let ttc = collector.clone();
+            e.on_reset_iteration(move |_| {
+                ttc.lock().unwrap().reset();
+            });
+            let ttc = collector.clone();
             e.on::<LinkEnterEvent, _>(move |e| {
-                ttc.borrow_mut().process_link_enter_event(e);
+                ttc.lock().unwrap().process_link_enter_event(e);
             });
+            let ttc = collector.clone();
             e.on::<LinkLeaveEvent, _>(move |e| {
-                ttc1.borrow_mut().process_link_leave_event(e);
+                ttc.lock().unwrap().process_link_leave_event(e);
             });
+            let ttc = collector;
             e.on::<PersonLeavesVehicleEvent, _>(move |e| {
-                ttc2.borrow_mut().process_person_leaves_vehicle_event(e);
+                ttc.lock().unwrap().process_person_leaves_vehicle_event(e);
             })
         })
     }
 }
 
+/// Link travel times per network mode, observed during the last mobsim run. Travel times are
+/// collected on every partition by a [`TravelTimeCollector`] and merged by
+/// [`TimeBinnedTravelTimeAndDisutility::update`] into time bins of a fixed size. A travel time is
+/// assigned to the bin in which the vehicle entered the link. The router of a mode uses the view
+/// returned by [`TimeBinnedTravelTimeAndDisutility::for_mode`].
+#[derive(Debug)]
+pub struct TimeBinnedTravelTimeAndDisutility {
+    bin_size: u64,
+    collectors: Vec<Arc<Mutex<TravelTimeCollector>>>,
+    travel_times: RwLock<IntMap<Id<String>, IntMap<Id<Link>, Vec<Option<Duration>>>>>,
+}
+
+impl TimeBinnedTravelTimeAndDisutility {
+    /// Creates travel times for `num_parts` partitions with bins of `bin_size` seconds, observed
+    /// on the vehicles in `vehicle_modes`, see [`routed_vehicle_modes`].
+    pub fn new(
+        num_parts: u32,
+        bin_size: u32,
+        vehicle_modes: IntMap<Id<InternalVehicle>, Id<String>>,
+    ) -> Self {
+        assert!(bin_size > 0, "Travel time bin size must be greater than 0.");
+        let vehicle_modes = Arc::new(vehicle_modes);
+        TimeBinnedTravelTimeAndDisutility {
+            bin_size: bin_size as u64,
+            collectors: (0..num_parts)
+                .map(|_| Arc::new(Mutex::new(TravelTimeCollector::new(vehicle_modes.clone()))))
+                .collect(),
+            travel_times: RwLock::new(IntMap::default()),
+        }
+    }
+
+    /// Returns the register function for the travel time collector of the given partition.
+    pub fn register(&self, rank: u32) -> Box<EventHandlerRegisterFn> {
+        TravelTimeCollector::register(self.collectors[rank as usize].clone())
+    }
+
+    /// Returns the travel times of the given network mode.
+    pub fn for_mode(self: &Arc<Self>, mode: Id<String>) -> ModeTravelTimeAndDisutility {
+        ModeTravelTimeAndDisutility {
+            mode,
+            travel_times: self.clone(),
+        }
+    }
+
+    /// Replaces the travel times with the average travel times per mode, link and time bin,
+    /// collected on all partitions since the start of the current iteration.
+    pub fn update(&self) {
+        let mut sums: IntMap<Id<String>, IntMap<Id<Link>, Vec<(Duration, u32)>>> =
+            IntMap::default();
+        for collector in &self.collectors {
+            let collector = collector.lock().unwrap();
+            for (mode, travel_times_by_link) in &collector.travel_times_by_mode {
+                let links = sums.entry(mode.clone()).or_default();
+                for (link, travel_times) in travel_times_by_link {
+                    let bins = links.entry(link.clone()).or_default();
+                    for (enter_time, duration) in travel_times {
+                        let bin = self.bin(*enter_time);
+                        if bins.len() <= bin {
+                            bins.resize(bin + 1, (Duration::ZERO, 0));
+                        }
+                        bins[bin].0 += *duration;
+                        bins[bin].1 += 1;
+                    }
+                }
+            }
+        }
+
+        *self.travel_times.write().unwrap() = sums
+            .into_iter()
+            .map(|(mode, links)| {
+                let links = links
+                    .into_iter()
+                    .map(|(link, bins)| {
+                        let averages = bins
+                            .into_iter()
+                            .map(|(sum, count)| (count > 0).then(|| sum / count))
+                            .collect();
+                        (link, averages)
+                    })
+                    .collect();
+                (mode, links)
+            })
+            .collect();
+    }
+
+    fn observed_travel_time(
+        &self,
+        mode: &Id<String>,
+        link: &Id<Link>,
+        time: SimTime,
+    ) -> Option<Duration> {
+        self.travel_times
+            .read()
+            .unwrap()
+            .get(mode)
+            .and_then(|links| links.get(link))
+            .and_then(|bins| bins.get(self.bin(time)).copied().flatten())
+    }
+
+    fn bin(&self, time: SimTime) -> usize {
+        (time.as_secs() / self.bin_size) as usize
+    }
+
+    fn freespeed_travel_time(link: &Link, departure_time: SimTime) -> Duration {
+        Duration::from_secs_f64(link.length / link.freespeed_at(departure_time))
+    }
+}
+
+/// An implementation of both `TravelTime` and `TravelDisutility` for one network mode, based on
+/// the travel times of a [`TimeBinnedTravelTimeAndDisutility`].
+///
+/// Links or bins without observations fall back to freespeed travel time at the departure time.
+/// Observed travel times are never lower than freespeed travel time, so that the highest freespeed
+/// of a link remains a lower bound for ALT.
+///
+/// The travel disutility is equal to the travel time.
+#[derive(Debug)]
+pub struct ModeTravelTimeAndDisutility {
+    mode: Id<String>,
+    travel_times: Arc<TimeBinnedTravelTimeAndDisutility>,
+}
+
+impl TravelTime for ModeTravelTimeAndDisutility {
+    fn travel_time(
+        &self,
+        link: &Link,
+        departure_time: SimTime,
+        _person: Option<&InternalPerson>,
+        _vehicle: Option<&InternalVehicle>,
+    ) -> Duration {
+        let freespeed =
+            TimeBinnedTravelTimeAndDisutility::freespeed_travel_time(link, departure_time);
+        match self
+            .travel_times
+            .observed_travel_time(&self.mode, &link.id, departure_time)
+        {
+            Some(observed) => freespeed.max(observed),
+            None => freespeed,
+        }
+    }
+}
+
+impl TravelDisutility for ModeTravelTimeAndDisutility {
+    fn travel_disutility(
+        &self,
+        link: &Link,
+        departure_time: SimTime,
+        person: Option<&InternalPerson>,
+        vehicle: Option<&InternalVehicle>,
+    ) -> Disutility {
+        // travel disutility is simply the travel time, as for the freespeed implementations
+        self.travel_time(link, departure_time, person, vehicle)
+            .as_secs_f64()
+    }
+
+    fn get_link_min_travel_disutility(&self, link: &Link) -> Disutility {
+        // observed travel times are clamped to freespeed, so the highest freespeed is the lower bound
+        link.length / link.max_freespeed()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::simulation::InternalAttributes;
-    use crate::simulation::events::{LinkEnterEvent, LinkLeaveEvent, PersonLeavesVehicleEvent};
+    use crate::simulation::events::EventsManager;
+    use crate::simulation::events::{
+        LinkEnterEvent, LinkLeaveEvent, PersonLeavesVehicleEvent, VehicleEntersTrafficEventBuilder,
+    };
     use crate::simulation::id::Id;
-    use crate::simulation::replanning::routing::travel_time_collector::TravelTimeCollector;
-    use crate::simulation::scenario::network::Link;
+    use crate::simulation::replanning::routing::least_cost_path_calculator::{
+        TravelDisutility, TravelTime,
+    };
+    use crate::simulation::replanning::routing::travel_time_collector::{
+        TimeBinnedTravelTimeAndDisutility, TravelTimeCollector, routed_vehicle_modes,
+    };
+    use crate::simulation::scenario::Coordinate;
+    use crate::simulation::scenario::network::{Link, Node};
     use crate::simulation::scenario::population::InternalPerson;
-    use crate::simulation::scenario::vehicles::InternalVehicle;
+    use crate::simulation::scenario::vehicles::{Garage, InternalVehicle, InternalVehicleType};
     use crate::simulation::time::SimTime;
     use macros::deterministic_id_test;
+    use nohash_hasher::{IntMap, IntSet};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn car() -> Id<String> {
+        Id::create("car")
+    }
+
+    fn cars(vehicles: &[&Id<InternalVehicle>]) -> IntMap<Id<InternalVehicle>, Id<String>> {
+        vehicles
+            .iter()
+            .map(|vehicle| ((*vehicle).clone(), car()))
+            .collect()
+    }
 
     fn link_enter_event(
         time: SimTime,
@@ -158,7 +395,7 @@ mod test {
         let link2 = Id::create("2");
         let vehicle1 = Id::create("1");
 
-        let mut collector = TravelTimeCollector::new();
+        let mut collector = TravelTimeCollector::new(Arc::new(cars(&[&vehicle1])));
         collector.process_link_leave_event(&link_leave_event(
             SimTime::from_secs(1),
             &link1,
@@ -175,10 +412,10 @@ mod test {
             &vehicle1,
         ));
 
-        assert_eq!(collector.get_travel_time_of_link(&link2), Some(2));
-        assert_eq!(collector.get_travel_time_of_link(&link1), None);
-        assert_eq!(collector.get_travel_times().keys().len(), 1);
-        assert_eq!(collector.get_travel_times().get(&link2), Some(&2u32));
+        assert_eq!(collector.get_travel_time_of_link(&car(), &link2), Some(2));
+        assert_eq!(collector.get_travel_time_of_link(&car(), &link1), None);
+        assert_eq!(collector.get_travel_times(&car()).keys().len(), 1);
+        assert_eq!(collector.get_travel_times(&car()).get(&link2), Some(&2u32));
         assert_eq!(collector.cache_enter_time_by_vehicle.get(&vehicle1), None)
     }
 
@@ -193,7 +430,8 @@ mod test {
         let vehicle2 = Id::create("2");
         let vehicle3 = Id::create("3");
 
-        let mut collector = TravelTimeCollector::new();
+        let mut collector =
+            TravelTimeCollector::new(Arc::new(cars(&[&vehicle1, &vehicle2, &vehicle3])));
         collector.process_link_leave_event(&link_leave_event(
             SimTime::from_secs(1),
             &link1,
@@ -226,11 +464,11 @@ mod test {
         ));
 
         // The average travel time on link 2 is 3
-        assert_eq!(Some(3), collector.get_travel_time_of_link(&link2));
-        assert_eq!(None, collector.get_travel_time_of_link(&link1));
+        assert_eq!(Some(3), collector.get_travel_time_of_link(&car(), &link2));
+        assert_eq!(None, collector.get_travel_time_of_link(&car(), &link1));
 
-        assert_eq!(collector.get_travel_times().keys().len(), 1);
-        assert_eq!(collector.get_travel_times().get(&link2), Some(&3u32));
+        assert_eq!(collector.get_travel_times(&car()).keys().len(), 1);
+        assert_eq!(collector.get_travel_times(&car()).get(&link2), Some(&3u32));
 
         // vehicle 1 and 2 have no cached traffic information
         assert_eq!(collector.cache_enter_time_by_vehicle.get(&vehicle1), None);
@@ -253,7 +491,7 @@ mod test {
         let vehicle1 = Id::create("1");
         let person1 = Id::create("p1");
 
-        let mut collector = TravelTimeCollector::new();
+        let mut collector = TravelTimeCollector::new(Arc::new(cars(&[&vehicle1])));
         collector.process_link_enter_event(&link_enter_event(
             SimTime::default(),
             &link1,
@@ -270,7 +508,7 @@ mod test {
             &vehicle1,
         ));
 
-        assert_eq!(collector.get_travel_time_of_link(&link1), None);
+        assert_eq!(collector.get_travel_time_of_link(&car(), &link1), None);
         assert_eq!(collector.cache_enter_time_by_vehicle.get(&vehicle1), None);
     }
 
@@ -283,7 +521,7 @@ mod test {
         let vehicle2 = Id::create("2");
         let person1 = Id::create("p1");
 
-        let mut collector = TravelTimeCollector::new();
+        let mut collector = TravelTimeCollector::new(Arc::new(cars(&[&vehicle1, &vehicle2])));
         collector.process_link_enter_event(&link_enter_event(
             SimTime::default(),
             &link1,
@@ -324,8 +562,231 @@ mod test {
             &vehicle1,
         ));
 
-        assert_eq!(collector.get_travel_time_of_link(&link1), Some(2));
-        assert_eq!(collector.get_travel_time_of_link(&link2), Some(10));
+        assert_eq!(collector.get_travel_time_of_link(&car(), &link1), Some(2));
+        assert_eq!(collector.get_travel_time_of_link(&car(), &link2), Some(10));
         assert_eq!(collector.cache_enter_time_by_vehicle.get(&vehicle1), None);
     }
+
+    #[deterministic_id_test]
+    /// Tests that the dwell time of a bus serving a stop on a link is not collected as car travel
+    /// time
+    fn test_ignores_vehicles_of_other_modes() {
+        let link1 = Id::create("1");
+        let link2 = Id::create("2");
+        let bus = Id::create("bus");
+        let car_vehicle = Id::create("car");
+        let mut collector = TravelTimeCollector::new(Arc::new(cars(&[&car_vehicle])));
+
+        collector.process_link_leave_event(&link_leave_event(SimTime::from_secs(1), &link1, &bus));
+        // the bus dwells at a stop on link 2 for 60 seconds, while a car passes the link
+        collector.process_link_enter_event(&link_enter_event(SimTime::from_secs(2), &link2, &bus));
+        collector.process_link_enter_event(&link_enter_event(
+            SimTime::from_secs(3),
+            &link2,
+            &car_vehicle,
+        ));
+        collector.process_link_leave_event(&link_leave_event(
+            SimTime::from_secs(13),
+            &link2,
+            &car_vehicle,
+        ));
+        collector.process_link_leave_event(&link_leave_event(SimTime::from_secs(72), &link2, &bus));
+
+        assert_eq!(Some(10), collector.get_travel_time_of_link(&car(), &link2));
+        assert_eq!(collector.cache_enter_time_by_vehicle.get(&bus), None);
+    }
+
+    #[deterministic_id_test]
+    /// Tests that only garage vehicles of the routed modes are collected, leaving out DRT vehicles
+    /// and transit vehicles, which are not in the garage
+    fn test_routed_vehicle_modes() {
+        let mut garage = Garage::default();
+        garage.add_veh_type(vehicle_type("car_type", "car"));
+        garage.add_veh_type(vehicle_type("bike_type", "bike"));
+        garage.add_veh_by_type(&Id::create("p1"), &Id::get_from_ext("car_type"));
+        garage.add_veh_by_type(&Id::create("p1"), &Id::get_from_ext("bike_type"));
+        let car_vehicle: Id<InternalVehicle> = Id::get_from_ext("p1_car_type");
+        let mut drt_vehicle = garage.vehicles[&car_vehicle].clone();
+        drt_vehicle.id = Id::create("drt");
+        drt_vehicle.attributes.insert("dvrpMode", "drt");
+        garage.add_veh(drt_vehicle);
+
+        let vehicle_modes = routed_vehicle_modes(&garage, &IntSet::from_iter([car()]));
+
+        assert_eq!(1, vehicle_modes.len());
+        assert_eq!(Some(&car()), vehicle_modes.get(&car_vehicle));
+    }
+
+    fn vehicle_type(id: &str, net_mode: &str) -> InternalVehicleType {
+        InternalVehicleType {
+            id: Id::create(id),
+            length: 7.5,
+            width: 1.0,
+            max_v: 30.0,
+            pce: 1.0,
+            fef: 1.0,
+            seats: 1,
+            standing_room: 0,
+            net_mode: Id::create(net_mode),
+            attributes: InternalAttributes::default(),
+        }
+    }
+
+    #[deterministic_id_test]
+    /// Tests whether travel times of all partitions are merged per time bin and clamped to
+    /// freespeed
+    fn test_time_binned_travel_times_are_merged_across_partitions() {
+        let from = Node::new(Id::create("n1"), Coordinate::new_2d(0., 0.), 0, 0);
+        let to = Node::new(Id::create("n2"), Coordinate::new_2d(100., 0.), 0, 0);
+        let mut link = Link::new_with_default(Id::create("1"), &from, &to);
+        link.length = 100.;
+        link.freespeed = 10.;
+        let vehicle1 = Id::create("1");
+        let vehicle2 = Id::create("2");
+        let vehicle3 = Id::create("3");
+
+        let travel_times = Arc::new(TimeBinnedTravelTimeAndDisutility::new(
+            2,
+            100,
+            cars(&[&vehicle1, &vehicle2, &vehicle3]),
+        ));
+        let car_travel_times = travel_times.for_mode(car());
+        let mut events0 = EventsManager::new();
+        let mut events1 = EventsManager::new();
+        travel_times.register(0)(&mut events0);
+        travel_times.register(1)(&mut events1);
+
+        // no observations yet => freespeed travel time
+        let freespeed = Duration::from_secs(10);
+        let tt =
+            |time: u64| car_travel_times.travel_time(&link, SimTime::from_secs(time), None, None);
+        assert_eq!(freespeed, tt(0));
+
+        // bin 0 is observed on both partitions, bin 2 only on partition 1
+        events0.process_event(&link_enter_event(
+            SimTime::from_secs(0),
+            &link.id,
+            &vehicle1,
+        ));
+        events0.process_event(&link_leave_event(
+            SimTime::from_secs(20),
+            &link.id,
+            &vehicle1,
+        ));
+        events1.process_event(&link_enter_event(
+            SimTime::from_secs(50),
+            &link.id,
+            &vehicle2,
+        ));
+        events1.process_event(&link_leave_event(
+            SimTime::from_secs(90),
+            &link.id,
+            &vehicle2,
+        ));
+        events1.process_event(&link_enter_event(
+            SimTime::from_secs(250),
+            &link.id,
+            &vehicle3,
+        ));
+        events1.process_event(&link_leave_event(
+            SimTime::from_secs(310),
+            &link.id,
+            &vehicle3,
+        ));
+        travel_times.update();
+
+        assert_eq!(Duration::from_secs(30), tt(0));
+        assert_eq!(Duration::from_secs(30), tt(99));
+        assert_eq!(freespeed, tt(100));
+        assert_eq!(Duration::from_secs(60), tt(200));
+        assert_eq!(freespeed, tt(300));
+        assert_eq!(
+            30.,
+            car_travel_times.travel_disutility(&link, SimTime::default(), None, None)
+        );
+        assert_eq!(10., car_travel_times.get_link_min_travel_disutility(&link));
+
+        // a new iteration drops the collected travel times, faster ones are clamped to freespeed
+        events0.reset_iteration(1);
+        events1.reset_iteration(1);
+        events0.process_event(&link_enter_event(
+            SimTime::from_secs(0),
+            &link.id,
+            &vehicle1,
+        ));
+        events0.process_event(&link_leave_event(
+            SimTime::from_secs(5),
+            &link.id,
+            &vehicle1,
+        ));
+        travel_times.update();
+
+        assert_eq!(freespeed, tt(0));
+        assert_eq!(freespeed, tt(200));
+    }
+
+    #[deterministic_id_test]
+    /// Tests that a bus crossing into another partition is ignored there, although that partition
+    /// never sees the bus entering traffic, and that travel times are kept apart per mode
+    fn test_time_binned_travel_times_ignore_other_modes_on_all_partitions() {
+        let from = Node::new(Id::create("n1"), Coordinate::new_2d(0., 0.), 0, 0);
+        let to = Node::new(Id::create("n2"), Coordinate::new_2d(100., 0.), 1, 0);
+        let mut link = Link::new_with_default(Id::create("1"), &from, &to);
+        link.length = 100.;
+        link.freespeed = 10.;
+        let car_vehicle = Id::create("car");
+        let bike = Id::create("bike");
+        let bus = Id::create("bus");
+        let mut vehicle_modes = cars(&[&car_vehicle]);
+        vehicle_modes.insert(bike.clone(), Id::create("bike"));
+
+        let travel_times = Arc::new(TimeBinnedTravelTimeAndDisutility::new(
+            2,
+            100,
+            vehicle_modes,
+        ));
+        let car_travel_times = travel_times.for_mode(car());
+        let bike_travel_times = travel_times.for_mode(Id::get_from_ext("bike"));
+        let mut events0 = EventsManager::new();
+        let mut events1 = EventsManager::new();
+        travel_times.register(0)(&mut events0);
+        travel_times.register(1)(&mut events1);
+
+        // the bus enters traffic on partition 0 and dwells at a stop on the link of partition 1
+        events0.process_event(
+            &VehicleEntersTrafficEventBuilder::default()
+                .time(SimTime::from_secs(0))
+                .vehicle(bus.clone())
+                .link(Id::create("0"))
+                .person(Id::create("pt_driver"))
+                .network_mode(Id::create("bus"))
+                .build()
+                .unwrap(),
+        );
+        events1.process_event(&link_enter_event(SimTime::from_secs(0), &link.id, &bus));
+        events1.process_event(&link_leave_event(SimTime::from_secs(100), &link.id, &bus));
+        events1.process_event(&link_enter_event(
+            SimTime::from_secs(0),
+            &link.id,
+            &car_vehicle,
+        ));
+        events1.process_event(&link_leave_event(
+            SimTime::from_secs(20),
+            &link.id,
+            &car_vehicle,
+        ));
+        events1.process_event(&link_enter_event(SimTime::from_secs(0), &link.id, &bike));
+        events1.process_event(&link_leave_event(SimTime::from_secs(50), &link.id, &bike));
+        travel_times.update();
+
+        let departure = SimTime::from_secs(0);
+        assert_eq!(
+            Duration::from_secs(20),
+            car_travel_times.travel_time(&link, departure, None, None)
+        );
+        assert_eq!(
+            Duration::from_secs(50),
+            bike_travel_times.travel_time(&link, departure, None, None)
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use crate::simulation::id::Id;
+use crate::simulation::io::xml::facilities;
 use crate::simulation::io::xml::facilities::{
     IOFacilities, IOFacility, IOFacilityActivity, IOOpenDay, IOOpenTime,
 };
@@ -8,6 +9,7 @@ use crate::simulation::scenario::network::Link;
 use crate::simulation::time::SimTime;
 use crate::simulation::{Attributable, Identifiable, InternalAttributes};
 use nohash_hasher::IntMap;
+use std::path::Path;
 
 /// Facility is a location that has modal access to the network.
 #[derive(Debug, Clone, PartialEq)]
@@ -90,6 +92,10 @@ impl ActivityFacilities {
         }
     }
 
+    pub fn from_file(file_path: &Path) -> Self {
+        ActivityFacilities::from(facilities::load_from_xml(file_path))
+    }
+
     pub fn add_facility(&mut self, facility: ActivityFacility) {
         let id = facility.id().clone();
         let previous = self.facilities.insert(id.clone(), facility);
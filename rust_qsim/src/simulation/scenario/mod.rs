@@ -7,7 +7,9 @@ pub mod vehicles;
 
 use crate::simulation::config::Config;
 use crate::simulation::network::sim_network::SimNetworkPartition;
+use crate::simulation::pt::TransitSchedule;
 use crate::simulation::{id, io};
+use facilities::ActivityFacilities;
 use network::Network;
 use population::Population;
 use std::sync::Arc;
@@ -82,14 +84,26 @@ pub struct Scenario {
     pub network: Network,
     pub garage: Garage,
     pub population: Population,
+    pub transit_schedule: TransitSchedule,
+    pub transit_vehicles: Garage,
+    pub facilities: ActivityFacilities,
     pub config: Arc<Config>,
 }
 
 impl Scenario {
     pub fn load<C: Into<Arc<Config>>>(config: C) -> Self {
-        info!("Start loading mod.");
+        Self::load_for_partition(config.into(), None)
+    }
 
-        let config = config.into();
+    /// Loads the scenario of a run with one process per partition. Only the persons starting on
+    /// partition `rank` are kept, so that every process holds and routes its own persons only.
+    /// The network and the other inputs are loaded completely, as routing needs them.
+    pub fn load_partition<C: Into<Arc<Config>>>(config: C, rank: u32) -> Self {
+        Self::load_for_partition(config.into(), Some(rank))
+    }
+
+    fn load_for_partition(config: Arc<Config>, rank: Option<u32>) -> Self {
+        info!("Start loading mod.");
 
         if let Some(path) = &config.ids().path {
             info!("Loading IDs from {:?}", path);
@@ -99,12 +113,19 @@ impl Scenario {
         // mandatory content to create a mod
         let network = Self::load_network(&config);
         let mut garage = Self::load_garage(&config);
-        let population = Self::load_population(&config, &mut garage);
+        // optional content
+        let transit_schedule = Self::load_transit_schedule(&config);
+        let transit_vehicles = Self::load_transit_vehicles(&config);
+        let facilities = Self::load_facilities(&config);
+        let population = Self::load_population(&config, &network, &mut garage, rank);
 
         Scenario {
             network,
             garage,
             population,
+            transit_schedule,
+            transit_vehicles,
+            facilities,
             config,
         }
     }
@@ -113,7 +134,17 @@ impl Scenario {
         if let Some(path) = &config.network().path {
             let net_in_path = io::resolve_path(config.context(), path);
             let num_parts = config.partitioning().num_parts;
-            Network::from_file_path(&net_in_path, num_parts, &config.partitioning().method)
+            let mut network =
+                Network::from_file_path(&net_in_path, num_parts, &config.partitioning().method);
+            if let Some(change_events_path) = &config.network().change_events_path {
+                let change_events_path = io::resolve_path(config.context(), change_events_path);
+                network.load_change_events(&change_events_path);
+            }
+            if let Some(lanes_path) = &config.network().lanes_path {
+                let lanes_path = io::resolve_path(config.context(), lanes_path);
+                network.load_lanes(&lanes_path);
+            }
+            network
         } else {
             Network::default()
         }
@@ -128,10 +159,47 @@ impl Scenario {
         }
     }
 
-    fn load_population(config: &Config, garage: &mut Garage) -> Population {
+    fn load_transit_schedule(config: &Config) -> TransitSchedule {
+        if let Some(path) = &config.transit_schedule().path {
+            let schedule_in_path = io::resolve_path(config.context(), path);
+            TransitSchedule::from_file(&schedule_in_path)
+        } else {
+            TransitSchedule::default()
+        }
+    }
+
+    fn load_transit_vehicles(config: &Config) -> Garage {
+        if let Some(path) = &config.transit_vehicles().path {
+            let vehicles_in_path = io::resolve_path(config.context(), path);
+            Garage::from_file(&vehicles_in_path)
+        } else {
+            Garage::default()
+        }
+    }
+
+    fn load_facilities(config: &Config) -> ActivityFacilities {
+        if let Some(path) = &config.facilities().path {
+            let facilities_in_path = io::resolve_path(config.context(), path);
+            ActivityFacilities::from_file(&facilities_in_path)
+        } else {
+            ActivityFacilities::default()
+        }
+    }
+
+    fn load_population(
+        config: &Config,
+        network: &Network,
+        garage: &mut Garage,
+        rank: Option<u32>,
+    ) -> Population {
         if let Some(path) = &config.population().path {
             let pop_in_path = io::resolve_path(config.context(), path);
-            Population::from_file(&pop_in_path, garage)
+            match rank {
+                None => Population::from_file(&pop_in_path, garage),
+                Some(rank) => {
+                    Population::from_file_filtered_part(&pop_in_path, network, garage, rank)
+                }
+            }
         } else {
             Population::default()
         }
@@ -143,6 +211,9 @@ impl Scenario {
 pub struct ScenarioCore {
     pub network: Arc<Network>,
     pub garage: Arc<Garage>,
+    pub transit_schedule: Arc<TransitSchedule>,
+    pub transit_vehicles: Arc<Garage>,
+    pub facilities: Arc<ActivityFacilities>,
     pub config: Arc<Config>,
 }
 
@@ -180,6 +251,9 @@ impl From<Scenario> for ControllerScenario {
             core: ScenarioCore {
                 network: Arc::new(scenario.network),
                 garage: Arc::new(scenario.garage),
+                transit_schedule: Arc::new(scenario.transit_schedule),
+                transit_vehicles: Arc::new(scenario.transit_vehicles),
+                facilities: Arc::new(scenario.facilities),
                 config: scenario.config,
             },
             population: scenario.population,
@@ -199,6 +273,20 @@ impl ControllerScenario {
             .collect()
     }
 
+    /// Creates the mobsim input of partition `rank` only, for runs with one process per partition.
+    /// The population must not contain persons of other partitions, see [Scenario::load_partition].
+    pub fn partition_for_mobsim(&mut self, rank: u32) -> MobsimInput {
+        let mut population = std::mem::take(&mut self.population);
+        let own = population.take_from_filtered_part(&self.core.network, rank);
+        assert!(
+            population.persons.is_empty(),
+            "Partition #{rank} holds {} persons of other partitions. Load the scenario with \
+            Scenario::load_partition.",
+            population.persons.len()
+        );
+        self.create_mobsim_input(rank, own)
+    }
+
     #[cfg(test)]
     pub fn merge_population_shards(&mut self, shards: Vec<PopulationShard>) {
         for shard in shards {
@@ -251,6 +339,9 @@ impl ControllerScenario {
 mod tests {
     use super::{ControllerScenario, Scenario};
     use crate::simulation::config::{Config, PartitionMethod};
+    use crate::simulation::id::Id;
+    use crate::simulation::pt::TransitSchedule;
+    use crate::simulation::scenario::facilities::ActivityFacilities;
     use crate::simulation::scenario::network::Network;
     use crate::simulation::scenario::population::Population;
     use crate::simulation::scenario::vehicles::Garage;
@@ -274,6 +365,9 @@ mod tests {
             network,
             garage,
             population,
+            transit_schedule: TransitSchedule::default(),
+            transit_vehicles: Garage::default(),
+            facilities: ActivityFacilities::default(),
             config,
         }
         .into();
@@ -287,4 +381,48 @@ mod tests {
 
         assert_eq!(original_len, scenario.population.persons.len());
     }
+
+    #[deterministic_id_test]
+    fn load_reads_transit_schedule_transit_vehicles_and_facilities_from_config() {
+        let mut config = Config::default();
+        config.network_mut().path =
+            Some(PathBuf::from("./assets/pt_tutorial/multimodalnetwork.xml"));
+        config.transit_schedule_mut().path =
+            Some(PathBuf::from("./assets/pt_tutorial/transitschedule.xml"));
+        config.transit_vehicles_mut().path =
+            Some(PathBuf::from("./assets/pt_tutorial/transitVehicles.xml"));
+        config.facilities_mut().path = Some(PathBuf::from("./assets/pt_tutorial/facilities.xml"));
+
+        let scenario = Scenario::load(config);
+
+        assert!(!scenario.transit_schedule.lines().is_empty());
+        assert_eq!(2, scenario.transit_vehicles.vehicles.len());
+        assert_eq!(2, scenario.facilities.facilities.len());
+        let work = scenario.facilities.get(&Id::get_from_ext("work")).unwrap();
+        assert_eq!("33", work.link_id.external());
+    }
+
+    #[deterministic_id_test]
+    fn load_partition_holds_only_own_persons() {
+        let mut config = Config::from_path("./tests/resources/3-links/3-links-config-socket.yml");
+        config.population_mut().path = Some(PathBuf::from("./assets/3-links/3-agent.xml"));
+        let config = Arc::new(config);
+
+        let mut loaded = 0;
+        for rank in 0..config.partitioning().num_parts {
+            let mut scenario: ControllerScenario =
+                Scenario::load_partition(config.clone(), rank).into();
+            for person in scenario.population.persons.values() {
+                let act = person.plan_element_at(0).unwrap().as_activity().unwrap();
+                assert_eq!(rank, scenario.core.network.get_link(&act.link_id).partition);
+            }
+            loaded += scenario.population.persons.len();
+
+            let input = scenario.partition_for_mobsim(rank);
+            assert_eq!(rank, input.partition.rank);
+            assert!(scenario.population.persons.is_empty());
+        }
+
+        assert_eq!(3, loaded);
+    }
 }
@@ -3,10 +3,17 @@ use crate::simulation::config::PartitionMethod;
 use crate::simulation::id::Id;
 use crate::simulation::io::proto::proto_network::{load_from_proto, write_to_proto};
 use crate::simulation::io::xml::attributes::IOAttributes;
+use crate::simulation::io::xml::lanes;
+use crate::simulation::io::xml::lanes::{IOLane, IOLaneDefinitions};
 use crate::simulation::io::xml::network;
 use crate::simulation::io::xml::network::{IOLink, IONetwork, IONode, write_to_xml};
+use crate::simulation::io::xml::network_change_events;
+use crate::simulation::io::xml::network_change_events::{
+    IOChangeType, IOChangeValue, IONetworkChangeEvents,
+};
 use crate::simulation::network::metis_partitioning;
 use crate::simulation::scenario::Coordinate;
+use crate::simulation::time::SimTime;
 use itertools::Itertools;
 use nohash_hasher::{IntMap, IntSet};
 use std::collections::HashSet;
@@ -43,6 +50,60 @@ pub struct Link {
     pub modes: IntSet<Id<String>>,
     pub partition: u32,
     pub attributes: InternalAttributes,
+    /// Scheduled changes of the capacity, freespeed and lanes, ordered by time. The fields above
+    /// hold the values before the first change.
+    pub changes: Vec<LinkChange>,
+    /// Lanes at the downstream end of the link. Links without lanes have a single queue.
+    pub lanes: Vec<Lane>,
+}
+
+/// A scheduled change of link attributes, like one of MATSim's network change events for a single
+/// link. Each change is applied to the values resulting from the previous changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkChange {
+    pub time: SimTime,
+    /// Change of the capacity in vehicles per hour.
+    pub capacity: Option<ChangeValue>,
+    pub freespeed: Option<ChangeValue>,
+    pub permlanes: Option<ChangeValue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeValue {
+    Absolute(f64),
+    Factor(f64),
+    Offset(f64),
+}
+
+impl ChangeValue {
+    pub fn apply(&self, value: f64) -> f64 {
+        match self {
+            ChangeValue::Absolute(absolute) => *absolute,
+            ChangeValue::Factor(factor) => value * factor,
+            ChangeValue::Offset(offset) => value + offset,
+        }
+    }
+}
+
+/// The time-variant attributes of a link at some point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkState {
+    pub capacity: f64,
+    pub freespeed: f64,
+    pub permlanes: f64,
+}
+
+/// A lane at the downstream end of a link, like the lanes of MATSim's lanes file which lead to
+/// other links. The upstream part of the link uses the link's attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lane {
+    /// Distance between the start of the lane and the end of the link.
+    pub length: f64,
+    /// The capacity in vehicles per hour.
+    pub capacity: f64,
+    pub permlanes: f64,
+    /// Links which can be reached from this lane.
+    pub to_links: Vec<Id<Link>>,
 }
 
 impl Default for Network {
@@ -246,6 +307,142 @@ impl Network {
     pub fn links_with_ids(&self) -> &IntMap<Id<Link>, Link> {
         &self.links
     }
+
+    /// Reads MATSim network change events and schedules them on the affected links.
+    pub fn load_change_events(&mut self, path: &Path) {
+        let io_events = network_change_events::load_from_xml(path);
+        self.add_change_events(io_events);
+    }
+
+    /// Schedules the changes on the affected links. Flow capacities of network change events are
+    /// given in vehicles per second and converted into vehicles per hour, like link capacities.
+    pub fn add_change_events(&mut self, io_events: IONetworkChangeEvents) {
+        for io_event in io_events.events {
+            let time = SimTime::parse(&io_event.start_time).unwrap_or_else(|e| {
+                panic!(
+                    "Invalid start time {} of network change event: {e}",
+                    io_event.start_time
+                )
+            });
+            let change = LinkChange {
+                time,
+                capacity: io_event
+                    .flow_capacity
+                    .map(|value| change_value(&value, SECONDS_PER_HOUR)),
+                freespeed: io_event.freespeed.map(|value| change_value(&value, 1.)),
+                permlanes: io_event.lanes.map(|value| change_value(&value, 1.)),
+            };
+            for link_ref in io_event.links {
+                let link = Id::try_get_from_ext(&link_ref.ref_id)
+                    .and_then(|id| self.links.get_mut(&id))
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Network change event refers to link {}, which is not part of the network.",
+                            link_ref.ref_id
+                        )
+                    });
+                link.changes.push(change.clone());
+            }
+        }
+
+        for link in self.links.values_mut() {
+            // the sort is stable, so changes at the same time keep the order of the file
+            link.changes.sort_by_key(|change| change.time);
+            for (time, state) in link.states() {
+                assert!(
+                    state.freespeed > 0.,
+                    "Network change events set the freespeed of link {} to {} at {}.",
+                    link.id,
+                    state.freespeed,
+                    time
+                );
+            }
+        }
+    }
+
+    /// Reads a MATSim lanes file and adds the lanes to the affected links.
+    pub fn load_lanes(&mut self, path: &Path) {
+        let io_lanes = lanes::load_from_xml(path);
+        self.add_lanes(io_lanes);
+    }
+
+    /// Adds the lanes leading to other links. Lanes which only lead to other lanes, like the
+    /// original lane at the upstream end of a link, are represented by the link itself. Lanes
+    /// without a capacity get the link's capacity per lane. Lanes don't need to lead to every
+    /// out-link. Vehicles turning into other links use the whole link.
+    pub fn add_lanes(&mut self, io_lanes: IOLaneDefinitions) {
+        for assignment in io_lanes.lanes_to_link_assignments {
+            let link = Id::try_get_from_ext(&assignment.link_id_ref)
+                .and_then(|id| self.links.get(&id))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Lanes file refers to link {}, which is not part of the network.",
+                        assignment.link_id_ref
+                    )
+                });
+            let lanes = assignment
+                .lanes
+                .iter()
+                .filter(|io_lane| !io_lane.leads_to.to_links.is_empty())
+                .map(|io_lane| self.create_lane(link, io_lane))
+                .collect();
+            let id = link.id.clone();
+            self.get_link_mut(&id).lanes = lanes;
+        }
+    }
+
+    fn create_lane(&self, link: &Link, io_lane: &IOLane) -> Lane {
+        let length = io_lane.starts_at.meter_from_link_end;
+        assert!(
+            length <= link.length,
+            "Lane {} of link {} starts {} m before the end of the link, but the link is only {} m long.",
+            io_lane.id,
+            link.id,
+            length,
+            link.length
+        );
+        let permlanes = io_lane.represented_lanes.map_or(1., |r| r.number);
+        let capacity = io_lane
+            .capacity
+            .map_or(link.capacity * permlanes / link.permlanes, |c| {
+                c.vehicles_per_hour
+            });
+        let out_links = &self.get_node(&link.to).out_links;
+        let to_links = io_lane
+            .leads_to
+            .to_links
+            .iter()
+            .map(|to_link| {
+                Id::try_get_from_ext(&to_link.ref_id)
+                    .filter(|id| out_links.contains(id))
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Lane {} of link {} leads to link {}, which does not start at the end of the link.",
+                            io_lane.id, link.id, to_link.ref_id
+                        )
+                    })
+            })
+            .collect();
+
+        Lane {
+            length,
+            capacity,
+            permlanes,
+            to_links,
+        }
+    }
+}
+
+const SECONDS_PER_HOUR: f64 = 3600.;
+
+/// Converts an IO change into a change of the internal value, which is `unit` times the IO value.
+/// Scale factors do not depend on the unit.
+fn change_value(io_value: &IOChangeValue, unit: f64) -> ChangeValue {
+    match io_value.change_type {
+        IOChangeType::Absolute => ChangeValue::Absolute(io_value.value * unit),
+        IOChangeType::ScaleFactor => ChangeValue::Factor(io_value.value),
+        IOChangeType::Offset => ChangeValue::Offset(io_value.value * unit),
+    }
 }
 
 impl From<IONetwork> for Network {
@@ -389,6 +586,8 @@ impl Link {
             modes,
             partition,
             attributes: InternalAttributes::default(),
+            changes: Vec::new(),
+            lanes: Vec::new(),
         }
     }
 
@@ -412,6 +611,52 @@ impl Link {
     pub fn contains_mode(&self, mode: &Id<String>) -> bool {
         self.modes.is_empty() || self.modes.iter().contains(mode)
     }
+
+    /// The attributes after applying all changes which take effect until `time`.
+    pub fn state_at(&self, time: SimTime) -> LinkState {
+        self.states()
+            .take_while(|(change_time, _)| *change_time <= time)
+            .last()
+            .map_or_else(|| self.initial_state(), |(_, state)| state)
+    }
+
+    pub fn freespeed_at(&self, time: SimTime) -> f64 {
+        self.state_at(time).freespeed
+    }
+
+    /// The highest freespeed of the link at any time. Travel times along the link are never
+    /// shorter than with this freespeed.
+    pub fn max_freespeed(&self) -> f64 {
+        self.states()
+            .map(|(_, state)| state.freespeed)
+            .fold(self.freespeed, f64::max)
+    }
+
+    /// The attributes after each change, together with the time of the change.
+    pub fn states(&self) -> impl Iterator<Item = (SimTime, LinkState)> + '_ {
+        self.changes
+            .iter()
+            .scan(self.initial_state(), |state, change| {
+                if let Some(capacity) = &change.capacity {
+                    state.capacity = capacity.apply(state.capacity);
+                }
+                if let Some(freespeed) = &change.freespeed {
+                    state.freespeed = freespeed.apply(state.freespeed);
+                }
+                if let Some(permlanes) = &change.permlanes {
+                    state.permlanes = permlanes.apply(state.permlanes);
+                }
+                Some((change.time, *state))
+            })
+    }
+
+    fn initial_state(&self) -> LinkState {
+        LinkState {
+            capacity: self.capacity,
+            freespeed: self.freespeed,
+            permlanes: self.permlanes,
+        }
+    }
 }
 
 pub fn from_file(path: &Path) -> Network {
@@ -469,9 +714,19 @@ pub mod utils {
 mod tests {
     use crate::simulation::config::{EdgeWeight, MetisOptions, PartitionMethod};
     use crate::simulation::id::Id;
+    use crate::simulation::io::xml::lanes::{
+        IOLane, IOLaneCapacity, IOLaneDefinitions, IOLanesToLinkAssignment, IOLeadsTo,
+        IORepresentedLanes, IOStartsAt, IOToLane, IOToLink,
+    };
     use crate::simulation::io::xml::network::{IOLink, IONode};
+    use crate::simulation::io::xml::network_change_events::{
+        IOChangeType, IOChangeValue, IOLinkRef, IONetworkChangeEvent, IONetworkChangeEvents,
+    };
     use crate::simulation::scenario::Coordinate;
-    use crate::simulation::scenario::network::{Link, Network, Node, add_io_link, add_io_node};
+    use crate::simulation::scenario::network::{
+        ChangeValue, Lane, Link, LinkState, Network, Node, add_io_link, add_io_node,
+    };
+    use crate::simulation::time::SimTime;
     use macros::deterministic_id_test;
 
     fn coord(x: f64, y: f64) -> Coordinate {
@@ -699,6 +954,251 @@ mod tests {
         assert_eq!(id, node.id);
     }
 
+    fn network_with_link() -> Network {
+        let mut network = Network::new();
+        let from = Node::new(Id::create("from"), coord(0., 0.), 0, 1);
+        let to = Node::new(Id::create("to"), coord(100., 0.), 0, 1);
+        let mut link = Link::new_with_default(Id::create("link"), &from, &to);
+        link.capacity = 3600.;
+        link.freespeed = 10.;
+        link.permlanes = 2.;
+        network.add_node(from);
+        network.add_node(to);
+        network.add_link(link);
+        network
+    }
+
+    fn change_event(
+        start_time: &str,
+        link: &str,
+        flow_capacity: Option<IOChangeValue>,
+        freespeed: Option<IOChangeValue>,
+        lanes: Option<IOChangeValue>,
+    ) -> IONetworkChangeEvent {
+        IONetworkChangeEvent {
+            start_time: start_time.to_string(),
+            links: vec![IOLinkRef {
+                ref_id: link.to_string(),
+            }],
+            flow_capacity,
+            freespeed,
+            lanes,
+        }
+    }
+
+    fn change_value(change_type: IOChangeType, value: f64) -> Option<IOChangeValue> {
+        Some(IOChangeValue { change_type, value })
+    }
+
+    #[test]
+    fn apply_change_value() {
+        assert_eq!(3., ChangeValue::Absolute(3.).apply(10.));
+        assert_eq!(5., ChangeValue::Factor(0.5).apply(10.));
+        assert_eq!(7., ChangeValue::Offset(-3.).apply(10.));
+    }
+
+    #[deterministic_id_test]
+    fn add_change_events() {
+        let mut network = network_with_link();
+        // events are not ordered by time, and each change builds on the previous ones
+        network.add_change_events(IONetworkChangeEvents {
+            events: vec![
+                change_event(
+                    "08:00:00",
+                    "link",
+                    change_value(IOChangeType::Absolute, 0.5),
+                    change_value(IOChangeType::ScaleFactor, 0.5),
+                    None,
+                ),
+                change_event(
+                    "07:00:00",
+                    "link",
+                    None,
+                    None,
+                    change_value(IOChangeType::Offset, 1.),
+                ),
+                change_event(
+                    "09:00:00",
+                    "link",
+                    change_value(IOChangeType::Offset, 0.5),
+                    change_value(IOChangeType::Absolute, 20.),
+                    None,
+                ),
+            ],
+        });
+
+        let link = network.get_link(&Id::get_from_ext("link"));
+        let state = |capacity, freespeed, permlanes| LinkState {
+            capacity,
+            freespeed,
+            permlanes,
+        };
+        let at = |hour: u64| link.state_at(SimTime::from_secs(hour * 3600));
+        assert_eq!(state(3600., 10., 2.), at(6));
+        assert_eq!(state(3600., 10., 3.), at(7));
+        // flow capacities of change events are given per second
+        assert_eq!(state(1800., 5., 3.), at(8));
+        assert_eq!(state(3600., 20., 3.), at(9));
+        assert_eq!(5., link.freespeed_at(SimTime::from_secs(8 * 3600 + 1)));
+        assert_eq!(20., link.max_freespeed());
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "not part of the network")]
+    fn add_change_events_reject_unknown_link() {
+        let mut network = network_with_link();
+        network.add_change_events(IONetworkChangeEvents {
+            events: vec![change_event(
+                "08:00:00",
+                "does-not-exist",
+                None,
+                change_value(IOChangeType::Absolute, 5.),
+                None,
+            )],
+        });
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "set the freespeed of link link to 0")]
+    fn add_change_events_reject_zero_freespeed() {
+        let mut network = network_with_link();
+        network.add_change_events(IONetworkChangeEvents {
+            events: vec![change_event(
+                "08:00:00",
+                "link",
+                None,
+                change_value(IOChangeType::Offset, -10.),
+                None,
+            )],
+        });
+    }
+
+    /// The network of [network_with_link] with the links `left` and `straight` starting at the end
+    /// of `link`.
+    fn network_with_turns() -> Network {
+        let mut network = network_with_link();
+        for (id, y) in [("left", 100.), ("straight", 0.)] {
+            let to = Node::new(Id::create(id), coord(200., y), 0, 1);
+            let link = Link::new_with_default(
+                Id::create(id),
+                network.get_node(&Id::get_from_ext("to")),
+                &to,
+            );
+            network.add_node(to);
+            network.add_link(link);
+        }
+        network
+    }
+
+    fn io_lane(id: &str, to_links: &[&str], length: f64, capacity: Option<f64>) -> IOLane {
+        IOLane {
+            id: id.to_string(),
+            leads_to: IOLeadsTo {
+                to_lanes: vec![],
+                to_links: to_links
+                    .iter()
+                    .map(|to_link| IOToLink {
+                        ref_id: to_link.to_string(),
+                    })
+                    .collect(),
+            },
+            capacity: capacity.map(|vehicles_per_hour| IOLaneCapacity { vehicles_per_hour }),
+            starts_at: IOStartsAt {
+                meter_from_link_end: length,
+            },
+            alignment: None,
+            represented_lanes: None,
+        }
+    }
+
+    fn lanes_of_link(link: &str, lanes: Vec<IOLane>) -> IOLaneDefinitions {
+        IOLaneDefinitions {
+            lanes_to_link_assignments: vec![IOLanesToLinkAssignment {
+                link_id_ref: link.to_string(),
+                lanes,
+            }],
+        }
+    }
+
+    #[deterministic_id_test]
+    fn add_lanes() {
+        let mut network = network_with_turns();
+        let original = IOLane {
+            leads_to: IOLeadsTo {
+                to_lanes: vec![
+                    IOToLane {
+                        lane_id: "link.left".to_string(),
+                    },
+                    IOToLane {
+                        lane_id: "link.straight".to_string(),
+                    },
+                ],
+                to_links: vec![],
+            },
+            represented_lanes: Some(IORepresentedLanes { number: 2. }),
+            ..io_lane("link.ol", &[], 100., Some(3600.))
+        };
+        network.add_lanes(lanes_of_link(
+            "link",
+            vec![
+                original,
+                io_lane("link.left", &["left"], 30., Some(900.)),
+                io_lane("link.straight", &["straight", "left"], 50., None),
+            ],
+        ));
+
+        // the original lane is represented by the link itself
+        let link = network.get_link(&Id::get_from_ext("link"));
+        assert_eq!(
+            vec![
+                Lane {
+                    length: 30.,
+                    capacity: 900.,
+                    permlanes: 1.,
+                    to_links: vec![Id::get_from_ext("left")],
+                },
+                // without capacity, the lane gets the capacity of one of the link's two lanes
+                Lane {
+                    length: 50.,
+                    capacity: 1800.,
+                    permlanes: 1.,
+                    to_links: vec![Id::get_from_ext("straight"), Id::get_from_ext("left")],
+                },
+            ],
+            link.lanes
+        );
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "not part of the network")]
+    fn add_lanes_reject_unknown_link() {
+        let mut network = network_with_turns();
+        network.add_lanes(lanes_of_link(
+            "does-not-exist",
+            vec![io_lane("lane", &["left"], 30., None)],
+        ));
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "leads to link link, which does not start at the end of the link")]
+    fn add_lanes_reject_unreachable_link() {
+        let mut network = network_with_turns();
+        network.add_lanes(lanes_of_link(
+            "link",
+            vec![io_lane("lane", &["link"], 30., None)],
+        ));
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "but the link is only 100 m long")]
+    fn add_lanes_reject_lane_longer_than_link() {
+        let mut network = network_with_turns();
+        network.add_lanes(lanes_of_link(
+            "link",
+            vec![io_lane("lane", &["left"], 150., None)],
+        ));
+    }
+
     #[deterministic_id_test]
     fn test_add_io_link() {
         let ext_from_id = String::from("from");
@@ -8,7 +8,9 @@ use crate::simulation::io::xml::population::{
     IOActivity, IOLeg, IOPTRouteDescription, IOPerson, IOPlan, IOPlanElement, IORoute,
 };
 use crate::simulation::scenario::Coordinate;
+use crate::simulation::scenario::facilities::ActivityFacility;
 use crate::simulation::scenario::network::{Link, Network};
+use crate::simulation::scenario::trip_structure_utils::is_stage_activity_type;
 use crate::simulation::scenario::vehicles::Garage;
 use crate::simulation::scenario::vehicles::InternalVehicle;
 use crate::simulation::time::SimTime;
@@ -185,6 +187,7 @@ pub struct InternalActivity {
     pub act_type: Id<String>,
     pub link_id: Id<Link>,
     pub coord: Option<Coordinate>,
+    pub facility_id: Option<Id<ActivityFacility>>,
     pub start_time: Option<SimTime>,
     pub end_time: Option<SimTime>,
     pub max_dur: Option<Duration>,
@@ -389,6 +392,7 @@ impl InternalActivity {
             coord,
             act_type: Id::create(act_type),
             link_id,
+            facility_id: None,
             start_time,
             end_time,
             max_dur,
@@ -410,7 +414,7 @@ impl InternalActivity {
 
     // i think this should go into the utils module rather than being here. paul, mar'26
     pub fn is_interaction(&self) -> bool {
-        self.act_type.external().contains("interaction")
+        is_stage_activity_type(self.act_type.external())
     }
 }
 
@@ -752,6 +756,7 @@ impl From<IOActivity> for InternalActivity {
                     io.y.expect("y coordinate should be given when x coord is given"),
                 )
             }),
+            facility_id: io.facility.map(|f| Id::create(&f)),
             start_time: parse_time_opt(&io.start_time),
             end_time: parse_time_opt(&io.end_time),
             max_dur: parse_duration_opt(&io.max_dur),
@@ -773,6 +778,7 @@ impl From<Activity> for InternalActivity {
                 value.coordinate.as_ref().unwrap().y,
                 value.coordinate.as_ref().unwrap().z,
             )),
+            facility_id: value.facility_id.map(|f| Id::get_from_ext(&f)),
             start_time: value.start_time_ns.map(SimTime::from_nanos),
             end_time: value.end_time_ns.map(SimTime::from_nanos),
             max_dur: value.max_dur_ns.map(Duration::from_nanos),
@@ -1,13 +1,14 @@
 use crate::simulation::config::Config;
 use crate::simulation::id::Id;
+use crate::simulation::pt::{TransitSchedule, TransitStopFacility};
 use crate::simulation::replanning::routing::{RoutingError, RoutingRequestBuilder, TripRouter};
 use crate::simulation::scenario::ControllerScenario;
 use crate::simulation::scenario::Coordinate;
-use crate::simulation::scenario::facilities::Facility;
+use crate::simulation::scenario::facilities::{ActivityFacilities, Facility};
 use crate::simulation::scenario::network::{Link, Network};
 use crate::simulation::scenario::population::{
-    InternalGenericRoute, InternalLeg, InternalPerson, InternalPlan, InternalPlanElement,
-    InternalRoute,
+    InternalActivity, InternalGenericRoute, InternalLeg, InternalPerson, InternalPlan,
+    InternalPlanElement, InternalPtRouteDescription, InternalRoute,
 };
 use crate::simulation::scenario::trip_structure_utils::{TripSpan, get_trip_spans_default};
 use crate::simulation::scenario::vehicles::{Garage, InternalVehicle};
@@ -55,6 +56,8 @@ pub(crate) fn prepare_for_sim(
     let context = PrepareForSimContext {
         network: &scenario.core.network,
         garage: &scenario.core.garage,
+        transit_schedule: &scenario.core.transit_schedule,
+        facilities: &scenario.core.facilities,
         config: scenario.core.config.as_ref(),
     };
 
@@ -75,6 +78,8 @@ pub(crate) fn prepare_for_sim(
 pub struct PrepareForSimContext<'a> {
     pub network: &'a Network,
     pub garage: &'a Garage,
+    pub transit_schedule: &'a TransitSchedule,
+    pub facilities: &'a ActivityFacilities,
     pub config: &'a Config,
 }
 
@@ -85,7 +90,7 @@ struct IndexedTripFailure {
 }
 
 #[derive(Debug, Error)]
-enum TripPreparationError {
+pub(crate) enum TripPreparationError {
     #[error("Trip contains no legs")]
     NoLegs,
     #[error("Trip has no unambiguous routing mode")]
@@ -193,23 +198,41 @@ fn check_and_adapt_trip(
         return Ok(());
     };
 
+    let new_elements = route_trip(
+        context,
+        person,
+        &working_plan.elements,
+        span,
+        &mode,
+        trip_router,
+    )?;
+
+    span.replace_trip_elements(&mut working_plan.to_mut().elements, new_elements);
+    Ok(())
+}
+
+/// Routes the trip of the given span with the given routing mode and returns the new trip
+/// elements. The departure time is derived from the plan elements up to the origin activity, so
+/// preceding trips must already have been routed. Activities of the trip need coordinates.
+pub(crate) fn route_trip(
+    context: &PrepareForSimContext<'_>,
+    person: &InternalPerson,
+    elements: &[InternalPlanElement],
+    span: TripSpan,
+    mode: &Id<String>,
+    trip_router: &TripRouter,
+) -> Result<Vec<InternalPlanElement>, TripPreparationError> {
     let departure_time = TimeInterpretation::decide_on_elements_end_time(
-        &working_plan.elements[..=span.origin_index()],
+        &elements[..=span.origin_index()],
         &SimTime::default(),
     )
     .ok_or(TripPreparationError::MissingDepartureTime)?;
 
-    let origin = span.origin(&working_plan.elements);
-    let dest = span.destination(&working_plan.elements);
-    let from_facility = Facility::new_link_wrapper(
-        origin.coord.clone().expect("coordinates were assigned"),
-        origin.link_id.clone(),
-    );
-    let to_facility = Facility::new_link_wrapper(
-        dest.coord.clone().expect("coordinates were assigned"),
-        dest.link_id.clone(),
-    );
-    let vehicle = vehicle_for_trip(context, person, span, &working_plan.elements, &mode)?;
+    let origin = span.origin(elements);
+    let dest = span.destination(elements);
+    let from_facility = activity_facility(context, origin);
+    let to_facility = activity_facility(context, dest);
+    let vehicle = vehicle_for_trip(context, person, span, elements, mode)?;
 
     let request = RoutingRequestBuilder::default()
         .from(&from_facility)
@@ -219,10 +242,23 @@ fn check_and_adapt_trip(
         .vehicle(vehicle)
         .build()
         .expect("all required routing request fields are set");
-    let new_elements = trip_router.calc_route(&mode, request)?;
+    Ok(trip_router.calc_route(mode, request)?)
+}
 
-    span.replace_trip_elements(&mut working_plan.to_mut().elements, new_elements);
-    Ok(())
+/// Returns the facility an activity takes place at. If the activity references a facility of the
+/// scenario, that facility is used. Otherwise, the activity's link and coordinate are wrapped.
+fn activity_facility(context: &PrepareForSimContext<'_>, activity: &InternalActivity) -> Facility {
+    if let Some(facility) = activity
+        .facility_id
+        .as_ref()
+        .and_then(|id| context.facilities.get(id))
+    {
+        return Facility::ActivityFacility(facility.clone());
+    }
+    Facility::new_link_wrapper(
+        activity.coord.clone().expect("coordinates were assigned"),
+        activity.link_id.clone(),
+    )
 }
 
 fn assign_activity_coordinates(context: &PrepareForSimContext<'_>, plan: &mut InternalPlan) {
@@ -232,6 +268,14 @@ fn assign_activity_coordinates(context: &PrepareForSimContext<'_>, plan: &mut In
         };
 
         if activity.coord.is_none() {
+            if let Some(facility) = activity
+                .facility_id
+                .as_ref()
+                .and_then(|id| context.facilities.get(id))
+            {
+                activity.coord = Some(facility.coord.clone());
+                continue;
+            }
             let link = context.network.get_link(&activity.link_id);
             let from = context.network.get_node(&link.from);
             let to = context.network.get_node(&link.to);
@@ -256,7 +300,7 @@ fn assess_trip(
 }
 
 /// Returns the main mode of a trip. Checks the routing mode as well.
-fn resolve_main_mode(legs: &[&InternalLeg]) -> Result<Id<String>, TripPreparationError> {
+pub(crate) fn resolve_main_mode(legs: &[&InternalLeg]) -> Result<Id<String>, TripPreparationError> {
     if legs.is_empty() {
         return Err(TripPreparationError::NoLegs);
     }
@@ -311,6 +355,12 @@ fn trip_is_valid(
                 return false;
             }
         }
+
+        if let InternalRoute::Pt(pt_route) = route {
+            if !pt_route_is_valid(context.transit_schedule, pt_route.description()) {
+                return false;
+            }
+        }
     }
 
     if is_network_mode(context, mode) {
@@ -369,6 +419,29 @@ fn network_route_is_valid(
         && links.windows(2).all(|pair| pair[0].to == pair[1].from)
 }
 
+/// Checks if a pt route refers to a transit route of the schedule and if its access and egress
+/// stops are served by that transit route, the access stop before the egress stop. Without a
+/// schedule, pt legs are teleported by the mobsim, so any pt route is accepted.
+fn pt_route_is_valid(schedule: &TransitSchedule, description: &InternalPtRouteDescription) -> bool {
+    if schedule.lines().is_empty() {
+        return true;
+    }
+    let Some(transit_route) = schedule.find_route(description) else {
+        return false;
+    };
+    // position of the first visit of the stop in the route profile, starting at `from`
+    let position = |stop: &str, from: usize| {
+        let stop_id = Id::<TransitStopFacility>::try_get_from_ext(stop)?;
+        transit_route.stops[from..]
+            .iter()
+            .position(|route_stop| route_stop.facility_id == stop_id)
+            .map(|position| from + position)
+    };
+    position(&description.access_facility_id, 0)
+        .and_then(|access| position(&description.egress_facility_id, access + 1))
+        .is_some()
+}
+
 fn vehicle_for_trip<'a>(
     context: &'a PrepareForSimContext<'_>,
     person: &InternalPerson,
@@ -415,18 +488,23 @@ fn is_network_mode(context: &PrepareForSimContext<'_>, mode: &Id<String>) -> boo
 
 #[cfg(test)]
 mod tests {
-    use super::prepare_for_sim;
+    use super::{
+        PrepareForSimContext, activity_facility, assign_activity_coordinates, prepare_for_sim,
+        pt_route_is_valid,
+    };
     use crate::simulation::InternalAttributes;
     use crate::simulation::config::Config;
     use crate::simulation::id::Id;
+    use crate::simulation::pt::TransitSchedule;
     use crate::simulation::replanning::routing::teleportation::TeleportationRoutingModule;
     use crate::simulation::replanning::routing::{
         RoutingError, RoutingModule, RoutingRequest, TripRouter,
     };
+    use crate::simulation::scenario::facilities::{ActivityFacilities, ActivityFacility, Facility};
     use crate::simulation::scenario::network::{Link, Network, Node};
     use crate::simulation::scenario::population::{
         InternalActivity, InternalGenericRoute, InternalLeg, InternalNetworkRoute, InternalPerson,
-        InternalPlan, InternalPlanElement, InternalRoute, Population,
+        InternalPlan, InternalPlanElement, InternalPtRouteDescription, InternalRoute, Population,
     };
     use crate::simulation::scenario::vehicles::{Garage, InternalVehicle, InternalVehicleType};
     use crate::simulation::scenario::{ControllerScenario, Coordinate, Scenario};
@@ -772,6 +850,145 @@ mod tests {
         );
     }
 
+    // Before: a pt route from the first to the last stop of its transit route; after: it is valid.
+    #[deterministic_id_test]
+    fn pt_route_is_valid_for_stops_of_transit_route() {
+        let schedule = pt_tutorial_schedule();
+
+        assert!(pt_route_is_valid(&schedule, &pt_route("1", "3")));
+        assert!(pt_route_is_valid(&schedule, &pt_route("2a", "3")));
+    }
+
+    // Before: pt routes with a stop that is unknown or not served by the transit route; after:
+    // they are invalid.
+    #[deterministic_id_test]
+    fn pt_route_is_invalid_for_unknown_stop() {
+        let schedule = pt_tutorial_schedule();
+
+        assert!(!pt_route_is_valid(&schedule, &pt_route("1", "unknown")));
+        assert!(!pt_route_is_valid(&schedule, &pt_route("unknown", "3")));
+        assert!(!pt_route_is_valid(&schedule, &pt_route("2b", "3")));
+    }
+
+    // Before: pt routes whose egress stop is served before or at the access stop; after: they are
+    // invalid.
+    #[deterministic_id_test]
+    fn pt_route_is_invalid_for_reversed_stops() {
+        let schedule = pt_tutorial_schedule();
+
+        assert!(!pt_route_is_valid(&schedule, &pt_route("3", "1")));
+        assert!(!pt_route_is_valid(&schedule, &pt_route("2a", "2a")));
+    }
+
+    // Before: activities with a known, an unknown and without facility id; after: the known
+    // facility is returned, otherwise the activity's link and coordinate are wrapped.
+    #[deterministic_id_test]
+    fn activity_facility_uses_facility_of_scenario() {
+        let network = network_with_link(Id::create("link-1"));
+        let facilities = facilities_with("facility-1", Coordinate::new_2d(7.0, 8.0));
+        let mut activity = InternalActivity::new(
+            Some(Coordinate::new_2d(1.0, 2.0)),
+            "act",
+            Id::get_from_ext("link-1"),
+            None,
+            None,
+            None,
+        );
+        let link_wrapper =
+            Facility::new_link_wrapper(Coordinate::new_2d(1.0, 2.0), Id::get_from_ext("link-1"));
+
+        with_context(&network, &facilities, |context| {
+            assert_eq!(link_wrapper, activity_facility(context, &activity));
+
+            activity.facility_id = Some(Id::create("unknown"));
+            assert_eq!(link_wrapper, activity_facility(context, &activity));
+
+            activity.facility_id = Some(Id::get_from_ext("facility-1"));
+            let facility = facilities.get(&Id::get_from_ext("facility-1")).unwrap();
+            assert_eq!(
+                Facility::ActivityFacility(facility.clone()),
+                activity_facility(context, &activity)
+            );
+        });
+    }
+
+    // Before: two activities without coordinates, one at a facility; after: the first has the
+    // facility's coordinate, the second the link midpoint.
+    #[deterministic_id_test]
+    fn assign_activity_coordinates_uses_facility_coordinate() {
+        let network = network_with_link(Id::create("link-1"));
+        let facilities = facilities_with("facility-1", Coordinate::new_2d(7.0, 8.0));
+        let mut at_facility =
+            InternalActivity::new(None, "act", Id::get_from_ext("link-1"), None, None, None);
+        at_facility.facility_id = Some(Id::get_from_ext("facility-1"));
+        let mut plan = InternalPlan::default();
+        plan.add_act(at_facility);
+        plan.add_act(InternalActivity::new(
+            None,
+            "act",
+            Id::get_from_ext("link-1"),
+            None,
+            None,
+            None,
+        ));
+
+        with_context(&network, &facilities, |context| {
+            assign_activity_coordinates(context, &mut plan)
+        });
+
+        let acts = plan.acts();
+        assert_eq!(Some(&Coordinate::new_2d(7.0, 8.0)), acts[0].coord.as_ref());
+        assert_eq!(
+            Some(&Coordinate::new_3d(5.0, 15.0, 10.0)),
+            acts[1].coord.as_ref()
+        );
+    }
+
+    fn pt_tutorial_schedule() -> TransitSchedule {
+        TransitSchedule::from_file("./assets/pt_tutorial/transitschedule.xml".as_ref())
+    }
+
+    fn pt_route(access: &str, egress: &str) -> InternalPtRouteDescription {
+        InternalPtRouteDescription {
+            transit_route_id: "1to3".to_string(),
+            boarding_time: None,
+            transit_line_id: "Blue Line".to_string(),
+            access_facility_id: access.to_string(),
+            egress_facility_id: egress.to_string(),
+        }
+    }
+
+    fn facilities_with(id: &str, coord: Coordinate) -> ActivityFacilities {
+        let mut facilities = ActivityFacilities::default();
+        facilities.add_facility(ActivityFacility {
+            id: Id::create(id),
+            coord,
+            link_id: Id::get_from_ext("link-1"),
+            mode_to_link: IntMap::default(),
+            desc: None,
+            activities: Vec::new(),
+            attributes: InternalAttributes::default(),
+        });
+        facilities
+    }
+
+    fn with_context<R>(
+        network: &Network,
+        facilities: &ActivityFacilities,
+        f: impl FnOnce(&PrepareForSimContext<'_>) -> R,
+    ) -> R {
+        let garage = Garage::default();
+        let transit_schedule = TransitSchedule::default();
+        let config = Config::default();
+        f(&PrepareForSimContext {
+            network,
+            garage: &garage,
+            transit_schedule: &transit_schedule,
+            facilities,
+            config: &config,
+        })
+    }
+
     fn scenario_with_population(population: Population) -> ControllerScenario {
         scenario_with_network_and_population(Network::new(), population)
     }
@@ -788,6 +1005,9 @@ mod tests {
             network,
             garage: Garage::default(),
             population,
+            transit_schedule: TransitSchedule::default(),
+            transit_vehicles: Garage::default(),
+            facilities: ActivityFacilities::default(),
             config: Arc::new(Config::default()),
         }
         .into()
@@ -803,6 +1023,9 @@ mod tests {
             network,
             garage,
             population,
+            transit_schedule: TransitSchedule::default(),
+            transit_vehicles: Garage::default(),
+            facilities: ActivityFacilities::default(),
             config: Arc::new(config),
         }
         .into()
@@ -951,6 +1174,7 @@ mod tests {
             id: Id::create(id),
             max_v: 10.0,
             pce: 1.0,
+            fef: 1.0,
             vehicle_type: Id::<InternalVehicleType>::create("car"),
             attributes: InternalAttributes::default(),
         }
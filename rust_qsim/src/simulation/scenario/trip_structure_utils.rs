@@ -49,6 +49,20 @@ impl TripSpan {
         self.destination_index
     }
 
+    /// Returns this span moved by `offset` elements, e.g., after the elements of an earlier trip
+    /// were replaced by a different number of elements.
+    pub fn shifted(&self, offset: isize) -> TripSpan {
+        let shift = |index: usize| {
+            index
+                .checked_add_signed(offset)
+                .expect("Trip span shifted before the start of the plan.")
+        };
+        TripSpan {
+            origin_index: shift(self.origin_index),
+            destination_index: shift(self.destination_index),
+        }
+    }
+
     /// Returns the origin activity of this trip.
     pub fn origin<'a>(&self, plan_elements: &'a [InternalPlanElement]) -> &'a InternalActivity {
         activity_at(plan_elements, self.origin_index)
@@ -182,6 +196,12 @@ where
     trip_spans(plan_elements, is_stage_activity)
 }
 
+/// The default stage-activity rule: activities whose type contains "interaction" are part of a
+/// trip, e.g., "car interaction" between the access walk and the car leg.
+pub fn is_stage_activity_type(act_type: &str) -> bool {
+    act_type.contains("interaction")
+}
+
 /// Returns the spans of all trips using the default stage-activity rule.
 pub fn get_trip_spans_default(plan_elements: &[InternalPlanElement]) -> Vec<TripSpan> {
     get_trip_spans(plan_elements, |a| a.is_interaction())
@@ -213,6 +233,70 @@ pub fn find_trip_span_starting_at_activity_default(
     find_trip_span_starting_at_activity(plan_elements, start_index, |a| a.is_interaction())
 }
 
+/// A sequence of trips of a plan, see [`get_subtours`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Subtour {
+    /// Indices of the trips which belong to this subtour, excluding the trips of nested subtours.
+    /// Indices refer to the trips returned by [`get_trip_spans_default`].
+    pub trips: Vec<usize>,
+    /// Index of the subtour this subtour is nested in.
+    pub parent: Option<usize>,
+    /// Whether the last trip returns to the origin of the first trip.
+    pub closed: bool,
+}
+
+/// Splits the trips of a plan into subtours. Whenever a trip returns to the origin of an earlier
+/// trip, the trips in between form a closed subtour, except for the trips of subtours which were
+/// closed before. All trips which are not part of a closed subtour form one open subtour.
+/// Locations are compared by facility, or by link for activities without facility.
+pub fn get_subtours(plan_elements: &[InternalPlanElement]) -> Vec<Subtour> {
+    let trips = get_trip_spans_default(plan_elements);
+    let mut subtours = Vec::new();
+    // the trip below each subtour on the stack is part of its parent
+    let mut parent_trips = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+
+    for (index, trip) in trips.iter().enumerate() {
+        stack.push(index);
+        let destination = trip.destination(plan_elements);
+        let start = stack
+            .iter()
+            .rposition(|t| same_location(trips[*t].origin(plan_elements), destination));
+        if let Some(start) = start {
+            let subtour_trips = stack.split_off(start);
+            parent_trips.push(stack.last().copied());
+            subtours.push(Subtour {
+                trips: subtour_trips,
+                parent: None,
+                closed: true,
+            });
+        }
+    }
+    if !stack.is_empty() {
+        parent_trips.push(None);
+        subtours.push(Subtour {
+            trips: stack,
+            parent: None,
+            closed: false,
+        });
+    }
+
+    for (index, parent_trip) in parent_trips.into_iter().enumerate() {
+        subtours[index].parent =
+            parent_trip.and_then(|trip| subtours.iter().position(|s| s.trips.contains(&trip)));
+    }
+    subtours
+}
+
+/// Activities are at the same location if they have the same facility, or, if one of them has no
+/// facility, the same link.
+pub(crate) fn same_location(a: &InternalActivity, b: &InternalActivity) -> bool {
+    match (&a.facility_id, &b.facility_id) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.link_id == b.link_id,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +347,7 @@ mod tests {
             act_type: Id::create(act_type),
             link_id: Id::create(link),
             coord: Some(Coordinate::default()),
+            facility_id: None,
             start_time: None,
             end_time: None,
             max_dur: None,
@@ -508,4 +593,88 @@ mod tests {
         let plan: Vec<InternalPlanElement> = vec![];
         assert!(find_trip_span_starting_at_activity_default(&plan, 0).is_none());
     }
+
+    #[deterministic_id_test]
+    fn test_get_subtours_nested() {
+        // home - work - lunch - work - home
+        let plan = vec![
+            make_activity("home", "1"),
+            make_leg("car"),
+            make_activity("work", "2"),
+            make_leg("walk"),
+            make_activity("lunch", "3"),
+            make_leg("walk"),
+            make_activity("work", "2"),
+            make_leg("car"),
+            make_activity("home", "1"),
+        ];
+
+        let subtours = get_subtours(&plan);
+
+        assert_eq!(
+            subtours,
+            vec![
+                Subtour {
+                    trips: vec![1, 2],
+                    parent: Some(1),
+                    closed: true,
+                },
+                Subtour {
+                    trips: vec![0, 3],
+                    parent: None,
+                    closed: true,
+                },
+            ]
+        );
+    }
+
+    #[deterministic_id_test]
+    fn test_get_subtours_open() {
+        // home - work - home - shop
+        let plan = vec![
+            make_activity("home", "1"),
+            make_leg("car"),
+            make_activity("work", "2"),
+            make_leg("car"),
+            make_activity("home", "1"),
+            make_leg("walk"),
+            make_activity("shop", "3"),
+        ];
+
+        let subtours = get_subtours(&plan);
+
+        assert_eq!(
+            subtours,
+            vec![
+                Subtour {
+                    trips: vec![0, 1],
+                    parent: None,
+                    closed: true,
+                },
+                Subtour {
+                    trips: vec![2],
+                    parent: None,
+                    closed: false,
+                },
+            ]
+        );
+    }
+
+    #[deterministic_id_test]
+    fn test_get_subtours_ignores_stage_activities() {
+        let plan = vec![
+            make_activity("home", "1"),
+            make_leg("walk"),
+            make_activity("car interaction", "1"),
+            make_leg("car"),
+            make_activity("work", "2"),
+            make_leg("car"),
+            make_activity("home", "1"),
+        ];
+
+        let subtours = get_subtours(&plan);
+
+        assert_eq!(1, subtours.len());
+        assert_eq!(vec![0, 1], subtours[0].trips);
+    }
 }
@@ -50,21 +50,33 @@ pub struct InternalVehicleType {
     pub max_v: f64,
     pub pce: f64,
     pub fef: f64,
+    pub seats: u32,
+    pub standing_room: u32,
     pub net_mode: Id<String>,
     pub attributes: InternalAttributes,
 }
 
+impl InternalVehicleType {
+    /// Number of passengers which fit into a vehicle of this type. Like in MATSim, the seats
+    /// include the driver's seat.
+    pub fn passenger_capacity(&self) -> u32 {
+        (self.seats + self.standing_room).saturating_sub(1)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct InternalVehicle {
     pub id: Id<InternalVehicle>,
     pub max_v: f64,
     pub pce: f64,
+    pub fef: f64,
     pub vehicle_type: Id<InternalVehicleType>,
     pub attributes: InternalAttributes,
 }
 
 impl From<IOVehicleType> for InternalVehicleType {
     fn from(io: IOVehicleType) -> Self {
+        let capacity = io.capacity.unwrap_or_default();
         InternalVehicleType {
             id: Id::create(&io.id),
             length: io.length.unwrap_or_default().meter,
@@ -72,6 +84,8 @@ impl From<IOVehicleType> for InternalVehicleType {
             max_v: io.maximum_velocity.unwrap_or_default().meter_per_second,
             pce: io.passenger_car_equivalents.unwrap_or_default().pce,
             fef: io.flow_efficiency_factor.unwrap_or_default().factor,
+            seats: capacity.seats(),
+            standing_room: capacity.standing_room(),
             net_mode: Id::create(&io.network_mode.unwrap_or_default().network_mode),
             attributes: io.attributes.map(Into::into).unwrap_or_default(),
         }
@@ -87,6 +101,8 @@ impl From<VehicleType> for InternalVehicleType {
             max_v: value.max_v,
             pce: value.pce,
             fef: value.fef,
+            seats: value.seats,
+            standing_room: value.standing_room,
             net_mode: Id::get(value.net_mode),
             attributes: InternalAttributes::default(),
         }
@@ -99,6 +115,9 @@ impl From<Vehicle> for InternalVehicle {
             id: Id::get(value.id),
             max_v: value.max_v,
             pce: value.pce,
+            fef: value
+                .fef
+                .expect("Vehicle message without flow efficiency factor."),
             vehicle_type: Id::get(value.r#type),
             attributes: InternalAttributes::from(&value.attributes),
         }
@@ -111,17 +130,28 @@ impl InternalVehicle {
             id: Id::create(&io.id),
             max_v: io_veh_type.max_v,
             pce: io_veh_type.pce,
+            fef: io_veh_type.fef,
             vehicle_type: Id::create(&io.vehicle_type),
             attributes: io.attributes.map(Into::into).unwrap_or_default(),
         }
     }
 
+    /// Creates a vehicle read from a binary file. A vehicle without flow efficiency factor uses
+    /// the one of its vehicle type.
+    pub fn from_proto(value: Vehicle, veh_type: &InternalVehicleType) -> Self {
+        InternalVehicle::from(Vehicle {
+            fef: Some(value.fef.unwrap_or(veh_type.fef)),
+            ..value
+        })
+    }
+
     #[cfg(test)]
     pub fn new(id: u64, veh_type: u64, max_v: f64, pce: f64) -> Self {
         InternalVehicle {
             id: Id::create(&id.to_string()),
             max_v,
             pce,
+            fef: 1.0,
             vehicle_type: Id::create(&veh_type.to_string()),
             attributes: Default::default(),
         }
@@ -166,6 +196,7 @@ fn add_io_veh_type(garage: &mut Garage, io_veh_type: IOVehicleType) {
     let id: Id<InternalVehicleType> = Id::create(&io_veh_type.id);
     let net_mode: Id<String> =
         Id::create(&io_veh_type.network_mode.unwrap_or_default().network_mode);
+    let capacity = io_veh_type.capacity.unwrap_or_default();
 
     let veh_type = InternalVehicleType {
         id,
@@ -183,6 +214,8 @@ fn add_io_veh_type(garage: &mut Garage, io_veh_type: IOVehicleType) {
             .flow_efficiency_factor
             .unwrap_or_default()
             .factor,
+        seats: capacity.seats(),
+        standing_room: capacity.standing_room(),
         net_mode,
         attributes: io_veh_type.attributes.map(Into::into).unwrap_or_default(),
     };
@@ -224,6 +257,12 @@ impl Garage {
             "Vehicle type with id {:?} already exists.",
             &veh_type.id
         );
+        assert!(
+            veh_type.fef > 0.,
+            "Flow efficiency factor of vehicle type {} must be greater than 0, but is {}.",
+            veh_type.id.external(),
+            veh_type.fef
+        );
 
         self.vehicle_types.insert(veh_type.id.clone(), veh_type);
     }
@@ -244,12 +283,19 @@ impl Garage {
             attributes: Default::default(),
             max_v: veh_type.max_v,
             pce: veh_type.pce,
+            fef: veh_type.fef,
         };
 
         self.add_veh(vehicle);
     }
 
     pub fn add_veh(&mut self, veh: InternalVehicle) {
+        assert!(
+            veh.fef > 0.,
+            "Flow efficiency factor of vehicle {} must be greater than 0, but is {}.",
+            veh.id.external(),
+            veh.fef
+        );
         let id = veh.id.clone();
         self.vehicles.insert(id, veh);
     }
@@ -358,6 +404,7 @@ mod tests {
             id: Id::create("0"),
             max_v: 0.0,
             pce: 0.0,
+            fef: 1.0,
             vehicle_type: Id::create("0"),
             attributes: Default::default(),
         });
@@ -378,6 +425,7 @@ mod tests {
             id,
             max_v: 0.0,
             pce: 0.0,
+            fef: 1.0,
             vehicle_type: veh_type_id,
             attributes: Default::default(),
         });
@@ -439,6 +487,38 @@ mod tests {
         assert!(veh_type_opt.is_some());
     }
 
+    #[deterministic_id_test]
+    fn passenger_capacity_excludes_driver() {
+        let io_veh_type = IOVehicleType {
+            id: "default".to_string(),
+            description: None,
+            capacity: None,
+            length: None,
+            width: None,
+            maximum_velocity: None,
+            engine_information: None,
+            cost_information: None,
+            passenger_car_equivalents: None,
+            network_mode: None,
+            flow_efficiency_factor: None,
+            attributes: None,
+        };
+
+        // the default vehicle type only has the driver's seat
+        let default_type = InternalVehicleType::from(io_veh_type);
+        assert_eq!(1, default_type.seats);
+        assert_eq!(0, default_type.passenger_capacity());
+
+        let mut veh_type = create_vehicle_type(&Id::create("bus"), Id::create("bus"));
+        veh_type.seats = 4;
+        veh_type.standing_room = 2;
+        assert_eq!(5, veh_type.passenger_capacity());
+
+        veh_type.seats = 0;
+        veh_type.standing_room = 0;
+        assert_eq!(0, veh_type.passenger_capacity());
+    }
+
     fn person(id: &str) -> InternalPerson {
         let mut plan = InternalPlan::default();
         plan.add_act(InternalActivity::new(
@@ -0,0 +1,471 @@
+use crate::simulation::config::{ModeParams, Scoring, TypicalDurationScoreComputation};
+use crate::simulation::id::Id;
+use nohash_hasher::IntMap;
+
+const SECONDS_PER_HOUR: f64 = 3600.;
+const SECONDS_PER_DAY: f64 = 24. * SECONDS_PER_HOUR;
+
+/// Scoring parameters derived from the `Scoring` config module. All marginal utilities are
+/// converted to utils per second, so that they can be applied to simulation times directly.
+#[derive(Debug, Clone)]
+pub(crate) struct ScoringParameters {
+    pub(crate) learning_rate: f64,
+    marginal_utility_of_performing_s: f64,
+    marginal_utility_of_waiting_s: f64,
+    marginal_utility_of_late_arrival_s: f64,
+    marginal_utility_of_early_departure_s: f64,
+    marginal_utility_of_money: f64,
+    activity_params: IntMap<Id<String>, ActivityUtilityParameters>,
+    mode_params: IntMap<Id<String>, ModeUtilityParameters>,
+    default_mode_params: ModeUtilityParameters,
+    aborted_plan_score: f64,
+}
+
+#[derive(Debug, Clone)]
+struct ActivityUtilityParameters {
+    typical_duration: f64,
+    zero_utility_duration: f64,
+    opening_time: Option<f64>,
+    closing_time: Option<f64>,
+    latest_start_time: Option<f64>,
+    earliest_end_time: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct ModeUtilityParameters {
+    constant: f64,
+    marginal_utility_of_traveling_s: f64,
+    marginal_utility_of_distance_m: f64,
+    monetary_distance_rate: f64,
+}
+
+impl ScoringParameters {
+    pub(crate) fn from_config(config: &Scoring) -> Self {
+        let activity_params = config
+            .activity_params
+            .iter()
+            .map(|params| {
+                assert!(
+                    params.typical_duration > 0,
+                    "typical_duration of activity type {} must be greater than 0.",
+                    params.activity_type
+                );
+                let typical_duration = params.typical_duration as f64;
+                let zero_utility_duration = match params.typical_duration_score_computation {
+                    TypicalDurationScoreComputation::Relative => typical_duration * (-1f64).exp(),
+                    TypicalDurationScoreComputation::Uniform => {
+                        typical_duration * (-10. * SECONDS_PER_HOUR / typical_duration).exp()
+                    }
+                };
+                let utility_params = ActivityUtilityParameters {
+                    typical_duration,
+                    zero_utility_duration,
+                    opening_time: params.opening_time.map(f64::from),
+                    closing_time: params.closing_time.map(f64::from),
+                    latest_start_time: params.latest_start_time.map(f64::from),
+                    earliest_end_time: params.earliest_end_time.map(f64::from),
+                };
+                (Id::create(&params.activity_type), utility_params)
+            })
+            .collect();
+
+        let mode_params: IntMap<_, _> = config
+            .mode_params
+            .iter()
+            .map(|params| {
+                (
+                    Id::create(&params.mode),
+                    ModeUtilityParameters::from(params),
+                )
+            })
+            .collect();
+        let default_mode_params = ModeUtilityParameters::from(&ModeParams::default_for_mode(""));
+
+        let marginal_utility_of_performing_s =
+            config.marginal_utility_of_performing / SECONDS_PER_HOUR;
+        let marginal_utility_of_waiting_s = config.marginal_utility_of_waiting / SECONDS_PER_HOUR;
+        let marginal_utility_of_late_arrival_s =
+            config.marginal_utility_of_late_arrival / SECONDS_PER_HOUR;
+        let marginal_utility_of_early_departure_s =
+            config.marginal_utility_of_early_departure / SECONDS_PER_HOUR;
+
+        // Same as in MATSim: an aborted plan is penalized with the worst marginal utility of the
+        // configuration, applied for a whole day.
+        let worst_marginal_utility = mode_params
+            .values()
+            .chain(std::iter::once(&default_mode_params))
+            .map(|p| p.marginal_utility_of_traveling_s)
+            .chain([
+                marginal_utility_of_late_arrival_s,
+                marginal_utility_of_early_departure_s,
+                marginal_utility_of_waiting_s,
+                -marginal_utility_of_performing_s,
+            ])
+            .fold(f64::INFINITY, f64::min);
+
+        ScoringParameters {
+            learning_rate: config.learning_rate,
+            marginal_utility_of_performing_s,
+            marginal_utility_of_waiting_s,
+            marginal_utility_of_late_arrival_s,
+            marginal_utility_of_early_departure_s,
+            marginal_utility_of_money: config.marginal_utility_of_money,
+            activity_params,
+            mode_params,
+            default_mode_params,
+            aborted_plan_score: worst_marginal_utility * SECONDS_PER_DAY,
+        }
+    }
+
+    fn mode_params(&self, mode: &Id<String>) -> &ModeUtilityParameters {
+        self.mode_params
+            .get(mode)
+            .unwrap_or(&self.default_mode_params)
+    }
+}
+
+impl From<&ModeParams> for ModeUtilityParameters {
+    fn from(params: &ModeParams) -> Self {
+        ModeUtilityParameters {
+            constant: params.constant,
+            marginal_utility_of_traveling_s: params.marginal_utility_of_traveling
+                / SECONDS_PER_HOUR,
+            marginal_utility_of_distance_m: params.marginal_utility_of_distance,
+            monetary_distance_rate: params.monetary_distance_rate,
+        }
+    }
+}
+
+/// Computes the Charypar-Nagel score of one executed plan. The scoring function is fed with the
+/// events of one person in chronological order. Times are passed in seconds since midnight.
+///
+/// Stage activities (i.e. `* interaction`) and activity types without configured params do not
+/// contribute to the score. The first and the last activity of a plan are scored as one
+/// overnight activity if they are of the same type. A person who is still travelling at the end
+/// of the simulation receives the aborted plan score in addition.
+#[derive(Debug)]
+pub(crate) struct CharyparNagelScoringFunction<'p> {
+    params: &'p ScoringParameters,
+    score: f64,
+    first_activity: Option<(Id<String>, f64)>,
+    current_activity: Option<(Id<String>, f64)>,
+    current_leg: Option<CurrentLeg>,
+}
+
+#[derive(Debug)]
+struct CurrentLeg {
+    mode: Id<String>,
+    departure_time: f64,
+    distance: f64,
+}
+
+impl<'p> CharyparNagelScoringFunction<'p> {
+    pub(crate) fn new(params: &'p ScoringParameters) -> Self {
+        CharyparNagelScoringFunction {
+            params,
+            score: 0.,
+            first_activity: None,
+            current_activity: None,
+            current_leg: None,
+        }
+    }
+
+    pub(crate) fn handle_activity_start(&mut self, time: f64, act_type: &Id<String>) {
+        if is_stage_activity(act_type) {
+            return;
+        }
+        self.current_activity = Some((act_type.clone(), time));
+    }
+
+    pub(crate) fn handle_activity_end(&mut self, time: f64, act_type: &Id<String>) {
+        if is_stage_activity(act_type) {
+            return;
+        }
+        match self.current_activity.take() {
+            Some((act_type, start)) => self.score += self.activity_score(&act_type, start, time),
+            None => self.first_activity = Some((act_type.clone(), time)),
+        }
+    }
+
+    pub(crate) fn handle_departure(&mut self, time: f64, mode: &Id<String>) {
+        self.current_leg = Some(CurrentLeg {
+            mode: mode.clone(),
+            departure_time: time,
+            distance: 0.,
+        });
+    }
+
+    /// Adds distance to the leg which is currently travelled. Distances reported outside a leg
+    /// are ignored.
+    pub(crate) fn handle_leg_distance(&mut self, distance: f64) {
+        if let Some(leg) = self.current_leg.as_mut() {
+            leg.distance += distance;
+        }
+    }
+
+    pub(crate) fn handle_arrival(&mut self, time: f64) {
+        if let Some(leg) = self.current_leg.take() {
+            self.score += self.leg_score(&leg.mode, time - leg.departure_time, leg.distance);
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> f64 {
+        if self.current_leg.take().is_some() {
+            self.score += self.params.aborted_plan_score;
+        }
+
+        match (self.first_activity.take(), self.current_activity.take()) {
+            (Some((first_type, first_end)), Some((last_type, last_start)))
+                if first_type == last_type =>
+            {
+                self.score +=
+                    self.activity_score(&last_type, last_start, first_end + SECONDS_PER_DAY);
+            }
+            (first, last) => {
+                if let Some((act_type, end)) = first {
+                    self.score += self.activity_score(&act_type, 0., end);
+                }
+                if let Some((act_type, start)) = last {
+                    self.score += self.activity_score(&act_type, start, start.max(SECONDS_PER_DAY));
+                }
+            }
+        }
+
+        self.score
+    }
+
+    fn leg_score(&self, mode: &Id<String>, travel_time: f64, distance: f64) -> f64 {
+        let params = self.params.mode_params(mode);
+        params.constant
+            + params.marginal_utility_of_traveling_s * travel_time
+            + params.marginal_utility_of_distance_m * distance
+            + params.monetary_distance_rate * self.params.marginal_utility_of_money * distance
+    }
+
+    fn activity_score(&self, act_type: &Id<String>, arrival: f64, departure: f64) -> f64 {
+        let Some(params) = self.params.activity_params.get(act_type) else {
+            return 0.;
+        };
+
+        // the activity can only be performed while the facility is open
+        let mut activity_start = arrival;
+        let mut activity_end = departure;
+        if let Some(opening_time) = params.opening_time {
+            activity_start = activity_start.max(opening_time);
+        }
+        if let Some(closing_time) = params.closing_time {
+            activity_end = activity_end.min(closing_time);
+        }
+        if params.opening_time.is_some_and(|t| t > departure)
+            || params.closing_time.is_some_and(|t| t < arrival)
+        {
+            activity_start = departure;
+            activity_end = departure;
+        }
+        let duration = activity_end - activity_start;
+
+        let mut score = 0.;
+
+        if arrival < activity_start {
+            score += self.params.marginal_utility_of_waiting_s * (activity_start - arrival);
+        }
+
+        if let Some(latest_start_time) = params.latest_start_time
+            && activity_start > latest_start_time
+        {
+            score += self.params.marginal_utility_of_late_arrival_s
+                * (activity_start - latest_start_time);
+        }
+
+        if duration >= params.zero_utility_duration {
+            let performing = self.params.marginal_utility_of_performing_s
+                * params.typical_duration
+                * (duration / params.zero_utility_duration).ln();
+            let waiting = self.params.marginal_utility_of_waiting_s * duration;
+            score += performing.max(waiting).max(0.);
+        } else {
+            // below the zero utility duration, the utility decreases linearly with the slope at the
+            // zero utility duration
+            let slope = self.params.marginal_utility_of_performing_s * params.typical_duration
+                / params.zero_utility_duration;
+            score -= slope * (params.zero_utility_duration - duration);
+        }
+
+        if let Some(earliest_end_time) = params.earliest_end_time
+            && activity_end < earliest_end_time
+        {
+            score += self.params.marginal_utility_of_early_departure_s
+                * (earliest_end_time - activity_end);
+        }
+
+        score
+    }
+}
+
+fn is_stage_activity(act_type: &Id<String>) -> bool {
+    act_type.external().ends_with("interaction")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CharyparNagelScoringFunction, ScoringParameters};
+    use crate::simulation::config::{ActivityParams, ModeParams, Scoring};
+    use crate::simulation::id::Id;
+    use assert_approx_eq::assert_approx_eq;
+    use macros::deterministic_id_test;
+
+    fn activity_params(act_type: &str, typical_duration: u32) -> ActivityParams {
+        ActivityParams {
+            activity_type: act_type.to_string(),
+            typical_duration,
+            typical_duration_score_computation: Default::default(),
+            opening_time: None,
+            closing_time: None,
+            latest_start_time: None,
+            earliest_end_time: None,
+        }
+    }
+
+    fn scoring_config() -> Scoring {
+        Scoring {
+            activity_params: vec![
+                activity_params("h", 12 * 3600),
+                activity_params("w", 8 * 3600),
+            ],
+            mode_params: vec![ModeParams {
+                constant: -1.,
+                marginal_utility_of_distance: -0.001,
+                ..ModeParams::default_for_mode("car")
+            }],
+            ..Scoring::default()
+        }
+    }
+
+    /// utility of performing an activity with typical duration t for d seconds with the default
+    /// marginal utility of performing of 6 utils/h and the relative zero utility duration t/e.
+    fn performing(typical_duration: f64, duration: f64) -> f64 {
+        6. / 3600. * typical_duration * (duration / (typical_duration * (-1f64).exp())).ln()
+    }
+
+    #[deterministic_id_test]
+    fn scores_home_work_home_plan() {
+        let params = ScoringParameters::from_config(&scoring_config());
+        let mut function = CharyparNagelScoringFunction::new(&params);
+        let home = Id::create("h");
+        let work = Id::create("w");
+        let car = Id::create("car");
+
+        function.handle_activity_end(6. * 3600., &home);
+        function.handle_departure(6. * 3600., &car);
+        function.handle_leg_distance(10_000.);
+        function.handle_arrival(7. * 3600.);
+        function.handle_activity_start(7. * 3600., &work);
+        function.handle_activity_end(15. * 3600., &work);
+        function.handle_departure(15. * 3600., &car);
+        function.handle_leg_distance(10_000.);
+        function.handle_arrival(16. * 3600.);
+        function.handle_activity_start(16. * 3600., &home);
+
+        // two car legs of one hour and 10km each
+        let legs = 2. * (-1. - 6. - 10.);
+        // overnight home activity from 16:00 to 06:00 on the next day
+        let home = performing(12. * 3600., 14. * 3600.);
+        let work = performing(8. * 3600., 8. * 3600.);
+
+        assert_approx_eq!(legs + home + work, function.finish());
+    }
+
+    #[deterministic_id_test]
+    fn scores_first_and_last_activity_separately_if_types_differ() {
+        let params = ScoringParameters::from_config(&scoring_config());
+        let mut function = CharyparNagelScoringFunction::new(&params);
+
+        function.handle_activity_end(8. * 3600., &Id::create("h"));
+        function.handle_departure(8. * 3600., &Id::create("walk"));
+        function.handle_arrival(8.5 * 3600.);
+        function.handle_activity_start(8.5 * 3600., &Id::create("w"));
+
+        // walk has no params, hence the default of -6 utils/h is used
+        let leg = -3.;
+        let home = performing(12. * 3600., 8. * 3600.);
+        let work = performing(8. * 3600., 15.5 * 3600.);
+
+        assert_approx_eq!(leg + home + work, function.finish());
+    }
+
+    #[deterministic_id_test]
+    fn ignores_stage_activities_and_unknown_activity_types() {
+        let params = ScoringParameters::from_config(&scoring_config());
+        let mut function = CharyparNagelScoringFunction::new(&params);
+
+        function.handle_activity_end(8. * 3600., &Id::create("shop"));
+        function.handle_departure(8. * 3600., &Id::create("walk"));
+        function.handle_arrival(8.5 * 3600.);
+        function.handle_activity_start(8.5 * 3600., &Id::create("car interaction"));
+        function.handle_activity_end(8.5 * 3600., &Id::create("car interaction"));
+        function.handle_departure(8.5 * 3600., &Id::create("walk"));
+        function.handle_arrival(9. * 3600.);
+        function.handle_activity_start(9. * 3600., &Id::create("shop"));
+
+        // only the two walk legs are scored
+        assert_approx_eq!(-6., function.finish());
+    }
+
+    #[deterministic_id_test]
+    fn short_activity_is_penalized_linearly() {
+        let params = ScoringParameters::from_config(&scoring_config());
+        let mut function = CharyparNagelScoringFunction::new(&params);
+        let work = Id::create("w");
+
+        function.handle_activity_start(10. * 3600., &work);
+        function.handle_activity_end(10. * 3600. + 60., &work);
+
+        let typical_duration = 8. * 3600.;
+        let zero_utility_duration = typical_duration * (-1f64).exp();
+        let slope = 6. / 3600. * typical_duration / zero_utility_duration;
+
+        assert_approx_eq!(-slope * (zero_utility_duration - 60.), function.finish());
+    }
+
+    #[deterministic_id_test]
+    fn respects_opening_times_and_late_arrival() {
+        let mut config = scoring_config();
+        config.marginal_utility_of_waiting = -1.;
+        config.activity_params[1].opening_time = Some(8 * 3600);
+        config.activity_params[1].closing_time = Some(16 * 3600);
+        config.activity_params[1].latest_start_time = Some(7 * 3600);
+        let params = ScoringParameters::from_config(&config);
+        let mut function = CharyparNagelScoringFunction::new(&params);
+        let work = Id::create("w");
+
+        function.handle_activity_start(7. * 3600., &work);
+        function.handle_activity_end(17. * 3600., &work);
+
+        // one hour of waiting, one hour late, 8 hours of performing
+        let expected = -1. - 18. + performing(8. * 3600., 8. * 3600.);
+        assert_approx_eq!(expected, function.finish());
+    }
+
+    #[deterministic_id_test]
+    fn stuck_person_receives_aborted_plan_score() {
+        let params = ScoringParameters::from_config(&scoring_config());
+        let mut function = CharyparNagelScoringFunction::new(&params);
+        let home = Id::create("h");
+
+        function.handle_activity_end(6. * 3600., &home);
+        function.handle_departure(6. * 3600., &Id::create("car"));
+
+        // the worst marginal utility is the late arrival with -18 utils/h
+        let expected = -18. * 24. + performing(12. * 3600., 6. * 3600.);
+        assert_approx_eq!(expected, function.finish());
+    }
+
+    #[deterministic_id_test]
+    #[should_panic(expected = "typical_duration of activity type h must be greater than 0")]
+    fn zero_typical_duration_panics() {
+        ScoringParameters::from_config(&Scoring {
+            activity_params: vec![activity_params("h", 0)],
+            ..Scoring::default()
+        });
+    }
+}
@@ -0,0 +1,432 @@
+pub mod charypar_nagel;
+
+use crate::simulation::events::{
+    ActivityEndEvent, ActivityStartEvent, EventHandlerRegisterFn, LinkEnterEvent,
+    PersonArrivalEvent, PersonDepartureEvent, PersonEntersVehicleEvent, PersonLeavesVehicleEvent,
+    PtTeleportationArrivalEvent, TeleportationArrivalEvent,
+};
+use crate::simulation::id::Id;
+use crate::simulation::scenario::network::{Link, Network};
+use crate::simulation::scenario::population::{InternalPerson, InternalPlan, Population};
+use crate::simulation::scenario::vehicles::InternalVehicle;
+use crate::simulation::scoring::charypar_nagel::{CharyparNagelScoringFunction, ScoringParameters};
+use crate::simulation::time::SimTime;
+use nohash_hasher::IntMap;
+use rayon::prelude::*;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// Scoring relevant information about a person, extracted from a mobsim event.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PersonRecord {
+    pub person: Id<InternalPerson>,
+    pub time: SimTime,
+    pub kind: PersonRecordKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PersonRecordKind {
+    ActivityStart(Id<String>),
+    ActivityEnd(Id<String>),
+    Departure(Id<String>),
+    Arrival,
+    Travelled(f64),
+    EntersVehicle(Id<InternalVehicle>),
+    LeavesVehicle(Id<InternalVehicle>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LinkEnterRecord {
+    pub vehicle: Id<InternalVehicle>,
+    pub time: SimTime,
+    pub link: Id<Link>,
+}
+
+/// All scoring relevant records of one partition, in the order of the events.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ScoringRecords {
+    pub persons: Vec<PersonRecord>,
+    pub link_enters: Vec<LinkEnterRecord>,
+}
+
+impl ScoringRecords {
+    fn push_person(&mut self, person: &Id<InternalPerson>, time: SimTime, kind: PersonRecordKind) {
+        self.persons.push(PersonRecord {
+            person: person.clone(),
+            time,
+            kind,
+        });
+    }
+}
+
+/// Collects scoring relevant events on every partition. As agents move between partitions during
+/// the mobsim, the events of one person might be spread over several partitions. Each partition
+/// writes into its own buffer, which is drained by the controller after the mobsim has finished.
+#[derive(Debug)]
+pub(crate) struct ScoringEventsCollector {
+    buffers: Vec<Arc<Mutex<ScoringRecords>>>,
+}
+
+impl ScoringEventsCollector {
+    pub(crate) fn new(num_parts: u32) -> Self {
+        ScoringEventsCollector {
+            buffers: (0..num_parts).map(|_| Arc::default()).collect(),
+        }
+    }
+
+    pub(crate) fn register(&self, rank: u32) -> Box<EventHandlerRegisterFn> {
+        let buffer = self.buffers[rank as usize].clone();
+        Box::new(move |events| {
+            let b = buffer.clone();
+            events.on_reset_iteration(move |_| {
+                *b.lock().unwrap() = ScoringRecords::default();
+            });
+
+            let b = buffer.clone();
+            events.on::<ActivityStartEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    PersonRecordKind::ActivityStart(e.act_type.clone()),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<ActivityEndEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    PersonRecordKind::ActivityEnd(e.act_type.clone()),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PersonDepartureEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    PersonRecordKind::Departure(e.leg_mode.clone()),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PersonArrivalEvent, _>(move |e| {
+                b.lock()
+                    .unwrap()
+                    .push_person(&e.person, e.time, PersonRecordKind::Arrival);
+            });
+            let b = buffer.clone();
+            events.on::<TeleportationArrivalEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    PersonRecordKind::Travelled(e.distance),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PtTeleportationArrivalEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    PersonRecordKind::Travelled(e.distance),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PersonEntersVehicleEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    PersonRecordKind::EntersVehicle(e.vehicle.clone()),
+                );
+            });
+            let b = buffer.clone();
+            events.on::<PersonLeavesVehicleEvent, _>(move |e| {
+                b.lock().unwrap().push_person(
+                    &e.person,
+                    e.time,
+                    PersonRecordKind::LeavesVehicle(e.vehicle.clone()),
+                );
+            });
+            events.on::<LinkEnterEvent, _>(move |e| {
+                buffer.lock().unwrap().link_enters.push(LinkEnterRecord {
+                    vehicle: e.vehicle.clone(),
+                    time: e.time,
+                    link: e.link.clone(),
+                });
+            });
+        })
+    }
+
+    /// Takes the records of all partitions, ordered by rank.
+    pub(crate) fn take_records(&self) -> Vec<ScoringRecords> {
+        self.buffers
+            .iter()
+            .map(|buffer| mem::take(&mut *buffer.lock().unwrap()))
+            .collect()
+    }
+}
+
+/// Replays the records of all partitions and blends the resulting score into the selected plan
+/// of every person of the population. Persons without any record receive a score of 0.
+pub(crate) fn score_population(
+    population: &mut Population,
+    records: Vec<ScoringRecords>,
+    params: &ScoringParameters,
+    network: &Network,
+) {
+    let mut records_per_person: IntMap<Id<InternalPerson>, Vec<PersonRecord>> = IntMap::default();
+    let mut links_per_vehicle: IntMap<Id<InternalVehicle>, Vec<(SimTime, Id<Link>)>> =
+        IntMap::default();
+
+    for partition in records {
+        for record in partition.persons {
+            records_per_person
+                .entry(record.person.clone())
+                .or_default()
+                .push(record);
+        }
+        for record in partition.link_enters {
+            links_per_vehicle
+                .entry(record.vehicle)
+                .or_default()
+                .push((record.time, record.link));
+        }
+    }
+
+    // Records of one partition are already ordered. The events of one person at the same time
+    // are always emitted on the same partition, so a stable sort keeps their order.
+    records_per_person
+        .par_iter_mut()
+        .for_each(|(_, records)| records.sort_by_key(|r| r.time));
+    links_per_vehicle
+        .par_iter_mut()
+        .for_each(|(_, links)| links.sort_by_key(|(time, _)| *time));
+
+    population.persons.par_iter_mut().for_each(|(id, person)| {
+        let records = records_per_person
+            .get(id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let score = score_person(records, &links_per_vehicle, params, network);
+        blend_score(person.selected_plan_mut(), score, params.learning_rate);
+    });
+}
+
+fn score_person(
+    records: &[PersonRecord],
+    links_per_vehicle: &IntMap<Id<InternalVehicle>, Vec<(SimTime, Id<Link>)>>,
+    params: &ScoringParameters,
+    network: &Network,
+) -> f64 {
+    let mut function = CharyparNagelScoringFunction::new(params);
+    let mut entered_vehicle: Option<SimTime> = None;
+
+    for record in records {
+        let time = record.time.as_duration().as_secs_f64();
+        match &record.kind {
+            PersonRecordKind::ActivityStart(act_type) => {
+                function.handle_activity_start(time, act_type)
+            }
+            PersonRecordKind::ActivityEnd(act_type) => function.handle_activity_end(time, act_type),
+            PersonRecordKind::Departure(mode) => function.handle_departure(time, mode),
+            PersonRecordKind::Arrival => function.handle_arrival(time),
+            PersonRecordKind::Travelled(distance) => function.handle_leg_distance(*distance),
+            PersonRecordKind::EntersVehicle(_) => entered_vehicle = Some(record.time),
+            PersonRecordKind::LeavesVehicle(vehicle) => {
+                let Some(entered) = entered_vehicle.take() else {
+                    continue;
+                };
+                let distance = links_per_vehicle
+                    .get(vehicle)
+                    .map(|links| {
+                        links
+                            .iter()
+                            .filter(|(t, _)| entered <= *t && *t <= record.time)
+                            .map(|(_, link)| network.get_link(link).length)
+                            .sum()
+                    })
+                    .unwrap_or(0.);
+                function.handle_leg_distance(distance);
+            }
+        }
+    }
+
+    function.finish()
+}
+
+/// Blends the new score into the plan score as MATSim does:
+/// `score = (1 - learning_rate) * old_score + learning_rate * new_score`. Plans without a score
+/// take the new score.
+pub(crate) fn blend_score(plan: &mut InternalPlan, score: f64, learning_rate: f64) {
+    plan.score = Some(match plan.score {
+        Some(old) => (1. - learning_rate) * old + learning_rate * score,
+        None => score,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PersonRecordKind, ScoringEventsCollector, ScoringRecords, score_population};
+    use crate::simulation::config::{ActivityParams, ModeParams, Scoring};
+    use crate::simulation::events::{
+        ActivityEndEventBuilder, EventsManager, LinkEnterEventBuilder, PersonDepartureEventBuilder,
+    };
+    use crate::simulation::id::Id;
+    use crate::simulation::scenario::Coordinate;
+    use crate::simulation::scenario::network::{Link, Network, Node};
+    use crate::simulation::scenario::population::{InternalPerson, InternalPlan, Population};
+    use crate::simulation::scoring::blend_score;
+    use crate::simulation::scoring::charypar_nagel::ScoringParameters;
+    use crate::simulation::time::SimTime;
+    use assert_approx_eq::assert_approx_eq;
+    use macros::deterministic_id_test;
+
+    #[test]
+    fn blend_score_uses_learning_rate() {
+        let mut plan = InternalPlan::default();
+
+        blend_score(&mut plan, 10., 0.5);
+        assert_eq!(Some(10.), plan.score);
+
+        blend_score(&mut plan, 20., 0.5);
+        assert_eq!(Some(15.), plan.score);
+
+        blend_score(&mut plan, 0., 1.0);
+        assert_eq!(Some(0.), plan.score);
+    }
+
+    #[deterministic_id_test]
+    fn collector_records_events_per_partition() {
+        let collector = ScoringEventsCollector::new(2);
+        let mut events = EventsManager::new();
+        collector.register(1)(&mut events);
+
+        events.process_event(
+            &ActivityEndEventBuilder::default()
+                .time(SimTime::from_secs(10))
+                .person(Id::create("p1"))
+                .link(Id::create("l1"))
+                .coordinate(Coordinate::new_2d(0., 0.))
+                .act_type(Id::create("h"))
+                .build()
+                .unwrap(),
+        );
+        events.process_event(
+            &PersonDepartureEventBuilder::default()
+                .time(SimTime::from_secs(10))
+                .person(Id::create("p1"))
+                .link(Id::create("l1"))
+                .leg_mode(Id::create("car"))
+                .routing_mode(Id::create("car"))
+                .build()
+                .unwrap(),
+        );
+        events.process_event(
+            &LinkEnterEventBuilder::default()
+                .time(SimTime::from_secs(11))
+                .link(Id::create("l2"))
+                .vehicle(Id::create("v1"))
+                .build()
+                .unwrap(),
+        );
+
+        let records = collector.take_records();
+        assert_eq!(2, records.len());
+        assert_eq!(ScoringRecords::default(), records[0]);
+        assert_eq!(
+            vec![
+                PersonRecordKind::ActivityEnd(Id::create("h")),
+                PersonRecordKind::Departure(Id::create("car")),
+            ],
+            records[1]
+                .persons
+                .iter()
+                .map(|r| r.kind.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, records[1].link_enters.len());
+
+        // buffers are drained after taking the records
+        assert_eq!(ScoringRecords::default(), collector.take_records()[1]);
+    }
+
+    #[deterministic_id_test]
+    fn scores_network_leg_across_partitions() {
+        let n1 = Node::new(Id::create("n1"), Coordinate::new_2d(0., 0.), 0, 0);
+        let n2 = Node::new(Id::create("n2"), Coordinate::new_2d(500., 0.), 0, 0);
+        let n3 = Node::new(Id::create("n3"), Coordinate::new_2d(1500., 0.), 1, 0);
+        let l1 = Link::new_with_default(Id::create("l1"), &n1, &n2);
+        let l2 = Link::new_with_default(Id::create("l2"), &n2, &n3);
+        let mut network = Network::new();
+        network.add_node(n1);
+        network.add_node(n2);
+        network.add_node(n3);
+        network.add_link(l1);
+        network.add_link(l2);
+
+        let person_id: Id<InternalPerson> = Id::create("p1");
+        let mut population = Population::from_persons(vec![InternalPerson::new(
+            person_id.clone(),
+            InternalPlan::default(),
+        )]);
+
+        let config = Scoring {
+            activity_params: vec![ActivityParams {
+                activity_type: "h".to_string(),
+                typical_duration: 12 * 3600,
+                typical_duration_score_computation: Default::default(),
+                opening_time: None,
+                closing_time: None,
+                latest_start_time: None,
+                earliest_end_time: None,
+            }],
+            mode_params: vec![ModeParams {
+                marginal_utility_of_traveling: 0.,
+                marginal_utility_of_distance: -0.01,
+                ..ModeParams::default_for_mode("car")
+            }],
+            ..Scoring::default()
+        };
+        let params = ScoringParameters::from_config(&config);
+
+        let mut part_0 = ScoringRecords::default();
+        let mut part_1 = ScoringRecords::default();
+        let vehicle = Id::create("p1_car");
+        let secs = SimTime::from_secs;
+        part_0.push_person(
+            &person_id,
+            secs(0),
+            PersonRecordKind::ActivityEnd(Id::create("h")),
+        );
+        part_0.push_person(
+            &person_id,
+            secs(0),
+            PersonRecordKind::Departure(Id::create("car")),
+        );
+        part_0.push_person(
+            &person_id,
+            secs(0),
+            PersonRecordKind::EntersVehicle(vehicle.clone()),
+        );
+        part_1.link_enters.push(super::LinkEnterRecord {
+            vehicle: vehicle.clone(),
+            time: secs(60),
+            link: Id::create("l2"),
+        });
+        part_1.push_person(
+            &person_id,
+            secs(100),
+            PersonRecordKind::LeavesVehicle(vehicle),
+        );
+        part_1.push_person(&person_id, secs(100), PersonRecordKind::Arrival);
+
+        score_population(&mut population, vec![part_0, part_1], &params, &network);
+
+        // The leg enters l2 only, which is 1000m long. The first activity ends at 0s and is
+        // penalized linearly with the slope at the zero utility duration, which results in
+        // -performing * typical_duration.
+        let first_activity = -6. * 12.;
+        let score = population.persons[&person_id]
+            .selected_plan()
+            .unwrap()
+            .score;
+        assert_approx_eq!(-10. + first_activity, score.unwrap());
+    }
+}
@@ -152,9 +152,8 @@ impl<C: SimCommunicator> SimulationBuilder<C> {
 
         let leg_engine = LegEngine::new(
             network_partition,
-            scenario.garage.clone(),
+            &scenario,
             self.net_message_broker,
-            scenario.config.qsim(),
             self.comp_env.clone(),
         );
 
@@ -58,6 +58,18 @@ impl SimulationVehicle {
         &self.passengers
     }
 
+    pub fn add_passenger(&mut self, passenger: SimulationAgent) {
+        self.passengers.push(passenger);
+    }
+
+    /// Removes and returns all passengers for which `predicate` is true.
+    pub fn remove_passengers<F>(&mut self, predicate: F) -> Vec<SimulationAgent>
+    where
+        F: FnMut(&mut SimulationAgent) -> bool,
+    {
+        self.passengers.extract_if(.., predicate).collect()
+    }
+
     pub fn id(&self) -> &Id<InternalVehicle> {
         &self.vehicle.id
     }
@@ -70,6 +82,12 @@ impl SimulationVehicle {
         self.vehicle.pce
     }
 
+    /// The flow capacity a vehicle consumes when leaving a link. Vehicles with a flow efficiency
+    /// factor above 1, e.g., autonomous or platooning vehicles, use less capacity than their pce.
+    pub fn flow_consumption(&self) -> f64 {
+        self.vehicle.pce / self.vehicle.fef
+    }
+
     pub fn curr_link_id(&self) -> Option<&Id<Link>> {
         self.driver().curr_link_id()
     }
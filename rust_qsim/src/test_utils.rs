@@ -3,7 +3,10 @@ use std::path::PathBuf;
 
 use crate::simulation::agents::SimulationAgentLogic;
 use crate::simulation::agents::agent::SimulationAgent;
+use crate::simulation::agents::transit_driver::TransitDriverLogic;
 use crate::simulation::id::Id;
+use crate::simulation::io::xml::transit::IOTransitSchedule;
+use crate::simulation::pt::TransitSchedule;
 use crate::simulation::scenario::Coordinate;
 use crate::simulation::scenario::population::{
     InternalActivity, InternalGenericRoute, InternalLeg, InternalNetworkRoute, InternalPerson,
@@ -11,6 +14,7 @@ use crate::simulation::scenario::population::{
 };
 use crate::simulation::scenario::vehicles::InternalVehicleType;
 use crate::simulation::time::SimTime;
+use crate::simulation::vehicles::SimulationVehicle;
 use crate::simulation::{InternalAttributes, config};
 use std::time::Duration;
 
@@ -57,6 +61,47 @@ pub fn create_agent(id: u64, route: Vec<&str>) -> SimulationAgent {
     agent
 }
 
+/// A bus driven along the given links, which serves a single stop on the first link of its route.
+pub fn create_bus(id: u64, pce: f64, route: Vec<&str>) -> SimulationVehicle {
+    let links: String = route
+        .iter()
+        .map(|link| format!("<link refId=\"{link}\"/>"))
+        .collect();
+    let schedule = TransitSchedule::from(
+        quick_xml::de::from_str::<IOTransitSchedule>(&format!(
+            "<transitSchedule>\
+                <transitStops>\
+                    <stopFacility id=\"s1\" x=\"0.0\" y=\"0.0\" linkRefId=\"{}\"/>\
+                </transitStops>\
+                <transitLine id=\"line\">\
+                    <transitRoute id=\"route\">\
+                        <transportMode>bus</transportMode>\
+                        <routeProfile>\
+                            <stop refId=\"s1\" departureOffset=\"00:00:00\"/>\
+                        </routeProfile>\
+                        <route>{links}</route>\
+                        <departures>\
+                            <departure id=\"d\" departureTime=\"00:00:00\" vehicleRefId=\"{id}\"/>\
+                        </departures>\
+                    </transitRoute>\
+                </transitLine>\
+            </transitSchedule>",
+            route.first().unwrap()
+        ))
+        .unwrap(),
+    );
+    let line = schedule.lines().values().next().unwrap();
+    let transit_route = line.routes.values().next().unwrap();
+    let driver = TransitDriverLogic::new(
+        &schedule,
+        line,
+        transit_route,
+        &transit_route.departures[0],
+        Id::create(&id.to_string()),
+    );
+    SimulationVehicle::from_parts(id, 0, 10., pce, SimulationAgent::new(Box::new(driver)))
+}
+
 pub fn create_folders(path: PathBuf) -> PathBuf {
     fs::create_dir_all(&path)
         .unwrap_or_else(|_e| panic!("Failed to create folders for path {path:?}"));
@@ -73,7 +118,9 @@ pub fn create_vehicle_type(
         width: 0.0,
         max_v: 0.0,
         pce: 0.0,
-        fef: 0.0,
+        fef: 1.0,
+        seats: 1,
+        standing_room: 0,
         net_mode,
         attributes: InternalAttributes::default(),
     }
@@ -87,5 +134,6 @@ pub fn config() -> config::QSim {
         sample_size: 1.0,
         stuck_threshold: u32::MAX,
         main_modes: vec![String::from("car")],
+        ..config::QSim::default()
     }
 }
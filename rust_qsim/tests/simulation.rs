@@ -1,5 +1,7 @@
 mod support;
 
+#[path = "simulation/drt.rs"]
+mod drt;
 #[path = "simulation/empty.rs"]
 mod empty;
 #[path = "simulation/equil.rs"]
@@ -10,5 +12,7 @@ mod equil_teleport;
 mod iterations;
 #[path = "simulation/pt.rs"]
 mod pt;
+#[path = "simulation/socket.rs"]
+mod socket;
 #[path = "simulation/three_links.rs"]
 mod three_links;
@@ -0,0 +1,60 @@
+use crate::support::simulation_executor::TestExecutorBuilder;
+use macros::deterministic_id_test;
+use rust_qsim::simulation::config::{CommandLineArgs, Config};
+use rust_qsim::simulation::controller::controller::ControllerBuilder;
+use rust_qsim::simulation::events::{EventHandlerRegisterFn, EventsManager};
+use rust_qsim::simulation::scenario::Scenario;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[deterministic_id_test(rust_qsim)]
+fn drt_serves_requests_with_single_vehicle() {
+    let config = Arc::new(Config::from_args(CommandLineArgs::new_with_path(
+        "./assets/drt/config.yml",
+    )));
+
+    let counts = Arc::new(Mutex::new(HashMap::<String, usize>::new()));
+    let handler: HashMap<u32, Vec<Box<EventHandlerRegisterFn>>> =
+        HashMap::from([(0, vec![count_events(counts.clone())])]);
+
+    TestExecutorBuilder::default()
+        .config(config)
+        .additional_handler(handler)
+        .build()
+        .unwrap()
+        .execute();
+
+    // The single vehicle can't reach passenger5 and passenger8 within the maximum wait time.
+    let counts = counts.lock().unwrap();
+    assert_eq!(Some(&10), counts.get("DrtRequest submitted"));
+    assert_eq!(Some(&2), counts.get("PassengerRequest rejected"));
+    assert_eq!(Some(&8), counts.get("passenger picked up"));
+    assert_eq!(Some(&8), counts.get("passenger dropped off"));
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn drt_rejects_partitioned_runs() {
+    let mut args = CommandLineArgs::new_with_path("./assets/drt/config.yml");
+    args.overrides
+        .push(("partitioning.num_parts".to_string(), "2".to_string()));
+    let scenario = Scenario::load(Arc::new(Config::from_args(args)));
+
+    let error = ControllerBuilder::default_with_scenario(scenario)
+        .build()
+        .err()
+        .unwrap();
+
+    assert!(error.contains("single partition"), "{error}");
+}
+
+fn count_events(counts: Arc<Mutex<HashMap<String, usize>>>) -> Box<EventHandlerRegisterFn> {
+    Box::new(move |events: &mut EventsManager| {
+        events.on_any(move |e| {
+            *counts
+                .lock()
+                .unwrap()
+                .entry(e.type_().to_string())
+                .or_default() += 1;
+        });
+    })
+}
@@ -11,7 +11,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Barrier};
 
 use crate::support::simulation_executor::TestExecutorBuilder;
-use rust_qsim::simulation::config::{CommandLineArgs, Config};
+use rust_qsim::simulation::config::{CommandLineArgs, Config, TrafficDynamics};
 use rust_qsim::simulation::controller::{ExternalServices, RequestSender};
 use rust_qsim::simulation::id::{Id, store_to_file};
 use rust_qsim::simulation::population::agent_source::PreplanningHorizonAgentSource;
@@ -84,6 +84,24 @@ fn equil_two_parts_matches_expected_events() {
         .execute();
 }
 
+/// A single vehicle never waits for storage capacity, so the holes of kinematic waves must not
+/// change any event, also not across partition boundaries.
+#[deterministic_id_test(rust_qsim)]
+fn equil_two_parts_with_kinematic_waves_matches_expected_events() {
+    let config_args = CommandLineArgs::new_with_path("./tests/resources/equil/equil-config-2.yml");
+    let mut config = Config::from_args(config_args);
+    config.qsim_mut().traffic_dynamics = TrafficDynamics::KinematicWaves;
+    config.output_mut().output_dir =
+        PathBuf::from("./test_output/simulation/equil_two_parts_kinematic_waves");
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(Some("./tests/resources/equil/expected_events.xml"))
+        .build()
+        .unwrap()
+        .execute();
+}
+
 #[deterministic_id_test(rust_qsim)]
 #[should_panic]
 fn equil_adaptive_planning_without_external_service_panics() {
@@ -1,9 +1,15 @@
 use crate::support::simulation_executor::TestExecutorBuilder;
 use macros::deterministic_id_test;
 use rust_qsim::simulation::config::{
-    CommandLineArgs, CompressionType, Config, StrategySetting, WriteEvents,
+    AnalysisFormat, CommandLineArgs, CompressionType, Config, StrategySetting, WeightSchedulePoint,
+    WriteEvents,
 };
+use rust_qsim::simulation::controller::controller::ControllerBuilder;
 use rust_qsim::simulation::events::utils::compare_xml_event_files;
+use rust_qsim::simulation::replanning::{PlanSelector, PlanStrategyModule, ReplanningContext};
+use rust_qsim::simulation::scenario::Scenario;
+use rust_qsim::simulation::scenario::population::InternalPerson;
+use rust_qsim::simulation::time::SimTime;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -81,6 +87,7 @@ fn equil_single_part_keep_last_selected_produces_same_events_each_iteration() {
         name: "KeepLastSelected".to_string(),
         weight: 1.0,
         subpopulation: "person".to_string(),
+        ..Default::default()
     }];
 
     TestExecutorBuilder::default()
@@ -94,6 +101,413 @@ fn equil_single_part_keep_last_selected_produces_same_events_each_iteration() {
     assert_events_equal(&iteration_1_events, &iteration_events_file(&output_dir, 3));
 }
 
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_subtour_mode_choice_switches_between_car_and_walk() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_subtour_mode_choice");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 3;
+    config.controller_mut().write_plans_interval = 1;
+    config.controller_mut().compression_type = CompressionType::None;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.replanning_mut().subtour_mode_choice.modes = vec!["car".into(), "walk".into()];
+    config.replanning_mut().strategy_settings = vec![StrategySetting {
+        name: "SubtourModeChoice".to_string(),
+        weight: 1.0,
+        subpopulation: "person".to_string(),
+        ..Default::default()
+    }];
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    let plans = std::fs::read_to_string(output_dir.join("output_plans.xml")).unwrap();
+    let plans: Vec<_> = plans.split("<plan ").skip(1).collect();
+    assert!(plans.len() > 1);
+    assert!(plans.iter().any(|plan| plan.contains(r#"<leg mode="car""#)));
+    assert!(
+        plans
+            .iter()
+            .any(|plan| !plan.contains(r#"<leg mode="car""#))
+    );
+}
+
+struct LateStartModule;
+
+impl PlanStrategyModule for LateStartModule {
+    fn handle(&self, person: &mut InternalPerson, plan_index: usize, _: &ReplanningContext) {
+        let plan = &mut person.plans_mut()[plan_index];
+        plan.acts_mut()[0].end_time = Some(SimTime::from_secs(7 * 3600));
+    }
+}
+
+struct OldestPlanSelector;
+
+impl PlanSelector for OldestPlanSelector {
+    fn select(&self, _: &InternalPerson, _: &ReplanningContext) -> usize {
+        0
+    }
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_runs_registered_strategy_and_selector() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_registered_strategy");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 1;
+    config.controller_mut().compression_type = CompressionType::None;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.replanning_mut().max_agent_plan_memory = 1;
+    config.replanning_mut().plan_selector_for_removal = "OldestPlan".to_string();
+    config.replanning_mut().strategy_settings = vec![StrategySetting {
+        name: "LateStart".to_string(),
+        weight: 1.0,
+        subpopulation: "person".to_string(),
+        ..Default::default()
+    }];
+
+    ControllerBuilder::default_with_scenario(Scenario::load(Arc::new(config)))
+        .plan_strategy_module("LateStart", |_| Box::new(LateStartModule))
+        .plan_selector("OldestPlan", |_| Box::new(OldestPlanSelector))
+        .build()
+        .unwrap()
+        .run();
+
+    // the initial plans are removed and only the adapted copies are kept
+    let plans = std::fs::read_to_string(output_dir.join("output_plans.xml")).unwrap();
+    assert!(!plans.contains(r#"end_time="06:00:00""#));
+    assert!(plans.contains(r#"end_time="07:00:00""#));
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_writes_annealed_strategy_weights() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_strategy_weights");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 3;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.replanning_mut().strategy_settings = vec![
+        StrategySetting {
+            name: "KeepLastSelected".to_string(),
+            weight: 1.0,
+            subpopulation: "person".to_string(),
+            ..Default::default()
+        },
+        StrategySetting {
+            name: "ReRoute".to_string(),
+            weight: 1.0,
+            subpopulation: "person".to_string(),
+            weight_schedule: vec![
+                WeightSchedulePoint {
+                    iteration: 0,
+                    weight: 1.0,
+                },
+                WeightSchedulePoint {
+                    iteration: 2,
+                    weight: 0.0,
+                },
+            ],
+            ..Default::default()
+        },
+    ];
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    // there is no replanning after the last iteration
+    let weights = std::fs::read_to_string(output_dir.join("strategy_weights.csv")).unwrap();
+    assert_eq!(
+        "iteration,subpopulation,strategy,weight\n\
+         0,person,KeepLastSelected,1\n\
+         0,person,ReRoute,1\n\
+         1,person,KeepLastSelected,1\n\
+         1,person,ReRoute,0.5\n\
+         2,person,KeepLastSelected,1\n\
+         2,person,ReRoute,0\n",
+        weights
+    );
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_writes_iteration_stats() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_iteration_stats");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 2;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.replanning_mut().strategy_settings = vec![StrategySetting {
+        name: "KeepLastSelected".to_string(),
+        weight: 1.0,
+        subpopulation: "person".to_string(),
+        ..Default::default()
+    }];
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    // all agents keep their plans, so every iteration has the same stats
+    let assert_stats = |file_name: &str, header: &str| {
+        let stats = std::fs::read_to_string(output_dir.join(file_name)).unwrap();
+        let lines: Vec<_> = stats.lines().collect();
+        assert_eq!(4, lines.len(), "unexpected {file_name}: {stats}");
+        assert_eq!(header, lines[0]);
+        let first = lines[1].strip_prefix("0,").unwrap();
+        assert_eq!(format!("1,{first}"), lines[2]);
+        assert_eq!(format!("2,{first}"), lines[3]);
+        first
+            .split(',')
+            .map(|v| v.parse().unwrap())
+            .collect::<Vec<f64>>()
+    };
+
+    let scores = assert_stats(
+        "scorestats.csv",
+        "iteration,avg_executed,avg_worst,avg_average,avg_best",
+    );
+    assert!(scores[1] <= scores[2] && scores[2] <= scores[3]);
+    assert_eq!(vec![1.0], assert_stats("modestats.csv", "iteration,car"));
+    let distances = assert_stats(
+        "traveldistancestats.csv",
+        "iteration,avg_planned_leg_distance,avg_planned_trip_distance",
+    );
+    assert!(distances[0] > 0.0 && distances[0] < distances[1]);
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_writes_leg_histogram_and_link_volumes_at_interval() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_leg_histogram_link_volumes");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 3;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.analysis_mut().write_interval = 2;
+    config.analysis_mut().format = AnalysisFormat::Csv;
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    let iteration_dir = |iteration: u32| output_dir.join(format!("ITERS/it.{iteration}"));
+    assert!(!iteration_dir(1).join("leg_histogram.csv").exists());
+    assert!(!iteration_dir(1).join("link_volumes.csv").exists());
+    for iteration in [0, 2, 3] {
+        let histogram =
+            std::fs::read_to_string(iteration_dir(iteration).join("leg_histogram.csv")).unwrap();
+        let mut lines = histogram.lines();
+        assert_eq!(Some("time,mode,departures,arrivals,en_route"), lines.next());
+        let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+        let sum = |column: usize| {
+            rows.iter()
+                .map(|row| row[column].parse::<u64>().unwrap())
+                .sum::<u64>()
+        };
+        assert!(sum(2) > 0);
+        assert_eq!(sum(2), sum(3));
+        // all legs have arrived at the end of the day
+        assert_eq!("0", rows.last().unwrap()[4]);
+
+        let volumes =
+            std::fs::read_to_string(iteration_dir(iteration).join("link_volumes.csv")).unwrap();
+        let mut lines = volumes.lines();
+        assert_eq!(Some("link,hour,volume"), lines.next());
+        assert!(lines.next().is_some());
+    }
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_writes_trips_and_legs() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_trips_and_legs");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 0;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.analysis_mut().format = AnalysisFormat::Csv;
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    // the agent drives from home to work, from work to work and back home
+    let trips = std::fs::read_to_string(output_dir.join("ITERS/it.0/trips.csv")).unwrap();
+    let rows: Vec<Vec<&str>> = trips
+        .lines()
+        .map(|line| line.split(',').collect())
+        .collect();
+    assert_eq!(
+        vec![
+            "person",
+            "trip_number",
+            "main_mode",
+            "dep_time",
+            "trav_time",
+            "wait_time",
+            "routed_distance",
+            "euclidean_distance",
+            "start_activity_type",
+            "end_activity_type"
+        ],
+        rows[0]
+    );
+    assert_eq!(4, rows.len());
+    for (row, (number, start, end)) in
+        rows[1..]
+            .iter()
+            .zip([("1", "h", "w"), ("2", "w", "w"), ("3", "w", "h")])
+    {
+        assert_eq!(number, row[1]);
+        assert_eq!("car", row[2]);
+        assert_eq!(start, row[8]);
+        assert_eq!(end, row[9]);
+    }
+    assert_eq!("21600", rows[1][3]);
+
+    // every car trip has an access walk, a car leg and an egress walk
+    let legs = std::fs::read_to_string(output_dir.join("ITERS/it.0/legs.csv")).unwrap();
+    let modes: Vec<_> = legs
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').nth(2).unwrap())
+        .collect();
+    assert_eq!(["walk", "car", "walk"].repeat(3), modes);
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_writes_counts_comparison() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_counts_comparison");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 0;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.analysis_mut().format = AnalysisFormat::Csv;
+    config.counts_mut().path = Some(PathBuf::from("./tests/resources/equil/equil-counts.xml"));
+    // every simulated vehicle stands for two real ones
+    config.qsim_mut().sample_size = 0.5;
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    let comparison =
+        std::fs::read_to_string(output_dir.join("ITERS/it.0/counts_comparison.csv")).unwrap();
+    assert_eq!(
+        "link,station,hour,observed,simulated,relative_error,geh\n\
+         20,morning,6,2,2,0,0\n\
+         1,afternoon,14,4,2,-0.5,1.1547005383792515\n\
+         5,,7,10,0,-1,4.47213595499958\n",
+        comparison
+    );
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_applies_network_change_events() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_network_change_events");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 0;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.analysis_mut().format = AnalysisFormat::Csv;
+    config.network_mut().change_events_path = Some(PathBuf::from(
+        "./tests/resources/equil/equil-network-change-events.xml",
+    ));
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    // the links 11 to 19 in the middle of the network are slowed down from 27.78 to 5 m/s in the
+    // morning. The way to work uses one of them and takes 1000 instead of 180 seconds on it. The way
+    // home does not use them.
+    let legs = std::fs::read_to_string(output_dir.join("ITERS/it.0/legs.csv")).unwrap();
+    let car_travel_times: Vec<&str> = legs
+        .lines()
+        .map(|line| line.split(',').collect::<Vec<_>>())
+        .filter(|row| row[2] == "car")
+        .map(|row| row[4])
+        .collect();
+    assert_eq!(vec!["1721", "0", "2340"], car_travel_times);
+}
+
+#[deterministic_id_test(rust_qsim)]
+fn equil_single_part_with_lanes() {
+    let output_dir = PathBuf::from("./test_output/simulation/equil_lanes");
+    let mut config = Config::from_args(CommandLineArgs::new_with_path(
+        "./tests/resources/equil/equil-config-1.yml",
+    ));
+    config.controller_mut().first_iteration = 0;
+    config.controller_mut().last_iteration = 0;
+    config.output_mut().write_events = WriteEvents::None;
+    config.output_mut().output_dir = output_dir.clone();
+    config.analysis_mut().format = AnalysisFormat::Csv;
+    config.network_mut().lanes_path =
+        Some(PathBuf::from("./tests/resources/equil/equil-lanes.xml"));
+
+    TestExecutorBuilder::default()
+        .config(Arc::new(config))
+        .expected_events(None)
+        .build()
+        .unwrap()
+        .execute();
+
+    let legs = std::fs::read_to_string(output_dir.join("ITERS/it.0/legs.csv")).unwrap();
+    let car_travel_times: Vec<&str> = legs
+        .lines()
+        .map(|line| line.split(',').collect::<Vec<_>>())
+        .filter(|row| row[2] == "car")
+        .map(|row| row[4])
+        .collect();
+    // the travel time on link 22 is rounded down separately for the link and its lane, so the way
+    // home is one second shorter than without lanes.
+    assert_eq!(vec!["900", "0", "2339"], car_travel_times);
+}
+
 #[deterministic_id_test(rust_qsim)]
 fn equil_single_part_writes_events_for_single_last_iteration() {
     let output_dir = PathBuf::from("./test_output/simulation/equil_event_interval_single_last");
@@ -0,0 +1,123 @@
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn three_links_over_tcp_sockets_match_expected_events() {
+    let output_dir = "./test_output/simulation/socket_3_links_tcp";
+    // the OS picks free ports for the two ranks, but they are released again before the ranks
+    // bind them. If another process takes one of them in between, retry with new ports.
+    for _ in 0..5 {
+        let listeners: Vec<_> = (0..2)
+            .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+            .collect();
+        let addresses = listeners
+            .iter()
+            .map(|l| l.local_addr().unwrap().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        drop(listeners);
+
+        match run(output_dir, &addresses, &[]) {
+            Ok(()) => {
+                compare(output_dir);
+                return;
+            }
+            Err(stderr) if stderr.contains("Address already in use") => continue,
+            Err(stderr) => panic!("socket_qsim failed:\n{stderr}"),
+        }
+    }
+    panic!("Failed to find free ports for the ranks.");
+}
+
+#[cfg(unix)]
+#[test]
+fn three_links_over_unix_sockets_match_expected_events() {
+    let dir = tempfile::tempdir().unwrap();
+    let addresses = (0..2)
+        .map(|rank| {
+            format!(
+                "unix:{}",
+                dir.path().join(format!("rank{rank}.sock")).display()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let output_dir = "./test_output/simulation/socket_3_links_unix";
+    run(output_dir, &addresses, &[])
+        .unwrap_or_else(|stderr| panic!("socket_qsim failed:\n{stderr}"));
+    compare(output_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn more_than_one_iteration_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let addresses = (0..2)
+        .map(|rank| {
+            format!(
+                "unix:{}",
+                dir.path().join(format!("rank{rank}.sock")).display()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let stderr = run(
+        "./test_output/simulation/socket_3_links_iterations",
+        &addresses,
+        &["controller.last_iteration=1"],
+    )
+    .unwrap_err();
+    assert!(stderr.contains("only a single iteration"), "{stderr}");
+}
+
+/// Runs `socket_qsim` on the 3-links scenario. Returns the standard error output if it fails.
+fn run(output_dir: &str, addresses: &str, overrides: &[&str]) -> Result<(), String> {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_socket_qsim"));
+    command
+        .arg("--config")
+        .arg("./tests/resources/3-links/3-links-config-socket.yml")
+        .arg("--set")
+        .arg(format!("output.output_dir={output_dir}"))
+        .arg("--addresses")
+        .arg(addresses);
+    for value in overrides {
+        command.arg("--set").arg(value);
+    }
+    let output = command.output().unwrap();
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+fn compare(output_dir: &str) {
+    let events_dir = Path::new(output_dir).join("ITERS/it.0/events");
+    let mut actual: Vec<_> = (0..2)
+        .flat_map(|rank| event_lines(&events_dir.join(format!("events.{rank}.xml"))))
+        .collect();
+    let mut expected = event_lines(Path::new("./tests/resources/3-links/expected_events.xml"));
+    actual.sort();
+    expected.sort();
+    assert_eq!(expected, actual);
+
+    for rank in 0..2 {
+        assert!(
+            Path::new(output_dir)
+                .join(format!("output_plans.{rank}.xml"))
+                .exists()
+        );
+    }
+}
+
+fn event_lines(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Failed to read {path:?}: {e}"))
+        .lines()
+        .filter(|line| line.starts_with("<event "))
+        .map(String::from)
+        .collect()
+}